@@ -7,6 +7,8 @@ use inflections::case::to_snake_case;
 
 fn main() -> std::io::Result<()> {
     let mut generated_tests = String::new();
+    // Each generated test's action, as fuzz corpus seeds.
+    let mut generated_corpus = String::new();
     let zones = [
         "Zone::Deck",
         "Zone::Hand { public: true }",
@@ -39,27 +41,21 @@ fn main() -> std::io::Result<()> {
                                 to_player,
                                 to_zone
                             ));
-                            generated_tests.push_str(&format!(
-                                "
-                                    #[test]
-                                    fn test_{stripped_name}() {{
-                                        test_move(
-                                            {card_ptr_bucket},
-                                            {base_card_type},
-                                            {from_player},
-                                            {from_zone},
-                                            {to_player},
-                                            {to_zone},
-                                        );
-                                    }}",
-                                stripped_name = stripped_name,
-                                card_ptr_bucket = card_ptr_bucket,
-                                base_card_type = base_card_type,
-                                from_player = from_player,
-                                from_zone = from_zone,
-                                to_player = to_player,
-                                to_zone = to_zone,
-                            ));
+                            generate(
+                                &mut generated_tests,
+                                &mut generated_corpus,
+                                &stripped_name,
+                                "test_move",
+                                "Move",
+                                &[
+                                    ("card_ptr_bucket", card_ptr_bucket.to_string()),
+                                    ("base_card_type", base_card_type.to_string()),
+                                    ("from_player", from_player.to_string()),
+                                    ("from_zone", from_zone.to_string()),
+                                    ("to_player", to_player.to_string()),
+                                    ("to_zone", to_zone.to_string()),
+                                ],
+                            );
                         }
                     }
                 }
@@ -83,24 +79,19 @@ fn main() -> std::io::Result<()> {
                         parent_zone, attachment_ptr_bucket, to_player, to_zone
                     ));
 
-                    generated_tests.push_str(&format!(
-                        "
-                            #[test]
-                            fn test_{stripped_name}() {{
-                                test_detach(
-                                    {parent_zone},
-                                    {attachment_ptr_bucket},
-                                    {to_player},
-                                    {to_zone},
-                                );
-                            }}
-                        ",
-                        stripped_name = stripped_name,
-                        parent_zone = parent_zone,
-                        attachment_ptr_bucket = attachment_ptr_bucket,
-                        to_player = to_player,
-                        to_zone = to_zone,
-                    ));
+                    generate(
+                        &mut generated_tests,
+                        &mut generated_corpus,
+                        &stripped_name,
+                        "test_detach",
+                        "Detach",
+                        &[
+                            ("parent_zone", parent_zone.to_string()),
+                            ("attachment_ptr_bucket", attachment_ptr_bucket.to_string()),
+                            ("to_player", to_player.to_string()),
+                            ("to_zone", to_zone.to_string()),
+                        ],
+                    );
                 }
             }
         }
@@ -131,28 +122,21 @@ fn main() -> std::io::Result<()> {
                                 card_zone,
                             ));
 
-                            generated_tests.push_str(&format!(
-                                "
-                                    #[test]
-                                    fn test_{stripped_name}() {{
-                                        test_attach(
-                                            {parent_base_card},
-                                            {parent_ptr_bucket},
-                                            {parent_zone},
-                                            {card_ptr_bucket},
-                                            {card_owner},
-                                            {card_zone},
-                                        );
-                                    }}
-                                ",
-                                stripped_name = stripped_name,
-                                parent_base_card = parent_base_card,
-                                parent_ptr_bucket = parent_ptr_bucket,
-                                parent_zone = parent_zone,
-                                card_ptr_bucket = card_ptr_bucket,
-                                card_owner = card_owner,
-                                card_zone = card_zone,
-                            ));
+                            generate(
+                                &mut generated_tests,
+                                &mut generated_corpus,
+                                &stripped_name,
+                                "test_attach",
+                                "Attach",
+                                &[
+                                    ("parent_base_card", parent_base_card.to_string()),
+                                    ("parent_ptr_bucket", parent_ptr_bucket.to_string()),
+                                    ("parent_zone", parent_zone.to_string()),
+                                    ("card_ptr_bucket", card_ptr_bucket.to_string()),
+                                    ("card_owner", card_owner.to_string()),
+                                    ("card_zone", card_zone.to_string()),
+                                ],
+                            );
                         }
                     }
                 }
@@ -185,28 +169,21 @@ fn main() -> std::io::Result<()> {
                                 card_zone,
                             ));
 
-                            generated_tests.push_str(&format!(
-                                "
-                                    #[test]
-                                    fn test_{stripped_name}() {{
-                                        test_attach_from_attached(
-                                            {parent_base_card},
-                                            {parent_ptr_bucket},
-                                            {parent_zone},
-                                            {card_ptr_bucket},
-                                            {card_owner},
-                                            {card_zone}
-                                        );
-                                    }}
-                                ",
-                                stripped_name = stripped_name,
-                                parent_base_card = parent_base_card,
-                                parent_ptr_bucket = parent_ptr_bucket,
-                                parent_zone = parent_zone,
-                                card_ptr_bucket = card_ptr_bucket,
-                                card_owner = card_owner,
-                                card_zone = card_zone,
-                            ));
+                            generate(
+                                &mut generated_tests,
+                                &mut generated_corpus,
+                                &stripped_name,
+                                "test_attach_from_attached",
+                                "AttachFromAttached",
+                                &[
+                                    ("parent_base_card", parent_base_card.to_string()),
+                                    ("parent_ptr_bucket", parent_ptr_bucket.to_string()),
+                                    ("parent_zone", parent_zone.to_string()),
+                                    ("card_ptr_bucket", card_ptr_bucket.to_string()),
+                                    ("card_owner", card_owner.to_string()),
+                                    ("card_zone", card_zone.to_string()),
+                                ],
+                            );
                         }
                     }
                 }
@@ -229,24 +206,19 @@ fn main() -> std::io::Result<()> {
                         "copy_card_ptr_{}_card_{}_zone_{}_deep_{}",
                         card_ptr_bucket, base_card_type, card_zone, deep,
                     ));
-                    generated_tests.push_str(&format!(
-                        "
-                            #[test]
-                            fn test_{stripped_name}() {{
-                              test_copy_card(
-                                 {card_ptr_bucket},
-                                 {base_card_type},
-                                 {card_zone},
-                                 {deep},
-                              );
-                            }}
-                        ",
-                        stripped_name = stripped_name,
-                        card_ptr_bucket = card_ptr_bucket,
-                        base_card_type = base_card_type,
-                        card_zone = card_zone,
-                        deep = deep,
-                    ))
+                    generate(
+                        &mut generated_tests,
+                        &mut generated_corpus,
+                        &stripped_name,
+                        "test_copy_card",
+                        "CopyCard",
+                        &[
+                            ("card_ptr_bucket", card_ptr_bucket.to_string()),
+                            ("base_card_type", base_card_type.to_string()),
+                            ("card_zone", card_zone.to_string()),
+                            ("deep", deep.to_string()),
+                        ],
+                    );
                 }
             }
         }
@@ -271,25 +243,19 @@ fn main() -> std::io::Result<()> {
                         "reset_card_ptr_{}_card_{}_attachment_{}_zone_{}",
                         card_ptr_bucket, base_card_type, attachment_type, card_zone,
                     ));
-                    generated_tests.push_str(&format!(
-                        "
-                            #[test]
-                            fn test_{stripped_name}() {{
-                              test_reset_card(
-                                  {card_ptr_bucket},
-                                  {attachment_type},
-                                  {base_card_type},
-                                  {card_zone},
-                              );
-                            }}
-
-                            ",
-                        stripped_name = stripped_name,
-                        card_ptr_bucket = card_ptr_bucket,
-                        attachment_type = attachment_type,
-                        base_card_type = base_card_type,
-                        card_zone = card_zone,
-                    ))
+                    generate(
+                        &mut generated_tests,
+                        &mut generated_corpus,
+                        &stripped_name,
+                        "test_reset_card",
+                        "ResetCard",
+                        &[
+                            ("card_ptr_bucket", card_ptr_bucket.to_string()),
+                            ("attachment_type", attachment_type.to_string()),
+                            ("base_card_type", base_card_type.to_string()),
+                            ("card_zone", card_zone.to_string()),
+                        ],
+                    );
                 }
             }
         }
@@ -297,13 +263,63 @@ fn main() -> std::io::Result<()> {
 
     // create file
 
-    let test_file_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("generated_tests.rs");
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+
+    let test_file_path = out_dir.join("generated_tests.rs");
     let mut file = File::create(test_file_path)?;
     file.write_all(generated_tests.as_bytes())?;
 
+    let corpus_file_path = out_dir.join("generated_corpus.rs");
+    let mut file = File::create(corpus_file_path)?;
+    file.write_all(
+        format!(
+            "
+                /// The action of every generated test, named after the test.
+                const GENERATED_CORPUS: &[(&str, Action)] = &[{}];
+            ",
+            generated_corpus
+        )
+        .as_bytes(),
+    )?;
+
     Ok(())
 }
 
+/// Adds a generated test calling `test` with `fields` in order, and the same fields as an `Action` to the corpus.
+fn generate(
+    generated_tests: &mut String,
+    generated_corpus: &mut String,
+    stripped_name: &str,
+    test: &str,
+    action: &str,
+    fields: &[(&str, String)],
+) {
+    let arguments: Vec<_> = fields.iter().map(|(_, value)| value.as_str()).collect();
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|(field, value)| format!("{}: {}", field, value))
+        .collect();
+
+    generated_tests.push_str(&format!(
+        "
+            #[test]
+            fn test_{}() {{
+                {}({});
+            }}
+        ",
+        stripped_name,
+        test,
+        arguments.join(", "),
+    ));
+    generated_corpus.push_str(&format!(
+        "
+            (\"{}\", Action::{} {{ {} }}),",
+        stripped_name,
+        action,
+        fields.join(", "),
+    ));
+}
+
 fn identifier_ify_string(string: &str) -> String {
     to_snake_case(
         &string
@@ -0,0 +1,26 @@
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Identifies the action that logged an event, when action correlation is enabled.
+///
+/// Every action applied to the game takes the next `action` number.
+/// Public events are numbered by `index` within their action, so gaps mean missing events.
+/// Events logged within secret mutations have no `index`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ActionCorrelation {
+    pub action: usize,
+    pub index: Option<usize>,
+}
+
+impl std::fmt::Display for ActionCorrelation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "action {}.{}", self.action, index),
+            None => write!(f, "action {}", self.action),
+        }
+    }
+}
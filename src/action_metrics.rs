@@ -0,0 +1,24 @@
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// How much an action used players' secrets, see `CardGame::action_metrics` and `GameState::last_action_metrics`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Default, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionMetrics {
+    /// The number of `GameContext::reveal` and `GameContext::reveal_unique` calls.
+    pub reveals: usize,
+
+    /// The total size of the revealed values, serialized as CBOR.
+    ///
+    /// Only reveals that have completed are counted, and only while `CardGame::set_measure_reveals` is enabled.
+    pub revealed_bytes: usize,
+
+    /// The number of `GameContext::mutate_secret` and `GameContext::mutate_secret_or_log` calls, including those logging secret events.
+    ///
+    /// The checkpoints taken and the held events released under `PanicPolicy::Lenient` aren't counted, since they're the engine's own bookkeeping.
+    pub mutations: usize,
+}
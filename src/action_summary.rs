@@ -0,0 +1,29 @@
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// What an action did, logged in a `CardEvent::ActionResolved` at the end of the action when action summaries are enabled.
+///
+/// The counts only depend on the operations the action ran, so every client sees the same summary.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionSummary {
+    /// The action's idempotency key, see `State::action_key`.
+    pub key: Option<String>,
+
+    /// The number of successful `CardGame::move_card` calls, including those made by other operations.
+    pub moved: usize,
+
+    /// The number of instance IDs created, including any padding.
+    pub created: usize,
+
+    /// The number of reveals.
+    pub revealed: usize,
+
+    /// The number of `CardGame::modify_card` calls, including those made by other operations,
+    /// plus one for each `CardGame::modify_all_cards` call.
+    pub modified: usize,
+}
@@ -0,0 +1,133 @@
+use {
+    crate::{error, BaseCard, CardState},
+    indexmap::IndexMap,
+    std::{
+        fmt::{Debug, Error, Formatter},
+        marker::PhantomData,
+        sync::Arc,
+    },
+};
+
+/// A base card defined by data in a [CardCatalog].
+pub trait CardDefinition: Debug + 'static {
+    type CardState: CardState;
+
+    fn new_card_state(&self, parent: Option<&Self::CardState>) -> Self::CardState;
+}
+
+/// A set of card definitions loaded at runtime, for use with [CatalogCard].
+pub trait CardCatalog: 'static {
+    type Definition: CardDefinition;
+
+    /// Gets the definition with the given ID, if it's in the catalog.
+    fn definition(id: &str) -> Option<Arc<Self::Definition>>;
+}
+
+/// A [BaseCard] backed by a [CardCatalog] definition.
+///
+/// Serializes as its definition ID, and fails to deserialize if the ID isn't in the catalog.
+pub struct CatalogCard<C: CardCatalog> {
+    id: String,
+    definition: Arc<C::Definition>,
+    catalog: PhantomData<C>,
+}
+
+impl<C: CardCatalog> CatalogCard<C> {
+    pub fn new(id: impl Into<String>) -> Result<Self, error::CatalogCardError> {
+        let id = id.into();
+
+        match C::definition(&id) {
+            Some(definition) => Ok(Self {
+                id,
+                definition,
+                catalog: PhantomData,
+            }),
+            None => Err(error::CatalogCardError::UnknownCard { id }),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn definition(&self) -> &C::Definition {
+        &self.definition
+    }
+}
+
+impl<C: CardCatalog> BaseCard for CatalogCard<C> {
+    type CardState = <C::Definition as CardDefinition>::CardState;
+
+    fn new_card_state(&self, parent: Option<&Self::CardState>) -> Self::CardState {
+        self.definition.new_card_state(parent)
+    }
+}
+
+impl<C: CardCatalog> Clone for CatalogCard<C> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            definition: self.definition.clone(),
+            catalog: PhantomData,
+        }
+    }
+}
+
+impl<C: CardCatalog> PartialEq for CatalogCard<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<C: CardCatalog> Debug for CatalogCard<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.id)
+    }
+}
+
+impl<C: CardCatalog> serde::Serialize for CatalogCard<C> {
+    fn serialize<T: serde::Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        self.id.serialize(serializer)
+    }
+}
+
+impl<'de, C: CardCatalog> serde::Deserialize<'de> for CatalogCard<C> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+
+        Self::new(id).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Card definitions by ID, for implementing [CardCatalog::definition].
+#[derive(Debug)]
+pub struct Catalog<D> {
+    definitions: IndexMap<String, Arc<D>>,
+}
+
+impl<D> Catalog<D> {
+    pub fn new(definitions: impl IntoIterator<Item = (String, D)>) -> Self {
+        Self {
+            definitions: definitions
+                .into_iter()
+                .map(|(id, definition)| (id, Arc::new(definition)))
+                .collect(),
+        }
+    }
+
+    pub fn definition(&self, id: &str) -> Option<Arc<D>> {
+        self.definitions.get(id).cloned()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.definitions.keys().map(String::as_str)
+    }
+}
+
+impl<D> Default for Catalog<D> {
+    fn default() -> Self {
+        Self {
+            definitions: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use {
+    crate::{error, State},
+    std::fmt::Debug,
+};
+
+/// An external source of truth for which base cards are legal, such as the current format or set.
+///
+/// A `GameState` can hold one on the server, see `GameState::set_card_database`.
+/// Cards created in a player's secret aren't validated, since the database isn't available there.
+pub trait CardDatabase<S: State>: Debug {
+    /// Checks that a card can be created.
+    fn validate_card(&self, base: &S::BaseCard) -> Result<(), error::IllegalCardError>;
+
+    /// Checks that a deck can be registered.
+    ///
+    /// By default, every card in the deck must be legal.
+    fn validate_deck(&self, deck: &[S::BaseCard]) -> Result<(), error::IllegalCardError> {
+        deck.iter().try_for_each(|base| self.validate_card(base))
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{
-    CardInstance, CardLocation, ExactCardLocation, InstanceID, OpaquePointer, Player, State,
+    ActionCorrelation, ActionSummary, Card, CardInstance, CardLocation, CardModifier, CardState,
+    EventSequence, ExactCardLocation, GameResult, InstanceID, LinkPolicy, ModifierID,
+    OpaquePointer, PileID, Player, Prompt, PromptResponse, State, Turn, Zone,
 };
 
 #[cfg(feature = "bindings")]
@@ -19,10 +21,29 @@ pub enum CardEvent<S: State> {
         location: ExactCardLocation,
     },
 
+    /// Emitted when `CardGame::new_card_in` creates a card directly in a zone.
+    #[serde(bound = "S: State")]
+    NewCard {
+        /// A public ID if the card is in public state, and its owner's pointer otherwise.
+        card: Card,
+        /// Will be Some(..) if the card is in public state or in the client's secret state.
+        /// If the card has an attachment, it'll be provided in this tuple.
+        instance: Option<(CardInstance<S>, Option<CardInstance<S>>)>,
+        location: ExactCardLocation,
+    },
+
     /// Emitted when a card in public state or in the client's secret state changes.
     #[serde(bound = "S: State")]
     ModifyCard { instance: CardInstance<S> },
 
+    /// Emitted instead of `CardEvent::ModifyCard` when only a card's state changes, and `CardState::diff` describes the change.
+    ModifyCardDelta { id: InstanceID, delta: Vec<u8> },
+
+    /// Emitted by `CardGame::modify_all_cards` instead of a `CardEvent::ModifyCard` or `CardEvent::ModifyCardDelta` per card,
+    /// once for public state and once for the client's secret state.
+    #[serde(bound = "S: State")]
+    ModifyCards { events: Vec<CardEvent<S>> },
+
     /// Emitted when a card moves zones.
     #[serde(bound = "S: State")]
     MoveCard {
@@ -31,6 +52,32 @@ pub enum CardEvent<S: State> {
         instance: Option<(CardInstance<S>, Option<CardInstance<S>>)>,
         from: CardLocation,
         to: ExactCardLocation,
+        /// Will be Some(..) in public events if `GameState::redacted_attachment_policy` reveals whether the card has an attachment,
+        /// so it survives the instance being redacted, by the move or by `State::redact_event`.
+        /// Events logged from a player's secret always carry the instance, so they leave this None.
+        has_attachment: Option<bool>,
+    },
+
+    /// Emitted when a card is copied, once the copy and its attachment, if any, are in limbo.
+    #[serde(bound = "S: State")]
+    CopyCard {
+        /// The copied card, as it was passed to `CardGame::copy_card`.
+        source: Card,
+        /// The copy: a public ID if it's in public state, and a pointer otherwise.
+        copy: Card,
+        /// Will be Some(..) if the copy is in public state or in the client's secret state.
+        /// If the copy has an attachment, it'll be provided in this tuple.
+        instance: Option<(CardInstance<S>, Option<CardInstance<S>>)>,
+        deep: bool,
+    },
+
+    /// Emitted when a card's base is changed by `CardGame::transform_card`.
+    #[serde(bound = "S: State")]
+    TransformCard {
+        card: Card,
+        /// Will be Some(..) if the card is in public state or in the client's secret state.
+        /// If the card has an attachment, it'll be provided in this tuple.
+        instance: Option<(CardInstance<S>, Option<CardInstance<S>>)>,
     },
 
     /// Emitted when a deck is shuffled.
@@ -39,6 +86,24 @@ pub enum CardEvent<S: State> {
         deck: Vec<InstanceID>,
     },
 
+    /// Emitted to a player when one of their piles is shuffled, see `CardGame::shuffle_pile`.
+    ShufflePile {
+        player: Player,
+        pile: PileID,
+        ids: Vec<InstanceID>,
+    },
+
+    /// Emitted when a player swaps cards between their deck and sideboard pile, see `CardGame::sideboard`.
+    Sideboard {
+        player: Player,
+        pile: PileID,
+        deck: usize,
+        sideboard: usize,
+    },
+
+    /// Emitted when a player's deck is replaced with new cards.
+    ReplaceDeck { player: Player, size: usize },
+
     /// Emitted when the field is re-ordered.
     SortField {
         player: Player,
@@ -46,12 +111,348 @@ pub enum CardEvent<S: State> {
         real: bool,
     },
 
+    /// Emitted instead of sorting the field when `State::field_order` isn't a total order on it,
+    /// with the `field-order-check` feature.
+    /// The field is ordered by `State::resolve_field_order` instead.
+    FieldOrderConflict {
+        player: Player,
+        field: Vec<InstanceID>,
+    },
+
+    /// Emitted when two cards are linked.
+    LinkCards {
+        cards: (InstanceID, InstanceID),
+        policy: LinkPolicy,
+    },
+
+    /// Emitted when two cards are unlinked.
+    UnlinkCards { cards: (InstanceID, InstanceID) },
+
+    /// Emitted when a card is cast onto the stack.
+    /// The card is a pointer if it was cast face-down.
+    Cast { player: Player, card: Card },
+
+    /// Emitted when a card in a player's secret hand is revealed in place, see `CardGame::reveal_hand_card`.
+    RevealHandCard {
+        player: Player,
+        id: InstanceID,
+        index: usize,
+    },
+
+    /// Emitted when a card in a player's public hand is moved back to their secret hand in place, see `CardGame::conceal_hand_card`.
+    ConcealHandCard {
+        player: Player,
+        id: InstanceID,
+        index: usize,
+    },
+
+    /// Emitted when a card is placed face-down in a public zone, see `CardGame::place_face_down`.
+    FaceDown {
+        player: Player,
+        card: Card,
+        zone: Zone,
+    },
+
+    /// Emitted when the top card of the stack resolves.
+    Resolve { card: Card },
+
+    /// Emitted when a card on the stack is countered.
+    Counter { card: Card },
+
+    /// Emitted only to a player peeking at one of their own secret cards.
+    #[serde(bound = "S: State")]
+    PeekCard {
+        card: Card,
+        instance: CardInstance<S>,
+    },
+
+    /// Emitted right before the `CardEvent::MoveCard` events of the two cards, see `CardGame::swap_cards`.
+    SwapCards { cards: (Card, Card) },
+
+    /// Emitted when two cards' attachments are swapped, instead of moving either attachment, see `CardGame::swap_attachments`.
+    #[serde(bound = "S: State")]
+    SwapAttachments {
+        a: Card,
+        b: Card,
+        /// Each card and its new attachment after the swap.
+        /// Will be Some(..) if the card is in public state or in the client's secret state.
+        #[allow(clippy::type_complexity)]
+        instances: (
+            Option<(CardInstance<S>, Option<CardInstance<S>>)>,
+            Option<(CardInstance<S>, Option<CardInstance<S>>)>,
+        ),
+    },
+
+    /// Emitted when a card is destroyed, see `CardGame::destroy_instance`.
+    /// Only the owner is told the IDs of secret cards, unless they were already public.
+    DestroyCard { id: InstanceID },
+
+    /// Emitted when a card is placed at a position on a player's field, see `MatchConfig::positioned_field`.
+    FieldPosition {
+        player: Player,
+        id: InstanceID,
+        index: usize,
+    },
+
+    /// Emitted when a coin is flipped or a die is rolled, with an outcome from 1 to `sides`.
+    RandomOutcome { sides: usize, outcome: usize },
+
+    /// Emitted when a projection of a card is revealed, or its revealed value changes.
+    RevealProjection {
+        card: Card,
+        projection: String,
+        value: Vec<u8>,
+    },
+
+    /// Emitted when a player commits to a secret value.
+    Commit { player: Player, key: String },
+
+    /// Emitted when a commitment is revealed.
+    RevealCommitment { player: Player, key: String },
+
+    /// Emitted when a phase starts, including the first phase of each turn.
+    /// Every event after this is wrapped in a `CardEvent::InPhase` naming this phase, until the next `CardEvent::PhaseChange`.
+    #[serde(bound = "S: State")]
+    PhaseChange { turn: Turn<S> },
+
+    /// Emitted when a modifier is added.
+    AddModifier { modifier: CardModifier },
+
+    /// Emitted when a modifier is removed.
+    RemoveModifier { id: ModifierID },
+
+    /// Emitted when a zone's version is bumped, while zone watermarks are enabled.
+    /// See `GameState::set_zone_watermarks`.
+    ZoneVersion {
+        player: Player,
+        zone: Zone,
+        version: u64,
+    },
+
+    /// Emitted at the end of an action for each player with cards in limbo, when the limbo policy is `LimboPolicy::Flag`,
+    /// or for the first such player before the action is undone, when it's `LimboPolicy::Forbid`.
+    LimboNotEmpty { player: Player },
+
+    /// Emitted when an action panics and is undone, when the panic policy is `PanicPolicy::Lenient`,
+    /// or when it leaves cards in limbo and is undone, when the limbo policy is `LimboPolicy::Forbid`.
+    ActionAborted,
+
+    /// Emitted when the game ends, see `CardGame::end_game`.
+    ///
+    /// No card events follow it, only the events ending the action, e.g. `CardEvent::ActionResolved`.
+    GameOver { result: GameResult },
+
+    /// Emitted when the next game of a `MatchState` starts.
+    NextGame {
+        wins: Vec<usize>,
+        draws: usize,
+        first_player: Player,
+    },
+
+    /// Emitted at the end of every action, when `MatchConfig::checksum_events` is enabled.
+    ///
+    /// The checksum is `GameState::checksum` right before this event is logged.
+    Checksum { checksum: u64 },
+
+    /// Emitted when an action suspends until a player responds, see `CardGame::prompt`.
+    Prompt { prompt: Prompt },
+
+    /// Emitted when a player responds to a prompt, before `State::resume` runs.
+    PromptResponse {
+        player: Player,
+        response: PromptResponse,
+    },
+
+    /// Emitted when a player's resource changes, see `CardGame::adjust_resource`.
+    #[serde(bound = "S: State")]
+    ResourceChanged {
+        player: Player,
+        kind: S::ResourceKind,
+        delta: i32,
+        value: i32,
+    },
+
+    /// Emitted last in every action, when `MatchConfig::action_summaries` is enabled.
+    ActionResolved { summary: ActionSummary },
+
     /// Game-specific event.
     #[serde(deserialize_with = "deserialize_game_event")]
     GameEvent {
         #[cfg_attr(feature = "bindings", ts(ts_type = "GameEvent"))]
         event: S::Event,
     },
+
+    /// Wraps every event when strict event ordering is enabled.
+    /// See `replay::order_events`.
+    #[serde(bound = "S: State")]
+    Sequenced {
+        sequence: EventSequence,
+        event: Box<CardEvent<S>>,
+    },
+
+    /// Wraps every event, outside any `CardEvent::Sequenced` wrapper, when action correlation is enabled.
+    #[serde(bound = "S: State")]
+    Correlated {
+        correlation: ActionCorrelation,
+        event: Box<CardEvent<S>>,
+    },
+
+    /// Wraps events logged from a player's secret, inside any other wrappers, when spectator events are enabled.
+    /// See `CardEvent::redact_for_spectator`.
+    #[serde(bound = "S: State")]
+    Secret {
+        player: Player,
+        event: Box<CardEvent<S>>,
+        /// Will be Some(..) if the other players were sent this event instead.
+        public: Option<Box<CardEvent<S>>>,
+    },
+
+    /// Wraps every event, inside any other wrappers, once turns have started.
+    /// See `CardGame::start_turn`.
+    #[serde(bound = "S: State")]
+    InPhase {
+        turn: Turn<S>,
+        event: Box<CardEvent<S>>,
+    },
+}
+
+impl<S: State> CardEvent<S> {
+    /// The event for a card changing from `before` to `after`, if it changed at all.
+    pub(crate) fn modify_card(before: &CardInstance<S>, after: &CardInstance<S>) -> Option<Self> {
+        if before.eq(after) {
+            return None;
+        }
+
+        let delta = if before.base == after.base && before.attachment == after.attachment {
+            after.state.diff(&before.state)
+        } else {
+            None
+        };
+
+        Some(match delta {
+            Some(delta) => Self::ModifyCardDelta {
+                id: after.id,
+                delta,
+            },
+            None => Self::ModifyCard {
+                instance: after.clone(),
+            },
+        })
+    }
+
+    /// The event's sequence token, if it was logged with strict event ordering enabled.
+    pub fn sequence(&self) -> Option<EventSequence> {
+        match self {
+            Self::Sequenced { sequence, .. } => Some(*sequence),
+            Self::Correlated { event, .. } => event.sequence(),
+            _ => None,
+        }
+    }
+
+    /// Strips any `CardEvent::Sequenced` wrapper, keeping any `CardEvent::Correlated` wrapper.
+    pub fn unsequenced(self) -> Self {
+        match self {
+            Self::Sequenced { event, .. } => event.unsequenced(),
+            Self::Correlated { correlation, event } => Self::Correlated {
+                correlation,
+                event: Box::new(event.unsequenced()),
+            },
+            event => event,
+        }
+    }
+
+    /// The action that logged the event, if it was logged with action correlation enabled.
+    pub fn correlation(&self) -> Option<ActionCorrelation> {
+        match self {
+            Self::Correlated { correlation, .. } => Some(*correlation),
+            Self::Sequenced { event, .. } => event.correlation(),
+            _ => None,
+        }
+    }
+
+    /// Strips any `CardEvent::Correlated` wrapper.
+    pub fn uncorrelated(self) -> Self {
+        match self {
+            Self::Correlated { event, .. } => event.uncorrelated(),
+            event => event,
+        }
+    }
+
+    /// The event as a spectator with neither player's secret would see it.
+    ///
+    /// Returns `None` for events logged from a player's secret with no public counterpart.
+    /// These can only be told apart from public events while spectator events are enabled, see `CardGame::set_spectator_events`.
+    pub fn redact_for_spectator(&self) -> Option<Self> {
+        match self {
+            Self::Secret { public, .. } => public.as_deref().cloned(),
+            Self::Sequenced { sequence, event } => {
+                event.redact_for_spectator().map(|event| Self::Sequenced {
+                    sequence: *sequence,
+                    event: Box::new(event),
+                })
+            }
+            Self::Correlated { correlation, event } => {
+                event.redact_for_spectator().map(|event| Self::Correlated {
+                    correlation: *correlation,
+                    event: Box::new(event),
+                })
+            }
+            event => Some(event.clone()),
+        }
+    }
+
+    /// The turn and phase the event happened in, if it was logged after turns started.
+    pub fn turn(&self) -> Option<&Turn<S>> {
+        match self {
+            Self::InPhase { turn, .. } | Self::PhaseChange { turn } => Some(turn),
+            Self::Sequenced { event, .. }
+            | Self::Correlated { event, .. }
+            | Self::Secret { event, .. } => event.turn(),
+            _ => None,
+        }
+    }
+
+    /// Strips any `CardEvent::InPhase` wrapper, along with any wrappers outside it.
+    pub fn unphased(self) -> Self {
+        match self {
+            Self::InPhase { event, .. } => *event,
+            Self::Sequenced { event, .. }
+            | Self::Correlated { event, .. }
+            | Self::Secret { event, .. } => event.unphased(),
+            event => event,
+        }
+    }
+
+    /// Strips any `CardEvent::Secret` wrapper, keeping any `CardEvent::Sequenced` and `CardEvent::Correlated` wrappers.
+    pub fn unsecret(self) -> Self {
+        match self {
+            Self::Secret { event, .. } => *event,
+            Self::Sequenced { sequence, event } => Self::Sequenced {
+                sequence,
+                event: Box::new(event.unsecret()),
+            },
+            Self::Correlated { correlation, event } => Self::Correlated {
+                correlation,
+                event: Box::new(event.unsecret()),
+            },
+            event => event,
+        }
+    }
+}
+
+/// Controls what opponents learn about the attachments of cards whose instances are redacted from `CardEvent::MoveCard` events.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum RedactedAttachmentPolicy {
+    /// Redacted events say nothing about attachments.
+    #[default]
+    Hidden,
+
+    /// Redacted events reveal whether the card has an attachment.
+    Presence,
 }
 
 fn deserialize_game_event<'de, D: serde::Deserializer<'de>, T>(_: D) -> Result<T, D::Error> {
@@ -64,17 +465,67 @@ impl<S: State> std::fmt::Display for CardEvent<S> {
             CardEvent::NewPointer { pointer, location } => {
                 write!(f, "New Pointer #{:?} to {:?})", pointer, location)
             }
+            CardEvent::NewCard {
+                card,
+                instance,
+                location,
+            } => write!(
+                f,
+                "{:?} created at {} with{} instance",
+                card,
+                location,
+                if instance.is_some() { "" } else { "out" }
+            ),
             CardEvent::ModifyCard { instance } => write!(f, "Card #{:?} modified", instance.id),
-            CardEvent::MoveCard { instance, from, to } => write!(
+            CardEvent::ModifyCardDelta { id, .. } => write!(f, "Card #{:?} modified", id),
+            CardEvent::ModifyCards { events } => write!(f, "{} cards modified", events.len()),
+            CardEvent::MoveCard {
+                instance, from, to, ..
+            } => write!(
                 f,
                 "Card moved from {} to {} with{} instance",
                 from,
                 to,
                 if instance.is_some() { "" } else { "out" }
             ),
+            CardEvent::CopyCard {
+                source,
+                copy,
+                instance,
+                deep,
+            } => write!(
+                f,
+                "{:?} {}copied to {:?} with{} instance",
+                source,
+                if *deep { "deep " } else { "" },
+                copy,
+                if instance.is_some() { "" } else { "out" }
+            ),
+            CardEvent::TransformCard { card, instance } => write!(
+                f,
+                "{:?} transformed with{} instance",
+                card,
+                if instance.is_some() { "" } else { "out" }
+            ),
             CardEvent::ShuffleDeck { player, deck } => {
                 write!(f, "Player {}'s deck shuffled: {:?}", player, deck)
             }
+            CardEvent::ShufflePile { player, pile, ids } => {
+                write!(f, "Player {}'s pile {} shuffled: {:?}", player, pile, ids)
+            }
+            CardEvent::Sideboard {
+                player,
+                pile,
+                deck,
+                sideboard,
+            } => write!(
+                f,
+                "Player {} sideboarded with pile {}: {} cards in deck, {} in sideboard",
+                player, pile, deck, sideboard
+            ),
+            CardEvent::ReplaceDeck { player, size } => {
+                write!(f, "Player {}'s deck replaced with {} cards", player, size)
+            }
             CardEvent::SortField {
                 player,
                 field,
@@ -88,7 +539,132 @@ impl<S: State> std::fmt::Display for CardEvent<S> {
                     field
                 )
             }
+            CardEvent::FieldOrderConflict { player, field } => write!(
+                f,
+                "Player {}'s field order is inconsistent: {:?}",
+                player, field
+            ),
+            CardEvent::LinkCards { cards, policy } => {
+                write!(f, "{:?} and {:?} linked: {:?}", cards.0, cards.1, policy)
+            }
+            CardEvent::UnlinkCards { cards } => {
+                write!(f, "{:?} and {:?} unlinked", cards.0, cards.1)
+            }
+            CardEvent::Cast { player, card } => write!(f, "Player {} cast {:?}", player, card),
+            CardEvent::RevealHandCard { player, id, index } => write!(
+                f,
+                "{:?} revealed at index {} of player {}'s hand",
+                id, index, player
+            ),
+            CardEvent::ConcealHandCard { player, id, index } => write!(
+                f,
+                "{:?} concealed at index {} of player {}'s hand",
+                id, index, player
+            ),
+            CardEvent::FaceDown { player, card, zone } => write!(
+                f,
+                "{:?} placed face-down in player {}'s {}",
+                card, player, zone
+            ),
+            CardEvent::Resolve { card } => write!(f, "{:?} resolved", card),
+            CardEvent::Counter { card } => write!(f, "{:?} countered", card),
+            CardEvent::PeekCard { card, instance } => {
+                write!(f, "Peeked at {:?}: {:?}", card, instance)
+            }
+            CardEvent::SwapCards { cards } => write!(f, "{:?} and {:?} swapped", cards.0, cards.1),
+            CardEvent::SwapAttachments { a, b, .. } => {
+                write!(f, "Attachments of {:?} and {:?} swapped", a, b)
+            }
+            CardEvent::DestroyCard { id } => write!(f, "{:?} destroyed", id),
+            CardEvent::FieldPosition { player, id, index } => {
+                write!(f, "{:?} placed at player {}'s field {}", id, player, index)
+            }
+            CardEvent::RandomOutcome { sides, outcome } => {
+                write!(f, "Rolled {} on a {}-sided die", outcome, sides)
+            }
+            CardEvent::RevealProjection {
+                card,
+                projection,
+                value,
+            } => write!(f, "{:?}'s {:?} revealed: {:?}", card, projection, value),
+            CardEvent::Commit { player, key } => {
+                write!(f, "Player {} committed to {:?}", player, key)
+            }
+            CardEvent::RevealCommitment { player, key } => {
+                write!(f, "Player {}'s {:?} commitment revealed", player, key)
+            }
+            CardEvent::PhaseChange { turn } => write!(
+                f,
+                "Player {}'s turn {}: {:?}",
+                turn.player, turn.number, turn.phase
+            ),
+            CardEvent::AddModifier { modifier } => write!(
+                f,
+                "{:?} added to {:?} from {:?} on layer {}: {:?}",
+                modifier.id, modifier.target, modifier.source, modifier.layer, modifier.modifier
+            ),
+            CardEvent::RemoveModifier { id } => write!(f, "{:?} removed", id),
+            CardEvent::ZoneVersion {
+                player,
+                zone,
+                version,
+            } => write!(f, "Player {}'s {} at version {}", player, zone, version),
+            CardEvent::LimboNotEmpty { player } => {
+                write!(f, "Player {}'s limbo is not empty", player)
+            }
+            CardEvent::ActionAborted => write!(f, "Action aborted"),
+            CardEvent::GameOver { result } => write!(f, "Game over: {}", result),
+            CardEvent::NextGame {
+                wins,
+                draws,
+                first_player,
+            } => write!(
+                f,
+                "Next game at {} with {} draws, player {} goes first",
+                wins.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("-"),
+                draws,
+                first_player
+            ),
+            CardEvent::Checksum { checksum } => write!(f, "Checksum {:016x}", checksum),
+            CardEvent::Prompt { prompt } => write!(
+                f,
+                "Player {} prompted for {:?}: {:?}",
+                prompt.player(),
+                prompt.key(),
+                prompt.choice()
+            ),
+            CardEvent::PromptResponse { player, response } => {
+                write!(f, "Player {} responded {:?}", player, response)
+            }
+            CardEvent::ResourceChanged {
+                player,
+                kind,
+                delta,
+                value,
+            } => write!(
+                f,
+                "Player {}'s {:?} changed by {} to {}",
+                player, kind, delta, value
+            ),
+            CardEvent::ActionResolved { summary } => write!(
+                f,
+                "Action resolved: {} moved, {} created, {} revealed, {} modified",
+                summary.moved, summary.created, summary.revealed, summary.modified
+            ),
             CardEvent::GameEvent { .. } => write!(f, "Game Event"),
+            CardEvent::Sequenced { sequence, event } => write!(f, "[{}] {}", sequence, event),
+            CardEvent::Correlated { correlation, event } => {
+                write!(f, "[{}] {}", correlation, event)
+            }
+            CardEvent::Secret { player, event, .. } => {
+                write!(f, "[player {}'s secret] {}", player, event)
+            }
+            CardEvent::InPhase { turn, event } => {
+                write!(f, "[turn {} {:?}] {}", turn.number, turn.phase, event)
+            }
         }
     }
 }
@@ -104,6 +680,22 @@ impl<S: State> PartialEq for CardEvent<S> {
                     location: other_location,
                 },
             ) => pointer == other_pointer && location == other_location,
+            (
+                Self::NewCard {
+                    card,
+                    instance,
+                    location,
+                },
+                Self::NewCard {
+                    card: other_card,
+                    instance: other_instance,
+                    location: other_location,
+                },
+            ) => {
+                card.eq(*other_card).unwrap_or(false)
+                    && instance == other_instance
+                    && location == other_location
+            }
             (
                 Self::ModifyCard { instance },
                 Self::ModifyCard {
@@ -111,13 +703,63 @@ impl<S: State> PartialEq for CardEvent<S> {
                 },
             ) => instance == other_instance,
             (
-                Self::MoveCard { instance, from, to },
+                Self::ModifyCardDelta { id, delta },
+                Self::ModifyCardDelta {
+                    id: other_id,
+                    delta: other_delta,
+                },
+            ) => id == other_id && delta == other_delta,
+            (
+                Self::ModifyCards { events },
+                Self::ModifyCards {
+                    events: other_events,
+                },
+            ) => events == other_events,
+            (
+                Self::MoveCard {
+                    instance,
+                    from,
+                    to,
+                    has_attachment,
+                },
                 Self::MoveCard {
                     instance: other_instance,
                     from: other_from,
                     to: other_to,
+                    has_attachment: other_has_attachment,
+                },
+            ) => {
+                instance == other_instance
+                    && from == other_from
+                    && to == other_to
+                    && has_attachment == other_has_attachment
+            }
+            (
+                Self::CopyCard {
+                    source,
+                    copy,
+                    instance,
+                    deep,
+                },
+                Self::CopyCard {
+                    source: other_source,
+                    copy: other_copy,
+                    instance: other_instance,
+                    deep: other_deep,
+                },
+            ) => {
+                source.eq(*other_source).unwrap_or(false)
+                    && copy.eq(*other_copy).unwrap_or(false)
+                    && instance == other_instance
+                    && deep == other_deep
+            }
+            (
+                Self::TransformCard { card, instance },
+                Self::TransformCard {
+                    card: other_card,
+                    instance: other_instance,
                 },
-            ) => instance == other_instance && from == other_from && to == other_to,
+            ) => card.eq(*other_card).unwrap_or(false) && instance == other_instance,
             (
                 Self::SortField {
                     player,
@@ -130,6 +772,293 @@ impl<S: State> PartialEq for CardEvent<S> {
                     real: other_real,
                 },
             ) => player == other_player && field == other_field && real == other_real,
+            (
+                Self::FieldOrderConflict { player, field },
+                Self::FieldOrderConflict {
+                    player: other_player,
+                    field: other_field,
+                },
+            ) => player == other_player && field == other_field,
+            (
+                Self::ShufflePile { player, pile, ids },
+                Self::ShufflePile {
+                    player: other_player,
+                    pile: other_pile,
+                    ids: other_ids,
+                },
+            ) => player == other_player && pile == other_pile && ids == other_ids,
+            (
+                Self::Sideboard {
+                    player,
+                    pile,
+                    deck,
+                    sideboard,
+                },
+                Self::Sideboard {
+                    player: other_player,
+                    pile: other_pile,
+                    deck: other_deck,
+                    sideboard: other_sideboard,
+                },
+            ) => {
+                player == other_player
+                    && pile == other_pile
+                    && deck == other_deck
+                    && sideboard == other_sideboard
+            }
+            (
+                Self::ReplaceDeck { player, size },
+                Self::ReplaceDeck {
+                    player: other_player,
+                    size: other_size,
+                },
+            ) => player == other_player && size == other_size,
+            (
+                Self::LinkCards { cards, policy },
+                Self::LinkCards {
+                    cards: other_cards,
+                    policy: other_policy,
+                },
+            ) => cards == other_cards && policy == other_policy,
+            (Self::UnlinkCards { cards }, Self::UnlinkCards { cards: other_cards }) => {
+                cards == other_cards
+            }
+            (
+                Self::Cast { player, card },
+                Self::Cast {
+                    player: other_player,
+                    card: other_card,
+                },
+            ) => player == other_player && card.eq(*other_card).unwrap_or(false),
+            (
+                Self::RevealHandCard { player, id, index },
+                Self::RevealHandCard {
+                    player: other_player,
+                    id: other_id,
+                    index: other_index,
+                },
+            ) => player == other_player && id == other_id && index == other_index,
+            (
+                Self::ConcealHandCard { player, id, index },
+                Self::ConcealHandCard {
+                    player: other_player,
+                    id: other_id,
+                    index: other_index,
+                },
+            ) => player == other_player && id == other_id && index == other_index,
+            (
+                Self::FaceDown { player, card, zone },
+                Self::FaceDown {
+                    player: other_player,
+                    card: other_card,
+                    zone: other_zone,
+                },
+            ) => {
+                player == other_player
+                    && card.eq(*other_card).unwrap_or(false)
+                    && zone.eq(*other_zone).unwrap_or(false)
+            }
+            (Self::Resolve { card }, Self::Resolve { card: other_card })
+            | (Self::Counter { card }, Self::Counter { card: other_card }) => {
+                card.eq(*other_card).unwrap_or(false)
+            }
+            (
+                Self::PeekCard { card, instance },
+                Self::PeekCard {
+                    card: other_card,
+                    instance: other_instance,
+                },
+            ) => card.eq(*other_card).unwrap_or(false) && instance == other_instance,
+            (Self::SwapCards { cards }, Self::SwapCards { cards: other_cards }) => {
+                cards.0.eq(other_cards.0).unwrap_or(false)
+                    && cards.1.eq(other_cards.1).unwrap_or(false)
+            }
+            (
+                Self::SwapAttachments { a, b, instances },
+                Self::SwapAttachments {
+                    a: other_a,
+                    b: other_b,
+                    instances: other_instances,
+                },
+            ) => {
+                a.eq(*other_a).unwrap_or(false)
+                    && b.eq(*other_b).unwrap_or(false)
+                    && instances == other_instances
+            }
+            (Self::DestroyCard { id }, Self::DestroyCard { id: other_id }) => id == other_id,
+            (
+                Self::FieldPosition { player, id, index },
+                Self::FieldPosition {
+                    player: other_player,
+                    id: other_id,
+                    index: other_index,
+                },
+            ) => player == other_player && id == other_id && index == other_index,
+            (
+                Self::RandomOutcome { sides, outcome },
+                Self::RandomOutcome {
+                    sides: other_sides,
+                    outcome: other_outcome,
+                },
+            ) => sides == other_sides && outcome == other_outcome,
+            (
+                Self::RevealProjection {
+                    card,
+                    projection,
+                    value,
+                },
+                Self::RevealProjection {
+                    card: other_card,
+                    projection: other_projection,
+                    value: other_value,
+                },
+            ) => {
+                card.eq(*other_card).unwrap_or(false)
+                    && projection == other_projection
+                    && value == other_value
+            }
+            (
+                Self::Commit { player, key },
+                Self::Commit {
+                    player: other_player,
+                    key: other_key,
+                },
+            )
+            | (
+                Self::RevealCommitment { player, key },
+                Self::RevealCommitment {
+                    player: other_player,
+                    key: other_key,
+                },
+            ) => player == other_player && key == other_key,
+            (Self::PhaseChange { turn }, Self::PhaseChange { turn: other_turn }) => {
+                turn == other_turn
+            }
+            (
+                Self::AddModifier { modifier },
+                Self::AddModifier {
+                    modifier: other_modifier,
+                },
+            ) => modifier == other_modifier,
+            (Self::RemoveModifier { id }, Self::RemoveModifier { id: other_id }) => id == other_id,
+            (
+                Self::ZoneVersion {
+                    player,
+                    zone,
+                    version,
+                },
+                Self::ZoneVersion {
+                    player: other_player,
+                    zone: other_zone,
+                    version: other_version,
+                },
+            ) => {
+                player == other_player
+                    && zone.eq(*other_zone).unwrap_or(false)
+                    && version == other_version
+            }
+            (
+                Self::LimboNotEmpty { player },
+                Self::LimboNotEmpty {
+                    player: other_player,
+                },
+            ) => player == other_player,
+            (Self::ActionAborted, Self::ActionAborted) => true,
+            (
+                Self::GameOver { result },
+                Self::GameOver {
+                    result: other_result,
+                },
+            ) => result == other_result,
+            (
+                Self::NextGame {
+                    wins,
+                    draws,
+                    first_player,
+                },
+                Self::NextGame {
+                    wins: other_wins,
+                    draws: other_draws,
+                    first_player: other_first_player,
+                },
+            ) => wins == other_wins && draws == other_draws && first_player == other_first_player,
+            (
+                Self::Checksum { checksum },
+                Self::Checksum {
+                    checksum: other_checksum,
+                },
+            ) => checksum == other_checksum,
+            (
+                Self::Prompt { prompt },
+                Self::Prompt {
+                    prompt: other_prompt,
+                },
+            ) => prompt == other_prompt,
+            (
+                Self::PromptResponse { player, response },
+                Self::PromptResponse {
+                    player: other_player,
+                    response: other_response,
+                },
+            ) => player == other_player && response == other_response,
+            (
+                Self::ResourceChanged {
+                    player,
+                    kind,
+                    delta,
+                    value,
+                },
+                Self::ResourceChanged {
+                    player: other_player,
+                    kind: other_kind,
+                    delta: other_delta,
+                    value: other_value,
+                },
+            ) => {
+                player == other_player
+                    && kind == other_kind
+                    && delta == other_delta
+                    && value == other_value
+            }
+            (
+                Self::ActionResolved { summary },
+                Self::ActionResolved {
+                    summary: other_summary,
+                },
+            ) => summary == other_summary,
+            (
+                Self::Sequenced { sequence, event },
+                Self::Sequenced {
+                    sequence: other_sequence,
+                    event: other_event,
+                },
+            ) => sequence == other_sequence && event == other_event,
+            (
+                Self::Correlated { correlation, event },
+                Self::Correlated {
+                    correlation: other_correlation,
+                    event: other_event,
+                },
+            ) => correlation == other_correlation && event == other_event,
+            (
+                Self::Secret {
+                    player,
+                    event,
+                    public,
+                },
+                Self::Secret {
+                    player: other_player,
+                    event: other_event,
+                    public: other_public,
+                },
+            ) => player == other_player && event == other_event && public == other_public,
+            (
+                Self::InPhase { turn, event },
+                Self::InPhase {
+                    turn: other_turn,
+                    event: other_event,
+                },
+            ) => turn == other_turn && event == other_event,
             _ => false,
         }
     }
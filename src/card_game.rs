@@ -1,17 +1,27 @@
 use {
     crate::{
-        error, BaseCard, Card, CardEvent, CardInstance, CardLocation, CardState, Context,
-        ExactCardLocation, GameState, InstanceID, InstanceOrPlayer, OpaquePointer, Player, Secret,
-        State, Zone,
+        card_modifier::Inverse,
+        error,
+        secret_mutations::{SecretMutation, SecretMutations},
+        trace::traced,
+        ActionMetrics, ActionSummary, BaseCard, Bucket, Card, CardEvent, CardInstance, CardLink,
+        CardLocation, CardModifier, CardState, Census, Choice, Context, ExactCardLocation,
+        FaceDownCard, GameContext, GameResult, GameState, InstanceID, InstanceOrPlayer,
+        KnownProjection, LimboPolicy, LinkPolicy, ModifierID, ModifierTarget, OpaquePointer,
+        PileID, Player, PlayerSecret, Prompt, PromptResponse, RedactedAttachmentPolicy, Secret,
+        State, TargetRequirement, TokenPolicy, Turn, Zone, ZoneSet, ZoneSizes,
+    },
+    rand::{
+        seq::{IteratorRandom, SliceRandom},
+        Rng, RngCore,
     },
-    rand::seq::IteratorRandom,
     std::{
         cmp::Ordering,
         convert::TryInto,
         future::Future,
-        iter::repeat,
         ops::{Deref, DerefMut},
         pin::Pin,
+        rc::Rc,
     },
 };
 
@@ -22,10 +32,34 @@ use crate::AnySecretData;
 
 type AttachCardResult = Result<(CardLocation, Option<InstanceID>), error::MoveCardError>;
 
+/// A card and its attachment, if they're visible.
+type InstanceView<S> = Option<(CardInstance<S>, Option<CardInstance<S>>)>;
+
 pub struct CardGame<S: State> {
     pub state: GameState<S>,
 
-    pub context: Context<S>,
+    pub context: GameContext<S>,
+
+    pending_hooks: Vec<StateHook>,
+
+    in_operation: bool,
+
+    running_hooks: bool,
+
+    acting_player: Option<Player>,
+
+    /// The counts of the current action so far, see `CardGame::action_summary`.
+    summary: ActionSummary,
+
+    /// The number of instance IDs at the start of the action.
+    instances_before: usize,
+}
+
+/// An async [State] callback waiting for the current operation to finish.
+enum StateHook {
+    Attach { parent: Card, attachment: Card },
+    Detach { parent: Card, attachment: Card },
+    SortField { player: Player },
 }
 
 impl<S: State> Deref for CardGame<S> {
@@ -43,38 +77,193 @@ impl<S: State> DerefMut for CardGame<S> {
 }
 
 impl<S: State> CardGame<S> {
-    pub fn new(state: GameState<S>, context: Context<S>) -> Self {
-        Self { state, context }
+    /// Only `GameState` creates card games, so the settings copied from the state here are written back by `CardGame::into_parts`.
+    pub(crate) fn new(state: GameState<S>, context: Context<S>) -> Self {
+        let mut context = GameContext::new(
+            context,
+            state.next_event_step,
+            state.config.coalesce_events,
+            state.next_action,
+            state.config.spectator_events,
+            state.reveals.is_some(),
+            state.players(),
+        );
+        context.set_measuring(state.measure_reveals());
+        context.set_turn(state.turn.clone());
+        context.set_transcriber(state.transcriber.clone());
+
+        let instances_before = state.instances.len();
+
+        Self {
+            state,
+            context,
+            pending_hooks: Default::default(),
+            in_operation: false,
+            running_hooks: false,
+            acting_player: None,
+            summary: Default::default(),
+            instances_before,
+        }
+    }
+
+    /// What the current action has done so far.
+    ///
+    /// The key is always `None`, see `State::action_key`.
+    pub fn action_summary(&self) -> ActionSummary {
+        ActionSummary {
+            created: self.instances.len() - self.instances_before,
+            revealed: self.context.metrics().reveals,
+            ..self.summary.clone()
+        }
+    }
+
+    /// How many reveals and secret mutations the current action has performed so far, see `GameState::last_action_metrics`.
+    pub fn action_metrics(&self) -> ActionMetrics {
+        self.context.metrics()
+    }
+
+    /// The player whose action is being applied, if any.
+    pub fn acting_player(&self) -> Option<Player> {
+        self.acting_player
+    }
+
+    /// Changes the player that subsequent operations are authorized for.
+    ///
+    /// While `MatchConfig::authorize_moves` is enabled, moves touching any other player's secret need `State::authorize_move`'s approval.
+    /// `None` authorizes every move.
+    pub fn set_acting_player(&mut self, player: Option<Player>) {
+        self.acting_player = player;
+    }
+
+    /// Enables or disables strict event ordering, starting with the next logged event.
+    ///
+    /// While enabled, every event is wrapped in a `CardEvent::Sequenced` with a global `EventSequence`.
+    pub fn set_strict_event_ordering(&mut self, enabled: bool) {
+        self.state.next_event_step = self.context.next_step;
+        self.state.set_strict_event_ordering(enabled);
+        self.context.next_step = self.state.next_event_step;
+    }
+
+    /// Enables or disables action correlation, starting with the next logged event.
+    ///
+    /// While enabled, every event is wrapped in a `CardEvent::Correlated` identifying the current action.
+    pub fn set_action_correlation(&mut self, enabled: bool) {
+        self.state.set_action_correlation(enabled);
+        self.context.set_action(self.state.next_action);
     }
 
-    pub async fn new_card(&mut self, player: Player, base: S::BaseCard, attachment: Option<S::BaseCard>) -> InstanceID {
-        let id = InstanceID(self.instances.len());
+    /// Enables or disables coalescing of redundant public events, starting with the next logged event.
+    ///
+    /// While enabled, public events are held until the end of the action, or until a secret mutation could log them.
+    /// A `CardEvent::SortField` replaces an immediately preceding one for the same player, and a
+    /// `CardEvent::ModifyCard` replaces the modifications of the same card immediately preceding it.
+    pub fn set_coalesce_events(&mut self, enabled: bool) {
+        self.state.set_coalesce_events(enabled);
+        self.context.set_coalescing(enabled);
+    }
+
+    /// Enables or disables spectator events, starting with the next logged event.
+    ///
+    /// While enabled, events logged from a player's secret are wrapped in a `CardEvent::Secret`,
+    /// so that `CardEvent::redact_for_spectator` can derive a spectator's view from any player's log.
+    pub fn set_spectator_events(&mut self, enabled: bool) {
+        self.state.set_spectator_events(enabled);
+        self.context.set_spectator(enabled);
+    }
+
+    /// Enables or disables measuring `ActionMetrics::revealed_bytes`, starting with the next reveal.
+    ///
+    /// While enabled, every revealed value is serialized once more to measure it.
+    pub fn set_measure_reveals(&mut self, enabled: bool) {
+        self.state.set_measure_reveals(enabled);
+        self.context.set_measuring(enabled);
+    }
+
+    /// Enables or disables recording of the values revealed during this action, see `GameState::set_reveal_recording`.
+    ///
+    /// Enabling it partway through an action only records the reveals from then on.
+    pub fn set_reveal_recording(&mut self, enabled: bool) {
+        self.state.set_reveal_recording(enabled);
+        self.context.set_reveal_recording(enabled);
+    }
+
+    /// Unpacks the game, saving the event ordering, action correlation, recorded reveals and action metrics back into the game state.
+    pub(crate) fn into_parts(self) -> (GameState<S>, Context<S>) {
+        let Self {
+            mut state, context, ..
+        } = self;
+
+        state.next_event_step = context.next_step;
+        state.reveals = context.reveals();
+        state.last_action_metrics = context.metrics();
+
+        if let Some(next_action) = &mut state.next_action {
+            *next_action += 1;
+        }
+
+        (state, context.into_inner())
+    }
+
+    /// Creates a new public card, if the card database accepts the card and its attachment.
+    ///
+    /// Fails with `IllegalCardError::GameOver` once the game is over.
+    pub async fn new_card(
+        &mut self,
+        player: Player,
+        base: S::BaseCard,
+        attachment: Option<S::BaseCard>,
+    ) -> Result<InstanceID, error::IllegalCardError> {
+        if let Some(result) = self.result() {
+            return Err(error::IllegalCardError::GameOver { result });
+        }
+
+        self.validate_card(&base)?;
+
+        if let Some(attachment) = &attachment {
+            self.validate_card(attachment)?;
+        }
+
+        Ok(self.new_public_card(player, base, attachment).await)
+    }
+
+    /// Creates a new public card, without checking the card database, e.g. for attachments provided by the game's state.
+    async fn new_public_card(
+        &mut self,
+        player: Player,
+        base: S::BaseCard,
+        attachment: Option<S::BaseCard>,
+    ) -> InstanceID {
+        let id = self.new_public_id();
         let state = base.new_card_state(None);
         let instance: CardInstance<S> = CardInstance {
             id,
             base: base.clone(),
             attachment: None,
             state,
+            token: false,
+            original_owner: player,
         };
 
-        self.instances
-            .push(InstanceOrPlayer::from(instance.clone()));
+        self.insert_instance(instance.clone());
 
         self.player_cards_mut(player).limbo.push(id);
+        self.bump_zone_version(player, Zone::Limbo { public: true });
 
         if let Some(attach_base) = attachment {
-            let attach_id = InstanceID(self.instances.len());
+            let attach_id = self.new_public_id();
             let state = attach_base.new_card_state(Some(&instance.state));
             let instance: CardInstance<S> = CardInstance {
                 id: attach_id,
                 base: attach_base.clone(),
                 attachment: None,
                 state,
+                token: false,
+                original_owner: player,
             };
 
-            self.instances
-                .push(InstanceOrPlayer::from(instance.clone()));
+            self.insert_instance(instance);
             self.player_cards_mut(player).limbo.push(attach_id);
+            self.bump_zone_version(player, Zone::Limbo { public: true });
 
             self.move_card(attach_id, player, Zone::Attachment { parent: id.into() })
                 .await
@@ -83,2549 +272,7098 @@ impl<S: State> CardGame<S> {
         id
     }
 
-    pub fn deck_card(&mut self, player: Player, index: usize) -> Card {
-        self.context.mutate_secret(player, |mut secret| {
-            let pointer = secret.deck()[index];
-            secret.pointers.push(pointer);
-        });
+    /// Creates a new card and its attachment directly in a zone, public or secret depending on the zone.
+    ///
+    /// Nothing passes through limbo: a single `CardEvent::NewCard` is logged for the zone,
+    /// with the card's instance for the players who can see it.
+    /// A card created as an attachment belongs to its parent's owner, and is secret if its parent is.
+    /// The parent's existing attachment is dusted first, as by `CardGame::attach`.
+    ///
+    /// Fails with `MoveCardError::IllegalCard` without creating anything if the card database rejects either card.
+    pub async fn new_card_in(
+        &mut self,
+        player: Player,
+        base: S::BaseCard,
+        attachment: Option<S::BaseCard>,
+        zone: Zone,
+    ) -> Result<Card, error::MoveCardError> {
+        self.check_in_progress()?;
 
-        let player_cards = self.player_cards_mut(player);
+        self.validate_card(&base)?;
 
-        player_cards.pointers += 1;
+        if let Some(attachment) = &attachment {
+            self.validate_card(attachment)?;
+        }
 
-        let pointer = OpaquePointer {
-            player,
-            index: player_cards.pointers - 1,
+        if let Zone::Attachment { parent } = zone {
+            if attachment.is_some() {
+                return Err(error::MoveCardError::NestedAttachment { parent });
+            }
+
+            return self.new_attachment(base, parent).await;
+        }
+
+        self.check_zone_capacity(player, zone)?;
+
+        let card = if zone.is_public().unwrap() {
+            self.new_public_card_in(player, base, attachment, zone)
+        } else {
+            self.new_secret_card_in(player, base, attachment, zone)
         };
 
-        self.context.log(CardEvent::NewPointer {
-            pointer,
-            location: ExactCardLocation {
-                player,
-                location: (Zone::Deck, index),
-            },
-        });
+        self.bump_zone_version(player, zone);
 
-        pointer.into()
+        self.run_hooks().await;
+
+        Ok(card)
     }
 
-    pub fn hand_card(&mut self, player: Player, index: usize) -> Card {
-        match self.player_cards(player).hand()[index] {
-            Some(id) => id.into(),
-            None => {
-                self.context.mutate_secret(player, |mut secret| {
-                    let pointer = secret.hand()[index].unwrap_or_else(|| {
-                        panic!(
-                            "player {} hand {} is neither public nor secret",
-                            player, index
-                        )
-                    });
-                    secret.pointers.push(pointer);
-                });
+    /// Creates a public card and its attachment in a public zone other than an attachment, see `CardGame::new_card_in`.
+    ///
+    /// Queues `State::after_attach` for the attachment, if there is one.
+    fn new_public_card_in(
+        &mut self,
+        player: Player,
+        base: S::BaseCard,
+        attachment: Option<S::BaseCard>,
+        zone: Zone,
+    ) -> Card {
+        let id = self.new_public_id();
+        let mut instance = CardInstance {
+            id,
+            state: base.new_card_state(None),
+            base,
+            attachment: None,
+            token: false,
+            original_owner: player,
+        };
 
-                let player_cards = self.player_cards_mut(player);
+        // The card takes its ID before its attachment does, so they can't get the same ID.
+        self.insert_instance(instance.clone());
 
-                player_cards.pointers += 1;
+        let attachment = attachment.map(|attachment| {
+            let attachment = CardInstance {
+                id: self.new_public_id(),
+                state: attachment.new_card_state(Some(&instance.state)),
+                base: attachment,
+                attachment: None,
+                token: false,
+                original_owner: player,
+            };
 
-                let pointer = OpaquePointer {
-                    player,
-                    index: player_cards.pointers - 1,
-                };
+            instance.attachment = Some(attachment.id);
+            S::on_attach(&mut instance, &attachment);
 
-                self.context.log(CardEvent::NewPointer {
-                    pointer,
-                    location: ExactCardLocation {
-                        player,
-                        location: (Zone::Hand { public: false }, index),
-                    },
-                });
+            attachment
+        });
 
-                pointer.into()
+        // Without sorting, the card goes at the end of the field.
+        let field_index = zone.is_field().then(|| {
+            if self.config.positioned_field {
+                self.player_cards(player).field.len()
+            } else {
+                self.field_index(player, &instance, attachment.as_ref())
             }
-        }
-    }
-
-    pub fn field_card(&self, player: Player, index: usize) -> InstanceID {
-        self.player_cards(player).field()[index]
-    }
+        });
 
-    pub fn graveyard_card(&self, player: Player, index: usize) -> InstanceID {
-        self.player_cards(player).graveyard()[index]
-    }
+        self.insert_instance(instance.clone());
 
-    pub fn public_dust_card(&self, player: Player, index: usize) -> InstanceID {
-        self.player_cards(player).dust()[index]
-    }
+        if let Some(attachment) = &attachment {
+            self.insert_instance(attachment.clone());
 
-    pub fn secret_dust_card(&mut self, player: Player, index: usize) -> Card {
-        self.context.mutate_secret(player, |mut secret| {
-            let pointer = secret.dust()[index];
-            secret.pointers.push(pointer);
-        });
+            self.queue_hook(StateHook::Attach {
+                parent: id.into(),
+                attachment: attachment.id.into(),
+            });
+        }
 
+        let old_field = self.player_cards(player).field.clone();
         let player_cards = self.player_cards_mut(player);
 
-        player_cards.pointers += 1;
-
-        let pointer = OpaquePointer {
-            player,
-            index: player_cards.pointers - 1,
-        };
+        let index = match zone {
+            Zone::Hand { public: true } => {
+                let index = player_cards.hand.len();
 
-        self.context.log(CardEvent::NewPointer {
-            pointer,
-            location: ExactCardLocation {
-                player,
-                location: (Zone::Dust { public: false }, index),
-            },
-        });
+                player_cards.hand.push(Some(id));
 
-        pointer.into()
-    }
+                self.context.mutate_secret(player, move |mut secret| {
+                    secret.hand.insert(index, None);
+                });
 
-    pub fn public_limbo_card(&self, player: Player, index: usize) -> InstanceID {
-        self.player_cards(player).limbo()[index]
-    }
+                index
+            }
+            Zone::Field => {
+                let index = field_index.expect("field_index should be Some when zone is Field");
 
-    pub fn secret_limbo_card(&mut self, player: Player, index: usize) -> Card {
-        self.context.mutate_secret(player, |mut secret| {
-            let pointer = secret.limbo()[index];
-            secret.pointers.push(pointer);
-        });
+                player_cards.field.insert(index, id);
 
-        let player_cards = self.player_cards_mut(player);
+                index
+            }
+            zone => {
+                let cards = match zone {
+                    Zone::Graveyard => &mut player_cards.graveyard,
+                    Zone::Limbo { public: true } => &mut player_cards.limbo,
+                    Zone::Casting => &mut player_cards.casting,
+                    Zone::Dust { public: true } => &mut player_cards.dust,
+                    Zone::HeroAbility => &mut player_cards.hero_ability,
+                    Zone::Boon => &mut player_cards.boon,
+                    zone => unreachable!("{} is not a public zone", zone),
+                };
 
-        player_cards.pointers += 1;
+                cards.push(id);
 
-        let pointer = OpaquePointer {
-            player,
-            index: player_cards.pointers - 1,
+                cards.len() - 1
+            }
         };
 
-        self.context.log(CardEvent::NewPointer {
-            pointer,
+        // As when moving a card to the field, the sort is emitted before the card appears.
+        if zone.is_field() {
+            let mut logs = vec![];
+
+            self.sort_field(player, old_field, false, &mut |event| logs.push(event));
+
+            for event in logs {
+                self.context.log(event);
+            }
+        }
+
+        self.context.log(CardEvent::NewCard {
+            card: id.into(),
+            instance: Some((instance, attachment)),
             location: ExactCardLocation {
                 player,
-                location: (Zone::Limbo { public: false }, index),
+                location: (zone, index),
             },
         });
 
-        pointer.into()
+        id.into()
     }
 
-    pub fn casting_card(&self, player: Player, index: usize) -> InstanceID {
-        self.player_cards(player).casting()[index]
-    }
+    /// Creates a secret card and its attachment in a secret zone, see `CardGame::new_card_in`.
+    ///
+    /// As with `CardGame::new_secret_cards`, an ID is reserved for the attachment even if there isn't one.
+    fn new_secret_card_in(
+        &mut self,
+        player: Player,
+        base: S::BaseCard,
+        attachment: Option<S::BaseCard>,
+        zone: Zone,
+    ) -> Card {
+        let start = self.instances.len();
+        let id = InstanceID(start + 1);
 
-    pub fn card_selection_card(&mut self, player: Player, index: usize) -> Card {
-        self.context.mutate_secret(player, |mut secret| {
-            let pointer = secret.card_selection()[index];
-            secret.pointers.push(pointer);
-        });
+        self.state
+            .reserve_instances(player, padded_count(2, self.config.secret_card_padding));
 
         let player_cards = self.player_cards_mut(player);
 
-        player_cards.pointers += 1;
-
         let pointer = OpaquePointer {
             player,
-            index: player_cards.pointers - 1,
+            index: player_cards.pointers,
         };
 
-        self.context.log(CardEvent::NewPointer {
-            pointer,
-            location: ExactCardLocation {
-                player,
-                location: (Zone::CardSelection, index),
-            },
-        });
+        player_cards.pointers += 1;
 
-        pointer.into()
-    }
+        // The public index, as when moving a card to the zone.
+        let index = match zone {
+            Zone::Deck => {
+                player_cards.deck += 1;
+                player_cards.deck - 1
+            }
+            Zone::Hand { public: false } => {
+                player_cards.hand.push(None);
+                player_cards.hand.len() - 1
+            }
+            Zone::CardSelection => {
+                player_cards.card_selection += 1;
+                0
+            }
+            Zone::Pile { pile } => {
+                player_cards.grow_pile(pile);
+                player_cards.pile(pile) - 1
+            }
+            Zone::Limbo { public: false } | Zone::Dust { public: false } => 0,
+            zone => unreachable!("{} is not a secret zone", zone),
+        };
 
-    pub fn deck_cards(&mut self, player: Player) -> Vec<Card> {
-        self.context.mutate_secret(player, |mut secret| {
-            secret.append_deck_to_pointers();
-        });
+        self.context.mutate_secret_or_log(
+            player,
+            |mut secret| {
+                let mut instance = CardInstance {
+                    id,
+                    state: base.new_card_state(None),
+                    base: base.clone(),
+                    attachment: None,
+                    token: false,
+                    original_owner: player,
+                };
 
-        let player_cards = self.player_cards_mut(player);
+                let attachment = attachment.clone().map(|attachment| {
+                    let attachment = CardInstance {
+                        id: InstanceID(start),
+                        state: attachment.new_card_state(Some(&instance.state)),
+                        base: attachment,
+                        attachment: None,
+                        token: false,
+                        original_owner: player,
+                    };
 
-        player_cards.pointers += player_cards.deck();
+                    instance.attachment = Some(attachment.id);
+                    S::on_attach(&mut instance, &attachment);
 
-        let pointers: Vec<OpaquePointer> = (player_cards.pointers - player_cards.deck()
-            ..player_cards.pointers)
-            .map(|index| OpaquePointer { player, index })
-            .collect();
+                    attachment
+                });
 
-        for (index, pointer) in pointers.iter().enumerate() {
-            self.context.log(CardEvent::NewPointer {
-                pointer: *pointer,
+                let index = match zone {
+                    Zone::Deck => secret.deck.len(),
+                    Zone::Hand { public: false } => secret.hand.len(),
+                    Zone::Limbo { public: false } => secret.limbo.len(),
+                    Zone::CardSelection => secret.card_selection.len(),
+                    Zone::Pile { pile } => secret.pile(pile).len(),
+                    Zone::Dust { public: false } => secret.dust.len(),
+                    zone => unreachable!("{} is not a secret zone", zone),
+                };
+
+                match zone {
+                    Zone::Deck => secret.deck.push(id),
+                    Zone::Hand { public: false } => secret.hand.push(Some(id)),
+                    Zone::Limbo { public: false } => secret.limbo.push(id),
+                    Zone::CardSelection => secret.card_selection.push(id),
+                    Zone::Pile { pile } => secret.push_pile(pile, id),
+                    Zone::Dust { public: false } => secret.dust.push(id),
+                    zone => unreachable!("{} is not a secret zone", zone),
+                }
+
+                secret.instances.insert(id, instance.clone());
+
+                if let Some(attachment) = &attachment {
+                    secret.instances.insert(attachment.id, attachment.clone());
+                }
+
+                secret.pointers.push(id);
+
+                secret.log(CardEvent::NewCard {
+                    card: pointer.into(),
+                    instance: Some((instance, attachment)),
+                    location: ExactCardLocation {
+                        player,
+                        location: (zone, index),
+                    },
+                });
+            },
+            CardEvent::NewCard {
+                card: pointer.into(),
+                instance: None,
                 location: ExactCardLocation {
                     player,
-                    location: (Zone::Deck, index),
+                    location: (zone, index),
                 },
-            });
-        }
+            },
+        );
 
-        pointers.into_iter().map(Into::into).collect()
+        pointer.into()
     }
 
-    pub fn hand_cards(&mut self, player: Player) -> Vec<Card> {
-        self.context.mutate_secret(player, |mut secret| {
-            secret.append_secret_hand_to_pointers();
-        });
-
-        let secret_hand_indices: Vec<usize> = self
-            .player_cards(player)
-            .hand()
-            .iter()
-            .enumerate()
-            .filter(|(_, id)| id.is_none())
-            .map(|(hand_index, _)| hand_index)
-            .collect();
+    /// Creates a card as a parent's attachment, in public state or in the secret holding the parent, see `CardGame::new_card_in`.
+    async fn new_attachment(
+        &mut self,
+        base: S::BaseCard,
+        parent: Card,
+    ) -> Result<Card, error::MoveCardError> {
+        let parent = self.reveal_public_id(parent).await?;
 
-        let num_secret_cards = secret_hand_indices.len();
-        let pointer_offset = self.player_cards(player).pointers;
+        let bucket = match parent {
+            Card::ID(id) => self.instances[id.0].player(),
+            Card::Pointer(OpaquePointer { player, index }) => {
+                let buckets = self.state.buckets();
 
-        self.player_cards_mut(player).pointers += num_secret_cards;
+                self.context
+                    .reveal_unique(
+                        player,
+                        move |secret| Bucket::of(&buckets, secret.pointers[index]).secret(),
+                        |_| true,
+                    )
+                    .await
+            }
+        };
 
-        for (pointer_index, hand_index) in secret_hand_indices.into_iter().enumerate() {
-            self.context.log(CardEvent::NewPointer {
-                pointer: OpaquePointer {
-                    player,
-                    index: pointer_offset + pointer_index,
-                },
-                location: ExactCardLocation {
-                    player,
-                    location: (Zone::Hand { public: false }, hand_index),
-                },
-            });
-        }
+        let zone = Zone::Attachment { parent };
 
-        let mut secret_hand = (self.player_cards(player).pointers - num_secret_cards
-            ..self.player_cards(player).pointers)
-            .map(|index| OpaquePointer { player, index });
+        let (owner, card) = match bucket {
+            None => {
+                let parent_id = parent.id().expect("public parent should have a public ID");
+                let owner = self.owner(parent_id);
+
+                if let Some(old) = self.instances[parent_id.0]
+                    .instance_ref()
+                    .and_then(|parent| parent.attachment)
+                {
+                    self.move_card(old, owner, Zone::Dust { public: true })
+                        .await?;
+                }
 
-        let hand = self
-            .player_cards(player)
-            .hand()
-            .iter()
-            .map(|id| {
-                id.map(Card::from).unwrap_or_else(|| {
-                    secret_hand
-                        .next()
-                        .expect("not enough secret hand cards")
-                        .into()
-                })
-            })
-            .collect();
+                let parent_state = self.instances[parent_id.0]
+                    .instance_ref()
+                    .expect("public parent should be in public state")
+                    .state
+                    .clone();
+
+                let attachment: CardInstance<S> = CardInstance {
+                    id: self.new_public_id(),
+                    state: base.new_card_state(Some(&parent_state)),
+                    base,
+                    attachment: None,
+                    token: false,
+                    original_owner: owner,
+                };
 
-        assert!(secret_hand.next().is_none());
+                self.insert_instance(attachment.clone());
 
-        hand
-    }
+                let id = attachment.id;
+                let mut logs = vec![];
 
-    pub fn field_cards(&self, player: Player) -> &Vec<InstanceID> {
-        self.player_cards(player).field()
-    }
+                self.context.log(CardEvent::NewCard {
+                    card: id.into(),
+                    instance: Some((attachment.clone(), None)),
+                    location: ExactCardLocation {
+                        player: owner,
+                        location: (zone, 0),
+                    },
+                });
 
-    pub fn graveyard_cards(&self, player: Player) -> &Vec<InstanceID> {
-        self.player_cards(player).graveyard()
-    }
+                self.modify_card_internal(
+                    parent,
+                    |parent, _| {
+                        parent.attachment = Some(id);
+                        S::on_attach(parent, &attachment);
+                    },
+                    &mut |event| logs.push(event),
+                )
+                .await;
 
-    pub fn public_dust_cards(&self, player: Player) -> &Vec<InstanceID> {
-        self.player_cards(player).dust()
-    }
+                for event in logs {
+                    self.context.log(event);
+                }
 
-    /// This reveals the number of cards in a player's secret dust.
-    pub async fn secret_dust_cards(&mut self, player: Player) -> Vec<Card> {
-        self.new_secret_pointers(player, |mut secret| {
-            secret
-                .dust()
-                .clone()
-                .into_iter()
-                .for_each(|id| secret.new_pointer(id));
-        })
-        .await
-    }
+                (owner, Card::from(id))
+            }
+            Some(owner) => {
+                // The parent's ID is only revealed if it's in another player's secret than the pointer's.
+                let parent_id = match parent {
+                    Card::Pointer(OpaquePointer { player, index }) if player != owner => Some(
+                        self.context
+                            .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
+                            .await,
+                    ),
+                    Card::Pointer(..) => None,
+                    Card::ID(id) => Some(id),
+                };
 
-    pub fn public_limbo_cards(&self, player: Player) -> &Vec<InstanceID> {
-        self.player_cards(player).limbo()
-    }
+                let id = InstanceID(self.instances.len());
 
-    /// This reveals the number of cards in a player's secret limbo.
-    pub async fn secret_limbo_cards(&mut self, player: Player) -> Vec<Card> {
-        self.new_secret_pointers(player, |mut secret| {
-            secret
-                .limbo()
-                .clone()
-                .into_iter()
-                .for_each(|id| secret.new_pointer(id));
-        })
-        .await
-    }
+                self.state
+                    .reserve_instances(owner, padded_count(1, self.config.secret_card_padding));
 
-    pub fn casting_cards(&self, player: Player) -> &Vec<InstanceID> {
-        self.player_cards(player).casting()
-    }
+                let player_cards = self.player_cards_mut(owner);
 
-    pub fn card_selection_cards(&mut self, player: Player) -> Vec<Card> {
-        self.context.mutate_secret(player, |mut secret| {
-            secret.append_card_selection_to_pointers();
-        });
+                let pointer = OpaquePointer {
+                    player: owner,
+                    index: player_cards.pointers,
+                };
 
-        let num_hand_selection = self.player_cards(player).card_selection();
+                player_cards.pointers += 1;
 
-        self.player_cards_mut(player).pointers += num_hand_selection;
+                self.context.mutate_secret_or_log(
+                    owner,
+                    |mut secret| {
+                        let parent_id = parent_id
+                            .unwrap_or_else(|| secret.pointers[parent.pointer().unwrap().index]);
 
-        let start_ptr =
-            self.player_cards(player).pointers - self.player_cards(player).card_selection();
-        let end_ptr = self.player_cards(player).pointers;
+                        if let Some(old) = secret.attachment_of(parent_id) {
+                            secret
+                                .secret
+                                .dust_card(old, secret.log)
+                                .expect("the parent's attachment should be in its secret");
+                        }
 
-        for (zone_index, pointer_index) in (start_ptr..end_ptr).enumerate() {
-            self.context.log(CardEvent::NewPointer {
-                pointer: OpaquePointer {
-                    player,
-                    index: pointer_index,
-                },
-                location: ExactCardLocation {
-                    player,
-                    location: (Zone::CardSelection, zone_index),
-                },
-            });
-        }
-        (start_ptr..end_ptr)
-            .map(|index| OpaquePointer { player, index }.into())
-            .collect()
-    }
+                        let attachment: CardInstance<S> = CardInstance {
+                            id,
+                            state: base
+                                .new_card_state(Some(&secret.instance(parent_id).unwrap().state)),
+                            base: base.clone(),
+                            attachment: None,
+                            token: false,
+                            original_owner: owner,
+                        };
 
-    pub async fn reveal_if_cards_eq(&mut self, a: impl Into<Card>, b: impl Into<Card>) -> bool {
-        let a = a.into();
-        let b = b.into();
+                        secret.instances.insert(id, attachment.clone());
+                        secret.pointers.push(id);
 
-        if let Ok(result) = a.eq(b) {
-            return result;
-        }
+                        secret.log(CardEvent::NewCard {
+                            card: pointer.into(),
+                            instance: Some((attachment.clone(), None)),
+                            location: ExactCardLocation {
+                                player: owner,
+                                location: (
+                                    Zone::Attachment {
+                                        parent: parent_id.into(),
+                                    },
+                                    0,
+                                ),
+                            },
+                        });
 
-        if let Card::Pointer(OpaquePointer {
-            player: a_player,
-            index: a_index,
-        }) = a
-        {
-            if let Card::Pointer(OpaquePointer {
-                player: b_player,
-                index: b_index,
-            }) = b
-            {
-                if a_player == b_player {
-                    return self
-                        .context
-                        .reveal_unique(
-                            a_player,
-                            move |secret| secret.pointers[a_index] == secret.pointers[b_index],
-                            |_| true,
-                        )
-                        .await;
-                }
-            }
-        }
+                        secret
+                            .secret
+                            .modify_card_internal(parent_id, secret.log, |parent, _| {
+                                parent.attachment = Some(id);
+                                S::on_attach(parent, &attachment);
+                            });
+                    },
+                    CardEvent::NewCard {
+                        card: pointer.into(),
+                        instance: None,
+                        location: ExactCardLocation {
+                            player: owner,
+                            location: (zone, 0),
+                        },
+                    },
+                );
 
-        let a = match a {
-            Card::ID(id) => id,
-            Card::Pointer(OpaquePointer { player, index }) => {
-                self.context
-                    .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
-                    .await
+                (owner, Card::from(pointer))
             }
         };
 
-        let b = match b {
-            Card::ID(id) => id,
-            Card::Pointer(OpaquePointer { player, index }) => {
-                self.context
-                    .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
-                    .await
-            }
-        };
+        self.queue_hook(StateHook::Attach {
+            parent,
+            attachment: card,
+        });
 
-        a == b
-    }
+        self.bump_zone_version(owner, zone);
 
-    pub async fn reveal_if_cards_ne(&mut self, a: impl Into<Card>, b: impl Into<Card>) -> bool {
-        !self.reveal_if_cards_eq(a, b).await
+        self.run_hooks().await;
+
+        Ok(card)
     }
 
-    pub async fn reveal_if_any(
+    /// Creates a new public token, like `CardGame::new_card`.
+    ///
+    /// Tokens and their copies are destroyed when they leave the field, see `MatchConfig::token_policy`.
+    pub async fn new_token(
         &mut self,
-        cards: Vec<Card>,
-        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
-    ) -> bool {
-        // todo!(): betterize this implementation
+        player: Player,
+        base: S::BaseCard,
+        attachment: Option<S::BaseCard>,
+    ) -> Result<InstanceID, error::IllegalCardError> {
+        let id = self.new_card(player, base, attachment).await?;
 
-        self.reveal_from_cards_fold(cards, f, false, move |acc, c| acc || *c)
-            .await
+        self.instances[id.0]
+            .instance_mut()
+            .expect("new card is not public")
+            .token = true;
+
+        Ok(id)
     }
 
-    pub async fn reveal_if_every(
-        &mut self,
-        cards: Vec<Card>,
-        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
-    ) -> bool {
-        !self.reveal_if_any(cards, move |card| !f(card)).await
+    /// A new public ID, reusing the ID of a destroyed card if there is one, see `CardGame::destroy_instance`.
+    fn new_public_id(&mut self) -> InstanceID {
+        self.state
+            .destroyed
+            .pop()
+            .unwrap_or(InstanceID(self.instances.len()))
     }
 
-    pub async fn reveal_from_card<T: AnySecretData>(
+    fn insert_instance(&mut self, instance: CardInstance<S>) {
+        let id = instance.id;
+
+        if id.0 < self.instances.len() {
+            self.state.set_instance(id, instance);
+        } else {
+            self.state.push_instance(instance);
+        }
+    }
+
+    /// Permanently destroys a card and its attachment, e.g. a token that ceases to exist.
+    ///
+    /// The card is dusted first, publicly if it's public and secretly otherwise,
+    /// so links and modifiers are handled as for any dusted card.
+    /// A public card is then removed from its owner's dust, and a `CardEvent::DestroyCard` is logged for it and its attachment.
+    /// A secret card is removed from its owner's secret dust inside the secret, and only its owner is told which IDs were destroyed,
+    /// unless the card's ID was already public.
+    ///
+    /// Returns the card's ID if it's known publicly.
+    /// Pointers to destroyed cards are invalidated, so moving or revealing from them fails.
+    /// Publicly known destroyed IDs are reused by new public cards, so references to a destroyed card shouldn't outlive it.
+    pub async fn destroy_instance(
         &mut self,
         card: impl Into<Card>,
-        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
-    ) -> T {
-        let card = card.into();
+    ) -> Result<Option<InstanceID>, error::MoveCardError> {
+        self.check_in_progress()?;
 
-        match card {
-            Card::ID(id) => match &self.instances[id.0] {
-                InstanceOrPlayer::Instance(instance) => {
-                    let CardLocation {
-                        player: owner,
-                        location,
-                    } = self.location(id);
-                    let location =
-                        location.unwrap_or_else(|| panic!("public {:?} has no zone", id));
+        // A pointer to a public card is destroyed as the public card.
+        let card = self.reveal_public_id(card.into()).await?;
 
-                    let attachment = instance.attachment().map(|attachment| {
-                        self.instances[attachment.0]
-                            .instance_ref()
-                            .unwrap_or_else(|| {
-                                panic!("public {:?} attachment {:?} not public", id, attachment)
-                            })
-                    });
+        let owner = self
+            .reveal_owner(card)
+            .await
+            .ok_or(error::MoveCardError::CardDoesNotExist { card })?;
 
-                    f(CardInfo {
-                        instance,
-                        owner,
-                        zone: location.0,
-                        attachment,
-                    })
-                }
-                InstanceOrPlayer::Player(owner) => {
-                    let owner = *owner;
+        let public = matches!(
+            card,
+            Card::ID(id) if matches!(self.instances[id.0], InstanceOrPlayer::Instance(..))
+        );
+
+        self.move_card(card, owner, Zone::Dust { public }).await?;
+
+        let id = if public {
+            let id = card.id().expect("public card has no ID");
+            let ids: Vec<_> = std::iter::once(id).chain(self.attachment_of(id)).collect();
+
+            self.player_cards_mut(owner)
+                .dust
+                .retain(|dusted| !ids.contains(dusted));
+
+            self.invalidate_pointers(&ids);
+
+            for id in ids {
+                self.state
+                    .set_instance(id, InstanceOrPlayer::Destroyed(owner));
+                self.state.inverses.retain(|inverse| inverse.id != id);
+                self.state.destroyed.push(id);
 
+                self.context.log(CardEvent::DestroyCard { id });
+            }
+
+            Some(id)
+        } else {
+            // The ID is only known if it was already revealed, e.g. by moving another player's pointer into the owner's secret.
+            let id = match card {
+                Card::Pointer(OpaquePointer { player, index }) if player != owner => Some(
                     self.context
-                        .reveal_unique(
-                            owner,
-                            move |secret| {
-                                secret.reveal_from_card(id, f.clone()).unwrap_or_else(|| {
-                                    panic!("{:?} not in player {:?} secret", id, owner)
-                                })
-                            },
-                            |_| true,
-                        )
-                        .await
-                }
-            },
-            Card::Pointer(OpaquePointer { player, index }) => {
-                let revealed = self
-                    .context
-                    .reveal_unique(
-                        player,
-                        {
-                            let f = f.clone();
+                        .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
+                        .await,
+                ),
+                Card::Pointer(..) => None,
+                Card::ID(id) => Some(id),
+            };
 
-                            move |secret| {
-                                secret
-                                    .reveal_from_card(secret.pointers[index], |instance| {
-                                        Either::A(f(instance))
-                                    })
-                                    .unwrap_or_else(|| Either::B(secret.pointers[index]))
-                            }
-                        },
-                        |_| true,
-                    )
-                    .await;
+            let destroy = move |mut secret: MutateSecretInfo<'_, S>| {
+                let id = id.unwrap_or_else(|| {
+                    secret
+                        .id(card)
+                        .unwrap_or_else(|| panic!("{:?} not in secret", card))
+                });
+                let attachment = secret
+                    .instance(id)
+                    .unwrap_or_else(|| panic!("{:?} not in secret", card))
+                    .attachment;
+                let ids: Vec<_> = std::iter::once(id).chain(attachment).collect();
 
-                match revealed {
-                    Either::A(result) => result,
-                    Either::B(id) => match &self.instances[id.0] {
-                        InstanceOrPlayer::Instance(instance) => {
-                            let CardLocation {
-                                player: owner,
-                                location,
-                            } = self.location(id);
-                            let location =
-                                location.unwrap_or_else(|| panic!("public {:?} has no zone", id));
-
-                            let attachment = instance.attachment().map(|attachment| {
-                                self.instances[attachment.0]
-                                    .instance_ref()
-                                    .unwrap_or_else(|| {
-                                        panic!(
-                                            "public {:?} attachment {:?} not public",
-                                            id, attachment
-                                        )
-                                    })
-                            });
+                secret.secret.destroy(&ids);
 
-                            f(CardInfo {
-                                instance,
-                                owner,
-                                zone: location.0,
-                                attachment,
-                            })
-                        }
-                        InstanceOrPlayer::Player(owner) => {
-                            let owner = *owner;
+                for id in ids {
+                    secret.log(CardEvent::DestroyCard { id });
+                }
+            };
 
-                            self.context
-                                .reveal_unique(
-                                    owner,
-                                    move |secret| {
-                                        secret.reveal_from_card(id, f.clone()).unwrap_or_else(
-                                            || panic!("{:?} not in player {:?} secret", id, owner),
-                                        )
-                                    },
-                                    |_| true,
-                                )
-                                .await
-                        }
-                    },
+            match id {
+                Some(id) => {
+                    self.context.mutate_secret_or_log(
+                        owner,
+                        destroy,
+                        CardEvent::DestroyCard { id },
+                    );
+
+                    // Other players' pointers can only be invalidated once the ID is public.
+                    self.invalidate_pointers(&[id]);
+                    self.state
+                        .set_instance(id, InstanceOrPlayer::Destroyed(owner));
+                    self.state.destroyed.push(id);
                 }
+                None => self.context.mutate_secret(owner, destroy),
             }
-        }
-    }
 
-    pub async fn reveal_from_cards<T: AnySecretData>(
-        &mut self,
-        cards: Vec<Card>,
-        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
-    ) -> Vec<T> {
-        // todo!(): betterize this implementation
+            self.player_cards_mut(owner).destroyed_pointers = true;
 
-        let mut revealed = Vec::with_capacity(cards.len());
+            id
+        };
 
-        for card in cards {
-            revealed.push(self.reveal_from_card(card, f.clone()).await);
-        }
+        self.run_hooks().await;
 
-        revealed
+        Ok(id)
     }
 
-    fn card_info(&self, pub_id: InstanceID) -> CardInfo<S> {
-        let CardLocation {
-            player: owner,
-            location,
-        } = self.location(pub_id);
-        let location = location.unwrap_or_else(|| panic!("public {:?} has no zone", pub_id));
+    /// Invalidates every player's pointers to destroyed cards, see `PlayerSecret::destroy`.
+    fn invalidate_pointers(&mut self, ids: &[InstanceID]) {
+        for player in self.players() {
+            if self.player_cards(player).pointers != 0 {
+                let destroyed = ids.to_vec();
 
-        let instance = &self.instances[pub_id.0].instance_ref().unwrap();
-        let attachment = instance.attachment().map(|attachment| {
-            self.instances[attachment.0]
-                .instance_ref()
-                .unwrap_or_else(|| {
-                    panic!("public {:?} attachment {:?} not public", pub_id, attachment)
-                })
-        });
+                self.context.mutate_secret(player, move |secret| {
+                    secret.secret.destroy(&destroyed);
+                });
 
-        CardInfo {
-            instance,
-            owner,
-            zone: location.0,
-            attachment,
+                self.player_cards_mut(player).destroyed_pointers = true;
+            }
         }
     }
 
-    pub async fn reveal_from_cards_fold<T, B, F, G>(
-        &mut self,
-        cards: Vec<Card>,
-        map: G,
-        init: B,
-        fold: F,
-    ) -> B
-    where
-        T: Clone + 'static,
-        B: AnySecretData + 'static,
-        F: Fn(B, &T) -> B + Clone + 'static,
-        G: Fn(CardInfo<S>) -> T + Clone + 'static,
-    {
-        let (public_cards, secret_cards) = {
-            let mut public_cards = vec![];
-            let mut secret_cards: [Vec<Card>; 2] = [vec![], vec![]];
-            for card in cards {
-                match card {
-                    Card::ID(id) => match &self.instances[id.0] {
-                        InstanceOrPlayer::Instance(_) => public_cards.push(id),
-                        InstanceOrPlayer::Player(owner) => secret_cards[*owner as usize].push(card),
-                    },
-                    Card::Pointer(OpaquePointer { player, .. }) => {
-                        secret_cards[player as usize].push(card)
-                    }
-                }
-            }
-            (public_cards, secret_cards)
-        };
+    /// Logs a `CardEvent::NewPointer`, unless `MatchConfig::suppress_pointer_events` is enabled.
+    fn log_new_pointer(&mut self, pointer: OpaquePointer, location: ExactCardLocation) {
+        if !self.config.suppress_pointer_events {
+            self.context
+                .log(CardEvent::NewPointer { pointer, location });
+        }
+    }
 
-        let [p0_cards, p1_cards] = secret_cards;
+    pub fn deck_card(&mut self, player: Player, index: usize) -> Card {
+        self.context.mutate_secret(player, |mut secret| {
+            let pointer = secret.deck()[index];
+            secret.pointers.push(pointer);
+        });
 
-        let every_single_public_card: indexmap::IndexMap<InstanceID, T> = self
-            .instances
-            .iter()
-            .filter_map(|instance| {
-                instance.instance_ref().map(|i| {
-                    let id = i.id();
-                    let info = self.card_info(id);
-                    (id, map(info))
-                })
-            })
-            .collect();
+        let player_cards = self.player_cards_mut(player);
 
-        let mut accumulated = public_cards.into_iter().fold(init, |prev, pub_id| {
-            fold(
-                prev,
-                every_single_public_card
-                    .get(&pub_id)
-                    .expect("public cards are public"),
-            )
-        });
+        player_cards.pointers += 1;
 
-        for (player, cards) in vec![p0_cards, p1_cards].into_iter().enumerate() {
-            if cards.is_empty() {
-                continue;
-            }
-            let map = map.clone();
-            let fold = fold.clone();
-            let every_single_public_card = every_single_public_card.clone();
-            accumulated = self
-                .context
-                .reveal_unique(
-                    player as u8,
-                    move |secret| {
-                        let map = map.clone();
-                        let fold = fold.clone();
-                        let cards = cards.clone();
-                        let every_single_public_card = every_single_public_card.clone();
-                        cards
-                            .into_iter()
-                            .fold(accumulated.clone(), move |prev, card| {
-                                let id = secret.id(card).unwrap();
-                                if secret.instances.get(&id).is_some() {
-                                    let map = map.clone();
-                                    fold(
-                                        prev,
-                                        &secret
-                                            .reveal_from_card(card, move |c| map(c))
-                                            .unwrap_or_else(|| {
-                                                panic!(
-                                                    "{:?} not in player {:?} secret",
-                                                    card, player
-                                                )
-                                            }),
-                                    )
-                                } else {
-                                    // card is in public state
-                                    fold(
-                                        prev,
-                                        every_single_public_card.get(&id).expect(
-                                            "Card must be in public, since it's not in secret.",
-                                        ),
-                                    )
-                                }
-                            })
-                    },
-                    |_| true,
-                )
-                .await;
-        }
+        let pointer = OpaquePointer {
+            player,
+            index: player_cards.pointers - 1,
+        };
 
-        accumulated
+        self.log_new_pointer(
+            pointer,
+            ExactCardLocation {
+                player,
+                location: (Zone::Deck, index),
+            },
+        );
+
+        pointer.into()
     }
 
-    pub async fn reveal_parent(&mut self, card: impl Into<Card>) -> Option<Card> {
-        let card = card.into();
+    /// Shows a player the top card of their deck, the last one, without revealing it to anyone else.
+    ///
+    /// Other players only see a new pointer to the card.
+    /// The player also gets its instance in a `CardEvent::PeekCard`.
+    pub fn peek_top_of_deck(&mut self, player: Player) -> Option<Card> {
+        let size = self.player_cards(player).deck();
 
-        match card {
-            Card::ID(id) => match self.instances[id.0] {
-                InstanceOrPlayer::Instance(..) => {
-                    let mut parents = self.instances.iter().filter_map(|instance| {
-                        instance.instance_ref().and_then(|instance| {
-                            if instance.attachment == Some(id) {
-                                Some(instance.id())
-                            } else {
-                                None
-                            }
-                        })
-                    });
+        if size == 0 {
+            return None;
+        }
 
-                    parents.next().map(|parent| {
-                        assert!(parents.next().is_none());
+        let card = self.deck_card(player, size - 1);
 
-                        parent.into()
-                    })
-                }
-                InstanceOrPlayer::Player(owner) => {
-                    let parents = self
-                        .new_secret_pointers(owner, |mut secret| {
-                            let parents: Vec<_> = secret
-                                .instances
-                                .values()
-                                .filter_map(|instance| {
-                                    if instance.attachment == Some(id) {
-                                        Some(instance.id())
-                                    } else {
-                                        None
-                                    }
-                                })
-                                .collect();
+        self.context.mutate_secret(player, |mut secret| {
+            let instance = secret
+                .instance(card)
+                .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", player, card))
+                .clone();
 
-                            assert!(parents.len() <= 1);
+            secret.log(CardEvent::PeekCard { card, instance });
+        });
 
-                            parents
-                                .into_iter()
-                                .for_each(|parent| secret.new_pointer(parent));
-                        })
-                        .await;
+        Some(card)
+    }
 
-                    assert!(parents.len() <= 1);
+    pub fn hand_card(&mut self, player: Player, index: usize) -> Card {
+        match self.player_cards(player).hand()[index] {
+            Some(id) => id.into(),
+            None => {
+                self.context.mutate_secret(player, |mut secret| {
+                    let pointer = secret.hand()[index].unwrap_or_else(|| {
+                        panic!(
+                            "player {} hand {} is neither public nor secret",
+                            player, index
+                        )
+                    });
+                    secret.pointers.push(pointer);
+                });
 
-                    parents.into_iter().next()
-                }
-            },
-            Card::Pointer(OpaquePointer { player, index }) => {
-                let id = self
-                    .context
-                    .reveal_unique(
-                        player,
-                        move |secret| {
-                            let id = secret.pointers[index];
+                let player_cards = self.player_cards_mut(player);
 
-                            let parents = secret
-                                .instances
-                                .values()
-                                .filter(|instance| instance.attachment == Some(id))
-                                .count();
+                player_cards.pointers += 1;
 
-                            match parents {
-                                0 => Some(id),
-                                1 => None,
-                                parents => unreachable!("{:?} has {} parents", id, parents),
-                            }
-                        },
-                        |_| true,
-                    )
-                    .await;
+                let pointer = OpaquePointer {
+                    player,
+                    index: player_cards.pointers - 1,
+                };
 
-                match id {
-                    None => {
-                        let parents = self
-                            .new_secret_pointers(player, |mut secret| {
-                                let id = secret.pointers[index];
+                self.log_new_pointer(
+                    pointer,
+                    ExactCardLocation {
+                        player,
+                        location: (Zone::Hand { public: false }, index),
+                    },
+                );
 
-                                let parents: Vec<_> = secret
-                                    .instances
-                                    .values()
-                                    .filter_map(|instance| {
-                                        if instance.attachment == Some(id) {
-                                            Some(instance.id())
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect();
+                pointer.into()
+            }
+        }
+    }
 
-                                assert!(parents.len() <= 1);
+    pub fn field_card(&self, player: Player, index: usize) -> InstanceID {
+        self.player_cards(player).field()[index]
+    }
 
-                                parents
-                                    .into_iter()
-                                    .for_each(|parent| secret.new_pointer(parent));
-                            })
-                            .await;
+    pub fn graveyard_card(&self, player: Player, index: usize) -> InstanceID {
+        self.player_cards(player).graveyard()[index]
+    }
 
-                        assert!(parents.len() <= 1);
+    pub fn public_dust_card(&self, player: Player, index: usize) -> InstanceID {
+        self.player_cards(player).dust()[index]
+    }
 
-                        parents.into_iter().next()
-                    }
-                    Some(id) => match self.instances[id.0] {
-                        InstanceOrPlayer::Instance(..) => {
-                            let mut parents = self.instances.iter().filter_map(|instance| {
-                                instance.instance_ref().and_then(|instance| {
-                                    if instance.attachment == Some(id) {
-                                        Some(instance.id())
-                                    } else {
-                                        None
-                                    }
-                                })
-                            });
+    pub fn secret_dust_card(&mut self, player: Player, index: usize) -> Card {
+        self.context.mutate_secret(player, |mut secret| {
+            let pointer = secret.dust()[index];
+            secret.pointers.push(pointer);
+        });
 
-                            parents.next().map(|parent| {
-                                assert!(parents.next().is_none());
+        let player_cards = self.player_cards_mut(player);
 
-                                parent.into()
-                            })
-                        }
-                        InstanceOrPlayer::Player(owner) => {
-                            let parents = self
-                                .new_secret_pointers(owner, |mut secret| {
-                                    let parents: Vec<_> = secret
-                                        .instances
-                                        .values()
-                                        .filter_map(|instance| {
-                                            if instance.attachment == Some(id) {
-                                                Some(instance.id())
-                                            } else {
-                                                None
-                                            }
-                                        })
-                                        .collect();
+        player_cards.pointers += 1;
 
-                                    assert!(parents.len() <= 1);
+        let pointer = OpaquePointer {
+            player,
+            index: player_cards.pointers - 1,
+        };
 
-                                    parents
-                                        .into_iter()
-                                        .for_each(|parent| secret.new_pointer(parent));
-                                })
-                                .await;
+        self.log_new_pointer(
+            pointer,
+            ExactCardLocation {
+                player,
+                location: (Zone::Dust { public: false }, index),
+            },
+        );
 
-                            assert!(parents.len() <= 1);
+        pointer.into()
+    }
 
-                            parents.into_iter().next()
-                        }
-                    },
-                }
-            }
-        }
+    pub fn public_limbo_card(&self, player: Player, index: usize) -> InstanceID {
+        self.player_cards(player).limbo()[index]
     }
 
-    pub async fn reveal_parents(&mut self, cards: Vec<Card>) -> Vec<Option<Card>> {
-        // todo!(): betterize this implementation
+    pub fn secret_limbo_card(&mut self, player: Player, index: usize) -> Card {
+        self.context.mutate_secret(player, |mut secret| {
+            let pointer = secret.limbo()[index];
+            secret.pointers.push(pointer);
+        });
 
-        let mut parents = Vec::with_capacity(cards.len());
+        let player_cards = self.player_cards_mut(player);
 
-        for card in cards {
-            parents.push(self.reveal_parent(card).await);
-        }
+        player_cards.pointers += 1;
 
-        parents
-    }
+        let pointer = OpaquePointer {
+            player,
+            index: player_cards.pointers - 1,
+        };
 
-    pub async fn filter_cards(
-        &mut self,
-        cards: Vec<Card>,
-        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
-    ) -> Vec<Card> {
-        let f = self.reveal_from_cards(cards.clone(), f).await;
+        self.log_new_pointer(
+            pointer,
+            ExactCardLocation {
+                player,
+                location: (Zone::Limbo { public: false }, index),
+            },
+        );
 
-        assert_eq!(f.len(), cards.len());
+        pointer.into()
+    }
 
-        cards
-            .into_iter()
-            .zip(f)
-            .filter_map(|(card, f)| if f { Some(card) } else { None })
-            .collect()
+    pub fn casting_card(&self, player: Player, index: usize) -> InstanceID {
+        self.player_cards(player).casting()[index]
     }
 
-    pub async fn reset_card(&mut self, card: impl Into<Card>) {
-        let card = card.into();
+    pub fn card_selection_card(&mut self, player: Player, index: usize) -> Card {
+        self.context.mutate_secret(player, |mut secret| {
+            let pointer = secret.card_selection()[index];
+            secret.pointers.push(pointer);
+        });
 
-        let card = if let Card::Pointer(OpaquePointer { player, index }) = card {
-            self.context
-                .reveal_unique(
-                    player,
-                    move |secret| {
-                        let id = secret.pointers[index];
+        let player_cards = self.player_cards_mut(player);
 
-                        if secret.instances.contains_key(&id) {
-                            card
-                        } else {
-                            id.into()
-                        }
-                    },
-                    |_| true,
-                )
-                .await
-        } else {
-            card
-        };
+        player_cards.pointers += 1;
 
-        match card {
-            Card::ID(id) => match &self.instances[id.0] {
-                InstanceOrPlayer::Instance(instance) => {
-                    // public ID to public instance
-                    let owner = self.location(id).player;
+        let pointer = OpaquePointer {
+            player,
+            index: player_cards.pointers - 1,
+        };
 
-                    let attachment_id = instance.attachment();
-                    let base = instance.base.clone();
-                    let base_clone = base.clone();
+        self.log_new_pointer(
+            pointer,
+            ExactCardLocation {
+                player,
+                location: (Zone::CardSelection, index),
+            },
+        );
 
-                    let new_state = self.context.reveal(owner, move |secret| {
-                        secret.secret.reset_card(&id, base.clone())
-                    }, |_| true).await;
-                    let new_attachment =  self.context.reveal(owner, move |secret| secret.secret.attachment(&id, base_clone.clone()), |_| true).await.clone();
-                    let attachment = attachment_id.map(|attachment| {
-                        self.instances[attachment.0]
-                            .instance_ref()
-                            .unwrap_or_else(|| {
-                                panic!("public {:?} attachment {:?} not public", id, attachment)
-                            })
-                    });
-                    match (
-                        attachment.map(|attachment| &attachment.base),
-                        new_attachment,
-                    ) {
-                        (None, None) => {
-                            // do nothing
-                        }
-                        (Some(..), None) => {
-                            // dust current attachment
+        pointer.into()
+    }
 
-                            let attachment = attachment
-                                .expect("attachment base exists, but no attachment")
-                                .id();
+    pub fn deck_cards(&mut self, player: Player) -> Vec<Card> {
+        self.context.mutate_secret(player, |mut secret| {
+            secret.append_deck_to_pointers();
+        });
 
-                            self.move_card(attachment, owner, Zone::Dust { public: true })
-                                .await
-                                .unwrap_or_else(|_| {
-                                    panic!(
-                                        "unable to move attachment {:?} to public dust",
-                                        attachment
-                                    )
-                                });
-                        }
-                        (None, Some(default)) => {
-                            // attach base attachment
+        let player_cards = self.player_cards_mut(player);
 
-                            // create attach state using new_card_state from implementor
-                            let new_state = default.new_card_state(Some(&new_state));
-                            let attachment = self.new_card(owner, default, None).await;
+        player_cards.pointers += player_cards.deck();
 
-                            self.modify_card(attachment, move |mut c| {
-                                c.state = new_state.clone();
-                            })
-                            .await;
+        let pointers: Vec<OpaquePointer> = (player_cards.pointers - player_cards.deck()
+            ..player_cards.pointers)
+            .map(|index| OpaquePointer { player, index })
+            .collect();
 
-                            self.move_card(
-                                attachment,
-                                owner,
-                                Zone::Attachment { parent: id.into() },
-                            )
-                            .await
-                            .unwrap_or_else(|_| {
-                                panic!("unable to attach public limbo {:?} to {:?}", attachment, id)
-                            });
-                        }
-                        (Some(current), Some(default)) if *current == default => {
-                            // reset current attachment
+        for (index, pointer) in pointers.iter().enumerate() {
+            self.log_new_pointer(
+                *pointer,
+                ExactCardLocation {
+                    player,
+                    location: (Zone::Deck, index),
+                },
+            );
+        }
 
-                            let attachment = attachment
-                                .expect("attachment base exists, but no attachment")
-                                .id();
-                            let new_state = default.new_card_state(Some(&new_state));
+        pointers.into_iter().map(Into::into).collect()
+    }
 
-                            self.modify_card(attachment, move |mut c| {
-                                c.state = new_state.clone();
-                            })
-                            .await;
-                        }
-                        (Some(..), Some(default)) => {
-                            // attach base attachment, will implicitly dust current attachment.
-                            let new_state = default.new_card_state(Some(&new_state));
+    pub fn hand_cards(&mut self, player: Player) -> Vec<Card> {
+        self.context.mutate_secret(player, |mut secret| {
+            secret.append_secret_hand_to_pointers();
+        });
 
-                            let attachment = self.new_card(owner, default, None).await;
-                            // create attach state using new_card_state from implementor
+        let secret_hand_indices: Vec<usize> = self
+            .player_cards(player)
+            .hand()
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| id.is_none())
+            .map(|(hand_index, _)| hand_index)
+            .collect();
 
-                            self.modify_card(attachment, move |mut c| {
-                                c.state = new_state.clone();
-                            })
-                            .await;
+        let num_secret_cards = secret_hand_indices.len();
+        let pointer_offset = self.player_cards(player).pointers;
 
-                            self.move_card(
-                                attachment,
-                                owner,
-                                Zone::Attachment { parent: id.into() },
-                            )
-                            .await
-                            .unwrap_or_else(|_| {
-                                panic!("unable to attach public limbo {:?} to {:?}", attachment, id)
-                            });
-                        }
-                    }
-                    
-                        
-                    self.modify_card(id, |mut c| {
-                        c.state = new_state.clone();
+        self.player_cards_mut(player).pointers += num_secret_cards;
 
-                        if let Some(attach) = c.attachment {
-                            S::on_attach(&mut *c, &attach);
-                        }
-                    })
-                    .await;
-                }
-                InstanceOrPlayer::Player(owner) => {
-                    // public ID to secret instance
+        for (pointer_index, hand_index) in secret_hand_indices.into_iter().enumerate() {
+            self.log_new_pointer(
+                OpaquePointer {
+                    player,
+                    index: pointer_offset + pointer_index,
+                },
+                ExactCardLocation {
+                    player,
+                    location: (Zone::Hand { public: false }, hand_index),
+                },
+            );
+        }
 
-                    let owner = *owner;
+        let mut secret_hand = (self.player_cards(player).pointers - num_secret_cards
+            ..self.player_cards(player).pointers)
+            .map(|index| OpaquePointer { player, index });
 
-                    self.new_secret_cards(owner, |mut secret| {
-                        let instance = secret
-                            .instance(id)
-                            .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", owner, id));
-                        let base = instance.base.clone();
+        let hand = self
+            .player_cards(player)
+            .hand()
+            .iter()
+            .map(|id| {
+                id.map(Card::from).unwrap_or_else(|| {
+                    secret_hand
+                        .next()
+                        .expect("not enough secret hand cards")
+                        .into()
+                })
+            })
+            .collect();
 
-                        let attachment = instance.attachment().map(|attachment| {
-                            secret.instance(attachment).unwrap_or_else(|| panic!("player {} secret {:?} attachment {:?} not secret", owner, id, attachment))
-                        });
+        assert!(secret_hand.next().is_none());
 
+        hand
+    }
 
-                            match (
-                                attachment,
-                                secret.secret.attachment(&id, base.clone()),
-                            ) {
-                                (None, None) => {
-                                    // do nothing
-                                }
-                                (Some(current), None) => {
-                                    // dust current attachment
+    pub fn field_cards(&self, player: Player) -> &Vec<InstanceID> {
+        self.player_cards(player).field()
+    }
 
-                                    let current_id = current.id();
+    pub fn graveyard_cards(&self, player: Player) -> &Vec<InstanceID> {
+        self.player_cards(player).graveyard()
+    }
 
-                                    secret.dust_card(current_id).expect("current_id is in this secret, and is not already dust.");
-                                }
-                                (None, Some(default)) => {
-                                    // attach base attachment
-                                    let new_attach = secret.new_card(default, None);
-                                    secret.attach_card(id, new_attach).expect("Unable to secretly attach a secret card to another card in the same secret.");
-                                }
-                                (Some(current), Some(default)) if current.base == default => {
-                                    // reset current attachment
-                                    let attachment_base_state = current.base.new_card_state(Some(&instance.state));
-                                    let current_id = current.id();
-                                    secret.instance_mut(current_id).unwrap().state = attachment_base_state;
-                            }
-                                (Some(_), Some(default)) => {
-                                     // attach base attachment, current attachment is implicitly dusted.
-                                     let new_attach = secret.new_card(default, None);
-                                     secret.attach_card(id, new_attach).expect("Unable to secretly attach a secret card to another card in the same secret.");
-                                }
-                            }
+    pub fn public_dust_cards(&self, player: Player) -> &Vec<InstanceID> {
+        self.player_cards(player).dust()
+    }
 
+    /// This reveals the number of cards in a player's secret dust.
+    pub async fn secret_dust_cards(&mut self, player: Player) -> Vec<Card> {
+        self.new_secret_pointers(player, |mut secret| {
+            secret
+                .dust()
+                .clone()
+                .into_iter()
+                .for_each(|id| secret.new_pointer(id));
+        })
+        .await
+    }
 
-                        let new_state = secret.secret.reset_card(&id, base.clone());
-                        secret.modify_card(id, |mut c| {
-                                c.state = new_state.clone();
-                            if let Some(attach) = c.attachment {
-                                S::on_attach(&mut *c, &attach);
-                            }
-                        }).expect("Failed to reset card in this secret.");
-                    }).await;
-                }
-            },
-            Card::Pointer(OpaquePointer { player, index }) => {
-                self.new_secret_cards(player, |mut secret| {
-                    let id = secret.pointers[index];
+    pub fn public_limbo_cards(&self, player: Player) -> &Vec<InstanceID> {
+        self.player_cards(player).limbo()
+    }
 
-                    let instance = secret
-                        .instance(id)
-                        .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", player, id));
-                    let base = instance.base.clone();
-                    
-                    let new_state = secret.secret.reset_card(&id, base.clone());
+    /// This reveals the number of cards in a player's secret limbo.
+    pub async fn secret_limbo_cards(&mut self, player: Player) -> Vec<Card> {
+        self.new_secret_pointers(player, |mut secret| {
+            secret
+                .limbo()
+                .clone()
+                .into_iter()
+                .for_each(|id| secret.new_pointer(id));
+        })
+        .await
+    }
 
-                    let attachment = instance.attachment().map(|attachment| {
-                        secret.instance(attachment).unwrap_or_else(|| panic!("player {} secret {:?} attachment {:?} not secret", player, id, attachment))
-                    });
+    /// The number of cards in one of a player's secret zones, or `None` if the zone isn't secret.
+    ///
+    /// This reveals the number of cards in a player's secret dust or secret limbo.
+    /// Deck, secret hand, card selection and pile sizes are already public.
+    pub async fn secret_zone_size(&mut self, player: Player, zone: Zone) -> Option<usize> {
+        match zone {
+            Zone::Deck => Some(self.player_cards(player).deck()),
+            Zone::Hand { public: false } => Some(
+                self.player_cards(player)
+                    .hand()
+                    .iter()
+                    .filter(|id| id.is_none())
+                    .count(),
+            ),
+            Zone::CardSelection => Some(self.player_cards(player).card_selection()),
+            Zone::Pile { pile } => Some(self.player_cards(player).pile(pile)),
+            Zone::Dust { public: false } => Some(
+                self.context
+                    .reveal_unique(player, |secret| secret.dust().len(), |_| true)
+                    .await,
+            ),
+            Zone::Limbo { public: false } => Some(
+                self.context
+                    .reveal_unique(player, |secret| secret.limbo().len(), |_| true)
+                    .await,
+            ),
+            _ => None,
+        }
+    }
 
-                    let next_instance = secret.next_instance.expect(
-                        "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
-                    );
+    /// The total number of cards in one of a player's zones, counting both public and secret cards,
+    /// or `None` for attachments, which have no size.
+    ///
+    /// The publicity of `zone` is ignored.
+    /// This reveals the number of cards in a player's secret dust or secret limbo.
+    pub async fn zone_size(&mut self, player: Player, zone: Zone) -> Option<usize> {
+        let size = match zone {
+            Zone::Dust { .. } => {
+                self.player_cards(player).dust().len()
+                    + self
+                        .secret_zone_size(player, Zone::Dust { public: false })
+                        .await?
+            }
+            Zone::Limbo { .. } => {
+                self.player_cards(player).limbo().len()
+                    + self
+                        .secret_zone_size(player, Zone::Limbo { public: false })
+                        .await?
+            }
+            _ => self.player_cards(player).zone_size(zone)?,
+        };
 
-                    match (attachment, secret.secret.attachment(&id, base.clone())) {
-                        (None, None) => {
-                            // do nothing
-                        }
-                        (Some(current), None) => {
-                            // dust current attachment
+        Some(size)
+    }
 
-                            let current_id = current.id();
+    /// The total number of cards in each of a player's zones, counting both public and secret cards.
+    ///
+    /// This reveals the number of cards in a player's secret dust and secret limbo.
+    pub async fn zone_sizes(&mut self, player: Player) -> ZoneSizes {
+        let (secret_dust, secret_limbo) = self
+            .context
+            .reveal_unique(
+                player,
+                |secret| (secret.dust().len(), secret.limbo().len()),
+                |_| true,
+            )
+            .await;
 
-                            secret
-                                .dust_card(current_id)
-                                .expect("current_id is in this secret, and is not already dust.");
-                        }
-                        (None, Some(default)) => {
-                            // attach base attachment
+        let player_cards = self.player_cards(player);
+
+        ZoneSizes {
+            deck: player_cards.deck(),
+            hand: player_cards.hand().len(),
+            field: player_cards.field().len(),
+            graveyard: player_cards.graveyard().len(),
+            dust: player_cards.dust().len() + secret_dust,
+            limbo: player_cards.limbo().len() + secret_limbo,
+            casting: player_cards.casting().len(),
+            card_selection: player_cards.card_selection(),
+            piles: player_cards.piles().iter().sum(),
+            hero_ability: player_cards.hero_ability().len(),
+            boon: player_cards.boon().len(),
+        }
+    }
 
-                            let state = default.new_card_state(Some(&instance.state));
+    /// Every zone of a player, taken in one call so it's consistent.
+    ///
+    /// Creates pointers to the player's deck, secret hand and card selection,
+    /// like `CardGame::deck_cards`, `CardGame::hand_cards` and `CardGame::card_selection_cards`.
+    /// This reveals the number of cards in a player's secret dust and secret limbo.
+    pub async fn census(&mut self, player: Player) -> Census {
+        let (secret_dust, secret_limbo) = self
+            .context
+            .reveal_unique(
+                player,
+                |secret| (secret.dust().len(), secret.limbo().len()),
+                |_| true,
+            )
+            .await;
 
-                            let attachment = CardInstance {
-                                id: next_instance,
-                                base: default,
-                                attachment: None,
-                                state,
-                            };
+        let deck = self.deck_cards(player);
+        let hand = self.hand_cards(player);
+        let card_selection = self.card_selection_cards(player);
 
-                            secret.instances.insert(next_instance, attachment);
+        let player_cards = self.player_cards(player);
 
-                            secret
-                                .attach_card(id, next_instance)
-                                .expect("Both id and next_instance are in this secret.");
-                        }
-                        (Some(current), Some(default)) if current.base == default => {
-                            // reset current attachment
-                            let attachment_base_state = current.base.new_card_state(Some(&instance.state));
-                            let current_id = current.id();
-                            secret.instance_mut(current_id).unwrap().state = attachment_base_state;
-                        }
-                        (Some(current), Some(default)) => {
-                            // dust current attachment
-                            let state = default.new_card_state(Some(&instance.state));
-                            let current_id = current.id();
-                            secret
-                                .dust_card(current_id)
-                                .expect("current_id is in this secret, and is not already dust.");
+        Census {
+            player,
+            deck,
+            hand,
+            field: player_cards.field().clone(),
+            graveyard: player_cards.graveyard().clone(),
+            public_dust: player_cards.dust().clone(),
+            secret_dust,
+            public_limbo: player_cards.limbo().clone(),
+            secret_limbo,
+            casting: player_cards.casting().clone(),
+            card_selection,
+            piles: player_cards.piles().clone(),
+            hero_ability: player_cards.hero_ability().clone(),
+            boon: player_cards.boon().clone(),
+        }
+    }
 
-                            // Attach base attachment
+    /// Checks that the acting player may move a card, if move authorization is enabled.
+    ///
+    /// Moves out of or into another player's secret need `State::authorize_move`'s approval.
+    fn authorize_move(
+        &self,
+        card: Card,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Result<(), error::MoveCardError> {
+        let player = match self.acting_player {
+            Some(player) if self.config.authorize_moves => player,
+            _ => return Ok(()),
+        };
 
-                            let attachment = CardInstance {
-                                id: next_instance,
-                                base: default,
-                                attachment: None,
-                                state,
-                            };
+        let from_bucket = self.bucket(card);
 
-                            secret.instances.insert(next_instance, attachment);
+        let to_bucket = match to_zone {
+            Zone::Attachment { parent } => self.bucket(parent),
+            zone if matches!(zone.is_public(), Ok(false)) => Some(to_player),
+            _ => None,
+        };
 
-                            secret
-                                .attach_card(id, next_instance)
-                                .expect("Both id and next_instance are in this secret.");
-                        }
-                    }
+        let foreign = [from_bucket, to_bucket]
+            .into_iter()
+            .flatten()
+            .any(|bucket| bucket != player);
 
-                    // unconditionally increment instance ID to avoid leaking attachment information
+        if foreign && !S::authorize_move(self, player, card, to_player, to_zone) {
+            return Err(error::MoveCardError::Unauthorized { player, card });
+        }
 
-                    secret
-                        .next_instance
-                        .as_mut()
-                        .expect(
-                            "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
-                        )
-                        .0 += 1;
+        Ok(())
+    }
 
-                    let attachment = secret
-                        .instance(id).unwrap().attachment().map(|attachment| {
-                            secret.instance(attachment).unwrap_or_else(|| panic!("player {} secret {:?} attachment {:?} not secret", player, id, attachment)).clone()
-                        });
+    /// The player whose secret a card is in, if any.
+    fn bucket(&self, card: Card) -> Option<Player> {
+        match card {
+            Card::ID(id) => self.instances[id.0].player(),
+            Card::Pointer(OpaquePointer { player, .. }) => Some(player),
+        }
+    }
 
-                    let instance = secret
-                        .instance_mut(id)
-                        .expect("immutable instance exists, but no mutable instance");
-                    
-                    instance.state = new_state.clone();
-                    
-                    if let Some(attach) = attachment {
-                        S::on_attach(instance, &attach);
-                    }
-                })
-                .await;
+    /// Creates a pointer to the first card in one of a player's secret zones satisfying `f`.
+    ///
+    /// This reveals whether any card in the zone satisfies `f`, but not which one.
+    pub async fn find_secret_card(
+        &mut self,
+        player: Player,
+        zone: Zone,
+        f: impl Fn(CardInfo<S>) -> bool,
+    ) -> Option<Card> {
+        self.new_secret_pointers(player, |mut secret| {
+            if let Some(id) = secret
+                .zone_cards(zone)
+                .into_iter()
+                .find(|id| secret.reveal_from_card(*id, &f).unwrap_or(false))
+            {
+                secret.new_pointer(id);
             }
-        }
+        })
+        .await
+        .pop()
     }
 
-    pub async fn reset_cards(&mut self, cards: Vec<Card>) {
-        // todo!(): betterize this implementation
+    /// Creates a pointer to the last card in one of a player's secret zones satisfying `f`.
+    ///
+    /// This reveals whether any card in the zone satisfies `f`, but not which one.
+    pub async fn find_last_secret_card(
+        &mut self,
+        player: Player,
+        zone: Zone,
+        f: impl Fn(CardInfo<S>) -> bool,
+    ) -> Option<Card> {
+        self.new_secret_pointers(player, |mut secret| {
+            if let Some(id) = secret
+                .zone_cards(zone)
+                .into_iter()
+                .rfind(|id| secret.reveal_from_card(*id, &f).unwrap_or(false))
+            {
+                secret.new_pointer(id);
+            }
+        })
+        .await
+        .pop()
+    }
 
-        for card in cards {
-            self.reset_card(card).await;
-        }
+    /// Creates a pointer to the first card in a player's secret hand satisfying `f`.
+    pub async fn find_secret_hand_card(
+        &mut self,
+        player: Player,
+        f: impl Fn(CardInfo<S>) -> bool,
+    ) -> Option<Card> {
+        self.find_secret_card(player, Zone::Hand { public: false }, f)
+            .await
     }
 
-    /// Copies a card.
-    ///
-    /// If the card is a public ID to player X's public instance, the card is copied to player X's public limbo.
-    /// If the card is a public ID to player X's secret instance, the card is copied to player X's secret limbo.
-    /// If the card is player X's secret pointer to a public instance, the card is copied to player X's secret limbo.
-    /// If the card is player X's secret pointer to player X's secret instance, the card is copied to player X's secret limbo.
-    /// If the card is player X's secret pointer to player Y's secret instance, the card is copied to player Y's secret limbo.
-    pub fn copy_card<'a>(
-        &'a mut self,
-        card: impl Into<Card>,
-        deep: bool,
-    ) -> Pin<Box<dyn Future<Output = Card> + 'a>> {
-        return inner(self, card.into(), deep);
+    /// Creates a pointer to the first card in a player's deck satisfying `f`.
+    pub async fn find_deck_card(
+        &mut self,
+        player: Player,
+        f: impl Fn(CardInfo<S>) -> bool,
+    ) -> Option<Card> {
+        self.find_secret_card(player, Zone::Deck, f).await
+    }
 
-        fn inner<'a, S: State>(
-            this: &'a mut CardGame<S>,
-            card: Card,
-            deep: bool,
-        ) -> Pin<Box<dyn Future<Output = Card> + 'a>> {
-            Box::pin(async move {
-                match card {
-                    Card::ID(id) => match &this.instances[id.0] {
-                        InstanceOrPlayer::Instance(instance) => {
-                            let owner = this.owner(id);
-                            let base = instance.base.clone();
-                            let base_clone = base.clone();
-                            let state = instance.state.copy_card();
-                            let attachment = if deep {
-                                if let Some(attachment) = instance.attachment {
-                                    Some(
-                                        this.copy_card(attachment, deep)
-                                            .await
-                                            .id()
-                                            .expect("public card attachment copy must be public"),
-                                    )
-                                } else {
-                                    None
-                                }
-                            } else if let Some(attachment) = this.context.reveal(owner, move |secret| secret.secret.attachment(&id, base_clone.clone()), |_| true).await {
-                                Some(this.new_card(owner, attachment, None).await)
-                            } else {
-                                None
-                            };
+    pub fn casting_cards(&self, player: Player) -> &Vec<InstanceID> {
+        self.player_cards(player).casting()
+    }
 
-                            let copy_id = InstanceID(this.instances.len());
-                            let copy = CardInstance {
-                                id: copy_id,
-                                base,
-                                state,
-                                attachment: None,
-                            };
-                            this.instances.push(InstanceOrPlayer::Instance(copy));
+    pub fn card_selection_cards(&mut self, player: Player) -> Vec<Card> {
+        self.context.mutate_secret(player, |mut secret| {
+            secret.append_card_selection_to_pointers();
+        });
 
-                            this.player_cards_mut(owner).limbo.push(copy_id);
+        let num_hand_selection = self.player_cards(player).card_selection();
 
-                            if let Some(attachment) = attachment {
-                                this.move_card(
-                                    attachment,
-                                    owner,
-                                    Zone::Attachment {
-                                        parent: copy_id.into(),
-                                    },
-                                )
-                                .await
-                                .unwrap();
-                            }
-                            copy_id.into()
-                        }
-                        InstanceOrPlayer::Player(owner) => {
-                            let owner = *owner;
-                            this.new_secret_cards(owner, |mut secret| {
-                            let (copy_id, attach_id) = {
-                                let mut next_instance = secret.next_instance.expect(
-                                    "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
-                                );
-                                let attach_id = next_instance;
-                                next_instance.0 += 1;
-                                let copy_id = next_instance;
-                                next_instance.0 += 1;
-                                secret.next_instance = Some(next_instance);
-                                (copy_id, attach_id)
-                            };
-                            let instance = &secret.instances[&id];
+        self.player_cards_mut(player).pointers += num_hand_selection;
 
-                            let base = instance.base.clone();
-                            let state = instance.state.copy_card();
+        let start_ptr =
+            self.player_cards(player).pointers - self.player_cards(player).card_selection();
+        let end_ptr = self.player_cards(player).pointers;
 
-                            let attachment = if deep {
-                                if let Some(attachment) = instance.attachment {
-                                    let old_attach = &secret.instances[&attachment];
-                                    assert!(old_attach.attachment.is_none(), "Attachments can't have attachments.");
-                                    let attachment = CardInstance {
-                                        id: attach_id,
-                                        base: old_attach.base().clone(),
-                                        attachment: None,
-                                        state: old_attach.state.copy_card(),
-                                    };
+        for (zone_index, pointer_index) in (start_ptr..end_ptr).enumerate() {
+            self.log_new_pointer(
+                OpaquePointer {
+                    player,
+                    index: pointer_index,
+                },
+                ExactCardLocation {
+                    player,
+                    location: (Zone::CardSelection, zone_index),
+                },
+            );
+        }
+        (start_ptr..end_ptr)
+            .map(|index| OpaquePointer { player, index }.into())
+            .collect()
+    }
 
-                                    secret.instances.insert(attach_id, attachment);
-                                    secret.limbo.push(attach_id);
-                                    Some(attach_id)
-                                } else {
-                                    None
-                                }
-                            } else if let Some(attach_base) = secret.secret.attachment(&id, base.clone()) {
-                                let attachment = CardInstance {
-                                    id: attach_id,
-                                    base: attach_base.clone(),
-                                    attachment: None,
-                                    state: attach_base.new_card_state(Some(&state)),
-                                };
+    /// Creates a pointer to the card at `index` in one of a player's piles, see `Zone::Pile`.
+    pub fn pile_card(&mut self, player: Player, pile: PileID, index: usize) -> Card {
+        self.context.mutate_secret(player, |mut secret| {
+            let pointer = secret.pile(pile)[index];
+            secret.pointers.push(pointer);
+        });
 
-                                secret.instances.insert(attach_id, attachment);
-                                secret.limbo.push(attach_id);
-                                Some(attach_id)
-                            } else {
-                                None
-                            };
-                            let copy = CardInstance {
-                                id: copy_id,
-                                base,
-                                state,
-                                attachment: None
-                            };
-                            secret.instances.insert(copy_id, copy);
-                            secret.limbo.push(copy_id);
-                            secret.pointers.push(copy_id);
-                            if let Some(attachment) = attachment {
-                                secret.attach_card(copy_id, attachment).unwrap();
-                            }
-                        })
-                        .await[0]
-                        }
-                    },
-                    Card::Pointer(OpaquePointer { player, index }) => {
-                        let buckets: Vec<_> = this
-                            .instances
-                            .iter()
-                            .map(InstanceOrPlayer::player)
-                            .collect();
+        let player_cards = self.player_cards_mut(player);
 
-                        let id = this
-                            .context
-                            .reveal_unique(
-                                player,
-                                move |secret| {
-                                    let id = secret.pointers[index];
+        player_cards.pointers += 1;
 
-                                    buckets[id.0].and_then(|bucket| {
-                                        if bucket == player {
-                                            None
-                                        } else {
-                                            Some(id)
-                                        }
-                                    })
-                                },
-                                |_| true,
-                            )
-                            .await;
+        let pointer = OpaquePointer {
+            player,
+            index: player_cards.pointers - 1,
+        };
 
-                        match id {
-                            None => {
-                                let instances = this.instances.clone();
+        self.log_new_pointer(
+            pointer,
+            ExactCardLocation {
+                player,
+                location: (Zone::Pile { pile }, index),
+            },
+        );
 
-                                this.new_secret_cards(player, |mut secret| {
-                                let (copy_id, attach_id) = {
-                                    let mut next_instance = secret
-                                        .next_instance
-                                        .expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call");
+        pointer.into()
+    }
 
-                                    let copy_id = next_instance;
-                                    next_instance.0 += 1;
-                                    let attach_id = next_instance;
-                                    next_instance.0 += 1;
-                                    secret.next_instance = Some(next_instance);
-                                    (copy_id, attach_id)
-                                };
+    /// Creates pointers to the cards in one of a player's piles, in order.
+    pub fn pile_cards(&mut self, player: Player, pile: PileID) -> Vec<Card> {
+        self.context.mutate_secret(player, |mut secret| {
+            secret.append_pile_to_pointers(pile);
+        });
 
-                                let id = secret.pointers[index];
+        let size = self.player_cards(player).pile(pile);
 
-                                let instance = instances[id.0].instance_ref().or_else(|| secret.instances.get(&id)).expect("instance is neither public nor in this secret");
+        self.player_cards_mut(player).pointers += size;
 
-                                let base = instance.base.clone();
-                                let state = instance.state.copy_card();
+        let end_ptr = self.player_cards(player).pointers;
+        let start_ptr = end_ptr - size;
 
-                                let attachment = if deep {
-                                    if let Some(attachment) = instance.attachment {
-                                let old_attach = instances[attachment.0].instance_ref().or_else(|| secret.instance(attachment)).unwrap();
-                                        assert!(old_attach.attachment.is_none());
-                                        let attachment = CardInstance {
-                                            id: attach_id,
-                                            base: old_attach.base().clone(),
-                                            attachment: None,
-                                            state: old_attach.state.copy_card(),
-                                        };
+        for (zone_index, pointer_index) in (start_ptr..end_ptr).enumerate() {
+            self.log_new_pointer(
+                OpaquePointer {
+                    player,
+                    index: pointer_index,
+                },
+                ExactCardLocation {
+                    player,
+                    location: (Zone::Pile { pile }, zone_index),
+                },
+            );
+        }
 
-                                        secret.instances.insert(attach_id, attachment);
-                                        secret.limbo.push(attach_id);
-                                        Some(attach_id)
-                                    } else {
-                                        None
-                                    }
-                                } else if let Some(attach_base) = secret.secret.attachment(&id, base.clone()) {
-                                    let attachment = CardInstance {
-                                        id: attach_id,
-                                        base: attach_base.clone(),
-                                        attachment: None,
-                                        state: attach_base.new_card_state(Some(&state)),
-                                    };
+        (start_ptr..end_ptr)
+            .map(|index| OpaquePointer { player, index }.into())
+            .collect()
+    }
 
-                                    secret.instances.insert(attach_id, attachment);
-                                    secret.limbo.push(attach_id);
-                                    Some(attach_id)
-                                } else {
-                                    None
-                                };
-                                let copy = CardInstance {
-                                    id: copy_id,
-                                    base,
-                                    state,
-                                    attachment: None
-                                };
-                                secret.instances.insert(copy_id, copy);
-                                secret.limbo.push(copy_id);
-                                secret.pointers.push(copy_id);
-                                if let Some(attachment) = attachment {
-                                    secret.attach_card(copy_id, attachment).unwrap();
-                                }
-                            }).await[0]
-                            }
-                            Some(id) => {
-                                let owner = this.instances[id.0]
-                                    .player()
-                                    .expect("instance is not in another player's secret");
+    /// Shuffles one of a player's piles with their secret randomness.
+    ///
+    /// Only the player is told the new order, in a `CardEvent::ShufflePile`.
+    pub fn shuffle_pile(&mut self, player: Player, pile: PileID) {
+        if self.result().is_some() {
+            return;
+        }
 
-                                this.new_secret_cards(owner, |mut secret| {
-                                let (copy_id, attach_id) = {
-                                        let mut next_instance = secret
-                                            .next_instance
-                                        .expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call");
+        self.context.mutate_secret(player, |secret| {
+            if let Some(ids) = secret.secret.piles.get_mut(pile.0) {
+                ids.shuffle(secret.random);
 
-                                        let copy_id = next_instance;
-                                        next_instance.0 += 1;
-                                        let attach_id = next_instance;
-                                        next_instance.0 += 1;
-                                        secret.next_instance = Some(next_instance);
-                                        (copy_id, attach_id)
-                                    };
+                (secret.log)(CardEvent::ShufflePile {
+                    player,
+                    pile,
+                    ids: ids.clone(),
+                });
+            }
+        });
+    }
 
-                                    let instance = &secret.instances[&id];
-                                    let base = instance.base.clone();
-                                    let state = instance.state.copy_card();
+    /// Shows a player the top card of one of their piles, the last one, like `CardGame::peek_top_of_deck`.
+    pub fn peek_top_of_pile(&mut self, player: Player, pile: PileID) -> Option<Card> {
+        let size = self.player_cards(player).pile(pile);
 
-                                    let attachment = if deep {
-                                        if let Some(attachment) = instance.attachment {
-                                            let old_attach = &secret.instances[&attachment];
-                                            assert!(old_attach.attachment.is_none());
-                                            let attachment = CardInstance {
-                                                id: attach_id,
-                                                base: old_attach.base().clone(),
-                                                attachment: None,
-                                                state: old_attach.state.copy_card(),
-                                            };
+        if size == 0 {
+            return None;
+        }
 
-                                            secret.instances.insert(attach_id, attachment);
-                                            secret.limbo.push(attach_id);
-                                            Some(attach_id)
-                                        } else {
-                                            None
-                                        }
-                                    } else if let Some(attach_base) = secret.secret.attachment(&id, base.clone()) {
-                                        let attachment = CardInstance {
-                                            id: attach_id,
-                                            base: attach_base.clone(),
-                                            attachment: None,
-                                            state: attach_base.new_card_state(Some(&state)),
-                                        };
+        let card = self.pile_card(player, pile, size - 1);
 
-                                        secret.instances.insert(attach_id, attachment);
-                                        secret.limbo.push(attach_id);
-                                        Some(attach_id)
-                                    } else {
-                                        None
-                                    };
+        self.context.mutate_secret(player, |mut secret| {
+            let instance = secret
+                .instance(card)
+                .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", player, card))
+                .clone();
 
-                                    let copy = CardInstance {
-                                        id: copy_id,
-                                        base,
-                                        state,
-                                        attachment: None
-                                    };
-                                    secret.instances.insert(copy_id, copy);
-                                    secret.limbo.push(copy_id);
-                                    secret.pointers.push(copy_id);
-                                    if let Some(attachment) = attachment {
-                                        secret.attach_card(copy_id, attachment).unwrap();
-                                        assert!(secret.instance(copy_id).unwrap().attachment.is_some());
-                                    }
-                                }).await[0]
-                            }
-                        }
-                    }
-                }
-            })
-        }
+            secret.log(CardEvent::PeekCard { card, instance });
+        });
+
+        Some(card)
     }
 
-    pub async fn copy_cards(&mut self, cards: Vec<Card>, deep: bool) -> Vec<Card> {
-        // todo!(): betterize this implementation
+    /// Moves up to `count` cards from the top of one of a player's piles to their secret hand, top card first.
+    pub async fn draw_from_pile(
+        &mut self,
+        player: Player,
+        pile: PileID,
+        count: usize,
+    ) -> Vec<Card> {
+        if self.result().is_some() {
+            return vec![];
+        }
 
-        let mut copies = Vec::new();
+        let mut cards = self.pile_cards(player, pile);
 
-        for card in cards {
-            copies.push(self.copy_card(card, deep).await);
-        }
+        cards.reverse();
+        cards.truncate(count);
 
-        copies
+        self.move_cards(cards.clone(), player, Zone::Hand { public: false })
+            .await;
+
+        cards
     }
 
-    /// Always returns a Card::ID if the card is in public state.
-    pub async fn modify_card(&mut self, card: impl Into<Card>, f: impl Fn(CardInfoMut<S>)) -> Card {
+    /// The location of a card, which may be an opaque pointer or a secret instance.
+    ///
+    /// This reveals the zone of a secret card, but not its index or ID.
+    /// A pointer to another player's secret card only reveals its owner.
+    /// A secret parent of an attachment isn't revealed by ID: the zone gets a new pointer to it, as in `CardGame::reveal_zone`.
+    pub async fn reveal_location(&mut self, card: impl Into<Card>) -> CardLocation {
         let card = card.into();
+        let buckets = self.state.buckets();
 
-        let card = if let Card::Pointer(OpaquePointer { player, index }) = card {
-            self.context
-                .reveal_unique(
-                    player,
-                    move |secret| {
-                        let id = secret.pointers[index];
-
-                        if secret.instances.contains_key(&id) {
-                            card
-                        } else {
-                            id.into()
-                        }
-                    },
-                    |_| true,
-                )
-                .await
-        } else {
-            card
-        };
+        let (owner, location) = match card {
+            Card::ID(id) => match self.instances[id.0] {
+                InstanceOrPlayer::Instance(..) | InstanceOrPlayer::Destroyed(..) => {
+                    return self.location(id)
+                }
+                InstanceOrPlayer::Player(owner) => (
+                    owner,
+                    self.context
+                        .reveal_unique(
+                            owner,
+                            move |secret| without_secret_parent(&buckets, secret.location(id)),
+                            |_| true,
+                        )
+                        .await,
+                ),
+            },
+            Card::Pointer(OpaquePointer { player, index }) => {
+                let revealed = self
+                    .context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            let id = secret.pointers[index];
 
-        match card {
-            Card::ID(id) => {
-                let Self { state, context } = self;
+                            match Bucket::of(&buckets, id) {
+                                Bucket::Secret(owner) if owner == player => {
+                                    Either::A(without_secret_parent(&buckets, secret.location(id)))
+                                }
+                                Bucket::Secret(owner) => Either::A(Some(CardLocation {
+                                    player: owner,
+                                    location: None,
+                                })),
+                                Bucket::Public => Either::B(id),
+                                // A destroyed card has no location.
+                                Bucket::Destroyed => Either::A(Some(CardLocation {
+                                    player,
+                                    location: None,
+                                })),
+                            }
+                        },
+                        |_| true,
+                    )
+                    .await;
 
-                match &state.instances[id.0] {
-                    InstanceOrPlayer::Instance(instance) => {
-                        let CardLocation {
-                            player: owner,
-                            location,
-                        } = state.location(id);
-                        let location =
-                            location.unwrap_or_else(|| panic!("public {:?} has no zone", id));
+                match revealed {
+                    Either::A(location) => (player, location),
+                    Either::B(id) => return self.location(id),
+                }
+            }
+        };
 
-                        let attachment = instance.attachment.map(|attachment| {
-                            state.instances[attachment.0]
-                                .instance_ref()
-                                .unwrap_or_else(|| {
-                                    panic!("public {:?} attachment {:?} not public", id, attachment)
-                                })
-                                .clone()
-                        });
+        if let Some(location) = location {
+            return location;
+        }
 
-                        let instance = state.instances[id.0]
-                            .instance_mut()
-                            .unwrap_or_else(|| panic!("{:?} vanished", id));
+        let parent = self
+            .new_secret_pointers(owner, move |mut secret| {
+                let parent = secret
+                    .parent_of(card)
+                    .unwrap_or_else(|| panic!("{:?} has no parent", card));
 
-                        let before = instance.clone();
+                secret.new_pointer(parent);
+            })
+            .await[0];
 
-                        f(CardInfoMut {
-                            instance,
-                            owner,
-                            zone: location.0,
-                            attachment: attachment.as_ref(),
-                            log: &mut |event| context.log(event),
-                        });
+        CardLocation {
+            player: owner,
+            location: Some((Zone::Attachment { parent }, None)),
+        }
+    }
 
-                        let after = state.instances[id.0]
-                            .instance_ref()
-                            .unwrap_or_else(|| panic!("{:?} vanished", id));
+    /// The owner of a card, which may be an opaque pointer or a secret instance.
+    ///
+    /// This reveals only the card's owner, or `None` if the card doesn't exist, e.g. because it was destroyed.
+    pub async fn reveal_owner(&mut self, card: impl Into<Card>) -> Option<Player> {
+        let card = card.into();
 
-                        if !before.eq(after) {
-                            context.log(CardEvent::ModifyCard {
-                                instance: after.clone(),
-                            })
-                        }
+        if !self.exists(card) {
+            return None;
+        }
 
-                        let mut logs = vec![];
+        match card {
+            Card::ID(id) => Some(self.owner(id)),
+            Card::Pointer(OpaquePointer { player, index }) => {
+                let buckets = self.state.buckets();
 
-                        match location.0 {
-                            Zone::Field => self.sort_field(
-                                owner,
-                                self.player_cards(owner).field.clone(),
-                                true,
-                                &mut |event| logs.push(event),
-                            ),
-                            Zone::Attachment {
-                                parent: Card::ID(parent_id),
-                            } => {
-                                if let Some((Zone::Field, ..)) = self.location(parent_id).location {
-                                    self.sort_field(
-                                        owner,
-                                        self.player_cards(owner).field.clone(),
-                                        true,
-                                        &mut |event| logs.push(event),
-                                    );
-                                }
+                // A secret card only reveals its owner, and a public card is already public.
+                let revealed = self
+                    .context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            let id = secret.pointers[index];
+
+                            match Bucket::of(&buckets, id) {
+                                Bucket::Public => Some(Either::A(id)),
+                                Bucket::Secret(owner) => Some(Either::B(owner)),
+                                Bucket::Destroyed => None,
                             }
-                            _ => (),
-                        }
+                        },
+                        |_| true,
+                    )
+                    .await;
 
-                        for event in logs.into_iter() {
-                            self.context.log(event);
+                revealed.map(|revealed| match revealed {
+                    Either::A(id) => self.owner(id),
+                    Either::B(owner) => owner,
+                })
+            }
+        }
+    }
+
+    /// The zone a card is in.
+    ///
+    /// This reveals the card's zone, but not its instance.
+    /// A secret parent of an attachment isn't revealed by ID: the zone gets a new pointer to it, owned by the parent's owner.
+    pub async fn reveal_zone(&mut self, card: impl Into<Card>) -> Zone {
+        let card = card.into();
+        let buckets = self.state.buckets();
+
+        let revealed = self
+            .reveal_from_card(card, move |info| match info.zone {
+                Zone::Attachment {
+                    parent: Card::ID(parent),
+                } if matches!(buckets[parent.0], Bucket::Secret(..)) => Either::B(info.owner),
+                zone => Either::A(zone),
+            })
+            .await;
+
+        let owner = match revealed {
+            Either::A(zone) => return zone,
+            Either::B(owner) => owner,
+        };
+
+        // A pointer into another player's secret was already followed by ID to reveal the zone.
+        let card = match card {
+            Card::Pointer(OpaquePointer { player, index }) if player != owner => self
+                .context
+                .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
+                .await
+                .into(),
+            card => card,
+        };
+
+        let parent = self
+            .new_secret_pointers(owner, move |mut secret| {
+                let parent = secret
+                    .parent_of(card)
+                    .unwrap_or_else(|| panic!("{:?} has no parent", card));
+
+                secret.new_pointer(parent);
+            })
+            .await[0];
+
+        Zone::Attachment { parent }
+    }
+
+    /// Whether a card is in `zone`.
+    ///
+    /// This reveals only whether the card is in the zone, not the instance or the card's actual zone.
+    pub async fn is_in_zone(&mut self, card: impl Into<Card>, zone: Zone) -> bool {
+        self.reveal_from_card(card, move |info| info.zone.eq(zone).unwrap_or(false))
+            .await
+    }
+
+    /// Whether two cards are the same instance.
+    ///
+    /// Pointers are compared inside their owners' secrets.
+    /// Comparing pointers of different players reveals only the first pointer's instance ID.
+    pub async fn reveal_if_cards_eq(&mut self, a: impl Into<Card>, b: impl Into<Card>) -> bool {
+        let a = a.into();
+        let b = b.into();
+
+        if let Ok(result) = a.eq(b) {
+            return result;
+        }
+
+        if let Card::Pointer(OpaquePointer {
+            player: a_player,
+            index: a_index,
+        }) = a
+        {
+            if let Card::Pointer(OpaquePointer {
+                player: b_player,
+                index: b_index,
+            }) = b
+            {
+                if a_player == b_player {
+                    return self
+                        .context
+                        .reveal_unique(
+                            a_player,
+                            move |secret| secret.pointers[a_index] == secret.pointers[b_index],
+                            |_| true,
+                        )
+                        .await;
+                }
+            }
+        }
+
+        let (id, OpaquePointer { player, index }) = match (a, b) {
+            (Card::ID(id), Card::Pointer(pointer)) | (Card::Pointer(pointer), Card::ID(id)) => {
+                (id, pointer)
+            }
+            (Card::Pointer(OpaquePointer { player, index }), Card::Pointer(pointer)) => {
+                let id = self
+                    .context
+                    .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
+                    .await;
+
+                (id, pointer)
+            }
+            (Card::ID(..), Card::ID(..)) => unreachable!("IDs are always comparable"),
+        };
+
+        self.context
+            .reveal_unique(player, move |secret| secret.pointers[index] == id, |_| true)
+            .await
+    }
+
+    /// Whether two cards are in the same zone of the same player.
+    ///
+    /// This reveals only whether the zones match, not the cards' instances or zones.
+    /// If the cards are in different players' secrets, the first card's owner and zone are revealed.
+    /// A pointer to a card outside its player's secret reveals the card's instance ID, like `CardGame::count_matching`.
+    pub async fn reveal_if_same_zone(&mut self, a: impl Into<Card>, b: impl Into<Card>) -> bool {
+        let mut cards = [a.into(), b.into()];
+
+        loop {
+            let holders = cards.map(|card| match card {
+                Card::ID(id) => match self.instances[id.0] {
+                    InstanceOrPlayer::Instance(..) | InstanceOrPlayer::Destroyed(..) => None,
+                    InstanceOrPlayer::Player(owner) => Some(owner),
+                },
+                Card::Pointer(OpaquePointer { player, .. }) => Some(player),
+            });
+
+            // Only public cards' locations are computed here, the rest are looked up in their secrets.
+            let public = [0, 1].map(|i| match (holders[i], cards[i]) {
+                (None, Card::ID(id)) => {
+                    let location = self.location(id);
+
+                    Some((location.player, location.location.map(|(zone, _)| zone)))
+                }
+                _ => None,
+            });
+
+            let [a, b] = cards;
+
+            let (index, id) = match holders {
+                [Some(a_player), Some(b_player)] if a_player != b_player => {
+                    let a_zone = match self
+                        .context
+                        .reveal_unique(a_player, move |secret| secret_zone_of(secret, a), |_| true)
+                        .await
+                    {
+                        Either::A(zone) => zone,
+                        Either::B(id) => {
+                            cards[0] = id.into();
+
+                            continue;
                         }
+                    };
+
+                    match self
+                        .context
+                        .reveal_unique(
+                            b_player,
+                            move |secret| match secret_zone_of(secret, b) {
+                                Either::A(b_zone) => Either::A(same_zone(&a_zone, &b_zone)),
+                                Either::B(id) => Either::B(id),
+                            },
+                            |_| true,
+                        )
+                        .await
+                    {
+                        Either::A(same) => return same,
+                        Either::B(id) => (1, id),
                     }
-                    InstanceOrPlayer::Player(owner) => {
-                        self.context.mutate_secret(*owner, |secret| {
-                            secret
-                                .secret
-                                .modify_card(card, secret.log, |instance| f(instance))
-                                .unwrap_or_else(|_| {
-                                    panic!("player {} secret {:?} not in secret", owner, card)
+                }
+                [None, None] => {
+                    return same_zone(public[0].as_ref().unwrap(), public[1].as_ref().unwrap())
+                }
+                [Some(player), _] | [_, Some(player)] => {
+                    let revealed = self
+                        .context
+                        .reveal_unique(
+                            player,
+                            move |secret| {
+                                let zones = [(0, a), (1, b)].map(|(i, card)| match public[i] {
+                                    Some(zone) => Either::A(zone),
+                                    None => secret_zone_of(secret, card),
                                 });
-                        });
+
+                                match zones {
+                                    [Either::A(a_zone), Either::A(b_zone)] => {
+                                        Either::A(same_zone(&a_zone, &b_zone))
+                                    }
+                                    [Either::B(id), _] => Either::B((0, id)),
+                                    [_, Either::B(id)] => Either::B((1, id)),
+                                }
+                            },
+                            |_| true,
+                        )
+                        .await;
+
+                    match revealed {
+                        Either::A(same) => return same,
+                        Either::B(found) => found,
                     }
                 }
+            };
+
+            // The pointer leads outside its player's secret, so follow its ID.
+            cards[index] = id.into();
+        }
+    }
+
+    pub async fn reveal_if_cards_ne(&mut self, a: impl Into<Card>, b: impl Into<Card>) -> bool {
+        !self.reveal_if_cards_eq(a, b).await
+    }
+
+    pub async fn reveal_if_any(
+        &mut self,
+        cards: Vec<Card>,
+        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
+    ) -> bool {
+        // todo!(): betterize this implementation
+
+        self.reveal_from_cards_fold(cards, f, false, move |acc, c| acc || *c)
+            .await
+    }
+
+    pub async fn reveal_if_every(
+        &mut self,
+        cards: Vec<Card>,
+        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
+    ) -> bool {
+        !self.reveal_if_any(cards, move |card| !f(card)).await
+    }
+
+    /// Checks an action's targets against their requirements, including secret targets.
+    ///
+    /// After `GameState::check_targets`, targets that can't be told apart publicly, e.g. two pointers,
+    /// are compared with `CardGame::reveal_if_cards_eq`, and only whether each secret target is legal is revealed.
+    pub async fn check_targets(
+        &mut self,
+        requirements: &[TargetRequirement<S>],
+        targets: &[Card],
+    ) -> Result<(), error::TargetError> {
+        self.state.check_targets(requirements, targets)?;
+
+        for (index, card) in targets.iter().enumerate() {
+            for other in &targets[..index] {
+                if other.eq(*card).is_err() && self.reveal_if_cards_eq(*other, *card).await {
+                    return Err(error::TargetError::Duplicate { card: *card });
+                }
             }
-            Card::Pointer(OpaquePointer { player, .. }) => {
-                self.context.mutate_secret(player, |secret| {
-                    secret
-                        .secret
-                        .modify_card(card, secret.log, |instance| f(instance))
-                        .unwrap_or_else(|_| {
-                            panic!("player {} secret {:?} not in secret", player, card)
-                        });
-                });
+        }
+
+        for (index, (requirement, card)) in requirements.iter().zip(targets).enumerate() {
+            let card = *card;
+
+            if self.bucket(card).is_some() {
+                let requirement = requirement.clone();
+
+                if !self
+                    .reveal_from_card(card, move |info| requirement.accepts(info))
+                    .await
+                {
+                    return Err(error::TargetError::Illegal { index, card });
+                }
             }
         }
 
-        card
+        Ok(())
     }
 
-    /// Internal API only.
-    /// Modifies a card and logs any changes.
-    /// TODO it would be nice to eliminiate all the duplication between here and `CardGame::modify_card`
-    pub(crate) async fn modify_card_internal(
+    /// Checks if any or every condition holds, where a condition holds if its player has a card in one of its zones satisfying its predicate.
+    ///
+    /// Reveals each player's secret at most once, no matter how many conditions or zones involve it,
+    /// and skips reveals that can't change the result.
+    /// Each reveal is only whether that player's remaining conditions combine to hold, not which of them hold.
+    pub async fn reveal_if(
         &mut self,
-        card: Card,
-        f: impl Fn(
-            &mut CardInstance<S>,
-            &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
-        ),
-        logger: &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
-    ) {
-        let card = if let Card::Pointer(OpaquePointer { player, index }) = card {
-            self.context
+        conditions: Vec<(Player, ZoneSet, CardPredicate<S>)>,
+        quantifier: Quantifier,
+    ) -> bool {
+        let results = self.public_conditions(&conditions);
+
+        for (result, (owner, zones, _)) in results.iter().zip(&conditions) {
+            match quantifier {
+                Quantifier::Any if *result => return true,
+                Quantifier::Every if !result && self.secret_zones(*owner, zones).is_empty() => {
+                    return false
+                }
+                _ => {}
+            }
+        }
+
+        for player in self.players() {
+            let pending: Vec<_> = conditions
+                .iter()
+                .zip(&results)
+                .filter(|((owner, _, _), result)| *owner == player && !**result)
+                .map(|((_, zones, f), _)| (self.secret_zones(player, zones), f.clone()))
+                .filter(|(zones, _)| !zones.is_empty())
+                .collect();
+
+            if pending.is_empty() {
+                continue;
+            }
+
+            let holds = self
+                .context
                 .reveal_unique(
                     player,
                     move |secret| {
-                        let id = secret.pointers[index];
+                        let mut results = pending
+                            .iter()
+                            .map(|(zones, f)| secret_condition(secret, zones, f));
 
-                        if secret.instances.contains_key(&id) {
-                            card
-                        } else {
-                            id.into()
+                        match quantifier {
+                            Quantifier::Any => results.any(|result| result),
+                            Quantifier::Every => results.all(|result| result),
                         }
                     },
                     |_| true,
                 )
-                .await
-        } else {
-            card
-        };
+                .await;
 
-        match card {
-            Card::ID(id) => {
-                let Self { state, .. } = self;
+            match quantifier {
+                Quantifier::Any if holds => return true,
+                Quantifier::Every if !holds => return false,
+                _ => {}
+            }
+        }
 
-                match &state.instances[id.0] {
-                    InstanceOrPlayer::Instance(_) => {
-                        let CardLocation {
-                            player: owner,
-                            location,
-                        } = state.location(id);
-                        let location =
-                            location.unwrap_or_else(|| panic!("public {:?} has no zone", id));
+        quantifier == Quantifier::Every
+    }
 
-                        let instance = state.instances[id.0]
-                            .instance_mut()
-                            .unwrap_or_else(|| panic!("{:?} vanished", id));
+    /// Checks which conditions hold, like `CardGame::reveal_if`, but reveals the result of each condition.
+    ///
+    /// Reveals each player's secret at most once, and only for conditions its public zones don't already satisfy.
+    pub async fn reveal_each_if(
+        &mut self,
+        conditions: Vec<(Player, ZoneSet, CardPredicate<S>)>,
+    ) -> Vec<bool> {
+        let mut results = self.public_conditions(&conditions);
 
-                        let before = instance.clone();
+        for player in self.players() {
+            let (indices, pending): (Vec<_>, Vec<_>) = conditions
+                .iter()
+                .enumerate()
+                .filter(|(i, (owner, _, _))| *owner == player && !results[*i])
+                .map(|(i, (_, zones, f))| (i, (self.secret_zones(player, zones), f.clone())))
+                .filter(|(_, (zones, _))| !zones.is_empty())
+                .unzip();
 
-                        f(instance, &mut |event| logger(event));
+            if pending.is_empty() {
+                continue;
+            }
 
-                        let instance = &*instance; // lose mutable ref
-                        if !before.eq(instance) {
-                            logger(CardEvent::ModifyCard {
-                                instance: instance.clone(),
-                            })
-                        }
-                        match location.0 {
-                            Zone::Field => self.sort_field(
-                                owner,
-                                self.player_cards(owner).field.clone(),
-                                true,
-                                logger,
-                            ),
-                            Zone::Attachment {
-                                parent: Card::ID(parent_id),
-                            } => {
-                                if let Some((Zone::Field, ..)) = self.location(parent_id).location {
-                                    self.sort_field(
-                                        owner,
-                                        self.player_cards(owner).field.clone(),
-                                        true,
-                                        logger,
-                                    );
-                                }
-                            }
-                            _ => (),
-                        }
-                    }
-                    InstanceOrPlayer::Player(owner) => {
-                        self.context.mutate_secret(*owner, |secret| {
-                            secret.secret.modify_card_internal(
-                                card,
-                                secret.log,
-                                |instance, log| f(instance, log),
-                            );
-                        });
-                    }
-                }
-            }
-            Card::Pointer(OpaquePointer { player, .. }) => {
-                self.context.mutate_secret(player, |secret| {
-                    secret
-                        .secret
-                        .modify_card_internal(card, secret.log, |instance, log| f(instance, log));
-                });
+            let revealed = self
+                .context
+                .reveal_unique(
+                    player,
+                    move |secret| {
+                        pending
+                            .iter()
+                            .map(|(zones, f)| secret_condition(secret, zones, f))
+                            .collect::<Vec<_>>()
+                    },
+                    |_| true,
+                )
+                .await;
+
+            for (i, result) in indices.into_iter().zip(revealed) {
+                results[i] = result;
             }
         }
+
+        results
     }
 
-    pub async fn modify_cards(&mut self, cards: Vec<Card>, f: impl Fn(CardInfoMut<S>)) {
-        // todo!(): betterize this implementation
+    /// A player's secret zones in a zone set, including each of their piles.
+    fn secret_zones(&self, player: Player, zones: &ZoneSet) -> Vec<Zone> {
+        zones.secret_zones(self.player_cards(player).piles().len())
+    }
 
-        for card in cards {
-            self.modify_card(card, &f).await;
+    /// Whether each condition of `CardGame::reveal_if` holds in its player's public zones.
+    fn public_conditions(&self, conditions: &[(Player, ZoneSet, CardPredicate<S>)]) -> Vec<bool> {
+        conditions
+            .iter()
+            .map(|(player, zones, f)| {
+                zones.public_zones().into_iter().any(|zone| {
+                    self.player_cards(*player)
+                        .zone_cards(zone)
+                        .into_iter()
+                        .any(|id| f(self.card_info(id)))
+                })
+            })
+            .collect()
+    }
+
+    /// Calls `f` with borrowed card info if the card is a public ID to a public instance.
+    ///
+    /// Returns `None` without revealing anything otherwise.
+    pub fn try_inspect_card<'a, T>(
+        &'a self,
+        card: impl Into<Card>,
+        f: impl FnOnce(CardInfo<'a, S>) -> T,
+    ) -> Option<T> {
+        match card.into() {
+            Card::ID(id) if self.instances[id.0].instance_ref().is_some() => {
+                Some(f(self.card_info(id)))
+            }
+            _ => None,
         }
     }
-    pub async fn change_base_card(&mut self, id: InstanceID, new_base: S::BaseCard) {
-        self.modify_card(id, |mut c| {
-            c.base = new_base.clone();
-        })
-        .await;
+
+    /// Like `CardGame::reveal_from_card`, but only reveals if the card isn't a public ID to a public instance.
+    pub async fn inspect_card<T: AnySecretData>(
+        &mut self,
+        card: impl Into<Card>,
+        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
+    ) -> T {
+        let card = card.into();
+
+        if let Some(value) = self.try_inspect_card(card, f.clone()) {
+            return value;
+        }
+
+        self.reveal_from_card(card, f).await
     }
-    pub async fn move_card(
+
+    pub async fn reveal_from_card<T: AnySecretData>(
         &mut self,
         card: impl Into<Card>,
-        to_player: Player,
-        to_zone: Zone,
-    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
-        return inner(self, card.into(), to_player, to_zone).await;
+        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
+    ) -> T {
+        self.try_reveal_from_card(card, f)
+            .await
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        async fn inner<S: State>(
-            this: &mut CardGame<S>,
-            card: Card,
-            to_player: Player,
-            to_zone: Zone,
-        ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
-            let old_field = if to_zone.is_field() {
-                Some(this.player_cards(to_player).field.clone())
-            } else {
-                None
-            };
-            let to_bucket = match to_zone {
-                Zone::Deck => Some(to_player),
-                Zone::Hand { public: false } => Some(to_player),
-                Zone::Hand { public: true } => None,
-                Zone::Field => None,
-                Zone::Graveyard => None,
-                Zone::Limbo { public: false } => Some(to_player),
-                Zone::Limbo { public: true } => None,
-                Zone::CardSelection => Some(to_player),
-                Zone::Casting => None,
-                Zone::Dust { public: false } => Some(to_player),
-                Zone::Dust { public: true } => None,
-                Zone::Attachment { parent } => {
-                    return this.attach_card(card, parent).await;
-                }
-                Zone::HeroAbility => None,
-                Zone::Boon => None,
-            };
-            // We always need to know who owns the card instance itself.
+    /// Like `CardGame::reveal_from_card`, but fails if the card doesn't exist, e.g. because it was destroyed.
+    ///
+    /// A pointer to a card that has since moved to public state or another player's secret is followed there.
+    pub async fn try_reveal_from_card<T: AnySecretData>(
+        &mut self,
+        card: impl Into<Card>,
+        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
+    ) -> Result<T, error::RevealCardError> {
+        let card = card.into();
 
-            // Either this card is in Public state (None) or a player's secret (Some(player)).
-            // We also need to know who owns the card, regardless of its secrecy, so we can later update the public state for that player.
-            let (bucket, owner) = match card {
-                Card::Pointer(OpaquePointer { player, index }) => {
-                    let buckets: Vec<_> = this
-                        .instances
-                        .iter()
-                        .enumerate()
-                        .map(|(i, instance)| (instance.player(), this.owner(InstanceID(i))))
-                        .collect();
+        if !self.exists(card) {
+            return Err(error::RevealCardError::CardDoesNotExist { card });
+        }
 
-                    this.context
-                        .reveal_unique(
-                            player,
-                            move |secret| buckets[secret.pointers[index].0],
-                            |_| true,
-                        )
-                        .await
-                }
-                Card::ID(id) => (this.instances[id.0].player(), this.owner(id)),
-            };
+        let id = match card {
+            Card::ID(id) => id,
+            Card::Pointer(OpaquePointer { player, index }) => {
+                let revealed = self
+                    .context
+                    .reveal_unique(
+                        player,
+                        {
+                            let f = f.clone();
 
-            let id = match card {
-                Card::ID(id) => Some(id),
-                Card::Pointer(OpaquePointer { player, index }) => {
-                    if bucket != Some(player) || to_bucket != Some(player) {
-                        Some(
-                            this.context
-                                .reveal_unique(
-                                    player,
-                                    move |secret| secret.pointers[index],
-                                    |_| true,
-                                )
-                                .await,
-                        )
-                    } else {
-                        // The pointer, the card, and the destination all exist within one player's secret.
-                        // Therefore, the instance ID need not be revealed.
+                            // A pointer to a destroyed card reveals `InstanceID::DESTROYED`.
+                            move |secret| {
+                                secret
+                                    .reveal_from_card(secret.pointers[index], |instance| {
+                                        Either::A(f(instance))
+                                    })
+                                    .unwrap_or_else(|| Either::B(secret.pointers[index]))
+                            }
+                        },
+                        |_| true,
+                    )
+                    .await;
 
-                        None
+                match revealed {
+                    Either::A(result) => return Ok(result),
+                    Either::B(InstanceID::DESTROYED) => {
+                        return Err(error::RevealCardError::CardDoesNotExist { card })
                     }
+                    Either::B(id) => id,
                 }
-            };
-            // Reveal the zone that a card came from
-            let location = match bucket {
-                None => {
-                    let id = id.expect("ID should have been revealed in this case");
+            }
+        };
 
-                    Some(
-                        this.location(id)
-                            .location
-                            .expect("CardLocation for a public card must be public."),
-                    )
-                }
-                Some(player) => {
-                    this.context.mutate_secret(player, move |mut secret| {
-                        let location =
-                            secret
-                                .location(id.unwrap_or_else(|| {
-                                    secret.pointers[card.pointer().unwrap().index]
-                                }))
-                                .location
-                                .expect("The secret should know the zone.");
+        match &self.instances[id.0] {
+            InstanceOrPlayer::Instance(..) => Ok(f(self.card_info(id))),
+            InstanceOrPlayer::Destroyed(..) => {
+                Err(error::RevealCardError::CardDoesNotExist { card })
+            }
+            // Revealed as a result, since an option would lose `Some(None)` in serialization.
+            InstanceOrPlayer::Player(owner) => self
+                .context
+                .reveal_unique(
+                    *owner,
+                    move |secret| secret.reveal_from_card(id, f.clone()).ok_or(()),
+                    |_| true,
+                )
+                .await
+                .map_err(|()| error::RevealCardError::CardDoesNotExist { card }),
+        }
+    }
 
-                        match location.0 {
-                            Zone::Limbo { public: false } | Zone::Attachment { .. } => {
-                                secret.deferred_locations.push(location);
-                            }
-                            _ => {
-                                // location will get revealed publicly
-                            }
-                        }
-                    });
+    pub async fn reveal_from_cards<T: AnySecretData>(
+        &mut self,
+        cards: Vec<Card>,
+        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
+    ) -> Vec<T> {
+        // todo!(): betterize this implementation
 
-                    this.context
-                        .reveal_unique(
-                            player,
-                            move |secret| {
-                                let location = secret
-                                    .location(id.unwrap_or_else(|| {
-                                        secret.pointers[card.pointer().unwrap().index]
-                                    }))
-                                    .location
-                                    .expect("The secret should know the zone.");
+        let mut revealed = Vec::with_capacity(cards.len());
 
-                                match location.0 {
-                                    Zone::Limbo { public: false } | Zone::Attachment { .. } => {
-                                        None // secret.deferred_locations has this key.
-                                    }
-                                    _ => Some(location),
-                                }
-                            },
-                            |_| true,
-                        )
-                        .await
-                }
-            };
+        for card in cards {
+            revealed.push(self.reveal_from_card(card, f.clone()).await);
+        }
 
-            // Special case, secret -> secret for a single player
-            if let Some(bucket_owner) = bucket {
-                if to_bucket == bucket {
-                    if let Some((zone, index)) = location {
-                        this.player_cards_mut(bucket_owner).remove_from(zone, index);
-                    }
+        revealed
+    }
 
-                    // Update the public state about where we put this card
-                    let player_state = this.player_cards_mut(to_player);
-                    match to_zone {
-                        Zone::Deck => {
-                            player_state.deck += 1;
-                        }
-                        Zone::Hand { public: false } => {
-                            player_state.hand.push(None);
-                        }
-                        Zone::Hand { public: true } => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::Field => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::Graveyard => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::Limbo { public: false } => {
-                            // do nothing, this is a secret
-                        }
-                        Zone::Limbo { public: true } => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::CardSelection => {
-                            player_state.card_selection += 1;
-                        }
-                        Zone::Casting => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::Dust { public: false } => {
-                            // do nothing, this is a secret
-                        }
-                        Zone::Dust { public: true } => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::Attachment { .. } => {
-                            unreachable!("Cannot move card to attachment zone")
-                        }
-                        Zone::HeroAbility => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
-                        Zone::Boon => {
-                            unreachable!("{}:{}:{}", file!(), line!(), column!());
-                        }
+    /// Like `CardGame::reveal_from_cards`, but only reveals from the chunk of `cards` starting at `start`, see `MatchConfig::batch_chunk_size`.
+    ///
+    /// Call it again with `BatchChunk::next`, e.g. in a later action, until it's `None`.
+    pub async fn reveal_from_cards_chunk<T: AnySecretData>(
+        &mut self,
+        cards: &[Card],
+        start: usize,
+        f: impl Fn(CardInfo<S>) -> T + Clone + 'static,
+    ) -> BatchChunk<T> {
+        let (chunk, next) = self.batch_chunk(cards, start);
+
+        BatchChunk {
+            results: self.reveal_from_cards(chunk.to_vec(), f).await,
+            next,
+        }
+    }
+
+    /// The chunk of a batch starting at `start`, and where the next chunk starts, if the batch continues.
+    ///
+    /// Chunk boundaries only depend on the batch's length and `MatchConfig::batch_chunk_size`,
+    /// so every client splits a batch at the same points.
+    fn batch_chunk<'c>(&self, cards: &'c [Card], start: usize) -> (&'c [Card], Option<usize>) {
+        let start = start.min(cards.len());
+        let end = match self.config.batch_chunk_size.filter(|size| *size > 0) {
+            Some(size) => cards.len().min(start.saturating_add(size)),
+            None => cards.len(),
+        };
+
+        (
+            &cards[start..end],
+            Some(end).filter(|end| *end < cards.len()),
+        )
+    }
+
+    pub async fn reveal_from_cards_fold<T, B, F, G>(
+        &mut self,
+        cards: Vec<Card>,
+        map: G,
+        init: B,
+        fold: F,
+    ) -> B
+    where
+        T: Clone + 'static,
+        B: AnySecretData + 'static,
+        F: Fn(B, &T) -> B + Clone + 'static,
+        G: Fn(CardInfo<S>) -> T + Clone + 'static,
+    {
+        let (public_cards, secret_cards) = {
+            let mut public_cards = vec![];
+            let mut secret_cards = vec![vec![]; self.all_player_cards().len()];
+            for card in cards {
+                match card {
+                    Card::ID(id) => match &self.instances[id.0] {
+                        InstanceOrPlayer::Instance(_) => public_cards.push(id),
+                        InstanceOrPlayer::Player(owner) => secret_cards[*owner as usize].push(card),
+                        InstanceOrPlayer::Destroyed(..) => panic!("{:?} was destroyed", id),
+                    },
+                    Card::Pointer(OpaquePointer { player, .. }) => {
+                        secret_cards[player as usize].push(card)
                     }
+                }
+            }
+            (public_cards, secret_cards)
+        };
 
-                    let to_location = (
-                        to_zone,
-                        match to_zone {
-                            Zone::Deck => this.player_cards(to_player).deck() - 1,
-                            Zone::Hand { public: false } => {
-                                this.player_cards(to_player).hand().len() - 1
-                            }
-                            Zone::Hand { public: true } => {
-                                unreachable!("{}:{}:{}", file!(), line!(), column!())
-                            }
-                            Zone::Field => unreachable!("{}:{}:{}", file!(), line!(), column!()),
-                            Zone::Graveyard => {
-                                unreachable!("{}:{}:{}", file!(), line!(), column!())
-                            }
-                            Zone::Limbo { public: false } => 0,
-                            Zone::Limbo { public: true } => {
-                                unreachable!("{}:{}:{}", file!(), line!(), column!())
-                            }
-                            Zone::CardSelection => 0,
-                            Zone::Casting => unreachable!("{}:{}:{}", file!(), line!(), column!()),
-                            Zone::Dust { public: false } => 0,
-                            Zone::Dust { public: true } => {
-                                unreachable!("{}:{}:{}", file!(), line!(), column!())
-                            }
-                            Zone::Attachment { .. } => 0,
-                            Zone::HeroAbility => {
-                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+        let every_single_public_card: indexmap::IndexMap<InstanceID, T> = self
+            .instances
+            .iter()
+            .filter_map(|instance| {
+                instance.instance_ref().map(|i| {
+                    let id = i.id();
+                    let info = self.card_info(id);
+                    (id, map(info))
+                })
+            })
+            .collect();
+
+        let mut accumulated = public_cards.into_iter().fold(init, |prev, pub_id| {
+            fold(
+                prev,
+                every_single_public_card
+                    .get(&pub_id)
+                    .expect("public cards are public"),
+            )
+        });
+
+        for (player, cards) in secret_cards.into_iter().enumerate() {
+            if cards.is_empty() {
+                continue;
+            }
+            let map = map.clone();
+            let fold = fold.clone();
+            let every_single_public_card = every_single_public_card.clone();
+            accumulated = self
+                .context
+                .reveal_unique(
+                    player as u8,
+                    move |secret| {
+                        let map = map.clone();
+                        let fold = fold.clone();
+                        let cards = cards.clone();
+                        let every_single_public_card = every_single_public_card.clone();
+                        cards
+                            .into_iter()
+                            .fold(accumulated.clone(), move |prev, card| {
+                                let id = secret.id(card).unwrap();
+                                if secret.instances.get(&id).is_some() {
+                                    let map = map.clone();
+                                    fold(
+                                        prev,
+                                        &secret
+                                            .reveal_from_card(card, move |c| map(c))
+                                            .unwrap_or_else(|| {
+                                                panic!(
+                                                    "{:?} not in player {:?} secret",
+                                                    card, player
+                                                )
+                                            }),
+                                    )
+                                } else {
+                                    // card is in public state
+                                    fold(
+                                        prev,
+                                        every_single_public_card.get(&id).expect(
+                                            "Card must be in public, since it's not in secret.",
+                                        ),
+                                    )
+                                }
+                            })
+                    },
+                    |_| true,
+                )
+                .await;
+        }
+
+        accumulated
+    }
+
+    pub async fn reveal_parent(&mut self, card: impl Into<Card>) -> Option<Card> {
+        let card = card.into();
+
+        match card {
+            Card::ID(id) => match self.instances[id.0] {
+                InstanceOrPlayer::Instance(..) => {
+                    let mut parents = self.instances.iter().filter_map(|instance| {
+                        instance.instance_ref().and_then(|instance| {
+                            if instance.attachment == Some(id) {
+                                Some(instance.id())
+                            } else {
+                                None
                             }
-                            Zone::Boon => {
-                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                        })
+                    });
+
+                    parents.next().map(|parent| {
+                        assert!(parents.next().is_none());
+
+                        parent.into()
+                    })
+                }
+                InstanceOrPlayer::Destroyed(..) => None,
+                InstanceOrPlayer::Player(owner) => {
+                    let parents = self
+                        .new_secret_pointers(owner, |mut secret| {
+                            let parents: Vec<_> = secret
+                                .instances
+                                .values()
+                                .filter_map(|instance| {
+                                    if instance.attachment == Some(id) {
+                                        Some(instance.id())
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .collect();
+
+                            assert!(parents.len() <= 1);
+
+                            parents
+                                .into_iter()
+                                .for_each(|parent| secret.new_pointer(parent));
+                        })
+                        .await;
+
+                    assert!(parents.len() <= 1);
+
+                    parents.into_iter().next()
+                }
+            },
+            Card::Pointer(OpaquePointer { player, index }) => {
+                let id = self
+                    .context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            let id = secret.pointers[index];
+
+                            let parents = secret
+                                .instances
+                                .values()
+                                .filter(|instance| instance.attachment == Some(id))
+                                .count();
+
+                            match parents {
+                                0 => Some(id),
+                                1 => None,
+                                parents => unreachable!("{:?} has {} parents", id, parents),
                             }
                         },
-                    );
-                    this.context.mutate_secret_or_log(
-                        bucket_owner,
-                        |mut secret| {
-                            let id = id
-                                .unwrap_or_else(|| secret.pointers[card.pointer().unwrap().index]);
-                            let old_location = secret.location(id);
+                        |_| true,
+                    )
+                    .await;
+
+                match id {
+                    None => {
+                        let parents = self
+                            .new_secret_pointers(player, |mut secret| {
+                                let id = secret.pointers[index];
+
+                                let parents: Vec<_> = secret
+                                    .instances
+                                    .values()
+                                    .filter_map(|instance| {
+                                        if instance.attachment == Some(id) {
+                                            Some(instance.id())
+                                        } else {
+                                            None
+                                        }
+                                    })
+                                    .collect();
+
+                                assert!(parents.len() <= 1);
+
+                                parents
+                                    .into_iter()
+                                    .for_each(|parent| secret.new_pointer(parent));
+                            })
+                            .await;
+
+                        assert!(parents.len() <= 1);
+
+                        parents.into_iter().next()
+                    }
+                    Some(id) => match self.instances[id.0] {
+                        InstanceOrPlayer::Instance(..) => {
+                            let mut parents = self.instances.iter().filter_map(|instance| {
+                                instance.instance_ref().and_then(|instance| {
+                                    if instance.attachment == Some(id) {
+                                        Some(instance.id())
+                                    } else {
+                                        None
+                                    }
+                                })
+                            });
+
+                            parents.next().map(|parent| {
+                                assert!(parents.next().is_none());
+
+                                parent.into()
+                            })
+                        }
+                        InstanceOrPlayer::Destroyed(..) => None,
+                        InstanceOrPlayer::Player(owner) => {
+                            let parents = self
+                                .new_secret_pointers(owner, |mut secret| {
+                                    let parents: Vec<_> = secret
+                                        .instances
+                                        .values()
+                                        .filter_map(|instance| {
+                                            if instance.attachment == Some(id) {
+                                                Some(instance.id())
+                                            } else {
+                                                None
+                                            }
+                                        })
+                                        .collect();
+
+                                    assert!(parents.len() <= 1);
+
+                                    parents
+                                        .into_iter()
+                                        .for_each(|parent| secret.new_pointer(parent));
+                                })
+                                .await;
+
+                            assert!(parents.len() <= 1);
+
+                            parents.into_iter().next()
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    /// Gets a card's attachment, if any, as a pointer if it's secret.
+    ///
+    /// Unlike `CardGame::reveal_from_card`, this doesn't reveal either instance,
+    /// only whether a secret card has an attachment, and the ID of a public card behind a pointer.
+    /// Returns `None` for a card that doesn't exist, e.g. because it was destroyed.
+    pub async fn reveal_attachment(&mut self, card: impl Into<Card>) -> Option<Card> {
+        let card = self.reveal_public_id(card.into()).await.ok()?;
+
+        let (player, card) = match card {
+            Card::ID(id) => match self.instances[id.0] {
+                InstanceOrPlayer::Instance(..) => return self.attachment_of(id).map(Into::into),
+                InstanceOrPlayer::Player(owner) => (owner, card),
+                InstanceOrPlayer::Destroyed(..) => return None,
+            },
+            Card::Pointer(OpaquePointer { player, .. }) => (player, card),
+        };
+
+        self.new_secret_pointers(player, |mut secret| {
+            let attachment = secret
+                .instance(card)
+                .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", player, card))
+                .attachment;
+
+            if let Some(attachment) = attachment {
+                secret.new_pointer(attachment);
+            }
+        })
+        .await
+        .pop()
+    }
+
+    pub async fn reveal_parents(&mut self, cards: Vec<Card>) -> Vec<Option<Card>> {
+        // todo!(): betterize this implementation
+
+        let mut parents = Vec::with_capacity(cards.len());
+
+        for card in cards {
+            parents.push(self.reveal_parent(card).await);
+        }
+
+        parents
+    }
+
+    pub async fn filter_cards(
+        &mut self,
+        cards: Vec<Card>,
+        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
+    ) -> Vec<Card> {
+        let f = self.reveal_from_cards(cards.clone(), f).await;
+
+        assert_eq!(f.len(), cards.len());
+
+        cards
+            .into_iter()
+            .zip(f)
+            .filter_map(|(card, f)| if f { Some(card) } else { None })
+            .collect()
+    }
+
+    /// The number of cards matching `f`.
+    ///
+    /// Secret cards are counted inside their players' secrets, so only the total is revealed, not which cards matched.
+    /// A pointer to a card in another player's secret reveals the card's instance ID.
+    /// Cards that don't exist, e.g. destroyed cards, don't match.
+    pub async fn count_matching(
+        &mut self,
+        cards: Vec<Card>,
+        f: impl Fn(CardInfo<S>) -> bool + Clone + 'static,
+    ) -> usize {
+        let public: Vec<_> = (0..self.instances.len())
+            .map(|id| match self.instances[id] {
+                InstanceOrPlayer::Instance(..) => Some(f(self.card_info(InstanceID(id)))),
+                InstanceOrPlayer::Player(..) | InstanceOrPlayer::Destroyed(..) => None,
+            })
+            .collect();
+
+        let mut count = 0;
+        let mut secret_cards: Vec<Vec<Card>> = self.players().map(|_| Vec::new()).collect();
+
+        for card in cards {
+            match card {
+                _ if !self.exists(card) => {}
+                Card::ID(id) => match self.instances[id.0] {
+                    InstanceOrPlayer::Instance(..) => count += usize::from(public[id.0].unwrap()),
+                    InstanceOrPlayer::Player(owner) => secret_cards[usize::from(owner)].push(card),
+                    InstanceOrPlayer::Destroyed(..) => {}
+                },
+                Card::Pointer(OpaquePointer { player, .. }) => {
+                    secret_cards[usize::from(player)].push(card)
+                }
+            }
+        }
+
+        while let Some(player) = self
+            .players()
+            .find(|player| !secret_cards[usize::from(*player)].is_empty())
+        {
+            let cards = std::mem::take(&mut secret_cards[usize::from(player)]);
+            let public = public.clone();
+            let f = f.clone();
+
+            let (matching, elsewhere): (usize, Vec<InstanceID>) = self
+                .context
+                .reveal_unique(
+                    player,
+                    move |secret| {
+                        let mut matching = 0;
+                        let mut elsewhere = Vec::new();
+
+                        for id in cards.iter().filter_map(|card| secret.id(*card)) {
+                            match secret.reveal_from_card(id, f.clone()).or(public[id.0]) {
+                                Some(true) => matching += 1,
+                                Some(false) => {}
+                                None => elsewhere.push(id),
+                            }
+                        }
+
+                        (matching, elsewhere)
+                    },
+                    |_| true,
+                )
+                .await;
+
+            count += matching;
+
+            for id in elsewhere {
+                match self.instances[id.0] {
+                    InstanceOrPlayer::Player(owner) if owner != player => {
+                        secret_cards[usize::from(owner)].push(id.into())
+                    }
+                    _ => panic!("{:?} not in player {:?} secret", id, player),
+                }
+            }
+        }
+
+        count
+    }
+
+    pub async fn reset_card(&mut self, card: impl Into<Card>) {
+        if self.result().is_some() {
+            return;
+        }
+
+        let card = match self.reveal_public_id(card.into()).await {
+            Ok(card) => card,
+            Err(..) => return,
+        };
+
+        match card {
+            Card::ID(id) => match &self.instances[id.0] {
+                InstanceOrPlayer::Instance(instance) => {
+                    // public ID to public instance
+                    let owner = self.location(id).player;
+
+                    let attachment_id = instance.attachment();
+                    let base = instance.base.clone();
+                    let base_clone = base.clone();
+                    let state = instance.state.clone();
+
+                    let new_state = self.context.reveal(owner, move |secret| {
+                        secret.secret.default_card_state(&id, base.clone(), &state)
+                    }, |_| true).await;
+                    let new_attachment =  self.context.reveal(owner, move |secret| secret.secret.attachment(&id, base_clone.clone()), |_| true).await.clone();
+                    let attachment = attachment_id.map(|attachment| {
+                        self.instances[attachment.0]
+                            .instance_ref()
+                            .unwrap_or_else(|| {
+                                panic!("public {:?} attachment {:?} not public", id, attachment)
+                            })
+                    });
+                    match (
+                        attachment.map(|attachment| &attachment.base),
+                        new_attachment,
+                    ) {
+                        (None, None) => {
+                            // do nothing
+                        }
+                        (Some(..), None) => {
+                            // dust current attachment
+
+                            let attachment = attachment
+                                .expect("attachment base exists, but no attachment")
+                                .id();
+
+                            self.move_card(attachment, owner, Zone::Dust { public: true })
+                                .await
+                                .unwrap_or_else(|_| {
+                                    panic!(
+                                        "unable to move attachment {:?} to public dust",
+                                        attachment
+                                    )
+                                });
+                        }
+                        (None, Some(default)) => {
+                            // attach base attachment
+
+                            // create attach state using new_card_state from implementor
+                            let new_state = default.new_card_state(Some(&new_state));
+                            let attachment = self.new_public_card(owner, default, None).await;
+
+                            self.modify_card(attachment, move |mut c| {
+                                c.state = new_state.clone();
+                            })
+                            .await;
+
+                            self.move_card(
+                                attachment,
+                                owner,
+                                Zone::Attachment { parent: id.into() },
+                            )
+                            .await
+                            .unwrap_or_else(|_| {
+                                panic!("unable to attach public limbo {:?} to {:?}", attachment, id)
+                            });
+                        }
+                        (Some(current), Some(default)) if *current == default => {
+                            // reset current attachment
+
+                            let attachment = attachment
+                                .expect("attachment base exists, but no attachment")
+                                .id();
+                            let new_state = default.new_card_state(Some(&new_state));
+
+                            self.modify_card(attachment, move |mut c| {
+                                c.state = new_state.clone();
+                            })
+                            .await;
+                        }
+                        (Some(..), Some(default)) => {
+                            // attach base attachment, will implicitly dust current attachment.
+                            let new_state = default.new_card_state(Some(&new_state));
+
+                            let attachment = self.new_public_card(owner, default, None).await;
+                            // create attach state using new_card_state from implementor
+
+                            self.modify_card(attachment, move |mut c| {
+                                c.state = new_state.clone();
+                            })
+                            .await;
+
+                            self.move_card(
+                                attachment,
+                                owner,
+                                Zone::Attachment { parent: id.into() },
+                            )
+                            .await
+                            .unwrap_or_else(|_| {
+                                panic!("unable to attach public limbo {:?} to {:?}", attachment, id)
+                            });
+                        }
+                    }
+                    
+                        
+                    self.modify_card(id, |mut c| {
+                        c.state = new_state.clone();
+
+                        if let Some(attach) = c.attachment {
+                            S::on_attach(&mut *c, &attach);
+                        }
+                    })
+                    .await;
+                }
+                InstanceOrPlayer::Destroyed(..) => panic!("{:?} was destroyed", id),
+                InstanceOrPlayer::Player(owner) => {
+                    // public ID to secret instance
+
+                    let owner = *owner;
+
+                    self.new_secret_cards(owner, |mut secret| {
+                        let instance = secret
+                            .instance(id)
+                            .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", owner, id));
+                        let base = instance.base.clone();
+
+                        let attachment = instance.attachment().map(|attachment| {
+                            secret.instance(attachment).unwrap_or_else(|| panic!("player {} secret {:?} attachment {:?} not secret", owner, id, attachment))
+                        });
+
+
+                            match (
+                                attachment,
+                                secret.secret.attachment(&id, base.clone()),
+                            ) {
+                                (None, None) => {
+                                    // do nothing
+                                }
+                                (Some(current), None) => {
+                                    // dust current attachment
+
+                                    let current_id = current.id();
+
+                                    secret.dust_card(current_id).expect("current_id is in this secret, and is not already dust.");
+                                }
+                                (None, Some(default)) => {
+                                    // attach base attachment
+                                    let new_attach = secret.new_card(default, None);
+                                    secret.attach_card(id, new_attach).expect("Unable to secretly attach a secret card to another card in the same secret.");
+                                }
+                                (Some(current), Some(default)) if current.base == default => {
+                                    // reset current attachment
+                                    let attachment_base_state = current.base.new_card_state(Some(&instance.state));
+                                    let current_id = current.id();
+                                    secret.secret_mut().instance_mut(current_id).unwrap().state = attachment_base_state;
+                            }
+                                (Some(_), Some(default)) => {
+                                     // attach base attachment, current attachment is implicitly dusted.
+                                     let new_attach = secret.new_card(default, None);
+                                     secret.attach_card(id, new_attach).expect("Unable to secretly attach a secret card to another card in the same secret.");
+                                }
+                            }
+
+
+                        let state = secret.instance(id).unwrap().state.clone();
+                        let new_state = secret.secret.default_card_state(&id, base.clone(), &state);
+                        secret.modify_card(id, |mut c| {
+                                c.state = new_state.clone();
+                            if let Some(attach) = c.attachment {
+                                S::on_attach(&mut *c, &attach);
+                            }
+                        }).expect("Failed to reset card in this secret.");
+                    }).await;
+                }
+            },
+            Card::Pointer(OpaquePointer { player, index }) => {
+                self.new_secret_cards(player, |mut secret| {
+                    let id = secret.pointers[index];
+
+                    let instance = secret
+                        .instance(id)
+                        .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", player, id));
+                    let base = instance.base.clone();
+                    
+                    let new_state = secret.secret.default_card_state(&id, base.clone(), &instance.state);
+
+                    let attachment = instance.attachment().map(|attachment| {
+                        secret.instance(attachment).unwrap_or_else(|| panic!("player {} secret {:?} attachment {:?} not secret", player, id, attachment))
+                    });
+
+                    let next_instance = secret.next_instance.expect(
+                        "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
+                    );
+
+                    match (attachment, secret.secret.attachment(&id, base.clone())) {
+                        (None, None) => {
+                            // do nothing
+                        }
+                        (Some(current), None) => {
+                            // dust current attachment
+
+                            let current_id = current.id();
+
+                            secret
+                                .dust_card(current_id)
+                                .expect("current_id is in this secret, and is not already dust.");
+                        }
+                        (None, Some(default)) => {
+                            // attach base attachment
+
+                            let state = default.new_card_state(Some(&instance.state));
+
+                            let attachment = CardInstance {
+                                id: next_instance,
+                                base: default,
+                                attachment: None,
+                                state,
+                                token: false,
+                                original_owner: secret.player(),
+                            };
+
+                            secret.secret_mut().instances.insert(next_instance, attachment);
+
+                            secret
+                                .attach_card(id, next_instance)
+                                .expect("Both id and next_instance are in this secret.");
+                        }
+                        (Some(current), Some(default)) if current.base == default => {
+                            // reset current attachment
+                            let attachment_base_state = current.base.new_card_state(Some(&instance.state));
+                            let current_id = current.id();
+                            secret.secret_mut().instance_mut(current_id).unwrap().state = attachment_base_state;
+                        }
+                        (Some(current), Some(default)) => {
+                            // dust current attachment
+                            let state = default.new_card_state(Some(&instance.state));
+                            let current_id = current.id();
+                            secret
+                                .dust_card(current_id)
+                                .expect("current_id is in this secret, and is not already dust.");
+
+                            // Attach base attachment
+
+                            let attachment = CardInstance {
+                                id: next_instance,
+                                base: default,
+                                attachment: None,
+                                state,
+                                token: false,
+                                original_owner: secret.player(),
+                            };
+
+                            secret.secret_mut().instances.insert(next_instance, attachment);
+
+                            secret
+                                .attach_card(id, next_instance)
+                                .expect("Both id and next_instance are in this secret.");
+                        }
+                    }
+
+                    // unconditionally increment instance ID to avoid leaking attachment information
+
+                    secret.secret_mut()
+                        .next_instance
+                        .as_mut()
+                        .expect(
+                            "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
+                        )
+                        .0 += 1;
+
+                    let attachment = secret
+                        .instance(id).unwrap().attachment().map(|attachment| {
+                            secret.instance(attachment).unwrap_or_else(|| panic!("player {} secret {:?} attachment {:?} not secret", player, id, attachment)).clone()
+                        });
+
+                    let instance = secret.secret_mut()
+                        .instance_mut(id)
+                        .expect("immutable instance exists, but no mutable instance");
+                    
+                    instance.state = new_state.clone();
+                    
+                    if let Some(attach) = attachment {
+                        S::on_attach(instance, &attach);
+                    }
+                })
+                .await;
+            }
+        }
+    }
+
+    pub async fn reset_cards(&mut self, cards: Vec<Card>) {
+        // todo!(): betterize this implementation
+
+        for card in cards {
+            self.reset_card(card).await;
+        }
+    }
+
+    /// Copies a card.
+    ///
+    /// If the card is a public ID to player X's public instance, the card is copied to player X's public limbo.
+    /// If the card is a public ID to player X's secret instance, the card is copied to player X's secret limbo.
+    /// If the card is player X's secret pointer to a public instance, the card is copied to player X's secret limbo.
+    /// If the card is player X's secret pointer to player X's secret instance, the card is copied to player X's secret limbo.
+    /// If the card is player X's secret pointer to player Y's secret instance, the card is copied to player Y's secret limbo.
+    ///
+    /// Logs a `CardEvent::CopyCard` with the copy's instance for the players who can see it.
+    /// Returns `None` once the game is over, or if the card was destroyed.
+    pub fn copy_card<'a>(
+        &'a mut self,
+        card: impl Into<Card>,
+        deep: bool,
+    ) -> Pin<Box<dyn Future<Output = Option<Card>> + 'a>> {
+        let card = card.into();
+
+        return Box::pin(async move {
+            if self.result().is_some()
+                || self.check_movable(card).is_err()
+                || self.check_pointer(card).await.is_err()
+            {
+                return None;
+            }
+
+            let copy = inner(self, card, deep).await;
+
+            self.log_copy(card, copy, deep);
+
+            Some(copy)
+        });
+
+        fn inner<'a, S: State>(
+            this: &'a mut CardGame<S>,
+            card: Card,
+            deep: bool,
+        ) -> Pin<Box<dyn Future<Output = Card> + 'a>> {
+            Box::pin(async move {
+                match card {
+                    Card::ID(id) => match &this.instances[id.0] {
+                        InstanceOrPlayer::Instance(instance) => {
+                            let owner = this.owner(id);
+                            let base = instance.base.clone();
+                            let base_clone = base.clone();
+                            let state = instance.state.copy_card();
+                            let token = instance.token;
+                            let attachment = if deep {
+                                if let Some(attachment) = instance.attachment {
+                                    Some(
+                                        this.copy_card(attachment, deep)
+                                            .await
+                                            .and_then(|copy| copy.id())
+                                            .expect("public card attachment copy must be public"),
+                                    )
+                                } else {
+                                    None
+                                }
+                            } else if let Some(attachment) = this.context.reveal(owner, move |secret| secret.secret.attachment(&id, base_clone.clone()), |_| true).await {
+                                Some(this.new_public_card(owner, attachment, None).await)
+                            } else {
+                                None
+                            };
+
+                            let copy_id = InstanceID(this.instances.len());
+                            let copy = CardInstance {
+                                id: copy_id,
+                                base,
+                                state,
+                                attachment: None,
+                                token,
+                                original_owner: owner,
+                            };
+                            this.state.push_instance(copy);
+
+                            this.player_cards_mut(owner).limbo.push(copy_id);
+
+                            if let Some(attachment) = attachment {
+                                this.move_card(
+                                    attachment,
+                                    owner,
+                                    Zone::Attachment {
+                                        parent: copy_id.into(),
+                                    },
+                                )
+                                .await
+                                .unwrap();
+                            }
+                            copy_id.into()
+                        }
+                        InstanceOrPlayer::Destroyed(..) => panic!("{:?} was destroyed", id),
+                        InstanceOrPlayer::Player(owner) => {
+                            let owner = *owner;
+                            this.new_secret_cards(owner, |mut secret| {
+                            let (copy_id, attach_id) = {
+                                let mut next_instance = secret.next_instance.expect(
+                                    "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
+                                );
+                                let attach_id = next_instance;
+                                next_instance.0 += 1;
+                                let copy_id = next_instance;
+                                next_instance.0 += 1;
+                                secret.secret_mut().next_instance = Some(next_instance);
+                                (copy_id, attach_id)
+                            };
+                            let instance = &secret.instances[&id];
+
+                            let base = instance.base.clone();
+                            let state = instance.state.copy_card();
+                            let token = instance.token;
+
+                            let attachment = if deep {
+                                if let Some(attachment) = instance.attachment {
+                                    let old_attach = &secret.instances[&attachment];
+                                    assert!(old_attach.attachment.is_none(), "Attachments can't have attachments.");
+                                    let attachment = CardInstance {
+                                        id: attach_id,
+                                        base: old_attach.base().clone(),
+                                        attachment: None,
+                                        state: old_attach.state.copy_card(),
+                                        token: old_attach.token,
+                                        original_owner: secret.player(),
+                                    };
+
+                                    secret.secret_mut().instances.insert(attach_id, attachment);
+                                    secret.secret_mut().limbo.push(attach_id);
+                                    Some(attach_id)
+                                } else {
+                                    None
+                                }
+                            } else if let Some(attach_base) = secret.secret.attachment(&id, base.clone()) {
+                                let attachment = CardInstance {
+                                    id: attach_id,
+                                    base: attach_base.clone(),
+                                    attachment: None,
+                                    state: attach_base.new_card_state(Some(&state)),
+                                    token: false,
+                                    original_owner: secret.player(),
+                                };
+
+                                secret.secret_mut().instances.insert(attach_id, attachment);
+                                secret.secret_mut().limbo.push(attach_id);
+                                Some(attach_id)
+                            } else {
+                                None
+                            };
+                            let copy = CardInstance {
+                                id: copy_id,
+                                base,
+                                state,
+                                attachment: None,
+                                token,
+                                original_owner: secret.player(),
+                            };
+                            secret.secret_mut().instances.insert(copy_id, copy);
+                            secret.secret_mut().limbo.push(copy_id);
+                            secret.secret_mut().pointers.push(copy_id);
+                            if let Some(attachment) = attachment {
+                                secret.attach_card(copy_id, attachment).unwrap();
+                            }
+                        })
+                        .await[0]
+                        }
+                    },
+                    Card::Pointer(OpaquePointer { player, index }) => {
+                        let buckets: Vec<_> = this
+                            .instances
+                            .iter()
+                            .map(InstanceOrPlayer::player)
+                            .collect();
+
+                        let id = this
+                            .context
+                            .reveal_unique(
+                                player,
+                                move |secret| {
+                                    let id = secret.pointers[index];
+
+                                    buckets[id.0].and_then(|bucket| {
+                                        if bucket == player {
+                                            None
+                                        } else {
+                                            Some(id)
+                                        }
+                                    })
+                                },
+                                |_| true,
+                            )
+                            .await;
+
+                        match id {
+                            None => {
+                                let instances = this.instances.clone();
+
+                                this.new_secret_cards(player, |mut secret| {
+                                let (copy_id, attach_id) = {
+                                    let mut next_instance = secret
+                                        .next_instance
+                                        .expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call");
+
+                                    let copy_id = next_instance;
+                                    next_instance.0 += 1;
+                                    let attach_id = next_instance;
+                                    next_instance.0 += 1;
+                                    secret.secret_mut().next_instance = Some(next_instance);
+                                    (copy_id, attach_id)
+                                };
+
+                                let id = secret.pointers[index];
+
+                                let instance = instances[id.0].instance_ref().or_else(|| secret.instances.get(&id)).expect("instance is neither public nor in this secret");
+
+                                let base = instance.base.clone();
+                                let state = instance.state.copy_card();
+                                let token = instance.token;
+
+                                let attachment = if deep {
+                                    if let Some(attachment) = instance.attachment {
+                                let old_attach = instances[attachment.0].instance_ref().or_else(|| secret.instance(attachment)).unwrap();
+                                        assert!(old_attach.attachment.is_none());
+                                        let attachment = CardInstance {
+                                            id: attach_id,
+                                            base: old_attach.base().clone(),
+                                            attachment: None,
+                                            state: old_attach.state.copy_card(),
+                                            token: old_attach.token,
+                                            original_owner: secret.player(),
+                                        };
+
+                                        secret.secret_mut().instances.insert(attach_id, attachment);
+                                        secret.secret_mut().limbo.push(attach_id);
+                                        Some(attach_id)
+                                    } else {
+                                        None
+                                    }
+                                } else if let Some(attach_base) = secret.secret.attachment(&id, base.clone()) {
+                                    let attachment = CardInstance {
+                                        id: attach_id,
+                                        base: attach_base.clone(),
+                                        attachment: None,
+                                        state: attach_base.new_card_state(Some(&state)),
+                                        token: false,
+                                        original_owner: secret.player(),
+                                    };
+
+                                    secret.secret_mut().instances.insert(attach_id, attachment);
+                                    secret.secret_mut().limbo.push(attach_id);
+                                    Some(attach_id)
+                                } else {
+                                    None
+                                };
+                                let copy = CardInstance {
+                                    id: copy_id,
+                                    base,
+                                    state,
+                                    attachment: None,
+                                    token,
+                                    original_owner: secret.player(),
+                                };
+                                secret.secret_mut().instances.insert(copy_id, copy);
+                                secret.secret_mut().limbo.push(copy_id);
+                                secret.secret_mut().pointers.push(copy_id);
+                                if let Some(attachment) = attachment {
+                                    secret.attach_card(copy_id, attachment).unwrap();
+                                }
+                            }).await[0]
+                            }
+                            Some(id) => {
+                                let owner = this.instances[id.0]
+                                    .player()
+                                    .expect("instance is not in another player's secret");
+
+                                this.new_secret_cards(owner, |mut secret| {
+                                let (copy_id, attach_id) = {
+                                        let mut next_instance = secret
+                                            .next_instance
+                                        .expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call");
+
+                                        let copy_id = next_instance;
+                                        next_instance.0 += 1;
+                                        let attach_id = next_instance;
+                                        next_instance.0 += 1;
+                                        secret.secret_mut().next_instance = Some(next_instance);
+                                        (copy_id, attach_id)
+                                    };
+
+                                    let instance = &secret.instances[&id];
+                                    let base = instance.base.clone();
+                                    let state = instance.state.copy_card();
+                                    let token = instance.token;
+
+                                    let attachment = if deep {
+                                        if let Some(attachment) = instance.attachment {
+                                            let old_attach = &secret.instances[&attachment];
+                                            assert!(old_attach.attachment.is_none());
+                                            let attachment = CardInstance {
+                                                id: attach_id,
+                                                base: old_attach.base().clone(),
+                                                attachment: None,
+                                                state: old_attach.state.copy_card(),
+                                                token: old_attach.token,
+                                                original_owner: secret.player(),
+                                            };
+
+                                            secret.secret_mut().instances.insert(attach_id, attachment);
+                                            secret.secret_mut().limbo.push(attach_id);
+                                            Some(attach_id)
+                                        } else {
+                                            None
+                                        }
+                                    } else if let Some(attach_base) = secret.secret.attachment(&id, base.clone()) {
+                                        let attachment = CardInstance {
+                                            id: attach_id,
+                                            base: attach_base.clone(),
+                                            attachment: None,
+                                            state: attach_base.new_card_state(Some(&state)),
+                                            token: false,
+                                            original_owner: secret.player(),
+                                        };
+
+                                        secret.secret_mut().instances.insert(attach_id, attachment);
+                                        secret.secret_mut().limbo.push(attach_id);
+                                        Some(attach_id)
+                                    } else {
+                                        None
+                                    };
+
+                                    let copy = CardInstance {
+                                        id: copy_id,
+                                        base,
+                                        state,
+                                        attachment: None,
+                                        token,
+                                        original_owner: secret.player(),
+                                    };
+                                    secret.secret_mut().instances.insert(copy_id, copy);
+                                    secret.secret_mut().limbo.push(copy_id);
+                                    secret.secret_mut().pointers.push(copy_id);
+                                    if let Some(attachment) = attachment {
+                                        secret.attach_card(copy_id, attachment).unwrap();
+                                        assert!(secret.instance(copy_id).unwrap().attachment.is_some());
+                                    }
+                                }).await[0]
+                            }
+                        }
+                    }
+                }
+            })
+        }
+    }
+
+    /// Logs a `CardEvent::CopyCard`, with the copy's instance only for players who can see it.
+    fn log_copy(&mut self, source: Card, copy: Card, deep: bool) {
+        match copy {
+            Card::ID(id) => {
+                let instance = self.instances[id.0].instance_ref().map(|instance| {
+                    let attachment = instance.attachment.and_then(|attachment| {
+                        self.instances[attachment.0].instance_ref().cloned()
+                    });
+
+                    (instance.clone(), attachment)
+                });
+
+                self.context.log(CardEvent::CopyCard {
+                    source,
+                    copy,
+                    instance,
+                    deep,
+                });
+            }
+            Card::Pointer(OpaquePointer { player, index }) => {
+                self.context.mutate_secret_or_log(
+                    player,
+                    |mut secret| {
+                        let instance = secret.instance(secret.pointers[index]).map(|instance| {
+                            let attachment = instance
+                                .attachment
+                                .and_then(|attachment| secret.instance(attachment).cloned());
+
+                            (instance.clone(), attachment)
+                        });
+
+                        secret.log(CardEvent::CopyCard {
+                            source,
+                            copy,
+                            instance,
+                            deep,
+                        });
+                    },
+                    CardEvent::CopyCard {
+                        source,
+                        copy,
+                        instance: None,
+                        deep,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Changes a card's base, keeping its ID, pointers and state.
+    ///
+    /// Unlike `CardGame::change_base_card`, this works on pointers without revealing the card's ID,
+    /// and reconciles the card's attachment according to the policy.
+    /// Logs a `CardEvent::TransformCard` with the card's instance for the players who can see it.
+    pub async fn transform_card(
+        &mut self,
+        card: impl Into<Card>,
+        new_base: S::BaseCard,
+        policy: TransformPolicy,
+    ) -> Card {
+        let card = card.into();
+
+        if self.result().is_some() {
+            return card;
+        }
+
+        let card = match self.reveal_public_id(card).await {
+            Ok(card) => card,
+            Err(..) => return card,
+        };
+
+        match card {
+            Card::ID(id) if self.instances[id.0].instance_ref().is_some() => {
+                let owner = self.owner(id);
+
+                let instance = self.instances[id.0]
+                    .instance_mut()
+                    .expect("public instance vanished");
+
+                instance.base = new_base.clone();
+
+                let attachment = instance.attachment;
+                let state = instance.state.clone();
+
+                let default = if policy == TransformPolicy::Replace {
+                    self.context
+                        .reveal(
+                            owner,
+                            move |secret| secret.secret.attachment(&id, new_base.clone()),
+                            |_| true,
+                        )
+                        .await
+                } else {
+                    None
+                };
+
+                if let Some(default) = default {
+                    let state = default.new_card_state(Some(&state));
+                    let attachment = self.new_public_card(owner, default, None).await;
+
+                    self.instances[attachment.0]
+                        .instance_mut()
+                        .expect("new card is not public")
+                        .state = state;
+
+                    self.move_card(attachment, owner, Zone::Attachment { parent: id.into() })
+                        .await
+                        .unwrap_or_else(|_| {
+                            panic!("unable to attach public limbo {:?} to {:?}", attachment, id)
+                        });
+                } else if let (Some(attachment), TransformPolicy::Dust | TransformPolicy::Replace) =
+                    (attachment, policy)
+                {
+                    self.move_card(attachment, owner, Zone::Dust { public: true })
+                        .await
+                        .unwrap_or_else(|_| {
+                            panic!("unable to move attachment {:?} to public dust", attachment)
+                        });
+                }
+            }
+            _ => {
+                let owner = match card {
+                    Card::ID(id) => self.owner(id),
+                    Card::Pointer(OpaquePointer { player, .. }) => player,
+                };
+
+                self.new_secret_cards(owner, move |mut secret| {
+                    let id = secret
+                        .id(card)
+                        .unwrap_or_else(|| panic!("{:?} not in secret", card));
+
+                    let next_instance = secret.next_instance.expect(
+                        "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
+                    );
+
+                    let instance = secret
+                        .secret_mut()
+                        .instance_mut(id)
+                        .unwrap_or_else(|| panic!("{:?} not in secret", card));
+
+                    instance.base = new_base.clone();
+
+                    let attachment = instance.attachment;
+                    let state = instance.state.clone();
+
+                    let default = if policy == TransformPolicy::Replace {
+                        secret.secret.attachment(&id, new_base.clone())
+                    } else {
+                        None
+                    };
+
+                    if let Some(default) = default {
+                        let attachment = CardInstance {
+                            id: next_instance,
+                            state: default.new_card_state(Some(&state)),
+                            base: default,
+                            attachment: None,
+                            token: false,
+                            original_owner: secret.player(),
+                        };
+
+                        secret
+                            .secret_mut()
+                            .instances
+                            .insert(next_instance, attachment);
+
+                        secret
+                            .attach_card(id, next_instance)
+                            .expect("Both id and next_instance are in this secret.");
+                    } else if let (Some(attachment), TransformPolicy::Dust | TransformPolicy::Replace) =
+                        (attachment, policy)
+                    {
+                        secret
+                            .dust_card(attachment)
+                            .expect("attachment is in this secret, and is not already dust.");
+                    }
+
+                    // unconditionally increment instance ID to avoid leaking attachment information
+
+                    secret
+                        .secret_mut()
+                        .next_instance
+                        .as_mut()
+                        .expect(
+                            "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
+                        )
+                        .0 += 1;
+                })
+                .await;
+            }
+        }
+
+        self.log_transform(card);
+
+        // A public card's new base can change its place on the field.
+        if let Card::ID(id) = card {
+            if self.instances[id.0].instance_ref().is_some() {
+                if let CardLocation {
+                    player,
+                    location: Some((Zone::Field, _)),
+                } = self.location(id)
+                {
+                    let mut logs = vec![];
+
+                    self.sort_field(
+                        player,
+                        self.player_cards(player).field.clone(),
+                        true,
+                        &mut |event| logs.push(event),
+                    );
+
+                    for event in logs.into_iter() {
+                        self.context.log(event);
+                    }
+                }
+            }
+        }
+
+        card
+    }
+
+    /// Logs a `CardEvent::TransformCard`, with the card's instance only for players who can see it.
+    fn log_transform(&mut self, card: Card) {
+        match card {
+            Card::ID(id) if self.instances[id.0].instance_ref().is_some() => {
+                let instance = self.instances[id.0].instance_ref().map(|instance| {
+                    let attachment = instance.attachment.and_then(|attachment| {
+                        self.instances[attachment.0].instance_ref().cloned()
+                    });
+
+                    (instance.clone(), attachment)
+                });
+
+                self.context
+                    .log(CardEvent::TransformCard { card, instance });
+            }
+            _ => {
+                let owner = match card {
+                    Card::ID(id) => self.owner(id),
+                    Card::Pointer(OpaquePointer { player, .. }) => player,
+                };
+
+                self.context.mutate_secret_or_log(
+                    owner,
+                    |mut secret| {
+                        let instance = secret.instance(card).map(|instance| {
+                            let attachment = instance
+                                .attachment
+                                .and_then(|attachment| secret.instance(attachment).cloned());
+
+                            (instance.clone(), attachment)
+                        });
+
+                        secret.log(CardEvent::TransformCard { card, instance });
+                    },
+                    CardEvent::TransformCard {
+                        card,
+                        instance: None,
+                    },
+                );
+            }
+        }
+    }
+
+    pub async fn copy_cards(&mut self, cards: Vec<Card>, deep: bool) -> Vec<Card> {
+        // todo!(): betterize this implementation
+
+        let mut copies = Vec::new();
+
+        for card in cards {
+            copies.extend(self.copy_card(card, deep).await);
+        }
+
+        copies
+    }
+
+    /// Always returns a Card::ID if the card is in public state.
+    pub async fn modify_card(&mut self, card: impl Into<Card>, f: impl Fn(CardInfoMut<S>)) -> Card {
+        let card = card.into();
+
+        if self.result().is_some() {
+            return card;
+        }
+
+        let card = match self.reveal_public_id(card).await {
+            Ok(card) => card,
+            Err(..) => return card,
+        };
+
+        self.modify_revealed_card(card, f).await
+    }
+
+    /// Modifies a card until `CardGame::expire_modifications` is called with `key`, e.g. for "until end of turn" effects.
+    ///
+    /// Expiring undoes the modification wherever the card is then.
+    /// Other changes to the card since are kept if its state can patch its diffs, see `CardState::patch`,
+    /// and are reverted too otherwise.
+    pub async fn modify_card_until(
+        &mut self,
+        card: impl Into<Card>,
+        key: &str,
+        f: impl Fn(CardInfoMut<S>),
+    ) -> Card {
+        let card = card.into();
+
+        if self.result().is_some() {
+            return card;
+        }
+
+        let card = match self.reveal_public_id(card).await {
+            Ok(card) => card,
+            Err(..) => return card,
+        };
+
+        self.modify_revealed_card_until(card, Some(key), f).await
+    }
+
+    /// Undoes every modification made with `modify_card_until` and `key`, latest first, wherever the cards are now.
+    ///
+    /// This reveals nothing about secret cards.
+    pub async fn expire_modifications(&mut self, key: &str) {
+        if self.result().is_some() {
+            return;
+        }
+
+        let expired = Inverse::take(&mut self.state.inverses, |inverse| inverse.key == key);
+
+        for inverse in expired.into_iter().rev() {
+            self.modify_card(inverse.id, move |info| {
+                inverse.apply(&mut info.instance.state)
+            })
+            .await;
+        }
+
+        for player in self.players() {
+            self.context.mutate_secret(player, |secret| {
+                secret.secret.expire(key, secret.log);
+            });
+        }
+    }
+
+    /// Reveals the ID of a pointer to a card that isn't in its player's secret anymore.
+    ///
+    /// Fails for a card that doesn't exist, including a destroyed card and an invalidated pointer to one,
+    /// see `CardGame::destroy_instance`.
+    async fn reveal_public_id(&mut self, card: Card) -> Result<Card, error::MoveCardError> {
+        if !self.exists(card) {
+            return Err(error::MoveCardError::CardDoesNotExist { card });
+        }
+
+        let revealed = match card {
+            Card::ID(_) => Some(card),
+            Card::Pointer(OpaquePointer { player, index }) => {
+                self.context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            let id = secret.pointers[index];
+
+                            if id == InstanceID::DESTROYED {
+                                None
+                            } else if secret.instances.contains_key(&id) {
+                                Some(card)
+                            } else {
+                                Some(id.into())
+                            }
+                        },
+                        |_| true,
+                    )
+                    .await
+            }
+        };
+
+        revealed.ok_or(error::MoveCardError::CardDoesNotExist { card })
+    }
+
+    /// Reveals one projection of a card's state, e.g. its cost, without revealing the rest of the card.
+    ///
+    /// Returns `None` if the card's state can't reveal the projection on its own, see `CardState::project`.
+    /// Projections of secret cards stay public knowledge while the cards stay in their player's secret,
+    /// and are revealed again in a `CardEvent::RevealProjection` at the end of each action that changes their values.
+    /// See `GameState::known_projection`.
+    pub async fn reveal_projection(
+        &mut self,
+        card: impl Into<Card>,
+        projection: &str,
+    ) -> Option<Vec<u8>> {
+        let card = self.reveal_public_id(card.into()).await.ok()?;
+
+        let player = match card {
+            Card::ID(id) => match &self.instances[id.0] {
+                InstanceOrPlayer::Instance(instance) => {
+                    let value = instance.state.project(projection)?;
+
+                    self.context.log(CardEvent::RevealProjection {
+                        card,
+                        projection: projection.to_owned(),
+                        value: value.clone(),
+                    });
+
+                    return Some(value);
+                }
+                InstanceOrPlayer::Player(owner) => *owner,
+                InstanceOrPlayer::Destroyed(..) => return None,
+            },
+            Card::Pointer(OpaquePointer { player, .. }) => player,
+        };
+
+        if let Some(value) = self.known_projection(card, projection) {
+            return Some(value);
+        }
+
+        let revealed = projection.to_owned();
+
+        let value = self
+            .context
+            .reveal_unique(
+                player,
+                move |secret| {
+                    secret
+                        .instance(card)
+                        .unwrap_or_else(|| {
+                            panic!("player {} secret {:?} not in secret", player, card)
+                        })
+                        .state
+                        .project(&revealed)
+                },
+                |_| true,
+            )
+            .await?;
+
+        self.context.mutate_secret(player, |secret| {
+            let id = secret
+                .secret
+                .id(card)
+                .unwrap_or_else(|| panic!("player {} secret {:?} not in secret", player, card));
+
+            secret.secret.projections.push((id, projection.to_owned()));
+        });
+
+        self.state.projections.push(KnownProjection {
+            player,
+            card,
+            projection: projection.to_owned(),
+            value: value.clone(),
+        });
+
+        self.context.log(CardEvent::RevealProjection {
+            card,
+            projection: projection.to_owned(),
+            value: value.clone(),
+        });
+
+        Some(value)
+    }
+
+    /// Reveals the current values of the known projections of secret cards,
+    /// and forgets those of cards that left their player's secret.
+    ///
+    /// This is done once at the end of each action.
+    pub(crate) async fn refresh_projections(&mut self) {
+        for player in self.players() {
+            if !self.projections.iter().any(|known| known.player == player) {
+                continue;
+            }
+
+            let values = self
+                .context
+                .reveal_unique(player, |secret| secret.projection_values(), |_| true)
+                .await;
+
+            self.context.mutate_secret(player, |secret| {
+                secret.secret.forget_projections();
+            });
+
+            let mut values = values.into_iter();
+            let mut events = vec![];
+
+            self.state.projections.retain_mut(|known| {
+                if known.player != player {
+                    return true;
+                }
+
+                match values.next().flatten() {
+                    Some(value) => {
+                        if value != known.value {
+                            known.value = value;
+
+                            events.push(CardEvent::RevealProjection {
+                                card: known.card,
+                                projection: known.projection.clone(),
+                                value: known.value.clone(),
+                            });
+                        }
+
+                        true
+                    }
+                    None => false,
+                }
+            });
+
+            for event in events {
+                self.context.log(event);
+            }
+        }
+    }
+
+    /// Modifies a card that's either public, or in the secret of the player the card refers to.
+    async fn modify_revealed_card(&mut self, card: Card, f: impl Fn(CardInfoMut<S>)) -> Card {
+        self.modify_revealed_card_until(card, None, f).await
+    }
+
+    /// Like `CardGame::modify_revealed_card`, remembering how to undo the modification if there's an expiry key,
+    /// see `CardGame::modify_card_until`.
+    async fn modify_revealed_card_until(
+        &mut self,
+        card: Card,
+        key: Option<&str>,
+        f: impl Fn(CardInfoMut<S>),
+    ) -> Card {
+        match card {
+            Card::ID(id) => {
+                let Self { state, context, .. } = self;
+
+                match &state.instances[id.0] {
+                    InstanceOrPlayer::Instance(instance) => {
+                        let CardLocation {
+                            player: owner,
+                            location,
+                        } = state.location(id);
+                        let location =
+                            location.unwrap_or_else(|| panic!("public {:?} has no zone", id));
+
+                        let attachment = instance.attachment.map(|attachment| {
+                            state.instances[attachment.0]
+                                .instance_ref()
+                                .unwrap_or_else(|| {
+                                    panic!("public {:?} attachment {:?} not public", id, attachment)
+                                })
+                                .clone()
+                        });
+
+                        let instance = state.instances[id.0]
+                            .instance_mut()
+                            .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+                        let before = instance.clone();
+
+                        f(CardInfoMut {
+                            instance,
+                            owner,
+                            zone: location.0,
+                            attachment: attachment.as_ref(),
+                            log: &mut |event| context.log(event),
+                        });
+
+                        let after = state.instances[id.0]
+                            .instance_ref()
+                            .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+                        if let Some(key) = key {
+                            let inverse = Inverse::new(key, id, &before.state, &after.state);
+
+                            state.inverses.extend(inverse);
+                        }
+
+                        if let Some(event) = CardEvent::modify_card(&before, after) {
+                            context.log(event);
+                        }
+
+                        let mut logs = vec![];
+
+                        match location.0 {
+                            Zone::Field => self.sort_field(
+                                owner,
+                                self.player_cards(owner).field.clone(),
+                                true,
+                                &mut |event| logs.push(event),
+                            ),
+                            Zone::Attachment {
+                                parent: Card::ID(parent_id),
+                            } => {
+                                if let Some((Zone::Field, ..)) = self.location(parent_id).location {
+                                    self.sort_field(
+                                        owner,
+                                        self.player_cards(owner).field.clone(),
+                                        true,
+                                        &mut |event| logs.push(event),
+                                    );
+                                }
+                            }
+                            _ => (),
+                        }
+
+                        for event in logs.into_iter() {
+                            self.context.log(event);
+                        }
+                    }
+                    InstanceOrPlayer::Destroyed(..) => {}
+                    InstanceOrPlayer::Player(owner) => {
+                        self.context.mutate_secret(*owner, |secret| {
+                            match key {
+                                Some(key) => {
+                                    secret.secret.modify_card_until(card, key, secret.log, &f)
+                                }
+                                None => secret.secret.modify_card(card, secret.log, &f),
+                            }
+                            .unwrap_or_else(|_| {
+                                panic!("player {} secret {:?} not in secret", owner, card)
+                            });
+                        });
+                    }
+                }
+            }
+            Card::Pointer(OpaquePointer { player, .. }) => {
+                self.context.mutate_secret(player, |secret| {
+                    match key {
+                        Some(key) => secret.secret.modify_card_until(card, key, secret.log, &f),
+                        None => secret.secret.modify_card(card, secret.log, &f),
+                    }
+                    .unwrap_or_else(|_| {
+                        panic!("player {} secret {:?} not in secret", player, card)
+                    });
+                });
+            }
+        }
+
+        self.summary.modified += 1;
+
+        self.run_hooks().await;
+
+        card
+    }
+
+    /// Internal API only.
+    /// Modifies a card and logs any changes.
+    /// TODO it would be nice to eliminiate all the duplication between here and `CardGame::modify_card`
+    pub(crate) async fn modify_card_internal(
+        &mut self,
+        card: Card,
+        f: impl Fn(
+            &mut CardInstance<S>,
+            &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
+        ),
+        logger: &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
+    ) {
+        let card = match self.reveal_public_id(card).await {
+            Ok(card) => card,
+            Err(..) => return,
+        };
+
+        match card {
+            Card::ID(id) => {
+                let Self { state, .. } = self;
+
+                match &state.instances[id.0] {
+                    InstanceOrPlayer::Instance(_) => {
+                        let CardLocation {
+                            player: owner,
+                            location,
+                        } = state.location(id);
+                        let location =
+                            location.unwrap_or_else(|| panic!("public {:?} has no zone", id));
+
+                        let instance = state.instances[id.0]
+                            .instance_mut()
+                            .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+                        let before = instance.clone();
+
+                        f(instance, &mut |event| logger(event));
+
+                        let instance = &*instance; // lose mutable ref
+                        if let Some(event) = CardEvent::modify_card(&before, instance) {
+                            logger(event);
+                        }
+                        match location.0 {
+                            Zone::Field => self.sort_field(
+                                owner,
+                                self.player_cards(owner).field.clone(),
+                                true,
+                                logger,
+                            ),
+                            Zone::Attachment {
+                                parent: Card::ID(parent_id),
+                            } => {
+                                if let Some((Zone::Field, ..)) = self.location(parent_id).location {
+                                    self.sort_field(
+                                        owner,
+                                        self.player_cards(owner).field.clone(),
+                                        true,
+                                        logger,
+                                    );
+                                }
+                            }
+                            _ => (),
+                        }
+                    }
+                    InstanceOrPlayer::Destroyed(..) => {}
+                    InstanceOrPlayer::Player(owner) => {
+                        self.context.mutate_secret(*owner, |secret| {
+                            secret.secret.modify_card_internal(
+                                card,
+                                secret.log,
+                                |instance, log| f(instance, log),
+                            );
+                        });
+                    }
+                }
+            }
+            Card::Pointer(OpaquePointer { player, .. }) => {
+                self.context.mutate_secret(player, |secret| {
+                    secret
+                        .secret
+                        .modify_card_internal(card, secret.log, |instance, log| f(instance, log));
+                });
+            }
+        }
+    }
+
+    pub async fn modify_cards(&mut self, cards: Vec<Card>, f: impl Fn(CardInfoMut<S>)) {
+        if self.result().is_some() {
+            return;
+        }
+
+        // todo!(): betterize this implementation
+
+        for card in cards {
+            self.modify_card(card, &f).await;
+        }
+    }
+
+    /// Modifies every card, in public state and in every player's secret, including attachments.
+    ///
+    /// Unlike `CardGame::modify_cards`, this doesn't reveal anything.
+    /// Each secret is modified in a single secret mutation,
+    /// and the changes are logged in one `CardEvent::ModifyCards` for public state and one for each secret.
+    pub async fn modify_all_cards(&mut self, f: impl Fn(CardInfoMut<S>)) {
+        if self.result().is_some() {
+            return;
+        }
+
+        let mut events = vec![];
+        let mut fields = vec![];
+
+        for id in (0..self.instances.len()).map(InstanceID) {
+            let Self { state, context, .. } = self;
+
+            let attachment = match &state.instances[id.0] {
+                InstanceOrPlayer::Instance(instance) => instance.attachment.map(|attachment| {
+                    state.instances[attachment.0]
+                        .instance_ref()
+                        .unwrap_or_else(|| {
+                            panic!("public {:?} attachment {:?} not public", id, attachment)
+                        })
+                        .clone()
+                }),
+                InstanceOrPlayer::Player(..) | InstanceOrPlayer::Destroyed(..) => continue,
+            };
+
+            let CardLocation {
+                player: owner,
+                location,
+            } = state.location(id);
+
+            let zone = match location {
+                Some((zone, ..)) => zone,
+                None => continue,
+            };
+
+            let instance = state.instances[id.0]
+                .instance_mut()
+                .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+            let before = instance.clone();
+
+            f(CardInfoMut {
+                instance,
+                owner,
+                zone,
+                attachment: attachment.as_ref(),
+                log: &mut |event| context.log(event),
+            });
+
+            let after = state.instances[id.0]
+                .instance_ref()
+                .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+            if let Some(event) = CardEvent::modify_card(&before, after) {
+                events.push(event);
+
+                let on_field = match zone {
+                    Zone::Field => true,
+                    Zone::Attachment {
+                        parent: Card::ID(parent_id),
+                    } => matches!(state.location(parent_id).location, Some((Zone::Field, ..))),
+                    _ => false,
+                };
+
+                if on_field && !fields.contains(&owner) {
+                    fields.push(owner);
+                }
+            }
+        }
+
+        if !events.is_empty() {
+            self.context.log(CardEvent::ModifyCards { events });
+        }
+
+        for player in self.players() {
+            self.context.mutate_secret(player, |secret| {
+                secret.secret.modify_all_cards(secret.log, &f);
+            });
+        }
+
+        let mut logs = vec![];
+
+        for owner in fields {
+            self.sort_field(
+                owner,
+                self.player_cards(owner).field.clone(),
+                true,
+                &mut |event| logs.push(event),
+            );
+        }
+
+        for event in logs.into_iter() {
+            self.context.log(event);
+        }
+
+        self.summary.modified += 1;
+
+        self.run_hooks().await;
+    }
+    /// Moves a card to a position on a player's field, see `MatchConfig::positioned_field`.
+    ///
+    /// The card goes at the end of the field if the position is past it.
+    pub async fn move_card_to_index(
+        &mut self,
+        card: impl Into<Card>,
+        to_player: Player,
+        index: usize,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        let card = card.into();
+
+        let result = self
+            .move_card_with(
+                card,
+                to_player,
+                Zone::Field,
+                MoveOptions { skip_sort: true },
+            )
+            .await?;
+
+        let id = self
+            .reveal_public_id(card)
+            .await?
+            .id()
+            .expect("card on the field is public");
+
+        self.reposition_card(id, index);
+
+        Ok(result)
+    }
+
+    /// Attaches a card to a parent, dusting the parent's previous attachment, if any.
+    ///
+    /// This is the same as moving the card to `Zone::Attachment`,
+    /// except it also reveals whether a secret parent had an attachment.
+    pub async fn attach(
+        &mut self,
+        card: impl Into<Card>,
+        parent: impl Into<Card>,
+    ) -> Result<AttachResult, error::MoveCardError> {
+        self.check_in_progress()?;
+
+        let card = card.into();
+        let parent = self.reveal_public_id(parent.into()).await?;
+
+        let (previous, parent_location) = match parent {
+            Card::ID(id) if self.instances[id.0].instance_ref().is_some() => {
+                (self.attachment_of(id).map(Card::from), self.location(id))
+            }
+            _ => {
+                let owner = match parent {
+                    Card::ID(id) => self.owner(id),
+                    Card::Pointer(OpaquePointer { player, .. }) => player,
+                };
+
+                let previous = self
+                    .new_secret_pointers(owner, move |mut secret| {
+                        let attachment = secret
+                            .instance(parent)
+                            .unwrap_or_else(|| panic!("{:?} not in secret", parent))
+                            .attachment;
+
+                        if let Some(attachment) = attachment {
+                            secret.new_pointer(attachment);
+                        }
+                    })
+                    .await
+                    .pop();
+
+                let location = CardLocation {
+                    player: owner,
+                    location: None,
+                };
+
+                (previous, location)
+            }
+        };
+
+        let (from, id) = self
+            .move_card(card, parent_location.player, Zone::Attachment { parent })
+            .await?;
+
+        Ok(AttachResult {
+            previous,
+            parent_location,
+            from,
+            id,
+        })
+    }
+
+    /// Swaps the attachments of two cards, without dusting either.
+    ///
+    /// Both cards are checked before either attachment moves, so a failed swap changes nothing.
+    /// Each card's `State::on_detach` and `State::on_attach` callbacks run at most once.
+    /// Only a `CardEvent::SwapAttachments` is logged, instead of the attachments' `CardEvent::MoveCard` events.
+    /// For secret cards, this reveals whether they have attachments,
+    /// and an attachment moving between public state and a secret, or between secrets, is revealed.
+    pub async fn swap_attachments(
+        &mut self,
+        a: impl Into<Card>,
+        b: impl Into<Card>,
+    ) -> Result<(), error::MoveCardError> {
+        self.check_in_progress()?;
+
+        let a = a.into();
+        let b = b.into();
+
+        self.check_pointer(a).await?;
+        self.check_pointer(b).await?;
+
+        let a = self.reveal_public_id(a).await?;
+        let b = self.reveal_public_id(b).await?;
+
+        self.check_movable(a)?;
+        self.check_movable(b)?;
+
+        if let Ok(true) = a.eq(b) {
+            return Ok(());
+        }
+
+        let a_attachment = self.reveal_attachment(a).await;
+        let b_attachment = self.reveal_attachment(b).await;
+
+        let owner = |state: &GameState<S>, card| match card {
+            Card::ID(id) => state.owner(id),
+            Card::Pointer(OpaquePointer { player, .. }) => player,
+        };
+        let bucket = |state: &GameState<S>, card| match card {
+            Card::ID(id) => state.instances[id.0].player(),
+            Card::Pointer(OpaquePointer { player, .. }) => Some(player),
+        };
+
+        let a_bucket = bucket(&self.state, a);
+        let b_bucket = bucket(&self.state, b);
+
+        // Check both halves of the swap before moving either attachment.
+        for (parent, attachment) in [(a, b_attachment), (b, a_attachment)] {
+            if let Some(attachment) = attachment {
+                if self
+                    .reveal_from_card(parent, |info| info.zone.is_attachment())
+                    .await
+                {
+                    return Err(error::MoveCardError::NestedAttachment { parent });
+                }
+
+                let to_player = owner(&self.state, parent);
+
+                self.authorize_move(attachment, to_player, Zone::Attachment { parent })?;
+            }
+        }
+
+        let a_moving = self
+            .reveal_moving_attachment(a_attachment, a_bucket, b_bucket)
+            .await;
+        let b_moving = self
+            .reveal_moving_attachment(b_attachment, b_bucket, a_bucket)
+            .await;
+
+        let swaps = [
+            AttachmentSwap {
+                parent: a,
+                bucket: a_bucket,
+                old: a_attachment,
+                new: b_attachment,
+                moves: a_bucket != b_bucket,
+                entering: b_moving,
+            },
+            AttachmentSwap {
+                parent: b,
+                bucket: b_bucket,
+                old: b_attachment,
+                new: a_attachment,
+                moves: a_bucket != b_bucket,
+                entering: a_moving,
+            },
+        ];
+
+        // Read every public instance before either attachment moves.
+        let public: Vec<_> = swaps
+            .iter()
+            .filter(|swap| swap.bucket.is_none())
+            .map(|swap| {
+                let instance = |card: Card| {
+                    self.instances[card.id().expect("Public cards have IDs.").0]
+                        .instance_ref()
+                        .expect("Card is in public state.")
+                        .clone()
+                };
+
+                (
+                    swap.parent.id().expect("Public cards have IDs."),
+                    swap.old.map(instance),
+                    swap.entering
+                        .as_ref()
+                        .map(|(instance, _)| instance.clone())
+                        .or_else(|| swap.new.map(instance)),
+                )
+            })
+            .collect();
+
+        // An attachment moving to another bucket takes its inverses with it, see `CardGame::modify_card_until`.
+        for swap in &swaps {
+            if let Some((instance, inverses)) = &swap.entering {
+                match swap.bucket {
+                    None => {
+                        self.state.set_instance(instance.id, instance.clone());
+                        self.state.inverses.extend(inverses.iter().cloned());
+                    }
+                    Some(player) => {
+                        self.state.set_instance(instance.id, player);
+                    }
+                }
+            }
+
+            if let (None, true, Some(old)) = (swap.bucket, swap.moves, swap.old) {
+                let id = old.id().expect("Attachments of public cards have IDs.");
+
+                Inverse::take(&mut self.state.inverses, |inverse| inverse.id == id);
+            }
+        }
+
+        for (parent, old, new) in public {
+            swap_attachment(
+                self.state.instances[parent.0]
+                    .instance_mut()
+                    .expect("Card is in public state."),
+                old.as_ref(),
+                new.as_ref(),
+            );
+        }
+
+        let public_views: Vec<_> = swaps
+            .iter()
+            .map(|swap| {
+                swap.bucket.is_none().then(|| {
+                    let instance = swap
+                        .parent
+                        .id()
+                        .and_then(|id| id.instance(&self.state, None))
+                        .expect("Card is in public state.")
+                        .clone();
+                    let attachment = instance.attachment.map(|attachment| {
+                        attachment
+                            .instance(&self.state, None)
+                            .expect("Attachments of public cards are in public state.")
+                            .clone()
+                    });
+
+                    (instance, attachment)
+                })
+            })
+            .collect();
+
+        let event = |views: Vec<InstanceView<S>>| {
+            let mut views = views.into_iter();
+
+            CardEvent::SwapAttachments {
+                a,
+                b,
+                instances: (views.next().flatten(), views.next().flatten()),
+            }
+        };
+
+        let mut buckets: Vec<_> = [a_bucket, b_bucket].into_iter().flatten().collect();
+
+        buckets.dedup();
+
+        // Each player with a secret parent sees it in their view, after any public view.
+        for (index, player) in buckets.into_iter().enumerate() {
+            let mutate = |mut secret: MutateSecretInfo<'_, S>| {
+                let views = swap_secret_attachments(secret.secret, player, &swaps)
+                    .into_iter()
+                    .zip(public_views.iter().cloned())
+                    .map(|(secret, public)| secret.or(public))
+                    .collect();
+
+                secret.log(event(views));
+            };
+
+            if index == 0 {
+                self.context
+                    .mutate_secret_or_log(player, mutate, event(public_views.clone()));
+            } else {
+                self.context.mutate_secret(player, mutate);
+            }
+        }
+
+        if a_bucket.is_none() && b_bucket.is_none() {
+            self.context.log(event(public_views));
+        }
+
+        for swap in &swaps {
+            if let Some(attachment) = swap.old {
+                self.summary.moved += 1;
+
+                self.queue_hook(StateHook::Detach {
+                    parent: swap.parent,
+                    attachment,
+                });
+            }
+
+            if let Some(attachment) = swap.new {
+                self.queue_hook(StateHook::Attach {
+                    parent: swap.parent,
+                    attachment,
+                });
+            }
+        }
+
+        self.run_hooks().await;
+
+        Ok(())
+    }
+
+    /// Reveals an attachment's instance and inverses if it's moving to another bucket, see `CardGame::swap_attachments`.
+    async fn reveal_moving_attachment(
+        &mut self,
+        attachment: Option<Card>,
+        from: Option<Player>,
+        to: Option<Player>,
+    ) -> Option<(CardInstance<S>, Vec<Inverse<S>>)> {
+        let attachment = attachment.filter(|_| from != to)?;
+
+        Some(match from {
+            None => {
+                let id = attachment
+                    .id()
+                    .expect("Attachments of public cards have IDs.");
+
+                (
+                    self.instances[id.0]
+                        .instance_ref()
+                        .expect("Attachments of public cards are in public state.")
+                        .clone(),
+                    self.state
+                        .inverses
+                        .iter()
+                        .filter(|inverse| inverse.id == id)
+                        .cloned()
+                        .collect(),
+                )
+            }
+            Some(player) => {
+                self.context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            let instance = secret
+                                .instance(attachment)
+                                .expect("Secret has the instance for this attachment")
+                                .clone();
+                            let inverses = secret
+                                .inverses
+                                .iter()
+                                .filter(|inverse| inverse.id == instance.id)
+                                .cloned()
+                                .collect::<Vec<_>>();
+
+                            (instance, inverses)
+                        },
+                        |_| true,
+                    )
+                    .await
+            }
+        })
+    }
+
+    /// Reveals a card in a player's secret hand, keeping its position in the hand.
+    ///
+    /// Logs a `CardEvent::RevealHandCard` after the card's `CardEvent::MoveCard`.
+    pub async fn reveal_hand_card(
+        &mut self,
+        player: Player,
+        card: impl Into<Card>,
+    ) -> Result<InstanceID, error::MoveCardError> {
+        self.check_in_progress()?;
+
+        let card = card.into();
+
+        // A card that doesn't exist isn't in the hand either.
+        let in_secret_hand = self
+            .try_reveal_from_card(card, move |info| {
+                info.owner == player && info.zone.is_secret_hand()
+            })
+            .await
+            .unwrap_or(false);
+
+        if !in_secret_hand {
+            return Err(error::MoveCardError::NotInSecretHand { player, card });
+        }
+
+        let (_, id) = self
+            .move_card(card, player, Zone::Hand { public: true })
+            .await?;
+
+        let id = id.expect("public hand card has no ID");
+
+        let index = self
+            .player_cards(player)
+            .hand()
+            .iter()
+            .position(|hand_id| *hand_id == Some(id))
+            .expect("revealed card isn't in the public hand");
+
+        self.context
+            .log(CardEvent::RevealHandCard { player, id, index });
+
+        Ok(id)
+    }
+
+    /// Conceals a card in a player's public hand, moving it back to their secret hand at the same position.
+    ///
+    /// Logs a `CardEvent::ConcealHandCard` after the card's `CardEvent::MoveCard`.
+    pub async fn conceal_hand_card(
+        &mut self,
+        player: Player,
+        card: impl Into<Card>,
+    ) -> Result<(), error::MoveCardError> {
+        self.check_in_progress()?;
+
+        let card = self.reveal_public_id(card.into()).await?;
+
+        let (id, index) = card
+            .id()
+            .and_then(|id| {
+                self.player_cards(player)
+                    .hand()
+                    .iter()
+                    .position(|hand_id| *hand_id == Some(id))
+                    .map(|index| (id, index))
+            })
+            .ok_or(error::MoveCardError::NotInPublicHand { player, card })?;
+
+        // Moving a card between its owner's public and secret hand keeps its position.
+        self.move_card(id, player, Zone::Hand { public: false })
+            .await?;
+
+        self.context
+            .log(CardEvent::ConcealHandCard { player, id, index });
+
+        Ok(())
+    }
+
+    /// Moves a card out of its owner's public or secret dust, e.g. to return a destroyed attachment to its owner's hand.
+    ///
+    /// Fails with `MoveCardError::NotInDust` if the card isn't in dust.
+    /// The card goes straight to the end of its new zone, or its sorted position on the field,
+    /// without the side effects of `CardGame::move_card`, e.g. on links and modifiers.
+    /// For secret cards, this reveals whether the card was in dust, but only the owner learns its index in secret dust.
+    pub async fn recover_from_dust(
+        &mut self,
+        card: impl Into<Card>,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        self.check_in_progress()?;
+
+        let card = card.into();
+
+        self.check_pointer(card).await?;
+
+        let card = self.reveal_public_id(card).await?;
+
+        let bucket = match card {
+            Card::ID(id) => self.instances[id.0].player(),
+            Card::Pointer(OpaquePointer { player, .. }) => Some(player),
+        };
+
+        let (owner, index) = match bucket {
+            None => match self.location(card.id().expect("Public cards have IDs.")) {
+                CardLocation {
+                    player,
+                    location: Some((Zone::Dust { public: true }, index)),
+                } => (player, index),
+                _ => return Err(error::MoveCardError::NotInDust { card }),
+            },
+            Some(player) => {
+                if !self
+                    .context
+                    .reveal_unique(
+                        player,
+                        move |secret| secret.zone(card).is_some_and(|zone| zone.is_dust()),
+                        |_| true,
+                    )
+                    .await
+                {
+                    return Err(error::MoveCardError::NotInDust { card });
+                }
+
+                (player, None)
+            }
+        };
+
+        if let Zone::Attachment { parent } = to_zone {
+            self.check_move(card, to_player, to_zone)?;
+            self.check_pointer(parent).await?;
+            self.check_attachment_cycle(card, parent).await?;
+            self.authorize_resolved_move(card, to_player, to_zone)
+                .await?;
+
+            let result = self.attach_card(card, parent).await;
+
+            if result.is_ok() {
+                self.summary.moved += 1;
+
+                self.queue_hook(StateHook::Attach {
+                    parent,
+                    attachment: card,
+                });
+            }
+
+            self.run_hooks().await;
+
+            return result;
+        }
+
+        self.check_zone_capacity(to_player, to_zone)?;
+        self.authorize_move(card, to_player, to_zone)?;
+
+        let from = CardLocation {
+            player: owner,
+            location: Some((
+                Zone::Dust {
+                    public: bucket.is_none(),
+                },
+                index,
+            )),
+        };
+
+        let to_bucket = match to_zone.is_public() {
+            Ok(false) => Some(to_player),
+            _ => None,
+        };
+
+        // The owner of secret dust is told the card's index there by whichever of its secrets' events logs the move.
+        if let Some(player) = bucket.filter(|_| to_bucket.is_none() || to_bucket == bucket) {
+            self.context.mutate_secret(player, move |mut secret| {
+                let location = secret
+                    .location(card)
+                    .location
+                    .expect("The card is in secret dust.");
+
+                secret.deferred_locations.push(location);
+            });
+        }
+
+        let mut mutations = SecretMutations::<S>::new();
+
+        match bucket {
+            None => {
+                self.player_cards_mut(owner)
+                    .dust
+                    .remove(index.expect("Public dust has indices."));
+            }
+            Some(player) => mutations.push(player, SecretMutation::RemoveDust(card)),
+        }
+
+        let card = self
+            .transfer_instance(card, bucket, to_bucket, &mut mutations)
+            .await;
+
+        let to_index = match to_bucket {
+            None => {
+                let id = card.id().expect("Cards in public state have IDs.");
+                let field_index = match to_zone {
+                    Zone::Field if !self.config.positioned_field => {
+                        let instance = id
+                            .instance(&self.state, None)
+                            .expect("Card is in public state.");
+                        let attachment = instance.attachment.map(|attachment| {
+                            attachment
+                                .instance(&self.state, None)
+                                .expect("Attachments of public cards are in public state.")
+                        });
+
+                        self.field_index(to_player, instance, attachment)
+                    }
+                    _ => self.player_cards(to_player).field.len(),
+                };
+
+                let player_cards = self.player_cards_mut(to_player);
+
+                match to_zone {
+                    Zone::Hand { .. } => {
+                        player_cards.hand.push(Some(id));
+
+                        let index = player_cards.hand.len() - 1;
+
+                        mutations.push(to_player, SecretMutation::InsertHand { index, id: None });
+
+                        index
+                    }
+                    Zone::Field => {
+                        player_cards.field.insert(field_index, id);
+
+                        field_index
+                    }
+                    zone => {
+                        let cards = match zone {
+                            Zone::Graveyard => &mut player_cards.graveyard,
+                            Zone::Dust { .. } => &mut player_cards.dust,
+                            Zone::Limbo { .. } => &mut player_cards.limbo,
+                            Zone::Casting => &mut player_cards.casting,
+                            Zone::HeroAbility => &mut player_cards.hero_ability,
+                            Zone::Boon => &mut player_cards.boon,
+                            _ => unreachable!("{:?} is a public zone", zone),
+                        };
+
+                        cards.push(id);
+
+                        cards.len() - 1
+                    }
+                }
+            }
+            Some(player) => {
+                mutations.push(
+                    player,
+                    SecretMutation::Push {
+                        card,
+                        zone: to_zone,
+                    },
+                );
+
+                let player_cards = self.player_cards_mut(player);
+
+                // Indices in secret limbo and dust are hidden.
+                match to_zone {
+                    Zone::Deck => {
+                        player_cards.deck += 1;
+                        player_cards.deck - 1
+                    }
+                    Zone::Hand { .. } => {
+                        player_cards.hand.push(None);
+                        player_cards.hand.len() - 1
+                    }
+                    Zone::CardSelection => {
+                        player_cards.card_selection += 1;
+                        player_cards.card_selection - 1
+                    }
+                    Zone::Pile { pile } => {
+                        player_cards.grow_pile(pile);
+                        player_cards.pile(pile) - 1
+                    }
+                    _ => 0,
+                }
+            }
+        };
+
+        mutations.flush(&mut self.context);
+
+        let to = ExactCardLocation {
+            player: to_player,
+            location: (to_zone, to_index),
+        };
+
+        match to_bucket {
+            None => {
+                let instance = card
+                    .id()
+                    .and_then(|id| id.instance(&self.state, None))
+                    .expect("Card is in public state.")
+                    .clone();
+                let attachment = instance.attachment.map(|attachment| {
+                    attachment
+                        .instance(&self.state, None)
+                        .expect("Attachments of public cards are in public state.")
+                        .clone()
+                });
+                let has_attachment = self.redacted_has_attachment(attachment.is_some());
+
+                let event = CardEvent::MoveCard {
+                    instance: Some((instance, attachment)),
+                    from: from.clone(),
+                    to,
+                    has_attachment,
+                };
+
+                match bucket {
+                    Some(player) => self.context.mutate_secret_or_log(
+                        player,
+                        |mut secret| {
+                            let mut event = event.clone();
+
+                            if let CardEvent::MoveCard { from, .. } = &mut event {
+                                from.location = secret.deferred_locations.pop();
+                            }
+
+                            secret.log(event);
+                        },
+                        event.clone(),
+                    ),
+                    None => self.context.log(event),
+                }
+            }
+            Some(player) => {
+                let has_attachment = match self.config.redacted_attachment_policy {
+                    RedactedAttachmentPolicy::Hidden => None,
+                    RedactedAttachmentPolicy::Presence => Some(
+                        self.context
+                            .reveal_unique(
+                                player,
+                                move |secret| secret.attachment_of(card).is_some(),
+                                |_| true,
+                            )
+                            .await,
+                    ),
+                };
+
+                self.context.mutate_secret_or_log(
+                    player,
+                    |mut secret| {
+                        let instance = secret
+                            .instance(card)
+                            .expect("Secret has the instance for this card")
+                            .clone();
+                        let attachment = instance.attachment.map(|attachment| {
+                            secret
+                                .instance(attachment)
+                                .expect("Secret has the instance for this ID")
+                                .clone()
+                        });
+                        let (zone, index) = secret
+                            .location(card)
+                            .location
+                            .expect("The card is in this secret's zone.");
+                        let from = if bucket == Some(player) {
+                            CardLocation {
+                                player: owner,
+                                location: secret.deferred_locations.pop(),
+                            }
+                        } else {
+                            from.clone()
+                        };
+
+                        secret.log(CardEvent::MoveCard {
+                            instance: Some((instance, attachment)),
+                            from,
+                            to: ExactCardLocation {
+                                player,
+                                location: (zone, index.expect("Secret zones have indices.")),
+                            },
+                            has_attachment: None,
+                        });
+                    },
+                    CardEvent::MoveCard {
+                        instance: None,
+                        from: from.clone(),
+                        to,
+                        has_attachment,
+                    },
+                );
+            }
+        }
+
+        if to_zone.is_deck() && self.config.shuffle_deck_on_insert {
+            self.context.mutate_secret(to_player, |secret| {
+                secret.secret.shuffle_deck(secret.random, secret.log);
+            });
+        }
+
+        self.summary.moved += 1;
+
+        self.bump_zone_version(
+            owner,
+            Zone::Dust {
+                public: bucket.is_none(),
+            },
+        );
+        self.bump_zone_version(to_player, to_zone);
+
+        Ok((from, card.id()))
+    }
+
+    /// Moves a card on the field to another position on its owner's field, see `MatchConfig::positioned_field`.
+    ///
+    /// The card goes at the end of the field if the position is past it.
+    pub fn reposition_card(&mut self, id: InstanceID, index: usize) {
+        if self.result().is_some() {
+            return;
+        }
+
+        let player = match self.location(id) {
+            CardLocation {
+                player,
+                location: Some((Zone::Field, _)),
+            } => player,
+            location => panic!("{:?} is at {:?}, not on the field", id, location),
+        };
+
+        let field = &mut self.player_cards_mut(player).field;
+
+        field.retain(|field_id| *field_id != id);
+
+        let index = index.min(field.len());
+
+        field.insert(index, id);
+
+        self.context
+            .log(CardEvent::FieldPosition { player, id, index });
+
+        self.bump_zone_version(player, Zone::Field);
+    }
+
+    /// Sorts a player's field with `State::field_order`, e.g. after moving cards with `MoveOptions::skip_sort`.
+    pub async fn resort_field(&mut self, player: Player) {
+        if self.result().is_some() {
+            return;
+        }
+
+        let mut logs = vec![];
+
+        self.sort_field(
+            player,
+            self.player_cards(player).field.clone(),
+            true,
+            &mut |event| logs.push(event),
+        );
+
+        for event in logs.into_iter() {
+            self.context.log(event);
+        }
+
+        self.run_hooks().await;
+    }
+
+    pub async fn change_base_card(&mut self, id: InstanceID, new_base: S::BaseCard) {
+        self.modify_card(id, |mut c| {
+            c.base = new_base.clone();
+        })
+        .await;
+    }
+    pub async fn move_card(
+        &mut self,
+        card: impl Into<Card>,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        self.move_card_with(card, to_player, to_zone, Default::default())
+            .await
+    }
+
+    /// Logs the events deferred in each player's secret, see `PlayerSecret::defer_log`.
+    ///
+    /// Each player's deferred events are logged to that player only, in the order they were deferred,
+    /// starting with player 0's.
+    /// Moving and attaching cards flush after logging their own events, so events deferred by a custom secret operation
+    /// are logged by the next flush, after the events of any move in between.
+    pub fn flush_deferred_logs(&mut self) {
+        for player in self.players() {
+            self.context.mutate_secret(player, |secret| {
+                for deferred_log in secret.secret.deferred_logs.drain(..) {
+                    (secret.log)(deferred_log);
+                }
+            });
+        }
+    }
+
+    /// Moves a card to one of its original owner's zones, e.g. when a stolen card is returned.
+    ///
+    /// This reveals the card's original owner, see `CardInstance::original_owner`.
+    pub async fn return_to_owner(
+        &mut self,
+        card: impl Into<Card>,
+        zone: Zone,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        let card = card.into();
+        let owner = self
+            .reveal_from_card(card, |info| info.original_owner())
+            .await;
+
+        self.move_card(card, owner, zone).await
+    }
+
+    /// Moves a card like `CardGame::move_card`, with options.
+    pub async fn move_card_with(
+        &mut self,
+        card: impl Into<Card>,
+        to_player: Player,
+        to_zone: Zone,
+        options: MoveOptions,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        let card = card.into();
+
+        self.check_move(card, to_player, to_zone)?;
+
+        self.check_pointer(card).await?;
+
+        if let Zone::Attachment { parent } = to_zone {
+            self.check_pointer(parent).await?;
+            self.check_attachment_cycle(card, parent).await?;
+        }
+
+        self.authorize_resolved_move(card, to_player, to_zone)
+            .await?;
+
+        self.move_card_unchecked(card, to_player, to_zone, options)
+            .await
+    }
+
+    /// Checks that the acting player may move a card like `CardGame::authorize_move`,
+    /// after following pointers to the secrets holding their cards.
+    ///
+    /// This reveals the IDs of pointers' cards that aren't in their players' secrets anymore,
+    /// but only while move authorization is enabled.
+    async fn authorize_resolved_move(
+        &mut self,
+        card: Card,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Result<(), error::MoveCardError> {
+        if self.acting_player.is_none() || !self.config.authorize_moves {
+            return Ok(());
+        }
+
+        let card = self.reveal_public_id(card).await?;
+
+        let to_zone = match to_zone {
+            Zone::Attachment { parent } => Zone::Attachment {
+                parent: self.reveal_public_id(parent).await?,
+            },
+            zone => zone,
+        };
+
+        self.authorize_move(card, to_player, to_zone)
+    }
+
+    /// Checks that a pointer doesn't point to a destroyed card.
+    ///
+    /// This reveals whether it does, but only once one of the player's pointers might.
+    async fn check_pointer(&mut self, card: Card) -> Result<(), error::MoveCardError> {
+        if let Card::Pointer(OpaquePointer { player, .. }) = card {
+            if self.player_cards(player).destroyed_pointers
+                && !self
+                    .context
+                    .reveal_unique(player, move |secret| secret.id(card).is_some(), |_| true)
+                    .await
+            {
+                return Err(error::MoveCardError::CardDoesNotExist { card });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a card isn't being attached to its own attachment, following pointers.
+    ///
+    /// An attachment is always in its parent's bucket, so this only reveals more than the cards' buckets if both are in the same secret.
+    async fn check_attachment_cycle(
+        &mut self,
+        card: Card,
+        parent: Card,
+    ) -> Result<(), error::MoveCardError> {
+        let card_id = self.reveal_public_id(card).await?;
+        let parent_id = self.reveal_public_id(parent).await?;
+
+        let cycle = match (self.bucket(card_id), self.bucket(parent_id)) {
+            (None, None) => match (card_id, parent_id) {
+                (Card::ID(card_id), Card::ID(parent_id)) => {
+                    self.parent_of(parent_id) == Some(card_id)
+                }
+                _ => false,
+            },
+            (Some(player), Some(parent_player)) if player == parent_player => {
+                self.context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            secret.parent_of(parent_id).is_some()
+                                && secret.parent_of(parent_id) == secret.id(card_id)
+                        },
+                        |_| true,
+                    )
+                    .await
+            }
+            _ => false,
+        };
+
+        if cycle {
+            return Err(error::MoveCardError::AttachmentCycle { card, parent });
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether `CardGame::move_card` would refuse to move a card, without moving it.
+    ///
+    /// Only public information is used, so nothing is revealed,
+    /// but a move of a secret card can still fail this way, e.g. by attaching it to itself through another pointer.
+    /// Pointers are authorized as if their cards were still in their players' secrets.
+    pub fn can_move_card(
+        &self,
+        card: impl Into<Card>,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Result<(), error::MoveCardError> {
+        let card = card.into();
+
+        self.check_move(card, to_player, to_zone)?;
+        self.authorize_move(card, to_player, to_zone)
+    }
+
+    /// Checks whether `CardGame::move_card` would refuse to move a card from public information, except for move authorization.
+    fn check_move(
+        &self,
+        card: Card,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Result<(), error::MoveCardError> {
+        self.check_in_progress()?;
+        self.check_movable(card)?;
+
+        if let Zone::Attachment { parent } = to_zone {
+            self.check_movable(parent)?;
+
+            if card.eq(parent).unwrap_or(false) {
+                return Err(error::MoveCardError::CannotAttachToSelf { card });
+            }
+
+            if let (Card::ID(id), Card::ID(parent_id)) = (card, parent) {
+                if self.parent_of(parent_id) == Some(id) {
+                    return Err(error::MoveCardError::AttachmentCycle { card, parent });
+                }
+            }
+        }
+
+        self.check_zone_capacity(to_player, to_zone)
+    }
+
+    /// Checks that a card exists and hasn't been destroyed.
+    fn check_movable(&self, card: Card) -> Result<(), error::MoveCardError> {
+        match card {
+            Card::ID(id) if self.is_destroyed(id) => Err(error::MoveCardError::DustedCard { card }),
+            _ if !self.exists(card) => Err(error::MoveCardError::CardDoesNotExist { card }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Swaps the locations of two cards, e.g. a card on the field and a card in hand.
+    ///
+    /// Each card takes the other's exact slot, so no other card changes index and no zone changes size.
+    /// A card's attachment goes with it, but attachments themselves can't be swapped, see `CardGame::swap_attachments`.
+    /// Unlike `CardGame::move_card`, swapping has no side effects:
+    /// tokens aren't dusted, links aren't enforced, modifiers don't expire and the field isn't sorted.
+    ///
+    /// A `CardEvent::SwapCards` is logged right before the two cards' `CardEvent::MoveCard` events.
+    /// For secret cards, this reveals their zones and indices, except indices in secret limbo and dust.
+    pub async fn swap_cards(
+        &mut self,
+        a: impl Into<Card>,
+        b: impl Into<Card>,
+    ) -> Result<(), error::MoveCardError> {
+        self.check_in_progress()?;
+
+        let a = a.into();
+        let b = b.into();
+
+        self.check_pointer(a).await?;
+        self.check_pointer(b).await?;
+
+        let a = self.reveal_public_id(a).await?;
+        let b = self.reveal_public_id(b).await?;
+
+        if let Ok(true) = a.eq(b) {
+            return Ok(());
+        }
+
+        let (a_owner, a_bucket, a_location) = self.reveal_swap_location(a).await?;
+        let (b_owner, b_bucket, b_location) = self.reveal_swap_location(b).await?;
+
+        self.authorize_move(a, b_owner, b_location.0)?;
+        self.authorize_move(b, a_owner, a_location.0)?;
+
+        self.context.log(CardEvent::SwapCards { cards: (a, b) });
+
+        let mut mutations = SecretMutations::<S>::new();
+
+        // Cards changing buckets reveal their IDs.
+        let a = self
+            .transfer_instance(a, a_bucket, b_bucket, &mut mutations)
+            .await;
+        let b = self
+            .transfer_instance(b, b_bucket, a_bucket, &mut mutations)
+            .await;
+
+        for (owner, bucket, (zone, index), other) in [
+            (a_owner, a_bucket, a_location, b),
+            (b_owner, b_bucket, b_location, a),
+        ] {
+            if bucket.is_none() {
+                self.player_cards_mut(owner).replace(
+                    zone,
+                    index.expect("Public cards have indices."),
+                    other.id().expect("Cards taking public slots have IDs."),
+                );
+            }
+        }
+
+        let mut buckets: Vec<_> = [a_bucket, b_bucket].into_iter().flatten().collect();
+
+        buckets.dedup();
+
+        for bucket in buckets {
+            mutations.push(bucket, SecretMutation::SwapIds(a, b));
+        }
+
+        mutations.flush(&mut self.context);
+
+        self.log_swapped_card(
+            a,
+            b,
+            (a_owner, a_bucket, a_location),
+            (b_owner, b_bucket, b_location),
+        )
+        .await;
+        self.log_swapped_card(
+            b,
+            a,
+            (b_owner, b_bucket, b_location),
+            (a_owner, a_bucket, a_location),
+        )
+        .await;
+
+        self.summary.moved += 2;
+
+        self.bump_zone_version(a_owner, a_location.0);
+
+        if a_owner != b_owner || !matches!(a_location.0.eq(b_location.0), Ok(true)) {
+            self.bump_zone_version(b_owner, b_location.0);
+        }
+
+        Ok(())
+    }
+
+    /// The owner, bucket and location of a card being swapped, see `CardGame::swap_cards`.
+    ///
+    /// Indices in secret limbo and dust are hidden, like in `CardEvent::MoveCard`.
+    async fn reveal_swap_location(
+        &mut self,
+        card: Card,
+    ) -> Result<(Player, Option<Player>, (Zone, Option<usize>)), error::MoveCardError> {
+        let bucket = match card {
+            Card::ID(id) => self.instances[id.0].player(),
+            // The pointer's ID was revealed unless the card is in the pointer's secret.
+            Card::Pointer(OpaquePointer { player, .. }) => Some(player),
+        };
+
+        let (owner, location) = match bucket {
+            None => {
+                let location = self.location(card.id().expect("Public cards have IDs."));
+
+                (location.player, location.location)
+            }
+            Some(player) => (
+                player,
+                self.context
+                    .reveal_unique(
+                        player,
+                        move |secret| secret.location(card).location.and_then(public_location),
+                        |_| true,
+                    )
+                    .await,
+            ),
+        };
+
+        match location {
+            Some(location) if !location.0.is_attachment() => Ok((owner, bucket, location)),
+            _ => Err(error::MoveCardError::SwapAttachment { card }),
+        }
+    }
+
+    /// Moves a card's instance, attachment and inverses to another bucket,
+    /// for cards that change zones without `CardGame::move_card`, see `CardGame::swap_cards`.
+    ///
+    /// Returns the card, by ID if it changed buckets.
+    async fn transfer_instance(
+        &mut self,
+        card: Card,
+        from: Option<Player>,
+        to: Option<Player>,
+        mutations: &mut SecretMutations<S>,
+    ) -> Card {
+        if from == to {
+            return card;
+        }
+
+        let (instance, attachment, inverses) = match from {
+            None => {
+                let id = card.id().expect("Public cards have IDs.");
+                let instance = self.instances[id.0]
+                    .instance_ref()
+                    .expect("Card is in public state.")
+                    .clone();
+                let attachment = instance.attachment.map(|attachment| {
+                    self.instances[attachment.0]
+                        .instance_ref()
+                        .expect("Attachments of public cards are in public state.")
+                        .clone()
+                });
+                let inverses = Inverse::take(&mut self.state.inverses, |inverse| {
+                    inverse.id == id || Some(inverse.id) == instance.attachment
+                });
+
+                (instance, attachment, inverses)
+            }
+            Some(player) => {
+                let (instance, attachment, inverses) = self
+                    .context
+                    .reveal_unique(
+                        player,
+                        move |secret| {
+                            let instance = secret
+                                .instance(card)
+                                .expect("Secret has the instance for this card")
+                                .clone();
+                            let attachment = instance.attachment.map(|attachment| {
+                                secret
+                                    .instance(attachment)
+                                    .expect("Secret has the instance for this ID")
+                                    .clone()
+                            });
+                            let inverses = secret
+                                .inverses
+                                .iter()
+                                .filter(|inverse| {
+                                    inverse.id == instance.id
+                                        || Some(inverse.id) == instance.attachment
+                                })
+                                .cloned()
+                                .collect::<Vec<_>>();
+
+                            (instance, attachment, inverses)
+                        },
+                        |_| true,
+                    )
+                    .await;
+
+                let id = instance.id;
+                let attachment_id = instance.attachment;
+
+                mutations.push(
+                    player,
+                    SecretMutation::RemoveInstance {
+                        id,
+                        attachment: attachment_id,
+                    },
+                );
+
+                (instance, attachment, inverses)
+            }
+        };
+
+        let id = instance.id;
+
+        match to {
+            None => {
+                self.state.set_instance(id, instance);
+
+                if let Some(attachment) = attachment {
+                    self.state.set_instance(attachment.id, attachment);
+                }
+
+                self.state.inverses.extend(inverses);
+            }
+            Some(player) => {
+                self.state.set_instance(id, player);
+
+                if let Some(attachment) = &attachment {
+                    self.state.set_instance(attachment.id, player);
+                }
+
+                mutations.push(player, SecretMutation::InsertInstance(instance));
+
+                if let Some(attachment) = attachment {
+                    mutations.push(player, SecretMutation::InsertInstance(attachment));
+                }
+
+                if !inverses.is_empty() {
+                    mutations.push(player, SecretMutation::ExtendInverses(inverses));
+                }
+            }
+        }
+
+        id.into()
+    }
+
+    /// Logs the `CardEvent::MoveCard` of a swapped card that now holds the other card's old slot, see `CardGame::swap_cards`.
+    ///
+    /// The secret holding the card learns its exact location.
+    /// A card leaving secret limbo or dust for public state tells its old secret the index it left.
+    async fn log_swapped_card(
+        &mut self,
+        card: Card,
+        other: Card,
+        (from_owner, from_bucket, from_location): (Player, Option<Player>, (Zone, Option<usize>)),
+        (to_owner, to_bucket, to_location): (Player, Option<Player>, (Zone, Option<usize>)),
+    ) {
+        let from = CardLocation {
+            player: from_owner,
+            location: Some(from_location),
+        };
+        let to = ExactCardLocation {
+            player: to_owner,
+            location: (to_location.0, to_location.1.unwrap_or(0)),
+        };
+
+        match to_bucket {
+            None => {
+                let id = card.id().expect("Cards in public state have IDs.");
+                let instance = id
+                    .instance(self, None)
+                    .expect("Card is in public state.")
+                    .clone();
+                let attachment = instance.attachment.map(|attachment| {
+                    attachment
+                        .instance(self, None)
+                        .expect("Attachments of public cards are in public state.")
+                        .clone()
+                });
+                let has_attachment = self.redacted_has_attachment(attachment.is_some());
+
+                let event = CardEvent::MoveCard {
+                    instance: Some((instance, attachment)),
+                    from,
+                    to,
+                    has_attachment,
+                };
+
+                match from_bucket {
+                    Some(player) if is_partial(Some(from_location)) => {
+                        // The card's old slot now holds the other card.
+                        self.context.mutate_secret_or_log(
+                            player,
+                            |mut secret| {
+                                let mut event = event.clone();
+
+                                if let CardEvent::MoveCard { from, .. } = &mut event {
+                                    *from = secret.location(other);
+                                }
+
+                                secret.log(event);
+                            },
+                            event.clone(),
+                        );
+                    }
+                    _ => self.context.log(event),
+                }
+            }
+            Some(player) => {
+                let has_attachment = match self.config.redacted_attachment_policy {
+                    RedactedAttachmentPolicy::Hidden => None,
+                    RedactedAttachmentPolicy::Presence => Some(
+                        self.context
+                            .reveal_unique(
+                                player,
+                                move |secret| secret.attachment_of(card).is_some(),
+                                |_| true,
+                            )
+                            .await,
+                    ),
+                };
+
+                self.context.mutate_secret_or_log(
+                    player,
+                    |mut secret| {
+                        let instance = secret
+                            .instance(card)
+                            .expect("Secret has the instance for this card")
+                            .clone();
+                        let attachment = instance.attachment.map(|attachment| {
+                            secret
+                                .instance(attachment)
+                                .expect("Secret has the instance for this ID")
+                                .clone()
+                        });
+                        let (zone, index) = secret
+                            .location(card)
+                            .location
+                            .expect("Swapped cards aren't attachments.");
+
+                        secret.log(CardEvent::MoveCard {
+                            instance: Some((instance, attachment)),
+                            // The card's old slot now holds the other card.
+                            from: if from_bucket == Some(player) {
+                                secret.location(other)
+                            } else {
+                                from.clone()
+                            },
+                            to: ExactCardLocation {
+                                player,
+                                location: (zone, index.expect("Secret zones have indices.")),
+                            },
+                            has_attachment: None,
+                        });
+                    },
+                    CardEvent::MoveCard {
+                        instance: None,
+                        from: from.clone(),
+                        to,
+                        has_attachment,
+                    },
+                );
+            }
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "move_card", level = "debug", skip(self, options))
+    )]
+    async fn move_card_unchecked(
+        &mut self,
+        card: Card,
+        to_player: Player,
+        to_zone: Zone,
+        options: MoveOptions,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        // The card may be a pointer, so remember every token on a field and check the ID it resolves to.
+        let tokens: Vec<_> = if self.config.token_policy == TokenPolicy::Destroy {
+            self.players()
+                .flat_map(|player| self.player_cards(player).field.iter().copied())
+                .filter(|id| {
+                    self.instances[id.0]
+                        .instance_ref()
+                        .is_some_and(|instance| instance.token)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let nested = std::mem::replace(&mut self.in_operation, true);
+
+        let result = inner(self, card, to_player, to_zone, options).await;
+
+        // An error moving a linked card, returned once this move is finished.
+        let mut linked = Ok(());
+
+        if let Ok((from, moved_id)) = &result {
+            self.summary.moved += 1;
+
+            if let Some((Zone::Attachment { parent }, _)) = from.location {
+                self.queue_hook(StateHook::Detach {
+                    parent,
+                    attachment: card,
+                });
+            }
+
+            if let Zone::Attachment { parent } = to_zone {
+                self.queue_hook(StateHook::Attach {
+                    parent,
+                    attachment: card,
+                });
+            }
+
+            let id = match moved_id {
+                Some(id) => Some(*id),
+                None => self.reveal_linked_id(card).await,
+            };
+
+            self.leave_stack(id, from.player).await;
+
+            if !to_zone.is_secret_limbo() {
+                self.leave_face_down(card, id);
+            }
+
+            if let Some(id) = id {
+                self.expire_modifiers(id, to_zone);
+                linked = self.enforce_card_link(id, to_player, to_zone).await;
+            }
+
+            if let Some((from_zone, _)) = from.location {
+                self.bump_zone_version(from.player, from_zone);
+            }
+
+            self.bump_zone_version(to_player, to_zone);
+        }
+
+        self.in_operation = nested;
+
+        if let Ok((from, Some(id))) = &result {
+            if tokens.contains(id)
+                && matches!(from.location, Some((Zone::Field, _)))
+                && !to_zone.is_field()
+            {
+                Box::pin(self.destroy_instance(*id)).await?;
+            }
+        }
+
+        self.run_hooks().await;
+
+        linked?;
+
+        return result;
+
+        async fn inner<S: State>(
+            this: &mut CardGame<S>,
+            card: Card,
+            to_player: Player,
+            to_zone: Zone,
+            options: MoveOptions,
+        ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+            let old_field = if to_zone.is_field() {
+                Some(this.player_cards(to_player).field.clone())
+            } else {
+                None
+            };
+            let to_bucket = match to_zone {
+                Zone::Deck => Some(to_player),
+                Zone::Hand { public: false } => Some(to_player),
+                Zone::Hand { public: true } => None,
+                Zone::Field => None,
+                Zone::Graveyard => None,
+                Zone::Limbo { public: false } => Some(to_player),
+                Zone::Limbo { public: true } => None,
+                Zone::CardSelection => Some(to_player),
+                Zone::Pile { .. } => Some(to_player),
+                Zone::Casting => None,
+                Zone::Dust { public: false } => Some(to_player),
+                Zone::Dust { public: true } => None,
+                Zone::Attachment { parent } => {
+                    return this.attach_card(card, parent).await;
+                }
+                Zone::HeroAbility => None,
+                Zone::Boon => None,
+            };
+            // We always need to know who owns the card instance itself.
+
+            // Either this card is in Public state (None) or a player's secret (Some(player)).
+            // We also need to know who owns the card, regardless of its secrecy, so we can later update the public state for that player.
+            let (bucket, owner) = match card {
+                Card::Pointer(OpaquePointer { player, index }) => {
+                    let buckets: Vec<_> = this
+                        .instances
+                        .iter()
+                        .enumerate()
+                        .map(|(i, instance)| (instance.player(), this.owner(InstanceID(i))))
+                        .collect();
+
+                    this.context
+                        .reveal_unique(
+                            player,
+                            move |secret| buckets[secret.pointers[index].0],
+                            |_| true,
+                        )
+                        .await
+                }
+                Card::ID(id) => (this.instances[id.0].player(), this.owner(id)),
+            };
+
+            let id = match card {
+                Card::ID(id) => Some(id),
+                Card::Pointer(OpaquePointer { player, index }) => {
+                    if bucket != Some(player) || to_bucket != Some(player) {
+                        Some(
+                            this.context
+                                .reveal_unique(
+                                    player,
+                                    move |secret| secret.pointers[index],
+                                    |_| true,
+                                )
+                                .await,
+                        )
+                    } else {
+                        // The pointer, the card, and the destination all exist within one player's secret.
+                        // Therefore, the instance ID need not be revealed.
+
+                        None
+                    }
+                }
+            };
+            // Reveal the zone that a card came from.
+            // A card in no zone is an error, and nothing has changed yet.
+            let location = match bucket {
+                None => {
+                    // A public card's ID is always revealed above, since the pointer's player isn't its bucket.
+                    let id = id.expect("ID should have been revealed in this case");
+
+                    Some(
+                        this.location(id)
+                            .location
+                            .ok_or(error::MoveCardError::CardDoesNotExist { card })?,
+                    )
+                }
+                Some(player) => {
+                    let location = this
+                        .context
+                        .reveal_unique(
+                            player,
+                            move |secret| {
+                                secret
+                                    .location(id.unwrap_or_else(|| {
+                                        secret.pointers[card.pointer().unwrap().index]
+                                    }))
+                                    .location
+                                    .map(public_location)
+                            },
+                            |_| true,
+                        )
+                        .await
+                        .ok_or(error::MoveCardError::CardDoesNotExist { card })?;
+
+                    // Moves within one secret log the exact location to the owner directly.
+                    if to_bucket != bucket && is_partial(location) {
+                        this.context.mutate_secret(player, move |mut secret| {
+                            if let Some(location) = secret
+                                .location(id.unwrap_or_else(|| {
+                                    secret.pointers[card.pointer().unwrap().index]
+                                }))
+                                .location
+                            {
+                                secret.deferred_locations.push(location);
+                            }
+                        });
+                    }
+
+                    location
+                }
+            };
+
+            // A card leaving an attachment zone in a secret has no public location, see `public_location`.
+            if let (Some(player), None) = (bucket, location) {
+                this.queue_secret_detach(player, card, id).await;
+            }
+
+            // From here on, the card is known to be in a zone of its bucket, so its instance is there too.
+            // The remaining `expect`s and `unreachable!`s only fail if the game state is inconsistent:
+            // `to_bucket` is derived from `to_zone`, and attachments were handled by `CardGame::attach_card`.
+
+            // Special case, secret -> secret for a single player
+            if let Some(bucket_owner) = bucket {
+                if to_bucket == bucket {
+                    if let Some((zone, index)) = location {
+                        this.player_cards_mut(bucket_owner).remove_from(zone, index);
+                    }
+
+                    // Update the public state about where we put this card
+                    let player_state = this.player_cards_mut(to_player);
+                    match to_zone {
+                        Zone::Deck => {
+                            player_state.deck += 1;
+                        }
+                        Zone::Hand { public: false } => {
+                            player_state.hand.push(None);
+                        }
+                        Zone::Hand { public: true } => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::Field => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::Graveyard => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::Limbo { public: false } => {
+                            // do nothing, this is a secret
+                        }
+                        Zone::Limbo { public: true } => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::CardSelection => {
+                            player_state.card_selection += 1;
+                        }
+                        Zone::Pile { pile } => {
+                            player_state.grow_pile(pile);
+                        }
+                        Zone::Casting => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::Dust { public: false } => {
+                            // do nothing, this is a secret
+                        }
+                        Zone::Dust { public: true } => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::Attachment { .. } => {
+                            unreachable!("Cannot move card to attachment zone")
+                        }
+                        Zone::HeroAbility => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                        Zone::Boon => {
+                            unreachable!("{}:{}:{}", file!(), line!(), column!());
+                        }
+                    }
+
+                    let to_location = (
+                        to_zone,
+                        match to_zone {
+                            Zone::Deck => this.player_cards(to_player).deck() - 1,
+                            Zone::Hand { public: false } => {
+                                this.player_cards(to_player).hand().len() - 1
+                            }
+                            Zone::Hand { public: true } => {
+                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                            }
+                            Zone::Field => unreachable!("{}:{}:{}", file!(), line!(), column!()),
+                            Zone::Graveyard => {
+                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                            }
+                            Zone::Limbo { public: false } => 0,
+                            Zone::Limbo { public: true } => {
+                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                            }
+                            Zone::CardSelection => 0,
+                            Zone::Pile { pile } => this.player_cards(to_player).pile(pile) - 1,
+                            Zone::Casting => unreachable!("{}:{}:{}", file!(), line!(), column!()),
+                            Zone::Dust { public: false } => 0,
+                            Zone::Dust { public: true } => {
+                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                            }
+                            Zone::Attachment { .. } => 0,
+                            Zone::HeroAbility => {
+                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                            }
+                            Zone::Boon => {
+                                unreachable!("{}:{}:{}", file!(), line!(), column!())
+                            }
+                        },
+                    );
+
+                    let has_attachment = match this.config.redacted_attachment_policy {
+                        RedactedAttachmentPolicy::Hidden => None,
+                        RedactedAttachmentPolicy::Presence => Some(
+                            this.context
+                                .reveal_unique(
+                                    bucket_owner,
+                                    move |secret| {
+                                        let id = id.unwrap_or_else(|| {
+                                            secret.pointers[card.pointer().unwrap().index]
+                                        });
+
+                                        secret.instance(id).unwrap().attachment.is_some()
+                                    },
+                                    |_| true,
+                                )
+                                .await,
+                        ),
+                    };
+
+                    this.context.mutate_secret_or_log(
+                        bucket_owner,
+                        |mut secret| {
+                            let id = id
+                                .unwrap_or_else(|| secret.pointers[card.pointer().unwrap().index]);
+                            let old_location = secret.location(id);
+
+                            let instance = secret.instance(id).unwrap().clone();
+                            let attachment = instance
+                                .attachment
+                                .map(|a_id| secret.instance(a_id).unwrap().clone());
+
+                            // Remove this card from its old zone in the secret.
+                            secret.secret.remove_id(secret.log, id);
+
+                            secret.log(CardEvent::MoveCard {
+                                instance: Some((instance, attachment)),
+                                from: old_location,
+                                to: ExactCardLocation {
+                                    player: bucket_owner,
+                                    location: (
+                                        to_zone,
+                                        match to_zone {
+                                            Zone::Deck => secret.deck.len(),
+                                            Zone::Hand { public: false } => secret.hand.len(),
+                                            Zone::Hand { public: true } => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::Field => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::Graveyard => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::Limbo { public: false } => secret.limbo.len(),
+                                            Zone::Limbo { public: true } => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::CardSelection => secret.card_selection.len(),
+                                            Zone::Pile { pile } => secret.pile(pile).len(),
+                                            Zone::Casting => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::Dust { public: false } => secret.dust.len(),
+                                            Zone::Dust { public: true } => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::Attachment { .. } => 0,
+                                            Zone::HeroAbility => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                            Zone::Boon => {
+                                                unreachable!(
+                                                    "{}:{}:{}",
+                                                    file!(),
+                                                    line!(),
+                                                    column!()
+                                                )
+                                            }
+                                        },
+                                    ),
+                                },
+                                has_attachment: None,
+                            });
+
+                            // Put the card in its new zone in the secret.
+                            match to_zone {
+                                Zone::Deck => secret.deck.push(id),
+                                Zone::Hand { public: false } => secret.hand.push(Some(id)),
+                                Zone::Hand { public: true } => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::Field => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::Graveyard => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::Limbo { public: false } => secret.limbo.push(id),
+                                Zone::Limbo { public: true } => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::CardSelection => secret.card_selection.push(id),
+                                Zone::Pile { pile } => secret.push_pile(pile, id),
+                                Zone::Casting => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::Dust { public: false } => secret.dust.push(id),
+                                Zone::Dust { public: true } => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::Attachment { .. } => {
+                                    unreachable!("Can't attach a spell with move_card.")
+                                }
+                                Zone::HeroAbility => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                                Zone::Boon => {
+                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
+                                }
+                            }
+                        },
+                        CardEvent::MoveCard {
+                            instance: None,
+                            from: CardLocation {
+                                player: bucket_owner,
+                                location,
+                            },
+                            to: ExactCardLocation {
+                                player: to_player,
+                                location: to_location,
+                            },
+                            has_attachment,
+                        },
+                    );
+
+                    return Ok((
+                        CardLocation {
+                            player: bucket_owner,
+                            location,
+                        },
+                        id,
+                    ));
+                }
+            }
+
+            let mut deferred_logs = vec![];
+            let mut mutations = SecretMutations::<S>::new();
+
+            // The inverses of the card and its attachment follow them to their new bucket, see `CardGame::modify_card_until`.
+            let mut inverses = Vec::new();
+
+            let (instance, attachment_instance) = match bucket {
+                None => {
+                    let id = id.expect("Card is in public state, but we don't know its id.");
+                    if let Some((
+                        Zone::Attachment {
+                            parent: Card::ID(old_parent),
+                        },
+                        ..,
+                    )) = location
+                    {
+                        let attach_clone = id
+                            .instance(this, None)
+                            .expect("Match is in None, so this id must be in public state.")
+                            .clone();
+                        this.modify_card_internal(
+                            old_parent.into(),
+                            |parent, _| {
+                                parent.attachment = None;
+                                S::on_detach(parent, &attach_clone);
+                            },
+                            &mut |event| deferred_logs.push(event),
+                        )
+                        .await;
+                    }
+
+                    if let Some(to_bucket_player) = to_bucket {
+                        let instance = this
+                            .state
+                            .set_instance(id, to_bucket_player)
+                            .instance()
+                            .expect("Card was identified as public, but it's actually InstanceOrPlayer::Player");
+
+                        let attachment = instance.attachment.map(|attachment_id| {
+                        this.state.set_instance(attachment_id, to_bucket_player).instance().expect("Since parent Card is public, attachment was identified as public, but it's actually InstanceOrPlayer::Player")
+                    });
+
+                        inverses = Inverse::take(&mut this.state.inverses, |inverse| {
+                            inverse.id == id || Some(inverse.id) == instance.attachment
+                        });
+
+                        if let Some((Zone::Hand { public: false }, index)) = location {
+                            mutations.push(
+                                owner,
+                                SecretMutation::RemoveHand(
+                                    index.expect("no index for secret hand card"),
+                                ),
+                            );
+                        }
+
+                        (Some(instance), attachment)
+                    } else {
+                        // we're moving from public to public
+                        (None, None)
+                    }
+                }
+                Some(player) => {
+                    let (instance, attachment_instance, revealed_inverses) = this
+                        .context
+                        .reveal_unique(
+                            player,
+                            move |secret| {
+                                let id = id.unwrap_or_else(|| {
+                                    secret.pointers[card.pointer().unwrap().index]
+                                });
+
+                                let instance = secret
+                                    .instance(id)
+                                    .expect("Secret has the instance for this ID");
+
+                                (
+                                    Some(instance.clone()),
+                                    instance.attachment.map(|attachment| {
+                                        secret
+                                            .instance(attachment)
+                                            .expect("Secret has the instance for this ID")
+                                            .clone()
+                                    }),
+                                    secret
+                                        .inverses
+                                        .iter()
+                                        .filter(|inverse| {
+                                            inverse.id == id
+                                                || Some(inverse.id) == instance.attachment
+                                        })
+                                        .cloned()
+                                        .collect::<Vec<_>>(),
+                                )
+                            },
+                            |_| true,
+                        )
+                        .await;
+
+                    inverses = revealed_inverses;
+
+                    mutations.push(
+                        player,
+                        SecretMutation::Take(
+                            instance.as_ref().expect("The instance was revealed.").id,
+                        ),
+                    );
+                    (instance, attachment_instance)
+                }
+            };
+
+            // At this point in time, either we already knew ID, or we've revealed it by revealing the instance.
+            let id = id
+                .or_else(|| instance.as_ref().map(|v| v.id))
+                .expect("Either we know ID or we've revealed the instance.");
+
+            // If this card came from a secret, we know it's leaving that secret. SX -> SX case handled above.
+            if let Some(bucket_owner) = bucket {
+                // Take its ID out of any zones in that secret.
+                mutations.push(bucket_owner, SecretMutation::RemoveId(id));
+            } else if let Some((Zone::Hand { public: true }, index)) = location {
+                mutations.push(
+                    owner,
+                    SecretMutation::RemoveHand(index.expect("no index for public hand card")),
+                );
+            }
+
+            match location {
+                Some((
+                    Zone::Attachment {
+                        parent: Card::ID(old_parent),
+                    },
+                    ..,
+                )) => {
+                    this.instances[old_parent.0]
+                        .instance_mut()
+                        .expect("Card should have been attached to a public parent")
+                        .attachment = None;
+                }
+                Some((zone, index)) => {
+                    this.player_cards_mut(owner).remove_from(zone, index);
+                }
+                None => (),
+            }
+
+            let field_index = if let Zone::Field = to_zone {
+                let my_instance = instance.as_ref().unwrap_or_else(|| {
+                id.instance(this, None)
+                    .expect("Card going to field isn't being removed from a secret, so should be in public state.")
+            });
+                let my_attachment = attachment_instance.as_ref().or_else(|| {
+                my_instance
+                    .attachment
+                    .map(|attach| attach.instance(this, None)
+                    .expect("Attachment of card going to field isn't being removed from a secret, so should be in public state."))
+            });
+
+                Some(this.field_index(to_player, my_instance, my_attachment))
+            } else {
+                None
+            };
+
+            // Without sorting, the card goes at the end of the field.
+            let field_index = if options.skip_sort || this.config.positioned_field {
+                field_index.map(|_| this.player_cards(to_player).field.len())
+            } else {
+                field_index
+            };
+
+            match to_zone {
+                Zone::Deck => {
+                    mutations.push(
+                        to_player,
+                        SecretMutation::Push {
+                            card: id.into(),
+                            zone: Zone::Deck,
+                        },
+                    );
+
+                    this.player_cards_mut(to_player).deck += 1;
+                }
+                Zone::Hand { public: false } => {
+                    // A card concealed in its owner's hand keeps its position.
+                    let index = match location {
+                        Some((Zone::Hand { public: true }, Some(index))) if to_player == owner => {
+                            index
+                        }
+                        _ => this.player_cards(to_player).hand.len(),
+                    };
+
+                    this.player_cards_mut(to_player).hand.insert(index, None);
+
+                    mutations.push(
+                        to_player,
+                        SecretMutation::InsertHand {
+                            index,
+                            id: Some(id),
+                        },
+                    );
+                }
+                Zone::Hand { public: true } => {
+                    let index = match location {
+                        Some((Zone::Hand { public: false }, Some(index))) if to_player == owner => {
+                            index
+                        }
+                        _ => this.player_cards(to_player).hand.len(),
+                    };
+
+                    this.player_cards_mut(to_player)
+                        .hand
+                        .insert(index, Some(id));
+
+                    mutations.push(to_player, SecretMutation::InsertHand { index, id: None });
+                }
+                Zone::Field => {
+                    this.player_cards_mut(to_player).field.insert(
+                        field_index.expect("field_index should be Some when to_zone is Field"),
+                        id,
+                    );
+                }
+                Zone::Graveyard => {
+                    this.player_cards_mut(to_player).graveyard.push(id);
+                }
+                Zone::Limbo { public: false } => {
+                    mutations.push(
+                        to_player,
+                        SecretMutation::Push {
+                            card: id.into(),
+                            zone: Zone::Limbo { public: false },
+                        },
+                    );
+                }
+                Zone::Limbo { public: true } => {
+                    this.player_cards_mut(to_player).limbo.push(id);
+                }
+                Zone::CardSelection => {
+                    mutations.push(
+                        to_player,
+                        SecretMutation::Push {
+                            card: id.into(),
+                            zone: Zone::CardSelection,
+                        },
+                    );
+
+                    this.player_cards_mut(to_player).card_selection += 1;
+                }
+                Zone::Pile { pile } => {
+                    mutations.push(
+                        to_player,
+                        SecretMutation::Push {
+                            card: id.into(),
+                            zone: Zone::Pile { pile },
+                        },
+                    );
+
+                    this.player_cards_mut(to_player).grow_pile(pile);
+                }
+                Zone::Casting => {
+                    this.player_cards_mut(to_player).casting.push(id);
+                }
+                Zone::Dust { public: false } => {
+                    mutations.push(
+                        to_player,
+                        SecretMutation::Push {
+                            card: id.into(),
+                            zone: Zone::Dust { public: false },
+                        },
+                    );
+                }
+                Zone::Dust { public: true } => {
+                    this.player_cards_mut(to_player).dust.push(id);
+                }
+                Zone::Attachment { .. } => unreachable!("Cannot move card to attachment zone"),
+                Zone::HeroAbility => this.player_cards_mut(to_player).hero_ability.push(id),
+                Zone::Boon => this.player_cards_mut(to_player).boon.push(id),
+            }
+
+            if let Some(instance) = instance.clone() {
+                // we have a new instance, need to put it somewhere.
+                let id = instance.id;
+
+                match to_bucket {
+                    None => {
+                        this.state.set_instance(id, instance);
+                    }
+                    Some(to_bucket_player) => {
+                        this.state.set_instance(id, to_bucket_player);
+
+                        mutations.push(to_bucket_player, SecretMutation::InsertInstance(instance));
+                    }
+                }
+
+                match to_bucket {
+                    None => this.state.inverses.extend(inverses),
+                    Some(to_bucket_player) if !inverses.is_empty() => {
+                        mutations.push(to_bucket_player, SecretMutation::ExtendInverses(inverses));
+                    }
+                    Some(..) => (),
+                }
+
+                // If we have an attachment_instance, we also need to put it somewhere the same way.
+                if let Some(attachment_instance) = attachment_instance.clone() {
+                    let attachment_id = attachment_instance.id;
+
+                    match to_bucket {
+                        None => {
+                            this.state.set_instance(attachment_id, attachment_instance);
+                        }
+                        Some(to_bucket_player) => {
+                            let attachment_id = attachment_instance.id;
+                            this.state.set_instance(attachment_id, to_bucket_player);
+
+                            mutations.push(
+                                to_bucket_player,
+                                SecretMutation::InsertInstance(attachment_instance),
+                            );
+                        }
+                    }
+                }
+            }
+
+            mutations.flush(&mut this.context);
+
+            // we have to emit a sort field before we emit the card move event, otherwise things with same ID will sort wrong.
+            if to_zone.is_field() && !options.skip_sort {
+                let mut logs = vec![];
+                this.sort_field(
+                    to_player,
+                    old_field
+                        .clone()
+                        .expect("If moving to field, we should have cached the old field."),
+                    false,
+                    &mut |event| logs.push(event),
+                );
+                for event in logs.into_iter() {
+                    this.context.log(event);
+                }
+            }
+
+            let move_card_event = (
+                instance.map(|i| (i, attachment_instance)).or_else(|| {
+                    id.instance(this, None).map(|instance| {
+                        (
+                            instance.clone(),
+                            instance
+                                .attachment
+                                .map(|a_id| a_id.instance(this, None).unwrap().clone()),
+                        )
+                    })
+                }),
+                CardLocation {
+                    player: owner,
+                    location,
+                },
+                ExactCardLocation {
+                    player: to_player,
+                    location: (
+                        to_zone,
+                        match to_zone {
+                            Zone::Deck => this.player_cards(to_player).deck() - 1,
+                            Zone::Hand { public } => match location {
+                                Some((
+                                    Zone::Hand {
+                                        public: from_public,
+                                    },
+                                    Some(index),
+                                )) if to_player == owner && from_public != public => index,
+                                _ => this.player_cards(to_player).hand.len() - 1,
+                            },
+                            Zone::Field => field_index
+                                .expect("field_index should be Some when to_zone is Field"),
+                            Zone::Graveyard => this.player_cards(to_player).graveyard().len() - 1,
+                            Zone::Limbo { public: false } => 0,
+                            Zone::Limbo { public: true } => {
+                                this.player_cards(to_player).limbo().len() - 1
+                            }
+                            Zone::CardSelection => {
+                                this.player_cards(to_player).card_selection() - 1
+                            }
+                            Zone::Pile { pile } => this.player_cards(to_player).pile(pile) - 1,
+                            Zone::Casting => this.player_cards(to_player).casting().len() - 1,
+                            Zone::Dust { public: false } => 0,
+                            Zone::Dust { public: true } => {
+                                this.player_cards(to_player).dust().len() - 1
+                            }
+                            Zone::Attachment { .. } => 0,
+                            Zone::HeroAbility { .. } => {
+                                this.player_cards(to_player).hero_ability().len() - 1
+                            }
+                            Zone::Boon { .. } => this.player_cards(to_player).boon().len() - 1,
+                        },
+                    ),
+                },
+            );
+
+            // The instance was revealed from the source bucket, so it says whether the card has an attachment.
+            let has_attachment = move_card_event.0.as_ref().and_then(|(instance, _)| {
+                this.redacted_has_attachment(instance.attachment.is_some())
+            });
+
+            if is_partial(move_card_event.1.location) {
+                this.context.mutate_secret_or_log(owner, |mut secret| {
+                let (instance, mut from, to) = move_card_event.clone();
+
+                    let missing_location = secret.deferred_locations.pop().expect("If from location is none, publically, and we're the player, we should have the deferred location.");
+                    from.location = Some(missing_location);
+
+                secret.log(CardEvent::MoveCard { instance, from, to, has_attachment: None });
+            },CardEvent::MoveCard { instance: move_card_event.0.clone(), from: move_card_event.1.clone(), to: move_card_event.2.clone(), has_attachment });
+            } else {
+                this.context.log(CardEvent::MoveCard {
+                    instance: move_card_event.0.clone(),
+                    from: move_card_event.1.clone(),
+                    to: move_card_event.2.clone(),
+                    has_attachment,
+                })
+            }
+
+            for deferred_log in deferred_logs {
+                this.context.log(deferred_log);
+            }
+
+            this.flush_deferred_logs();
+
+            match to_zone {
+                Zone::Deck => {
+                    if this.config.shuffle_deck_on_insert {
+                        this.context.mutate_secret(to_player, |secret| {
+                            secret.secret.shuffle_deck(secret.random, secret.log);
+                        });
+                    }
+                }
+                Zone::Field if !options.skip_sort => {
+                    let mut logs = vec![];
+                    this.sort_field(
+                        to_player,
+                        old_field
+                            .expect("If moving to field, we should have cached the old field."),
+                        true,
+                        &mut |event| logs.push(event),
+                    );
+                    for event in logs.into_iter() {
+                        this.context.log(event);
+                    }
+                }
+                _ => (),
+            }
+
+            Ok((
+                CardLocation {
+                    player: owner,
+                    location,
+                },
+                Some(id),
+            ))
+        }
+    }
+
+    /// Links two cards according to `policy`.
+    ///
+    /// A card can only have one link, so any existing links on either card are broken.
+    /// Links are public, so linking a pointer reveals its ID.
+    /// Moving a pointer to a linked card within its player's secret reveals that it's linked.
+    pub async fn link_cards(
+        &mut self,
+        a: impl Into<Card>,
+        b: impl Into<Card>,
+        policy: LinkPolicy,
+    ) -> Result<(), error::LinkCardsError> {
+        if let Some(result) = self.result() {
+            return Err(error::LinkCardsError::GameOver { result });
+        }
+
+        let a = self.reveal_id(a.into()).await;
+        let b = self.reveal_id(b.into()).await;
+
+        if a == b {
+            return Err(error::LinkCardsError::SameCard { card: a.into() });
+        }
+
+        self.unlink_card(a);
+        self.unlink_card(b);
+
+        self.links.push(CardLink {
+            cards: (a, b),
+            policy,
+        });
+
+        self.context.log(CardEvent::LinkCards {
+            cards: (a, b),
+            policy,
+        });
+
+        Ok(())
+    }
+
+    /// Breaks the card's link, if any.
+    pub fn unlink_card(&mut self, id: InstanceID) -> Option<CardLink> {
+        if self.result().is_some() {
+            return None;
+        }
+
+        let index = self.links.iter().position(|link| link.contains(id))?;
+        let link = self.links.remove(index);
+
+        self.context
+            .log(CardEvent::UnlinkCards { cards: link.cards });
+
+        Some(link)
+    }
+
+    /// Has a player secretly commit to a value now, to be revealed later with `CardGame::reveal_commitment`.
+    ///
+    /// `choose` runs in the player's secret, with access to secret randomness.
+    /// Other players only learn that the player committed to something with `key`.
+    pub async fn commit<T: AnySecretData>(
+        &mut self,
+        player: Player,
+        key: &str,
+        choose: impl Fn(&PlayerSecret<S>, &mut dyn rand::RngCore) -> T,
+    ) -> Result<(), error::CommitmentError> {
+        if let Some(result) = self.result() {
+            return Err(error::CommitmentError::GameOver { result });
+        }
+
+        if self.has_commitment(player, key) {
+            return Err(error::CommitmentError::AlreadyCommitted {
+                player,
+                key: key.to_string(),
+            });
+        }
+
+        self.context.mutate_secret(player, |secret| {
+            let value = serde_cbor::to_vec(&choose(secret.secret, secret.random))
+                .expect("commitment should serialize");
+
+            secret.secret.commitments.insert(key.to_string(), value);
+        });
+
+        self.state.commitments.push((player, key.to_string()));
+
+        self.context.log(CardEvent::Commit {
+            player,
+            key: key.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Reveals the value a player committed to with `key`, ending the commitment.
+    ///
+    /// The value is read from the player's secret, so it's the one they committed to.
+    pub async fn reveal_commitment<T: AnySecretData>(
+        &mut self,
+        player: Player,
+        key: &str,
+    ) -> Result<T, error::CommitmentError> {
+        let index = self
+            .commitments
+            .iter()
+            .position(|(committer, committed)| *committer == player && committed == key)
+            .ok_or_else(|| error::CommitmentError::NotCommitted {
+                player,
+                key: key.to_string(),
+            })?;
+
+        let owned_key = key.to_string();
+
+        let value = self
+            .context
+            .reveal_unique(
+                player,
+                move |secret| {
+                    secret.commitment(&owned_key).unwrap_or_else(|| {
+                        panic!("player {} secret has no {:?}", player, owned_key)
+                    })
+                },
+                |_| true,
+            )
+            .await;
+
+        self.context.mutate_secret(player, |secret| {
+            secret.secret.commitments.shift_remove(key);
+        });
+
+        self.state.commitments.remove(index);
+
+        self.context.log(CardEvent::RevealCommitment {
+            player,
+            key: key.to_string(),
+        });
+
+        Ok(value)
+    }
+
+    /// Casts a card onto the stack, moving it to its owner's casting zone.
+    ///
+    /// A card cast face-down waits in its owner's secret limbo instead, so it stays secret until it resolves.
+    /// It appears on the stack as one of its owner's pointers, so the stack can follow it in its owner's secret.
+    /// Returns the card as it appears on the stack.
+    pub async fn cast(
+        &mut self,
+        card: impl Into<Card>,
+        face_down: bool,
+    ) -> Result<Card, error::MoveCardError> {
+        let card = card.into();
+        let player = self
+            .reveal_owner(card)
+            .await
+            .ok_or(error::MoveCardError::CardDoesNotExist { card })?;
+
+        let zone = if face_down {
+            Zone::Limbo { public: false }
+        } else {
+            Zone::Casting
+        };
+
+        let (_, id) = self.move_card(card, player, zone).await?;
+
+        let card = match (id, card) {
+            (Some(id), _) if !face_down => id.into(),
+            (_, Card::Pointer(OpaquePointer { player: holder, .. })) if holder == player => card,
+            _ if face_down => self
+                .new_secret_pointers(player, |mut secret| {
+                    let id = *secret
+                        .limbo()
+                        .last()
+                        .expect("the card was just moved to secret limbo");
+
+                    secret.new_pointer(id);
+                })
+                .await
+                .remove(0),
+            _ => card,
+        };
+
+        self.state.stack.push(card);
+
+        self.context.log(CardEvent::Cast { player, card });
+
+        Ok(card)
+    }
+
+    /// Places a card face-down in one of its owner's public zones, e.g. a face-down permanent on the field.
+    ///
+    /// The card waits in its owner's secret limbo, so it stays secret until `CardGame::turn_face_up`.
+    /// Returns the card as it appears in `GameState::face_down_cards`.
+    pub async fn place_face_down(
+        &mut self,
+        card: impl Into<Card>,
+        zone: Zone,
+    ) -> Result<Card, error::MoveCardError> {
+        let card = card.into();
+
+        if !matches!(zone.is_public(), Ok(true)) {
+            return Err(error::MoveCardError::NotPublicZone { zone });
+        }
+
+        let player = self
+            .reveal_owner(card)
+            .await
+            .ok_or(error::MoveCardError::CardDoesNotExist { card })?;
+
+        self.check_zone_capacity(player, zone)?;
+        self.move_card(card, player, Zone::Limbo { public: false })
+            .await?;
+
+        self.state
+            .face_down
+            .push(FaceDownCard { player, card, zone });
+
+        self.context.log(CardEvent::FaceDown { player, card, zone });
+
+        Ok(card)
+    }
+
+    /// Turns a face-down card face-up, moving it from its owner's secret limbo into the zone it was placed in.
+    ///
+    /// The card must be given as it appears in `GameState::face_down_cards`.
+    pub async fn turn_face_up(
+        &mut self,
+        card: impl Into<Card>,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        let card = card.into();
+
+        let index = self
+            .face_down
+            .iter()
+            .position(|face_down| face_down.card.eq(card).unwrap_or(false))
+            .ok_or(error::MoveCardError::NotFaceDown { card })?;
+
+        // The card's own place can't count against its zone's capacity, so it's only put back if the move fails.
+        let face_down = self.state.face_down.remove(index);
+
+        let result = self.move_card(card, face_down.player, face_down.zone).await;
+
+        if result.is_err() {
+            let index = index.min(self.face_down.len());
+
+            self.state.face_down.insert(index, face_down);
+        }
+
+        result
+    }
+
+    /// Pops the top card off the stack and resolves it with `State::resolve`.
+    pub async fn resolve_top(&mut self) -> Option<Card> {
+        if self.result().is_some() {
+            return None;
+        }
+
+        let card = self.state.stack.pop()?;
+
+        self.context.log(CardEvent::Resolve { card });
+
+        S::resolve(self, card).await;
+
+        Some(card)
+    }
+
+    /// Removes a card from the stack without resolving it, moving it to its owner's `to_zone`, e.g. the graveyard or dust.
+    ///
+    /// The card must be given as it appears on the stack.
+    pub async fn counter(
+        &mut self,
+        card: impl Into<Card>,
+        to_zone: Zone,
+    ) -> Result<(CardLocation, Option<InstanceID>), error::MoveCardError> {
+        let card = card.into();
+
+        if !self
+            .stack
+            .iter()
+            .any(|entry| entry.eq(card).unwrap_or(false))
+        {
+            return Err(error::MoveCardError::NotOnStack { card });
+        }
+
+        let player = self
+            .reveal_owner(card)
+            .await
+            .ok_or(error::MoveCardError::CardDoesNotExist { card })?;
+        let result = self.move_card(card, player, to_zone).await?;
+
+        self.context.log(CardEvent::Counter { card });
+
+        Ok(result)
+    }
+
+    /// Removes a card that just moved out of `from`'s bucket from the stack, if it's on it.
+    ///
+    /// Public entries are keyed by instance ID.
+    /// Face-down entries are `from`'s pointers, so they're resolved in `from`'s secret,
+    /// and leave the stack once the card they resolve to is no longer in `from`'s secret limbo.
+    async fn leave_stack(&mut self, id: Option<InstanceID>, from: Player) {
+        if let Some(id) = id {
+            self.state
+                .stack
+                .retain(|entry| !matches!(entry, Card::ID(entry) if *entry == id));
+        }
+
+        let face_down = self.face_down_casts(from);
+
+        if face_down.is_empty() || self.context.conceded == Some(from) {
+            return;
+        }
+
+        let left: Vec<usize> = self
+            .context
+            .reveal_unique(
+                from,
+                move |secret| {
+                    face_down
+                        .iter()
+                        .copied()
+                        .filter(|index| {
+                            secret
+                                .id(OpaquePointer {
+                                    player: from,
+                                    index: *index,
+                                })
+                                .is_none_or(|id| !secret.limbo().contains(&id))
+                        })
+                        .collect()
+                },
+                |_| true,
+            )
+            .await;
+
+        self.state.stack.retain(|entry| match entry {
+            Card::Pointer(pointer) => pointer.player != from || !left.contains(&pointer.index),
+            Card::ID(_) => true,
+        });
+    }
+
+    /// The indices of `player`'s pointers on the stack, which are the cards they cast face-down.
+    fn face_down_casts(&self, player: Player) -> Vec<usize> {
+        self.stack
+            .iter()
+            .filter_map(|entry| match entry {
+                Card::Pointer(OpaquePointer {
+                    player: holder,
+                    index,
+                }) if *holder == player => Some(*index),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Removes a card that just moved from the face-down cards, if it's among them.
+    fn leave_face_down(&mut self, card: Card, id: Option<InstanceID>) {
+        self.state.face_down.retain(|entry| {
+            !entry.card.eq(card).unwrap_or(false)
+                && !matches!(id, Some(id) if entry.card.eq(id).unwrap_or(false))
+        });
+    }
+
+    /// Ends the game, logging a `CardEvent::GameOver`.
+    ///
+    /// Card operations are frozen from then on, and every later action is rejected.
+    /// Fallible operations fail with a `GameOver` error, e.g. `MoveCardError::GameOver`,
+    /// and the others do nothing, returning no cards, or the given card unchanged.
+    /// Revealing and pointing to cards still works.
+    /// In a `MatchState`, the next game starts once the action is applied.
+    pub fn end_game(&mut self, result: GameResult) {
+        assert!(self.result.is_none(), "the game is already over");
+
+        self.state.result = Some(result);
+
+        self.context.log(CardEvent::GameOver { result });
+    }
+
+    /// Ends the game with a player's concession, whoever's turn it is.
+    ///
+    /// The player may have disconnected, so nothing more is revealed from their secret during the action:
+    /// an effect that goes on to reveal from their secret ends at that reveal, instead of waiting for them.
+    /// Does nothing if the game is already over.
+    pub fn concede(&mut self, player: Player) {
+        if self.result.is_some() {
+            return;
+        }
+
+        self.context.conceded = Some(player);
+
+        self.end_game(GameResult::Conceded(player));
+    }
+
+    /// Mutates a player's game-specific secret data, see `State::Secret`.
+    ///
+    /// `f` can log events to the player with `PlayerSecretMut::log`,
+    /// and can return a game event, e.g. a projection of the new data, to log to the player afterwards.
+    pub fn modify_player_secret(
+        &mut self,
+        player: Player,
+        f: impl Fn(PlayerSecretMut<S>) -> Option<S::Event>,
+    ) {
+        self.context.mutate_secret(player, |secret| {
+            let event = f(PlayerSecretMut {
+                secret: &mut secret.secret.secret,
+                player,
+                log: &mut *secret.log,
+            });
+
+            if let Some(event) = event {
+                (secret.log)(CardEvent::GameEvent { event });
+            }
+        });
+    }
+
+    /// Adds `delta` to one of a player's public resources, logging a `CardEvent::ResourceChanged`.
+    ///
+    /// Returns the new amount, which may be negative.
+    pub fn adjust_resource(&mut self, player: Player, kind: S::ResourceKind, delta: i32) -> i32 {
+        let counter = self
+            .player_cards_mut(player)
+            .counters
+            .entry(kind.clone())
+            .or_default();
+
+        *counter += delta;
+
+        let value = *counter;
+
+        self.context.log(CardEvent::ResourceChanged {
+            player,
+            kind,
+            delta,
+            value,
+        });
+
+        value
+    }
+
+    /// Suspends the action until a player makes a choice, e.g. for "your opponent discards a card".
+    ///
+    /// The action should return after prompting, and is continued by `State::resume`
+    /// when the player's response, identified by `State::prompt_response`, is applied.
+    /// No other action is accepted in the meantime, except concessions.
+    ///
+    /// Returns an error without prompting if no response could satisfy the choice, see `Choice::check`.
+    pub fn prompt(
+        &mut self,
+        player: Player,
+        key: impl Into<String>,
+        choice: Choice,
+    ) -> Result<(), error::PromptError> {
+        assert!(self.state.prompt.is_none(), "a prompt is already pending");
 
-                            let instance = secret.instance(id).unwrap().clone();
-                            let attachment = instance
-                                .attachment
-                                .map(|a_id| secret.instance(a_id).unwrap().clone());
+        choice.check()?;
 
-                            // Remove this card from its old zone in the secret.
-                            secret.secret.remove_id(secret.log, id);
+        let prompt = Prompt {
+            player,
+            key: key.into(),
+            choice,
+        };
 
-                            secret.log(CardEvent::MoveCard {
-                                instance: Some((instance, attachment)),
-                                from: old_location,
-                                to: ExactCardLocation {
-                                    player: bucket_owner,
-                                    location: (
-                                        to_zone,
-                                        match to_zone {
-                                            Zone::Deck => secret.deck.len(),
-                                            Zone::Hand { public: false } => secret.hand.len(),
-                                            Zone::Hand { public: true } => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::Field => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::Graveyard => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::Limbo { public: false } => secret.limbo.len(),
-                                            Zone::Limbo { public: true } => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::CardSelection => secret.card_selection.len(),
-                                            Zone::Casting => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::Dust { public: false } => secret.dust.len(),
-                                            Zone::Dust { public: true } => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::Attachment { .. } => 0,
-                                            Zone::HeroAbility => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                            Zone::Boon => {
-                                                unreachable!(
-                                                    "{}:{}:{}",
-                                                    file!(),
-                                                    line!(),
-                                                    column!()
-                                                )
-                                            }
-                                        },
-                                    ),
-                                },
-                            });
+        self.state.prompt = Some(prompt.clone());
 
-                            // Put the card in its new zone in the secret.
-                            match to_zone {
-                                Zone::Deck => secret.deck.push(id),
-                                Zone::Hand { public: false } => secret.hand.push(Some(id)),
-                                Zone::Hand { public: true } => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::Field => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::Graveyard => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::Limbo { public: false } => secret.limbo.push(id),
-                                Zone::Limbo { public: true } => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::CardSelection => secret.card_selection.push(id),
-                                Zone::Casting => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::Dust { public: false } => secret.dust.push(id),
-                                Zone::Dust { public: true } => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::Attachment { .. } => {
-                                    unreachable!("Can't attach a spell with move_card.")
-                                }
-                                Zone::HeroAbility => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                                Zone::Boon => {
-                                    unreachable!("{}:{}:{}", file!(), line!(), column!())
-                                }
-                            }
-                        },
-                        CardEvent::MoveCard {
-                            instance: None,
-                            from: CardLocation {
-                                player: bucket_owner,
-                                location,
-                            },
-                            to: ExactCardLocation {
-                                player: to_player,
-                                location: to_location,
-                            },
-                        },
-                    );
+        self.context.log(CardEvent::Prompt { prompt });
 
-                    return Ok((
-                        CardLocation {
-                            player: bucket_owner,
-                            location,
-                        },
-                        id,
-                    ));
-                }
-            }
+        Ok(())
+    }
 
-            let mut deferred_logs = vec![];
+    /// Resumes the action suspended by the pending prompt, see `CardGame::prompt`.
+    pub(crate) async fn respond(&mut self, response: PromptResponse) {
+        let prompt = self
+            .state
+            .prompt
+            .take()
+            .expect("no prompt is waiting for a response");
+
+        self.context.log(CardEvent::PromptResponse {
+            player: prompt.player,
+            response: response.clone(),
+        });
 
-            let (instance, attachment_instance) = match bucket {
-                None => {
-                    let id = id.expect("Card is in public state, but we don't know its id.");
-                    if let Some((
-                        Zone::Attachment {
-                            parent: Card::ID(old_parent),
-                        },
-                        ..,
-                    )) = location
-                    {
-                        let attach_clone = id
-                            .instance(this, None)
-                            .expect("Match is in None, so this id must be in public state.")
-                            .clone();
-                        this.modify_card_internal(
-                            old_parent.into(),
-                            |parent, _| {
-                                parent.attachment = None;
-                                S::on_detach(parent, &attach_clone);
-                            },
-                            &mut |event| deferred_logs.push(event),
-                        )
-                        .await;
-                    }
+        S::resume(self, prompt, response).await;
+    }
 
-                    if let Some(to_bucket_player) = to_bucket {
-                        let instance = std::mem::replace(
-                        &mut this.instances[id.0],
-                        InstanceOrPlayer::Player(to_bucket_player),
-                    )
-                    .instance()
-                    .expect(
-                        "Card was identified as public, but it's actually InstanceOrPlayer::Player",
-                    );
+    /// Starts a player's turn in `phase`, ending the current phase if any.
+    ///
+    /// Turns are opt-in: until this is first called, there is no turn, and events aren't wrapped in `CardEvent::InPhase`.
+    pub async fn start_turn(&mut self, player: Player, phase: S::Phase) {
+        let number = self.turn.as_ref().map_or(0, |turn| turn.number) + 1;
 
-                        let attachment = instance.attachment.map(|attachment_id| {
-                        std::mem::replace(&mut this.instances[attachment_id.0], InstanceOrPlayer::Player(to_bucket_player)).instance().expect("Since parent Card is public, attachment was identified as public, but it's actually InstanceOrPlayer::Player")
-                    });
+        self.change_phase(Turn {
+            player,
+            number,
+            phase,
+        })
+        .await;
+    }
 
-                        this.context.mutate_secret(owner, |mut secret| {
-                            if let Some((Zone::Hand { public: false }, index)) = location {
-                                secret
-                                    .hand
-                                    .remove(index.expect("no index for secret hand card"));
-                            }
-                        });
+    /// Ends the current phase and starts `phase` within the same turn.
+    pub async fn set_phase(&mut self, phase: S::Phase) -> Result<(), error::TurnError> {
+        let turn = match self.turn.clone() {
+            Some(turn) => turn,
+            None => return Err(error::TurnError::NoTurn),
+        };
 
-                        (Some(instance), attachment)
-                    } else {
-                        // we're moving from public to public
-                        (None, None)
-                    }
-                }
-                Some(player) => {
-                    let (instance, attachment_instance) = this
-                        .context
-                        .reveal_unique(
-                            player,
-                            move |secret| {
-                                let id = id.unwrap_or_else(|| {
-                                    secret.pointers[card.pointer().unwrap().index]
-                                });
+        self.change_phase(Turn { phase, ..turn }).await;
 
-                                let instance = secret
-                                    .instance(id)
-                                    .expect("Secret has the instance for this ID");
+        Ok(())
+    }
 
-                                (
-                                    Some(instance.clone()),
-                                    instance.attachment.map(|attachment| {
-                                        secret
-                                            .instance(attachment)
-                                            .expect("Secret has the instance for this ID")
-                                            .clone()
-                                    }),
-                                )
-                            },
-                            |_| true,
-                        )
-                        .await;
+    async fn change_phase(&mut self, turn: Turn<S>) {
+        if let Some(Turn { player, phase, .. }) = self.turn.clone() {
+            S::on_phase_end(self, player, phase).await;
+        }
 
-                    this.context.mutate_secret(player, move |mut secret| {
-                        let id =
-                            id.unwrap_or_else(|| secret.pointers[card.pointer().unwrap().index]);
-                        // find what collection id is in and remove it
-                        secret.deck.retain(|i| *i != id);
-                        secret.hand.retain(|i| *i != Some(id));
-                        secret.limbo.retain(|i| *i != id);
-                        secret.card_selection.retain(|i| *i != id);
-                        secret.dust.retain(|i| *i != id);
-
-                        let parent_id = secret
-                            .instances
-                            .values()
-                            .find(|c| c.attachment == Some(id))
-                            .map(|c| c.id);
-                        // We're removing the attachment from a card in the secret
-                        if let Some(parent_id) = parent_id {
-                            let attach_clone = secret.instance(id).unwrap().clone();
-                            let mut deferred_logs = vec![];
-                            secret.modify_card_internal(
-                                parent_id,
-                                &mut |event| deferred_logs.push(event),
-                                |parent, _| {
-                                    parent.attachment = None;
-                                    S::on_detach(parent, &attach_clone);
-                                },
-                            );
-                            secret.deferred_logs.append(&mut deferred_logs);
-                        }
-                        // We're removing a card with an attachment from the secret
-                        if let Some(attachment_id) = secret.instance(id).unwrap().attachment {
-                            secret.instances.remove(&attachment_id);
-                        }
+        let Turn { player, phase, .. } = turn.clone();
+
+        self.state.turn = Some(turn.clone());
+
+        self.context
+            .log(CardEvent::PhaseChange { turn: turn.clone() });
+        self.context.set_turn(Some(turn));
+
+        S::on_phase_start(self, player, phase).await;
+    }
+
+    /// Adds a modifier to a public card, granted by a public `source` if any, see `CardState::apply_modifier`.
+    ///
+    /// Modifiers are public, so pointers are only followed to public cards, and no secret card's ID is revealed.
+    /// Returns `None` without adding anything if either card is secret.
+    pub async fn add_modifier(
+        &mut self,
+        target: impl Into<Card>,
+        source: Option<Card>,
+        layer: i32,
+        modifier: Vec<u8>,
+    ) -> Option<ModifierID> {
+        if self.result().is_some() {
+            return None;
+        }
+
+        let target = self.public_id(target.into()).await?;
+
+        self.insert_modifier(ModifierTarget::Card(target), source, layer, modifier)
+            .await
+    }
+
+    /// Adds a modifier to every card, granted by a public `source` if any, like `CardGame::add_modifier`.
+    pub async fn add_global_modifier(
+        &mut self,
+        source: Option<Card>,
+        layer: i32,
+        modifier: Vec<u8>,
+    ) -> Option<ModifierID> {
+        if self.result().is_some() {
+            return None;
+        }
+
+        self.insert_modifier(ModifierTarget::All, source, layer, modifier)
+            .await
+    }
+
+    /// The ID of a public card, following pointers to public cards, or `None` for a secret card.
+    async fn public_id(&mut self, card: Card) -> Option<InstanceID> {
+        match self.reveal_public_id(card).await {
+            Ok(Card::ID(id)) if self.instances[id.0].instance_ref().is_some() => Some(id),
+            _ => None,
+        }
+    }
+
+    async fn insert_modifier(
+        &mut self,
+        target: ModifierTarget,
+        source: Option<Card>,
+        layer: i32,
+        modifier: Vec<u8>,
+    ) -> Option<ModifierID> {
+        let source = match source {
+            Some(source) => Some(self.public_id(source).await?),
+            None => None,
+        };
+
+        let id = ModifierID(self.next_modifier);
+
+        self.next_modifier += 1;
 
-                        // Finally, remove the card from the secret's instances.
-                        secret.instances.remove(&id);
-                    });
-                    (instance, attachment_instance)
-                }
-            };
+        let modifier = CardModifier {
+            id,
+            source,
+            target,
+            layer,
+            modifier,
+        };
 
-            // At this point in time, either we already knew ID, or we've revealed it by revealing the instance.
-            let id = id
-                .or_else(|| instance.as_ref().map(|v| v.id))
-                .expect("Either we know ID or we've revealed the instance.");
+        self.modifiers.push(modifier.clone());
 
-            // If this card came from a secret, we know it's leaving that secret. SX -> SX case handled above.
-            if let Some(bucket_owner) = bucket {
-                this.context.mutate_secret(bucket_owner, |secret| {
-                    // Take its ID out of any zones in that secret.
-                    secret.secret.remove_id(secret.log, id);
-                });
-            } else if let Some((Zone::Hand { public: true }, index)) = location {
-                this.context.mutate_secret(owner, |mut secret| {
-                    secret
-                        .hand
-                        .remove(index.expect("no index for public hand card"));
-                });
-            }
+        self.context.log(CardEvent::AddModifier { modifier });
 
-            match location {
-                Some((
-                    Zone::Attachment {
-                        parent: Card::ID(old_parent),
-                    },
-                    ..,
-                )) => {
-                    this.instances[old_parent.0]
-                        .instance_mut()
-                        .expect("Card should have been attached to a public parent")
-                        .attachment = None;
-                }
-                Some((zone, index)) => {
-                    this.player_cards_mut(owner).remove_from(zone, index);
-                }
-                None => (),
-            }
+        Some(id)
+    }
 
-            let field_index = if let Zone::Field = to_zone {
-                let my_instance = instance.as_ref().unwrap_or_else(|| {
-                id.instance(this, None)
-                    .expect("Card going to field isn't being removed from a secret, so should be in public state.")
-            });
-                let my_attachment = attachment_instance.as_ref().or_else(|| {
-                my_instance
-                    .attachment
-                    .map(|attach| attach.instance(this, None)
-                    .expect("Attachment of card going to field isn't being removed from a secret, so should be in public state."))
-            });
+    pub fn remove_modifier(&mut self, id: ModifierID) -> Option<CardModifier> {
+        if self.result().is_some() {
+            return None;
+        }
 
-                let field_index = this
-                    .player_cards(to_player)
-                    .field
-                    .iter()
-                    .map(|id| {
-                        let instance = id
-                            .instance(this, None)
-                            .expect("Instances on the field are in public state");
-                        CardInfo {
-                            instance,
-                            owner: to_player,
-                            zone: Zone::Field,
-                            attachment: instance.attachment.map(|attach| {
-                                attach.instance(this, None).expect(
-                                    "Attachments on instances on the field are in public state",
-                                )
-                            }),
-                        }
-                    })
-                    .position(move |card| {
-                        S::field_order(
-                            card,
-                            CardInfo {
-                                instance: &my_instance,
-                                owner: to_player,
-                                zone: Zone::Field,
-                                attachment: my_attachment,
-                            },
-                        ) == Ordering::Greater
-                    })
-                    .unwrap_or_else(|| this.player_cards(to_player).field.len());
-                Some(field_index)
-            } else {
-                None
-            };
+        let index = self
+            .modifiers
+            .iter()
+            .position(|modifier| modifier.id == id)?;
+        let modifier = self.modifiers.remove(index);
 
-            match to_zone {
-                Zone::Deck => {
-                    this.context.mutate_secret(to_player, |mut secret| {
-                        secret.deck.push(id);
-                    });
+        self.context.log(CardEvent::RemoveModifier { id });
 
-                    this.player_cards_mut(to_player).deck += 1;
-                }
-                Zone::Hand { public: false } => {
-                    this.context.mutate_secret(to_player, |mut secret| {
-                        secret.hand.push(Some(id));
-                    });
+        Some(modifier)
+    }
 
-                    this.player_cards_mut(to_player).hand.push(None);
-                }
-                Zone::Hand { public: true } => {
-                    let index = match location {
-                        Some((Zone::Hand { public: false }, Some(index))) if to_player == owner => {
-                            index
-                        }
-                        _ => this.player_cards(to_player).hand.len(),
-                    };
+    /// Removes the modifiers granted by a card that just moved, and those on it if it was dusted or left public state.
+    ///
+    /// So no modifier ever involves a secret card.
+    fn expire_modifiers(&mut self, id: InstanceID, to_zone: Zone) {
+        let target_expired = to_zone.is_dust() || self.instances[id.0].player().is_some();
 
-                    this.player_cards_mut(to_player)
-                        .hand
-                        .insert(index, Some(id));
+        let expired: Vec<_> = self
+            .modifiers
+            .iter()
+            .filter(|modifier| {
+                modifier.source == Some(id)
+                    || (target_expired && modifier.target == ModifierTarget::Card(id))
+            })
+            .map(|modifier| modifier.id)
+            .collect();
 
-                    this.context.mutate_secret(to_player, |mut secret| {
-                        secret.hand.insert(index, None);
-                    });
-                }
-                Zone::Field => {
-                    this.player_cards_mut(to_player).field.insert(
-                        field_index.expect("field_index should be Some when to_zone is Field"),
-                        id,
-                    );
-                }
-                Zone::Graveyard => {
-                    this.player_cards_mut(to_player).graveyard.push(id);
-                }
-                Zone::Limbo { public: false } => {
-                    this.context.mutate_secret(to_player, |mut secret| {
-                        secret.limbo.push(id);
-                    });
-                }
-                Zone::Limbo { public: true } => {
-                    this.player_cards_mut(to_player).limbo.push(id);
-                }
-                Zone::CardSelection => {
-                    this.context.mutate_secret(to_player, |mut secret| {
-                        secret.card_selection.push(id);
-                    });
+        for id in expired {
+            self.remove_modifier(id);
+        }
+    }
 
-                    this.player_cards_mut(to_player).card_selection += 1;
-                }
-                Zone::Casting => {
-                    this.player_cards_mut(to_player).casting.push(id);
-                }
-                Zone::Dust { public: false } => {
-                    this.context.mutate_secret(to_player, |mut secret| {
-                        secret.dust.push(id);
-                    });
-                }
-                Zone::Dust { public: true } => {
-                    this.player_cards_mut(to_player).dust.push(id);
-                }
-                Zone::Attachment { .. } => unreachable!("Cannot move card to attachment zone"),
-                Zone::HeroAbility => this.player_cards_mut(to_player).hero_ability.push(id),
-                Zone::Boon => this.player_cards_mut(to_player).boon.push(id),
+    async fn reveal_id(&mut self, card: Card) -> InstanceID {
+        match card {
+            Card::ID(id) => id,
+            Card::Pointer(OpaquePointer { player, index }) => {
+                self.context
+                    .reveal_unique(player, move |secret| secret.pointers[index], |_| true)
+                    .await
             }
+        }
+    }
 
-            if let Some(instance) = instance.clone() {
-                // we have a new instance, need to put it somewhere.
-                let id = instance.id;
+    /// Reveals the ID of a pointer only if it points to a linked card in its player's secret.
+    ///
+    /// Modifiers only involve public cards, see `CardGame::expire_modifiers`, so they never need a reveal.
+    async fn reveal_linked_id(&mut self, card: Card) -> Option<InstanceID> {
+        let OpaquePointer { player, index } = card.pointer()?;
 
-                match to_bucket {
-                    None => {
-                        this.instances[id.0] = instance.into();
-                    }
-                    Some(to_bucket_player) => {
-                        this.instances[id.0] = to_bucket_player.into();
+        let linked: Vec<_> = self
+            .links
+            .iter()
+            .flat_map(|link| [link.cards.0, link.cards.1])
+            .filter(|id| self.instances[id.0].player() == Some(player))
+            .collect();
 
-                        this.context
-                            .mutate_secret(to_bucket_player, move |mut secret| {
-                                secret.instances.insert(instance.id, instance.clone());
-                            });
-                    }
-                }
+        if linked.is_empty() {
+            return None;
+        }
 
-                // If we have an attachment_instance, we also need to put it somewhere the same way.
-                if let Some(attachment_instance) = attachment_instance.clone() {
-                    let attachment_id = attachment_instance.id;
+        self.context
+            .reveal_unique(
+                player,
+                move |secret| Some(secret.pointers[index]).filter(|id| linked.contains(id)),
+                |_| true,
+            )
+            .await
+    }
 
-                    match to_bucket {
-                        None => {
-                            this.instances[attachment_id.0] = attachment_instance.into();
-                        }
-                        Some(to_bucket_player) => {
-                            let attachment_id = attachment_instance.id;
-                            this.instances[attachment_id.0] = to_bucket_player.into();
+    /// Applies the link policy of a card that just moved to its linked card.
+    ///
+    /// Fails if the linked card can't follow, after the card itself has moved.
+    fn enforce_card_link(
+        &mut self,
+        id: InstanceID,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> Pin<Box<dyn Future<Output = Result<(), error::MoveCardError>> + '_>> {
+        Box::pin(async move {
+            let link = match self.link(id) {
+                Some(link) => *link,
+                None => return Ok(()),
+            };
+            let other = link.other(id).expect("link should contain the moved card");
 
-                            this.context
-                                .mutate_secret(to_bucket_player, move |mut secret| {
-                                    secret.instances.insert(
-                                        attachment_instance.id,
-                                        attachment_instance.clone(),
-                                    );
-                                });
-                        }
-                    }
+            if to_zone.is_dust() {
+                self.unlink_card(id);
+
+                let public = self.instances[other.0].instance_ref().is_some();
+
+                if !matches!(self.location(other).location, Some((Zone::Dust { .. }, _))) {
+                    self.move_card(other, self.owner(other), Zone::Dust { public })
+                        .await?;
                 }
+            } else if link.policy == LinkPolicy::MoveTogether
+                && !matches!(to_zone, Zone::Attachment { .. })
+            {
+                // Unlink without logging while the linked card follows, so it doesn't try to move this card back.
+                self.links.retain(|link| !link.contains(id));
+
+                let result = self.move_card(other, to_player, to_zone).await;
+
+                self.links.push(link);
+
+                result?;
             }
-            // we have to emit a sort field before we emit the card move event, otherwise things with same ID will sort wrong.
-            if to_zone.is_field() {
-                let mut logs = vec![];
-                this.sort_field(
-                    to_player,
-                    old_field
-                        .clone()
-                        .expect("If moving to field, we should have cached the old field."),
-                    false,
-                    &mut |event| logs.push(event),
-                );
-                for event in logs.into_iter() {
-                    this.context.log(event);
+
+            Ok(())
+        })
+    }
+
+    /// The `has_attachment` of a `CardEvent::MoveCard` that may be redacted, see `MatchConfig::redacted_attachment_policy`.
+    fn redacted_has_attachment(&self, has_attachment: bool) -> Option<bool> {
+        match self.config.redacted_attachment_policy {
+            RedactedAttachmentPolicy::Hidden => None,
+            RedactedAttachmentPolicy::Presence => Some(has_attachment),
+        }
+    }
+
+    /// Where a card goes on a player's field, by `State::field_order`.
+    fn field_index(
+        &self,
+        player: Player,
+        instance: &CardInstance<S>,
+        attachment: Option<&CardInstance<S>>,
+    ) -> usize {
+        let state = &self.state;
+
+        self.player_cards(player)
+            .field
+            .iter()
+            .map(|id| {
+                let instance = id
+                    .instance(self, None)
+                    .expect("Instances on the field are in public state");
+                CardInfo {
+                    instance,
+                    owner: player,
+                    zone: Zone::Field,
+                    attachment: instance.attachment.map(|attach| {
+                        attach
+                            .instance(self, None)
+                            .expect("Attachments on instances on the field are in public state")
+                    }),
                 }
-            }
+            })
+            .position(move |card| {
+                S::field_order_with(
+                    state,
+                    card,
+                    CardInfo {
+                        instance,
+                        owner: player,
+                        zone: Zone::Field,
+                        attachment,
+                    },
+                ) == Ordering::Greater
+            })
+            .unwrap_or_else(|| self.player_cards(player).field.len())
+    }
 
-            let move_card_event = (
-                instance.map(|i| (i, attachment_instance)).or_else(|| {
-                    id.instance(this, None).map(|instance| {
-                        (
-                            instance.clone(),
-                            instance
-                                .attachment
-                                .map(|a_id| a_id.instance(this, None).unwrap().clone()),
-                        )
-                    })
-                }),
-                CardLocation {
-                    player: owner,
-                    location,
-                },
-                ExactCardLocation {
-                    player: to_player,
-                    location: (
-                        to_zone,
-                        match to_zone {
-                            Zone::Deck => this.player_cards(to_player).deck() - 1,
-                            Zone::Hand { .. } => match location {
-                                Some((Zone::Hand { public: false }, Some(index)))
-                                    if to_player == owner =>
-                                {
-                                    index
-                                }
-                                _ => this.player_cards(to_player).hand.len() - 1,
-                            },
-                            Zone::Field => field_index
-                                .expect("field_index should be Some when to_zone is Field"),
-                            Zone::Graveyard => this.player_cards(to_player).graveyard().len() - 1,
-                            Zone::Limbo { public: false } => 0,
-                            Zone::Limbo { public: true } => {
-                                this.player_cards(to_player).limbo().len() - 1
-                            }
-                            Zone::CardSelection => {
-                                this.player_cards(to_player).card_selection() - 1
-                            }
-                            Zone::Casting => this.player_cards(to_player).casting().len() - 1,
-                            Zone::Dust { public: false } => 0,
-                            Zone::Dust { public: true } => {
-                                this.player_cards(to_player).dust().len() - 1
-                            }
-                            Zone::Attachment { .. } => 0,
-                            Zone::HeroAbility { .. } => {
-                                this.player_cards(to_player).hero_ability().len() - 1
-                            }
-                            Zone::Boon { .. } => this.player_cards(to_player).boon().len() - 1,
-                        },
-                    ),
-                },
-            );
+    /// Bumps the version of a player's zone, logging it if zone watermarks are enabled.
+    fn bump_zone_version(&mut self, player: Player, zone: Zone) {
+        if let Some(version) = self.state.bump_zone_version(player, zone) {
+            self.context.log(CardEvent::ZoneVersion {
+                player,
+                zone,
+                version,
+            });
+        }
+    }
+
+    fn queue_hook(&mut self, hook: StateHook) {
+        self.pending_hooks.push(hook);
+    }
 
-            if move_card_event.1.location.is_none() {
-                this.context.mutate_secret_or_log(owner, |mut secret| {
-                let (instance, mut from, to) = move_card_event.clone();
+    /// Queues `State::after_detach` for a card about to leave an attachment zone in a player's secret.
+    ///
+    /// The parent gets a new pointer in the secret, so its ID isn't revealed.
+    async fn queue_secret_detach(&mut self, player: Player, card: Card, id: Option<InstanceID>) {
+        let attachment = id.map_or(card, Card::from);
+
+        let parent = self
+            .new_secret_pointers(player, move |mut secret| {
+                if let Some(parent) = secret.parent_of(attachment) {
+                    secret.new_pointer(parent);
+                }
+            })
+            .await
+            .pop();
 
-                    let missing_location = secret.deferred_locations.pop().expect("If from location is none, publically, and we're the player, we should have the deferred location.");
-                    from.location = Some(missing_location);
+        if let Some(parent) = parent {
+            self.queue_hook(StateHook::Detach {
+                parent,
+                attachment: card,
+            });
+        }
+    }
 
-                secret.log(CardEvent::MoveCard { instance, from, to });
-            },CardEvent::MoveCard { instance: move_card_event.0.clone(), from: move_card_event.1.clone(), to: move_card_event.2.clone() });
-            } else {
-                this.context.log(CardEvent::MoveCard {
-                    instance: move_card_event.0.clone(),
-                    from: move_card_event.1.clone(),
-                    to: move_card_event.2.clone(),
-                })
-            }
+    /// Runs the queued async [State] callbacks, unless an operation or another callback is still running.
+    ///
+    /// Callbacks queued while the callbacks run are run after them, until none are left.
+    async fn run_hooks(&mut self) {
+        if self.in_operation {
+            return;
+        }
 
-            for deferred_log in deferred_logs {
-                this.context.log(deferred_log);
-            }
+        if self.running_hooks {
+            return;
+        }
 
-            for log_player in 0..2 {
-                this.context.mutate_secret(log_player, |secret| {
-                    for deferred_log in secret.secret.deferred_logs.drain(..) {
-                        (secret.log)(deferred_log);
-                    }
-                });
+        self.running_hooks = true;
+
+        loop {
+            let hooks = std::mem::take(&mut self.pending_hooks);
+
+            if hooks.is_empty() {
+                break;
             }
 
-            match to_zone {
-                Zone::Deck => {
-                    if this.shuffle_deck_on_insert {
-                        this.context.mutate_secret(to_player, |secret| {
-                            secret.secret.shuffle_deck(secret.random, secret.log);
-                        });
+            for hook in hooks {
+                match hook {
+                    StateHook::Attach { parent, attachment } => {
+                        S::after_attach(self, parent, attachment).await
                     }
-                }
-                Zone::Field => {
-                    let mut logs = vec![];
-                    this.sort_field(
-                        to_player,
-                        old_field
-                            .expect("If moving to field, we should have cached the old field."),
-                        true,
-                        &mut |event| logs.push(event),
-                    );
-                    for event in logs.into_iter() {
-                        this.context.log(event);
+                    StateHook::Detach { parent, attachment } => {
+                        S::after_detach(self, parent, attachment).await
                     }
+                    StateHook::SortField { player } => S::after_sort_field(self, player).await,
                 }
-                _ => (),
             }
-
-            Ok((
-                CardLocation {
-                    player: owner,
-                    location,
-                },
-                Some(id),
-            ))
         }
+
+        self.running_hooks = false;
     }
 
     pub async fn move_cards(
@@ -2645,6 +7383,85 @@ impl<S: State> CardGame<S> {
         results
     }
 
+    /// Like `CardGame::move_cards`, but only moves the chunk of `cards` starting at `start`, see `MatchConfig::batch_chunk_size`.
+    ///
+    /// Call it again with `BatchChunk::next`, e.g. in a later action, until it's `None`.
+    pub async fn move_cards_chunk(
+        &mut self,
+        cards: &[Card],
+        start: usize,
+        to_player: Player,
+        to_zone: Zone,
+    ) -> BatchChunk<Result<(CardLocation, Option<InstanceID>), error::MoveCardError>> {
+        let (chunk, next) = self.batch_chunk(cards, start);
+
+        BatchChunk {
+            results: self.move_cards(chunk.to_vec(), to_player, to_zone).await,
+            next,
+        }
+    }
+
+    /// Moves all of a player's public and secret limbo cards to `to_zone`.
+    pub async fn drain_limbo(
+        &mut self,
+        player: Player,
+        to_zone: Zone,
+    ) -> Vec<Result<(CardLocation, Option<InstanceID>), error::MoveCardError>> {
+        let mut cards: Vec<Card> = self
+            .public_limbo_cards(player)
+            .iter()
+            .copied()
+            .map(Into::into)
+            .collect();
+
+        cards.extend(self.secret_limbo_cards(player).await);
+
+        self.move_cards(cards, player, to_zone).await
+    }
+
+    /// Applies the limbo policy at the end of an action.
+    ///
+    /// Fails with the first player whose limbo isn't empty when the limbo policy is `LimboPolicy::Forbid`.
+    pub(crate) async fn enforce_limbo_policy(&mut self) -> Result<(), error::LimboError> {
+        if self.config.limbo_policy == LimboPolicy::Allow {
+            return Ok(());
+        }
+
+        for player in self.players() {
+            if self.context.conceded == Some(player) {
+                continue;
+            }
+
+            // Face-down cards and cards cast face-down wait in secret limbo until they leave it.
+            let face_down = self
+                .face_down
+                .iter()
+                .filter(|card| card.player == player)
+                .count()
+                + self.face_down_casts(player).len();
+
+            let empty = self.player_cards(player).limbo.is_empty()
+                && self
+                    .context
+                    .reveal_unique(
+                        player,
+                        move |secret| secret.limbo.len() <= face_down,
+                        |_| true,
+                    )
+                    .await;
+
+            if !empty {
+                match self.config.limbo_policy {
+                    LimboPolicy::Allow => (),
+                    LimboPolicy::Flag => self.context.log(CardEvent::LimboNotEmpty { player }),
+                    LimboPolicy::Forbid => return Err(error::LimboError::NotEmpty { player }),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn draw_card(&mut self, player: Player) -> Option<Card> {
         let cards = self.draw_cards(player, 1).await;
 
@@ -2654,6 +7471,10 @@ impl<S: State> CardGame<S> {
     }
 
     pub async fn draw_cards(&mut self, player: Player, count: usize) -> Vec<Card> {
+        if self.result().is_some() {
+            return vec![];
+        }
+
         let cards = self
             .deck_cards(player)
             .into_iter()
@@ -2665,88 +7486,422 @@ impl<S: State> CardGame<S> {
         cards
     }
 
+    /// Flips a coin with shared randomness, returning `true` for heads.
+    ///
+    /// Logs a `CardEvent::RandomOutcome` with 2 sides, and outcome 1 for heads.
+    pub async fn flip_coin(&mut self) -> bool {
+        self.roll(2).await == 1
+    }
+
+    /// Rolls a die with `sides` sides with shared randomness, returning a number from 1 to `sides`.
+    pub async fn roll(&mut self, sides: usize) -> usize {
+        assert!(sides > 0, "can't roll a die with no sides");
+
+        let outcome = self.context.random().await.gen_range(1..=sides);
+
+        self.context
+            .log(CardEvent::RandomOutcome { sides, outcome });
+
+        outcome
+    }
+
+    /// Picks a uniformly random card from one of a player's zones, if it isn't empty.
+    ///
+    /// Only a pointer to the card is revealed if the zone is secret.
+    /// The public and secret parts of the hand, dust and limbo are separate zones.
+    pub async fn random_card(&mut self, player: Player, zone: Zone) -> Option<Card> {
+        self.random_cards_weighted(player, zone, 1, |_| 1)
+            .await
+            .into_iter()
+            .next()
+    }
+
+    /// Randomly picks up to `count` distinct cards satisfying `f` from one of a player's zones.
+    ///
+    /// For secret zones, `f` is checked in the owner's secret, so only pointers to the picked cards are revealed,
+    /// and their number, if fewer than `count` cards satisfy `f`.
+    pub async fn choose_random_matching(
+        &mut self,
+        player: Player,
+        zone: Zone,
+        f: impl Fn(CardInfo<S>) -> bool,
+        count: usize,
+    ) -> Vec<Card> {
+        self.random_cards_weighted(player, zone, count, |card| u32::from(f(card)))
+            .await
+    }
+
+    /// Randomly picks up to `count` distinct cards from one of a player's zones,
+    /// each with a chance proportional to its weight, e.g. for "discover" effects weighted by rarity.
+    ///
+    /// Cards with a weight of 0 are never picked.
+    /// The weights of secret cards are computed in their owner's secret, so only pointers to the picked cards are revealed.
+    /// The cards are in the order they were picked.
+    pub async fn random_cards_weighted(
+        &mut self,
+        player: Player,
+        zone: Zone,
+        count: usize,
+        weight: impl Fn(CardInfo<S>) -> u32,
+    ) -> Vec<Card> {
+        let rolls: Vec<_> = {
+            let mut random = self.context.random().await;
+
+            (0..count).map(|_| random.next_u64()).collect()
+        };
+
+        match zone.is_public() {
+            Ok(true) => {
+                let weights = self
+                    .player_cards(player)
+                    .zone_cards(zone)
+                    .into_iter()
+                    .map(|id| (id, weight(self.card_info(id))))
+                    .collect();
+
+                weighted_sample(weights, &rolls)
+                    .into_iter()
+                    .map(Card::from)
+                    .collect()
+            }
+            Ok(false) => {
+                self.new_secret_pointers(player, |mut secret| {
+                    let weights = secret
+                        .zone_cards(zone)
+                        .into_iter()
+                        .map(|id| {
+                            let weight = secret
+                                .reveal_from_card(id, &weight)
+                                .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+                            (id, weight)
+                        })
+                        .collect();
+
+                    for id in weighted_sample(weights, &rolls) {
+                        secret.new_pointer(id);
+                    }
+                })
+                .await
+            }
+            Err(_) => panic!("can't pick random cards from {:?}", zone),
+        }
+    }
+
     pub async fn new_secret_cards(
         &mut self,
         player: Player,
         f: impl Fn(SecretCardsInfo<S>),
     ) -> Vec<Card> {
+        if self.result().is_some() {
+            return vec![];
+        }
+
+        let start = self.instances.len();
+        let padding = self.config.secret_card_padding;
+
+        self.context.mutate_secret(player, |mut secret| {
+            secret.next_instance = Some(InstanceID(start));
+
+            f(secret.into())
+        });
+
+        let first = self.player_cards(player).pointers;
+
+        let (pointers, end) = self
+            .context
+            .reveal_unique(
+                player,
+                move |secret| {
+                    let end = secret.next_instance.expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call").0;
+
+                    (
+                        first + padded_count(secret.pointers.len() - first, padding),
+                        start + padded_count(end - start, padding),
+                    )
+                },
+                |_| true,
+            )
+            .await;
+
+        assert!(pointers >= first);
+        assert!(end >= start);
+
+        self.context.mutate_secret(player, move |mut secret| {
+            secret.next_instance = None;
+
+            // Fake pointers don't point to any card, like pointers to destroyed cards.
+            secret.pointers.resize(pointers, InstanceID::DESTROYED);
+        });
+
+        self.state.reserve_instances(player, end - start);
+
+        if padding > 1 {
+            self.player_cards_mut(player).destroyed_pointers = true;
+        }
+
+        let player_cards = self.player_cards_mut(player);
+
+        let cards = (player_cards.pointers..pointers)
+            .map(|index| OpaquePointer { player, index }.into())
+            .collect();
+
+        player_cards.pointers = pointers;
+
+        cards
+    }
+
+    pub async fn new_secret_cards_with_fakes(
+        &mut self,
+        player: Player,
+        f: impl Fn(SecretCardsWithFakesInfo<S>),
+    ) -> Vec<Card> {
+        if self.result().is_some() {
+            return vec![];
+        }
+
+        let start = self.instances.len();
+        let padding = self.config.secret_card_padding;
+
+        self.context.mutate_secret(player, |mut secret| {
+            secret.next_instance = Some(InstanceID(start));
+
+            f(secret.into())
+        });
+
+        let first = self.player_cards(player).pointers;
+
+        let (pointers, end) = self
+            .context
+            .reveal_unique(
+                player,
+                move |secret| {
+                    let end = secret.next_instance.expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call").0;
+
+                    (
+                        first + padded_count(secret.pointers.len() - first, padding),
+                        start + padded_count(end - start, padding),
+                    )
+                },
+                |_| true,
+            )
+            .await;
+
+        assert!(pointers >= first);
+        assert!(end >= start);
+
+        self.context.mutate_secret(player, move |mut secret| {
+            secret.next_instance = None;
+
+            // Fake pointers don't point to any card, like pointers to destroyed cards.
+            secret.pointers.resize(pointers, InstanceID::DESTROYED);
+        });
+
+        self.state.reserve_instances(player, end - start);
+
+        if padding > 1 {
+            self.player_cards_mut(player).destroyed_pointers = true;
+        }
+
+        let player_cards = self.player_cards_mut(player);
+
+        let cards = (player_cards.pointers..pointers)
+            .map(|index| OpaquePointer { player, index }.into())
+            .collect();
+
+        player_cards.pointers = pointers;
+
+        cards
+    }
+
+    /// Replaces a player's deck with new cards, in the given order.
+    ///
+    /// The old deck is moved to the player's secret dust, logging a `CardEvent::MoveCard` for each card to the player.
+    /// Pointers to the old deck's cards keep pointing to them in dust, and their links are broken without dusting the linked cards.
+    /// Modifiers only involve public cards, so none apply to the old deck.
+    /// The new cards get their default attachments from the player's secret.
+    /// Fails without changing anything if the match config or the card database rejects the deck.
+    pub async fn replace_deck(
+        &mut self,
+        player: Player,
+        deck: Vec<S::BaseCard>,
+    ) -> Result<(), error::IllegalCardError> {
+        if let Some(result) = self.result() {
+            return Err(error::IllegalCardError::GameOver { result });
+        }
+
+        self.validate_deck(&deck)?;
+
+        let linked: Vec<_> = self
+            .links
+            .iter()
+            .flat_map(|link| [link.cards.0, link.cards.1])
+            .filter(|id| self.instances[id.0].player() == Some(player))
+            .collect();
+
+        if !linked.is_empty() {
+            let unlinked = self
+                .context
+                .reveal_unique(
+                    player,
+                    move |secret| {
+                        secret
+                            .deck
+                            .iter()
+                            .copied()
+                            .filter(|id| linked.contains(id))
+                            .collect::<Vec<_>>()
+                    },
+                    |_| true,
+                )
+                .await;
+
+            for id in unlinked {
+                self.unlink_card(id);
+            }
+        }
+
         let start = self.instances.len();
+        let padding = self.config.secret_card_padding;
+        let size = deck.len();
 
         self.context.mutate_secret(player, |mut secret| {
+            while let Some(&old) = secret.deck.first() {
+                secret
+                    .secret
+                    .dust_card(old, secret.log)
+                    .expect("the old deck should be in its secret");
+            }
+
             secret.next_instance = Some(InstanceID(start));
 
-            f(secret.into())
+            let mut cards = SecretCardsInfo::from(secret);
+
+            for base in &deck {
+                let id = cards.next_card();
+                let attachment = cards.secret.attachment(&id, base.clone());
+
+                let id = cards.new_instance(base.clone(), attachment);
+
+                if let Some(attachment) = cards.instances[&id].attachment {
+                    let attachment = cards.instances[&attachment].clone();
+                    let instance = cards.secret_mut().instances.get_mut(&id).unwrap();
+
+                    S::on_attach(instance, &attachment);
+                }
+
+                cards.secret_mut().deck.push(id);
+            }
         });
 
-        let (pointers, end) = self
+        let end = self
             .context
             .reveal_unique(
                 player,
-                |secret| {
-                    (secret.pointers.len(), secret.next_instance.expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call").0)
+                move |secret| {
+                    let end = secret.next_instance.expect("`PlayerSecret::next_instance` missing during `CardGame::replace_deck` call").0;
+
+                    start + padded_count(end - start, padding)
                 },
                 |_| true,
             )
             .await;
 
-        assert!(pointers >= self.player_cards(player).pointers);
-        assert!(end >= start);
-
         self.context.mutate_secret(player, |mut secret| {
             secret.next_instance = None;
         });
 
-        self.instances
-            .extend(repeat(InstanceOrPlayer::Player(player)).take(end - start));
+        self.state.reserve_instances(player, end - start);
 
-        let player_cards = self.player_cards_mut(player);
-
-        let cards = (player_cards.pointers..pointers)
-            .map(|index| OpaquePointer { player, index }.into())
-            .collect();
+        self.player_cards_mut(player).deck = size;
 
-        player_cards.pointers = pointers;
+        self.context.log(CardEvent::ReplaceDeck { player, size });
+        self.bump_zone_version(player, Zone::Deck);
 
-        cards
+        Ok(())
     }
 
-    pub async fn new_secret_cards_with_fakes(
+    /// Swaps cards between a player's deck and their sideboard, one of their piles, e.g. between games of a match.
+    ///
+    /// `f` picks the deck cards to move to the sideboard and the sideboard cards to move to the deck.
+    /// It runs inside the player's secret, so only the new deck and sideboard sizes are revealed.
+    /// The deck is then shuffled.
+    /// Fails without changing anything if the new deck size is outside the match config's bounds.
+    pub async fn sideboard(
         &mut self,
         player: Player,
-        f: impl Fn(SecretCardsWithFakesInfo<S>),
-    ) {
-        let start = self.instances.len();
-
-        self.context.mutate_secret(player, |mut secret| {
-            secret.next_instance = Some(InstanceID(start));
+        pile: PileID,
+        f: impl Fn(&PlayerSecret<S>) -> (Vec<InstanceID>, Vec<InstanceID>) + 'static,
+    ) -> Result<(), error::IllegalCardError> {
+        if let Some(result) = self.result() {
+            return Err(error::IllegalCardError::GameOver { result });
+        }
 
-            f(secret.into())
-        });
+        let f = Rc::new(f);
 
-        let (pointers, end) = self
+        let (deck, sideboard) = self
             .context
             .reveal_unique(
                 player,
-                |secret| {
-                    (secret.pointers.len(), secret.next_instance.expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call").0)
+                {
+                    let f = f.clone();
+
+                    move |secret| {
+                        let (out, into) = f(secret);
+                        let (deck, sideboard) = secret.sideboarded(pile, &out, &into);
+
+                        (deck.len(), sideboard.len())
+                    }
                 },
                 |_| true,
             )
             .await;
 
-        assert!(pointers >= self.player_cards(player).pointers);
-        assert!(end >= start);
+        if let Some((min, max)) = self.config.deck_size {
+            if !(min..=max).contains(&deck) {
+                return Err(error::IllegalCardError::DeckSize {
+                    size: deck,
+                    min,
+                    max,
+                });
+            }
+        }
 
-        self.context.mutate_secret(player, |mut secret| {
-            secret.next_instance = None;
-        });
+        self.context.mutate_secret(player, |secret| {
+            let (out, into) = f(secret.secret);
+            let (deck, sideboard) = secret.secret.sideboarded(pile, &out, &into);
+
+            if secret.secret.piles.len() <= pile.0 {
+                secret
+                    .secret
+                    .piles
+                    .resize_with(pile.0 + 1, Default::default);
+            }
 
-        self.instances
-            .extend(repeat(InstanceOrPlayer::Player(player)).take(end - start));
+            secret.secret.deck = deck;
+            secret.secret.piles[pile.0] = sideboard;
+            secret.secret.shuffle_deck(secret.random, secret.log);
+        });
 
         let player_cards = self.player_cards_mut(player);
 
-        player_cards.pointers = pointers;
+        if player_cards.piles.len() <= pile.0 {
+            player_cards.piles.resize(pile.0 + 1, 0);
+        }
+
+        player_cards.deck = deck;
+        player_cards.piles[pile.0] = sideboard;
+
+        self.context.log(CardEvent::Sideboard {
+            player,
+            pile,
+            deck,
+            sideboard,
+        });
+        self.bump_zone_version(player, Zone::Deck);
+        self.bump_zone_version(player, Zone::Pile { pile });
+
+        Ok(())
     }
 
     pub async fn new_secret_pointers(
@@ -2834,16 +7989,14 @@ impl<S: State> CardGame<S> {
         match card {
             Card::ID(id) => self.instances[id.0].instance_ref().is_some(),
             Card::Pointer(OpaquePointer { player, index }) => {
-                let is_public: Vec<_> = self
-                    .instances
-                    .iter()
-                    .map(|instance| instance.instance_ref().is_some())
-                    .collect();
+                let buckets = self.state.buckets();
 
                 self.context
                     .reveal_unique(
                         player,
-                        move |secret| is_public[secret.pointers[index].0],
+                        move |secret| {
+                            Bucket::of(&buckets, secret.pointers[index]) == Bucket::Public
+                        },
                         |_| true,
                     )
                     .await
@@ -2888,7 +8041,7 @@ impl<S: State> CardGame<S> {
         let card = card.into();
         let parent = parent.into();
 
-        Box::pin(async move {
+        let future = async move {
             let buckets: Vec<_> = self
                 .instances
                 .iter()
@@ -2939,10 +8092,7 @@ impl<S: State> CardGame<S> {
 
                     if let Some(attachment) = attachment {
                         self.move_card(attachment, owner, Zone::Dust { public: true })
-                            .await
-                            .unwrap_or_else(|_| {
-                                panic!("unable to move attachment {:?} to public dust", attachment)
-                            });
+                            .await?;
                     }
 
                     Some(id)
@@ -3009,40 +8159,34 @@ impl<S: State> CardGame<S> {
                     )
                 }
                 Some(player) => {
-                    self.context.mutate_secret(player, move |mut secret| {
-                        let location =
-                            secret
+                    // Attaching to another player's secret card logs the event to this player as public.
+                    if parent_bucket.is_none() || parent_bucket == card_bucket {
+                        self.context.mutate_secret(player, move |mut secret| {
+                            let location = secret
                                 .location(card_id.unwrap_or_else(|| {
                                     secret.pointers[card.pointer().unwrap().index]
                                 }))
                                 .location
                                 .expect("The secret should know the zone.");
 
-                        match location.0 {
-                            Zone::Limbo { public: false } | Zone::Attachment { .. } => {
+                            if is_partial(public_location(location)) {
                                 secret.deferred_locations.push(location);
                             }
-                            _ => {
-                                // location will be revealed publicly
-                            }
-                        }
-                    });
+                        });
+                    }
+
                     self.context
                         .reveal_unique(
                             player,
                             move |secret| {
-                                let location = secret
-                                    .location(card_id.unwrap_or_else(|| {
-                                        secret.pointers[card.pointer().unwrap().index]
-                                    }))
-                                    .location
-                                    .expect("The secret should know the zone.");
-
-                                match location.0 {
-                                    Zone::Limbo { public: false } => None,
-                                    Zone::Attachment { .. } => None,
-                                    zone => Some((zone, location.1)),
-                                }
+                                public_location(
+                                    secret
+                                        .location(card_id.unwrap_or_else(|| {
+                                            secret.pointers[card.pointer().unwrap().index]
+                                        }))
+                                        .location
+                                        .expect("The secret should know the zone."),
+                                )
                             },
                             |_| true,
                         )
@@ -3050,6 +8194,10 @@ impl<S: State> CardGame<S> {
                 }
             };
 
+            if let (Some(player), None) = (card_bucket, location) {
+                self.queue_secret_detach(player, card, card_id).await;
+            }
+
             let mut deferred_logs = vec![];
 
             if let Some((zone, index)) = location {
@@ -3081,9 +8229,7 @@ impl<S: State> CardGame<S> {
             self.context.mutate_secret(owner, |mut secret| {
                 // Either we know the ID, or it's in this secret!
                 let id = card_id.unwrap_or_else(|| secret.pointers[card.pointer().unwrap().index]);
-                let mut deferred_logs = vec![];
-                secret.remove_id(&mut |event| deferred_logs.push(event), id);
-                secret.deferred_logs.extend(deferred_logs);
+                secret.with_deferred_log(|secret, log| secret.remove_id(log, id));
             });
 
             // Step 3 and 4 only need to be performed if the source and destination buckets are different.
@@ -3112,24 +8258,40 @@ impl<S: State> CardGame<S> {
                     Some(card_id) => card_id,
                 };
 
-                let instance = match card_bucket {
+                // The card's inverses follow it, see `CardGame::modify_card_until`.
+                let (instance, inverses) = match card_bucket {
                     None => {
                         let parent_bucket_player = parent_bucket
                             .expect("parent bucket isn't public, but also not a player's secret");
 
-                        std::mem::replace(
-                            &mut self.instances[card_id.0],
-                            InstanceOrPlayer::Player(parent_bucket_player),
-                        )
-                        .instance()
-                        .unwrap()
+                        let instance = self
+                            .state
+                            .set_instance(card_id, parent_bucket_player)
+                            .instance()
+                            .unwrap();
+
+                        let inverses = Inverse::take(&mut self.state.inverses, |inverse| {
+                            inverse.id == card_id
+                        });
+
+                        (instance, inverses)
                     }
                     Some(card_bucket_player) => {
-                        let instance = self
+                        let revealed = self
                             .context
                             .reveal_unique(
                                 card_bucket_player,
-                                move |secret| secret.instance(card_id).unwrap().clone(),
+                                move |secret| {
+                                    (
+                                        secret.instance(card_id).unwrap().clone(),
+                                        secret
+                                            .inverses
+                                            .iter()
+                                            .filter(|inverse| inverse.id == card_id)
+                                            .cloned()
+                                            .collect::<Vec<_>>(),
+                                    )
+                                },
                                 |_| true,
                             )
                             .await;
@@ -3137,9 +8299,10 @@ impl<S: State> CardGame<S> {
                         self.context
                             .mutate_secret(card_bucket_player, |mut secret| {
                                 secret.instances.remove(&card_id);
+                                secret.inverses.retain(|inverse| inverse.id != card_id);
                             });
 
-                        instance
+                        revealed
                     }
                 };
 
@@ -3147,14 +8310,16 @@ impl<S: State> CardGame<S> {
                 // Add card to parent's bucket.
                 match parent_bucket {
                     None => {
-                        self.instances[card_id.0] = InstanceOrPlayer::Instance(instance);
+                        self.state.set_instance(card_id, instance);
+                        self.state.inverses.extend(inverses);
                     }
                     Some(parent_bucket_player) => {
-                        self.instances[card_id.0] = InstanceOrPlayer::Player(parent_bucket_player);
+                        self.state.set_instance(card_id, parent_bucket_player);
 
                         self.context
                             .mutate_secret(parent_bucket_player, |mut secret| {
                                 secret.instances.insert(card_id, instance.clone());
+                                secret.inverses.extend(inverses.iter().cloned());
                             });
                     }
                 }
@@ -3192,6 +8357,9 @@ impl<S: State> CardGame<S> {
                 card_id => card_id,
             };
 
+            // An attachment can't have an attachment of its own.
+            let has_attachment = self.redacted_has_attachment(false);
+
             match parent_id {
                 None => {
                     let parent_bucket_player = parent_bucket
@@ -3202,13 +8370,11 @@ impl<S: State> CardGame<S> {
                             let card_id = card_id
                                 .unwrap_or_else(|| secret.pointers[card.pointer().unwrap().index]);
                             let parent_id = secret.pointers[parent.pointer().unwrap().index];
-                            let location = location.or_else(|| {
-                                if card_bucket == Some(parent_bucket_player) {
-                                    Some(secret.deferred_locations.pop().expect("Has deferred location, because we're attaching from -> to the same secret, so this secret has the from."))
-                                } else {
-                                    None
-                                }
-                            });
+                            let location = if is_partial(location) && card_bucket == Some(parent_bucket_player) {
+                                Some(secret.deferred_locations.pop().expect("Has deferred location, because we're attaching from -> to the same secret, so this secret has the from."))
+                            } else {
+                                location
+                            };
                             secret.secret
                                 .attach_card(parent_id, card_id, location, secret.log)
                                 .unwrap();
@@ -3222,6 +8388,7 @@ impl<S: State> CardGame<S> {
                                 player: parent_bucket_player,
                                 location: (Zone::Attachment { parent }, 0),
                             },
+                            has_attachment,
                         });
                 }
                 Some(parent_id) => match parent_bucket {
@@ -3270,6 +8437,7 @@ impl<S: State> CardGame<S> {
                                             0,
                                         ),
                                     },
+                                    has_attachment: None,
                                 });
 
                                 S::on_attach(parent, &new_attach);
@@ -3278,13 +8446,17 @@ impl<S: State> CardGame<S> {
                         )
                         .await;
                         for msg in logs.into_iter() {
-                            if let CardEvent::MoveCard { instance, from, to } = msg {
+                            if let CardEvent::MoveCard {
+                                instance, from, to, ..
+                            } = msg
+                            {
                                 let from_player = from.player;
-                                if from.location.is_some() {
+                                if !is_partial(from.location) {
                                     self.context.log(CardEvent::MoveCard {
                                         instance: instance.clone(),
                                         from: from.clone(),
                                         to: to.clone(),
+                                        has_attachment,
                                     })
                                 } else {
                                     self.context.mutate_secret_or_log(from_player, |mut secret| {
@@ -3299,9 +8471,10 @@ impl<S: State> CardGame<S> {
                                             player: from_player,
                                             location,
                                         },
-                                        to: to.clone()
+                                        to: to.clone(),
+                                        has_attachment: None,
                                     })
-                                }, CardEvent::MoveCard { instance: instance.clone(), from: from.clone(), to: to.clone() });
+                                }, CardEvent::MoveCard { instance: instance.clone(), from: from.clone(), to: to.clone(), has_attachment });
                                 }
                             } else {
                                 deferred_logs.push(msg);
@@ -3314,13 +8487,11 @@ impl<S: State> CardGame<S> {
                                 let card_id = card_id.unwrap_or_else(|| {
                                     secret.pointers[card.pointer().unwrap().index]
                                 });
-                                let location = location.or_else(||
-                                    {   if card_bucket == Some(parent_bucket_player) {
-                                            Some(secret.deferred_locations.pop().expect("Has deferred location, because we're attaching from -> to the same secret, so this secret has the from."))
-                                        } else {
-                                            None
-                                        }
-                                    });
+                                let location = if is_partial(location) && card_bucket == Some(parent_bucket_player) {
+                                    Some(secret.deferred_locations.pop().expect("Has deferred location, because we're attaching from -> to the same secret, so this secret has the from."))
+                                } else {
+                                    location
+                                };
                                 secret.secret
                                     .attach_card(parent_id, card_id, location, secret.log)
                                     .unwrap();
@@ -3339,6 +8510,7 @@ impl<S: State> CardGame<S> {
                                         0,
                                     ),
                                 },
+                                has_attachment,
                             });
                     }
                 },
@@ -3348,13 +8520,7 @@ impl<S: State> CardGame<S> {
                 self.context.log(deferred_log);
             }
 
-            for log_player in 0..2 {
-                self.context.mutate_secret(log_player, |secret| {
-                    for deferred_log in secret.secret.deferred_logs.drain(..) {
-                        (secret.log)(deferred_log);
-                    }
-                });
-            }
+            self.flush_deferred_logs();
 
             Ok((
                 CardLocation {
@@ -3363,7 +8529,12 @@ impl<S: State> CardGame<S> {
                 },
                 card_id,
             ))
-        })
+        };
+
+        Box::pin(traced!(
+            future,
+            tracing::debug_span!("attach_card", ?card, ?parent)
+        ))
     }
 
     fn sort_field(
@@ -3373,9 +8544,13 @@ impl<S: State> CardGame<S> {
         actually_update: bool,
         logger: &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
     ) {
+        if self.config.positioned_field {
+            return;
+        }
+
         let mut field = self.player_cards(player).field.clone();
 
-        field.sort_by(|a, b| {
+        let compare = |a: &InstanceID, b: &InstanceID| {
             let a = self.instances[a.0]
                 .instance_ref()
                 .expect("field card is not public");
@@ -3406,8 +8581,25 @@ impl<S: State> CardGame<S> {
                 }),
             };
 
-            S::field_order(a, b)
-        });
+            S::field_order_with(&self.state, a, b)
+        };
+
+        #[cfg(feature = "field-order-check")]
+        let conflict = !is_total_order(&field, compare);
+
+        #[cfg(not(feature = "field-order-check"))]
+        let conflict = false;
+
+        if conflict {
+            logger(CardEvent::FieldOrderConflict {
+                player,
+                field: field.clone(),
+            });
+
+            S::resolve_field_order(&mut field);
+        } else {
+            field.sort_by(compare);
+        }
 
         if field != old_field {
             logger(CardEvent::SortField {
@@ -3415,6 +8607,21 @@ impl<S: State> CardGame<S> {
                 field: field.clone(),
                 real: actually_update,
             });
+
+            if actually_update {
+                self.queue_hook(StateHook::SortField { player });
+            }
+        }
+
+        if actually_update && field != self.player_cards(player).field {
+            // Moves already bump the field, so only bump it again if sorting reordered it.
+            if let Some(version) = self.state.bump_zone_version(player, Zone::Field) {
+                logger(CardEvent::ZoneVersion {
+                    player,
+                    zone: Zone::Field,
+                    version,
+                });
+            }
         }
 
         if actually_update {
@@ -3424,6 +8631,27 @@ impl<S: State> CardGame<S> {
     }
 }
 
+/// Checks that `compare` is a total order on `items`: antisymmetric and transitive.
+#[cfg(feature = "field-order-check")]
+fn is_total_order<T>(items: &[T], compare: impl Fn(&T, &T) -> Ordering) -> bool {
+    items.iter().all(|a| {
+        items.iter().all(|b| {
+            let ab = compare(a, b);
+
+            ab == compare(b, a).reverse()
+                && items.iter().all(|c| {
+                    let ac = compare(a, c);
+
+                    match (ab, compare(b, c)) {
+                        (Ordering::Equal, bc) => ac == bc,
+                        (ab, Ordering::Equal) => ac == ab,
+                        (ab, bc) => ab != bc || ac == ab,
+                    }
+                })
+        })
+    })
+}
+
 #[derive(Debug)]
 pub struct CardInfo<'a, S: State> {
     pub instance: &'a CardInstance<S>,
@@ -3440,6 +8668,68 @@ impl<S: State> Deref for CardInfo<'_, S> {
     }
 }
 
+/// A condition on a card, for `CardGame::reveal_if`.
+pub type CardPredicate<S> = Rc<dyn Fn(CardInfo<S>) -> bool>;
+
+/// One chunk of a batch operation, e.g. `CardGame::move_cards_chunk`.
+#[derive(Clone, Debug)]
+pub struct BatchChunk<T> {
+    /// The results for the chunk's cards, in order.
+    pub results: Vec<T>,
+
+    /// Where the next chunk starts in the batch, or `None` if this was the last chunk.
+    pub next: Option<usize>,
+}
+
+/// The result of `CardGame::attach`.
+#[derive(Clone, Debug)]
+pub struct AttachResult {
+    /// The parent's previous attachment, which was dusted.
+    /// This is a pointer if the parent is secret.
+    pub previous: Option<Card>,
+
+    /// Where the parent is. Only the owner is known if the parent is secret.
+    pub parent_location: CardLocation,
+
+    /// Where the attached card was.
+    pub from: CardLocation,
+
+    /// The attached card's ID, if it was revealed.
+    pub id: Option<InstanceID>,
+}
+
+/// Options for `CardGame::move_card_with`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MoveOptions {
+    /// Leaves a card moved to the field where it was inserted, instead of sorting the field.
+    /// See `CardGame::resort_field`.
+    pub skip_sort: bool,
+}
+
+/// How `CardGame::reveal_if` combines its conditions.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Quantifier {
+    /// At least one condition must hold.
+    Any,
+
+    /// All conditions must hold.
+    Every,
+}
+
+/// What `CardGame::transform_card` does with a card's attachment.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TransformPolicy {
+    /// The attachment stays attached.
+    Keep,
+
+    /// The attachment is dusted.
+    Dust,
+
+    /// The attachment is replaced with the new base's default attachment from `Secret::attachment`, if any,
+    /// and dusted otherwise.
+    Replace,
+}
+
 pub struct CardInfoMut<'a, S: State> {
     pub instance: &'a mut CardInstance<S>,
     pub owner: Player,
@@ -3462,19 +8752,36 @@ impl<S: State> DerefMut for CardInfoMut<'_, S> {
     }
 }
 
-pub struct SecretCardsInfo<'a, S: State>(MutateSecretInfo<'a, S>);
+/// A player's game-specific secret data, for `CardGame::modify_player_secret`.
+pub struct PlayerSecretMut<'a, S: State> {
+    pub secret: &'a mut S::Secret,
+    pub player: Player,
+
+    /// Logs an event to the player only.
+    pub log: &'a mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
+}
 
-impl<'a, S: State> Deref for SecretCardsInfo<'a, S> {
-    type Target = MutateSecretInfo<'a, S>;
+impl<S: State> Deref for PlayerSecretMut<'_, S> {
+    type Target = S::Secret;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.secret
     }
 }
 
-impl<S: State> DerefMut for SecretCardsInfo<'_, S> {
+impl<S: State> DerefMut for PlayerSecretMut<'_, S> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        self.secret
+    }
+}
+
+pub struct SecretCardsInfo<'a, S: State>(MutateSecretInfo<'a, S>);
+
+impl<S: State> Deref for SecretCardsInfo<'_, S> {
+    type Target = PlayerSecret<S>;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.secret
     }
 }
 
@@ -3485,45 +8792,69 @@ impl<'a, S: State> From<MutateSecretInfo<'a, S>> for SecretCardsInfo<'a, S> {
 }
 
 impl<S: State> SecretCardsInfo<'_, S> {
-    pub fn new_card(&mut self, base: S::BaseCard, attachment: Option<S::BaseCard>) -> InstanceID {
-        let mut next_instance = self.next_instance.expect(
+    fn secret_mut(&mut self) -> &mut PlayerSecret<S> {
+        self.0.secret
+    }
+
+    pub fn random(&mut self) -> &mut dyn rand::RngCore {
+        self.0.random
+    }
+
+    /// The ID the next card created by `SecretCardsInfo::new_card` will get.
+    fn next_card(&self) -> InstanceID {
+        let next_instance = self.next_instance.expect(
             "`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards` call",
         );
-        
+
+        InstanceID(next_instance.0 + 1)
+    }
+
+    /// Creates a card and its attachment in this secret, without putting the card in any zone.
+    ///
+    /// An ID is reserved for the attachment even if there isn't one, so that the IDs don't reveal it.
+    /// `S::on_attach` isn't called yet.
+    fn new_instance(&mut self, base: S::BaseCard, attachment: Option<S::BaseCard>) -> InstanceID {
+        let card = self.next_card();
+        let state = base.new_card_state(None);
+
         let attachment = attachment.map(|attachment| {
-            let state = attachment.new_card_state(None);
+            let id = InstanceID(card.0 - 1);
             let instance = CardInstance {
-                id: next_instance,
+                id,
+                state: attachment.new_card_state(None),
                 base: attachment,
                 attachment: None,
-                state,
+                token: false,
+                original_owner: self.player(),
             };
 
-            self.instances.insert(next_instance, instance);
+            self.secret_mut().instances.insert(id, instance);
 
-            next_instance
+            id
         });
 
-        next_instance.0 += 1;
-
-        let card = next_instance;
-        let state = base.new_card_state(None);
         let instance = CardInstance {
-            id: next_instance,
+            id: card,
             base,
             attachment,
             state,
+            token: false,
+            original_owner: self.player(),
         };
 
-        self.instances.insert(next_instance, instance);
+        self.secret_mut().instances.insert(card, instance);
+        self.secret_mut().next_instance = Some(InstanceID(card.0 + 1));
 
-        next_instance.0 += 1;
+        card
+    }
 
-        self.next_instance = Some(next_instance);
+    pub fn new_card(&mut self, base: S::BaseCard, attachment: Option<S::BaseCard>) -> InstanceID {
+        let card = self.new_instance(base, attachment);
+        let attachment = self.instances[&card].attachment;
 
-        self.limbo.push(card);
+        self.secret_mut().limbo.push(card);
 
-        self.pointers.push(card);
+        self.secret_mut().pointers.push(card);
 
         let new_pointer_evt = CardEvent::NewPointer {
             pointer: OpaquePointer {
@@ -3535,7 +8866,7 @@ impl<S: State> SecretCardsInfo<'_, S> {
                 location: (Zone::Limbo { public: false }, self.limbo.len() - 1),
             },
         };
-        self.log(new_pointer_evt);
+        (self.0.log)(new_pointer_evt);
 
         if let Some(attach_id) = attachment {
             let attachment = self.instance(attach_id).unwrap().clone();
@@ -3605,23 +8936,17 @@ impl<'a, S: State> From<MutateSecretInfo<'a, S>> for SecretCardsWithFakesInfo<'a
 
 impl<S: State> SecretCardsWithFakesInfo<'_, S> {
     pub fn new_fake_card(&mut self) {
-        self.next_instance.as_mut().expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards_with_fakes` call").0 += 1;
+        self.0.secret_mut().next_instance.as_mut().expect("`PlayerSecret::next_instance` missing during `CardGame::new_secret_cards_with_fakes` call").0 += 1;
     }
 }
 
 pub struct SecretPointersInfo<'a, S: State>(MutateSecretInfo<'a, S>);
 
-impl<'a, S: State> Deref for SecretPointersInfo<'a, S> {
-    type Target = MutateSecretInfo<'a, S>;
+impl<S: State> Deref for SecretPointersInfo<'_, S> {
+    type Target = PlayerSecret<S>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-impl<S: State> DerefMut for SecretPointersInfo<'_, S> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        self.0.secret
     }
 }
 
@@ -3633,18 +8958,248 @@ impl<'a, S: State> From<MutateSecretInfo<'a, S>> for SecretPointersInfo<'a, S> {
 
 impl<S: State> SecretPointersInfo<'_, S> {
     pub fn new_pointer(&mut self, id: InstanceID) {
-        self.pointers.push(id);
+        self.0.secret.pointers.push(id);
+    }
+}
+
+/// Picks a distinct ID for each roll, with chances proportional to their weights, until the weights run out.
+fn weighted_sample(mut weights: Vec<(InstanceID, u32)>, rolls: &[u64]) -> Vec<InstanceID> {
+    weights.retain(|(_, weight)| *weight > 0);
+
+    let mut picked = Vec::with_capacity(rolls.len());
+
+    for roll in rolls {
+        let total: u64 = weights.iter().map(|(_, weight)| u64::from(*weight)).sum();
+
+        if total == 0 {
+            break;
+        }
+
+        let mut target = roll % total;
+
+        let index = weights
+            .iter()
+            .position(|(_, weight)| {
+                if target < u64::from(*weight) {
+                    true
+                } else {
+                    target -= u64::from(*weight);
+                    false
+                }
+            })
+            .expect("target should be within the total weight");
+
+        picked.push(weights.remove(index).0);
+    }
+
+    picked
+}
+
+/// Rounds `created` up to the next multiple of `padding`, if padding is enabled.
+fn padded_count(created: usize, padding: usize) -> usize {
+    if padding > 1 {
+        created.next_multiple_of(padding)
+    } else {
+        created
+    }
+}
+
+/// The owner and zone of a card in `secret`, or its ID if it's a pointer to a card outside `secret`.
+fn secret_zone_of<S: State>(
+    secret: &PlayerSecret<S>,
+    card: Card,
+) -> Either<(Player, Option<Zone>), InstanceID> {
+    let id = secret
+        .id(card)
+        .unwrap_or_else(|| panic!("{:?} not in secret", card));
+
+    if !secret.instances.contains_key(&id) {
+        return Either::B(id);
+    }
+
+    let location = secret.location(id);
+
+    Either::A((location.player, location.location.map(|(zone, _)| zone)))
+}
+
+/// Whether two zones from `secret_zone_of` are the same known zone.
+fn same_zone(a: &(Player, Option<Zone>), b: &(Player, Option<Zone>)) -> bool {
+    match (a, b) {
+        ((a_player, Some(a_zone)), (b_player, Some(b_zone))) => {
+            a_player == b_player && a_zone.eq(*b_zone).unwrap_or(false)
+        }
+        _ => false,
+    }
+}
+
+/// Whether a player's secret has a card in one of `zones` satisfying a condition of `CardGame::reveal_if`.
+fn secret_condition<S: State>(
+    secret: &PlayerSecret<S>,
+    zones: &[Zone],
+    f: &CardPredicate<S>,
+) -> bool {
+    zones.iter().any(|zone| {
+        secret.zone_cards(*zone).into_iter().any(|id| {
+            secret
+                .reveal_from_card(id, |info| f(info))
+                .unwrap_or_else(|| panic!("{:?} not in player {:?} secret", id, secret.player()))
+        })
+    })
+}
+
+/// One card's half of `CardGame::swap_attachments`.
+struct AttachmentSwap<S: State> {
+    parent: Card,
+    bucket: Option<Player>,
+    old: Option<Card>,
+    new: Option<Card>,
+    /// Whether the attachments are moving between buckets.
+    moves: bool,
+    /// The new attachment's instance and inverses, if it's entering the card's bucket.
+    entering: Option<(CardInstance<S>, Vec<Inverse<S>>)>,
+}
+
+/// Swaps the attachments of a player's secret cards for `CardGame::swap_attachments`.
+///
+/// Returns each of the player's cards and its new attachment, in the order of `swaps`.
+fn swap_secret_attachments<S: State>(
+    secret: &mut PlayerSecret<S>,
+    player: Player,
+    swaps: &[AttachmentSwap<S>],
+) -> Vec<InstanceView<S>> {
+    let instance = |secret: &PlayerSecret<S>, card: Card| {
+        secret
+            .instance(card)
+            .expect("Secret has the instance for this card")
+            .clone()
+    };
+
+    // Read every instance before either attachment moves, since the cards may trade attachments within this secret.
+    let instances: Vec<_> = swaps
+        .iter()
+        .filter(|swap| swap.bucket == Some(player))
+        .map(|swap| {
+            (
+                swap,
+                secret
+                    .id(swap.parent)
+                    .expect("Secret has the ID for this card"),
+                swap.old.map(|card| instance(secret, card)),
+                swap.entering
+                    .as_ref()
+                    .map(|(instance, _)| instance.clone())
+                    .or_else(|| swap.new.map(|card| instance(secret, card))),
+            )
+        })
+        .collect();
+
+    for (swap, parent, old, new) in instances {
+        if let (true, Some(old)) = (swap.moves, &old) {
+            secret.instances.remove(&old.id);
+            secret.inverses.retain(|inverse| inverse.id != old.id);
+        }
+
+        if let (Some(new), Some((_, inverses))) = (&new, &swap.entering) {
+            secret.instances.insert(new.id, new.clone());
+            secret.inverses.extend(inverses.iter().cloned());
+        }
+
+        swap_attachment(
+            secret
+                .instances
+                .get_mut(&parent)
+                .expect("Secret has the instance for this card"),
+            old.as_ref(),
+            new.as_ref(),
+        );
+    }
+
+    swaps
+        .iter()
+        .map(|swap| {
+            (swap.bucket == Some(player)).then(|| {
+                let parent = instance(secret, swap.parent);
+                let attachment = parent
+                    .attachment
+                    .map(|attachment| instance(secret, attachment.into()));
+
+                (parent, attachment)
+            })
+        })
+        .collect()
+}
+
+/// Gives a card a new attachment, running `State::on_detach` and `State::on_attach` at most once each.
+fn swap_attachment<S: State>(
+    parent: &mut CardInstance<S>,
+    old: Option<&CardInstance<S>>,
+    new: Option<&CardInstance<S>>,
+) {
+    parent.attachment = None;
+
+    if let Some(old) = old {
+        S::on_detach(parent, old);
+    }
+
+    parent.attachment = new.map(|new| new.id);
+
+    if let Some(new) = new {
+        S::on_attach(parent, new);
+    }
+}
+
+/// A secret card's location without its index, see `CardGame::reveal_location`.
+fn without_index(location: CardLocation) -> CardLocation {
+    CardLocation {
+        location: location.location.map(|(zone, _)| (zone, None)),
+        ..location
+    }
+}
+
+/// A secret card's location without its index, or `None` if it's attached to a secret parent, whose ID isn't public.
+fn without_secret_parent(buckets: &[Bucket], location: CardLocation) -> Option<CardLocation> {
+    match location.location {
+        Some((
+            Zone::Attachment {
+                parent: Card::ID(parent),
+            },
+            _,
+        )) if matches!(buckets[parent.0], Bucket::Secret(..)) => None,
+        _ => Some(without_index(location)),
+    }
+}
+
+/// The part of a secret card's location that players other than its owner see in `CardEvent::MoveCard`.
+///
+/// Indices into secret limbo and dust are hidden because those zones' sizes aren't public,
+/// and attachments are hidden because their parents may be secret.
+fn public_location(location: (Zone, Option<usize>)) -> Option<(Zone, Option<usize>)> {
+    match location.0 {
+        Zone::Limbo { public: false } | Zone::Dust { public: false } => Some((location.0, None)),
+        Zone::Attachment { .. } => None,
+        _ => Some(location),
+    }
+}
+
+/// Whether a location from `public_location` hides anything, so its owner has to be told the exact location separately.
+fn is_partial(location: Option<(Zone, Option<usize>)>) -> bool {
+    match location {
+        Some((Zone::Limbo { public: false } | Zone::Dust { public: false }, index)) => {
+            index.is_none()
+        }
+        Some(_) => false,
+        None => true,
     }
 }
 
-type MutateSecretInfo<'a, S> = arcadeum::store::MutateSecretInfo<
+pub(crate) type MutateSecretInfo<'a, S> = arcadeum::store::MutateSecretInfo<
     'a,
     <GameState<S> as arcadeum::store::State>::Secret,
     <GameState<S> as arcadeum::store::State>::Event,
 >;
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
-enum Either<A, B> {
+pub(crate) enum Either<A, B> {
     A(A),
     B(B),
 }
@@ -1,5 +1,5 @@
 use {
-    crate::{BaseCard, InstanceID, State},
+    crate::{BaseCard, InstanceID, Player, State},
     std::ops::{Deref, DerefMut},
 };
 
@@ -21,6 +21,10 @@ pub struct CardInstance<S: State> {
 
     #[serde(bound = "S: State")]
     pub(crate) state: <S::BaseCard as BaseCard>::CardState,
+
+    pub(crate) token: bool,
+
+    pub(crate) original_owner: Player,
 }
 
 impl<S: State> Deref for CardInstance<S> {
@@ -44,6 +48,8 @@ impl<S: State> PartialEq for CardInstance<S> {
             && self.base == other.base
             && self.attachment == other.attachment
             && self.state.eq(&other.state)
+            && self.token == other.token
+            && self.original_owner == other.original_owner
     }
 }
 
@@ -55,12 +61,15 @@ impl<S: State> CardInstance<S> {
         base: S::BaseCard,
         attachment: Option<InstanceID>,
         state: <S::BaseCard as BaseCard>::CardState,
+        original_owner: Player,
     ) -> Self {
         Self {
             id,
             base,
             attachment,
             state,
+            token: false,
+            original_owner,
         }
     }
     pub fn id(&self) -> InstanceID {
@@ -74,4 +83,18 @@ impl<S: State> CardInstance<S> {
     pub fn attachment(&self) -> Option<InstanceID> {
         self.attachment
     }
+
+    /// Whether this card was created by `CardGame::new_token`, or copied from one.
+    ///
+    /// Tokens are destroyed when they leave the field, see `MatchConfig::token_policy`.
+    pub fn is_token(&self) -> bool {
+        self.token
+    }
+
+    /// The player the card was created for, which doesn't change when another player takes it.
+    ///
+    /// Copies belong to the player they were created for, see `CardGame::return_to_owner`.
+    pub fn original_owner(&self) -> Player {
+        self.original_owner
+    }
 }
@@ -0,0 +1,57 @@
+use crate::InstanceID;
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// How a pair of linked cards is kept together.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum LinkPolicy {
+    /// Dusting either card dusts the other.
+    DustTogether,
+
+    /// Moving either card moves the other to the same zone.
+    /// Moves to attachment zones aren't mirrored.
+    MoveTogether,
+}
+
+/// A pair of cards linked by `CardGame::link_cards`.
+///
+/// Links are public, and are broken when either card is dusted.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug)]
+pub struct CardLink {
+    pub(crate) cards: (InstanceID, InstanceID),
+    pub(crate) policy: LinkPolicy,
+}
+
+impl CardLink {
+    pub fn cards(&self) -> (InstanceID, InstanceID) {
+        self.cards
+    }
+
+    pub fn policy(&self) -> LinkPolicy {
+        self.policy
+    }
+
+    pub fn contains(&self, id: InstanceID) -> bool {
+        self.cards.0 == id || self.cards.1 == id
+    }
+
+    /// The card linked to `id` by this link, if `id` is part of it.
+    pub fn other(&self, id: InstanceID) -> Option<InstanceID> {
+        if self.cards.0 == id {
+            Some(self.cards.1)
+        } else if self.cards.1 == id {
+            Some(self.cards.0)
+        } else {
+            None
+        }
+    }
+}
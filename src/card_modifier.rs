@@ -0,0 +1,175 @@
+use crate::{BaseCard, CardInstance, CardState, InstanceID, State};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Identifies a modifier added by `CardGame::add_modifier` or `CardGame::add_global_modifier`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct ModifierID(pub(crate) usize);
+
+/// The cards a modifier applies to.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ModifierTarget {
+    Card(InstanceID),
+
+    /// Every card, e.g. for global effects.
+    All,
+}
+
+/// A continuous modifier of a card's state.
+///
+/// Modifiers aren't baked into `CardInstance` states, so resetting a card keeps them.
+/// See `GameState::effective_state`.
+///
+/// Modifiers are public, and so are the cards they involve.
+/// A modifier is removed when its source card moves, e.g. when an attachment is detached,
+/// and a card modifier is removed when its target is dusted or leaves public state.
+///
+/// The modifier itself is serialized however the game expects, e.g. with `serde_cbor`,
+/// and applied with `CardState::apply_modifier`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct CardModifier {
+    pub(crate) id: ModifierID,
+    pub(crate) source: Option<InstanceID>,
+    pub(crate) target: ModifierTarget,
+    pub(crate) layer: i32,
+    pub(crate) modifier: Vec<u8>,
+}
+
+impl CardModifier {
+    pub fn id(&self) -> ModifierID {
+        self.id
+    }
+
+    /// The card granting this modifier, if any.
+    pub fn source(&self) -> Option<InstanceID> {
+        self.source
+    }
+
+    pub fn target(&self) -> ModifierTarget {
+        self.target
+    }
+
+    /// Modifiers are applied in increasing layer order, and in the order they were added within a layer.
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    pub fn modifier(&self) -> &[u8] {
+        &self.modifier
+    }
+
+    pub fn applies_to(&self, id: InstanceID) -> bool {
+        match self.target {
+            ModifierTarget::Card(target) => target == id,
+            ModifierTarget::All => true,
+        }
+    }
+
+    /// Applies modifiers to an instance's state, see `GameState::effective_state`.
+    ///
+    /// This can be used inside reveal closures, with modifiers cloned from `GameState::modifiers`.
+    pub fn apply_all<S: State>(
+        modifiers: &[CardModifier],
+        instance: &CardInstance<S>,
+    ) -> <S::BaseCard as BaseCard>::CardState {
+        let mut modifiers: Vec<_> = modifiers
+            .iter()
+            .filter(|modifier| modifier.applies_to(instance.id))
+            .collect();
+
+        modifiers.sort_by_key(|modifier| (modifier.layer, modifier.id.0));
+
+        let mut state = instance.state.clone();
+
+        for modifier in modifiers {
+            state.apply_modifier(&modifier.modifier);
+        }
+
+        state
+    }
+}
+
+/// How to undo a modification of a card made with an expiry key, see `CardGame::modify_card_until`.
+///
+/// Inverses follow their cards between the public state and player secrets.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub(crate) struct Inverse<S: State> {
+    pub(crate) key: String,
+    pub(crate) id: InstanceID,
+
+    #[serde(bound = "S: State")]
+    undo: Undo<S>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+enum Undo<S: State> {
+    /// A `CardState::diff` from the modified state back to the state before, applied with `CardState::patch`.
+    Patch(Vec<u8>),
+
+    /// The whole state before the modification, for states that can't patch their diffs.
+    #[serde(bound = "S: State")]
+    Restore(<S::BaseCard as BaseCard>::CardState),
+}
+
+impl<S: State> Inverse<S> {
+    /// How to undo a modification of a card's state from `before` to `after`, or `None` if it didn't change.
+    pub(crate) fn new(
+        key: &str,
+        id: InstanceID,
+        before: &<S::BaseCard as BaseCard>::CardState,
+        after: &<S::BaseCard as BaseCard>::CardState,
+    ) -> Option<Self> {
+        if CardState::eq(after, before) {
+            return None;
+        }
+
+        let undo = before
+            .diff(after)
+            .filter(|diff| {
+                let mut patched = after.clone();
+
+                patched.patch(diff) && CardState::eq(&patched, before)
+            })
+            .map_or_else(|| Undo::Restore(before.clone()), Undo::Patch);
+
+        Some(Self {
+            key: key.to_string(),
+            id,
+            undo,
+        })
+    }
+
+    /// Undoes the modification.
+    ///
+    /// Changes made since are kept if the modification can be patched, and reverted too otherwise.
+    pub(crate) fn apply(&self, state: &mut <S::BaseCard as BaseCard>::CardState) {
+        match &self.undo {
+            Undo::Patch(diff) => {
+                state.patch(diff);
+            }
+            Undo::Restore(before) => *state = before.clone(),
+        }
+    }
+
+    /// Takes the inverses matching `f` out of `inverses`, in order.
+    pub(crate) fn take(inverses: &mut Vec<Self>, f: impl Fn(&Self) -> bool) -> Vec<Self> {
+        let (taken, kept) = std::mem::take(inverses).into_iter().partition(f);
+
+        *inverses = kept;
+
+        taken
+    }
+}
@@ -3,9 +3,34 @@ use std::fmt::Debug;
 pub trait CardState: serde::Serialize + serde::de::DeserializeOwned + Clone + Debug {
     fn eq(&self, other: &Self) -> bool;
 
+    /// Describes how this state changed from `before`, serialized however the game's clients expect,
+    /// e.g. with `serde_cbor`, and sent in a `CardEvent::ModifyCardDelta` event.
+    /// Returning `None` sends the whole card in a `CardEvent::ModifyCard` event instead, which is the default.
+    fn diff(&self, _before: &Self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Applies a change described by `CardState::diff`, see `CardGame::modify_card_until`.
+    /// Returning `false` means the change can't be applied, which is the default.
+    fn patch(&mut self, _diff: &[u8]) -> bool {
+        false
+    }
+
     /// This is called to create a copy of this card.
     /// It should return a valid state for this card as if it was standalone - no attachments.
     fn copy_card(&self) -> Self {
         self.clone()
     }
+
+    /// Applies a modifier added by `CardGame::add_modifier`, serialized however the game expects,
+    /// e.g. with `serde_cbor`, see `GameState::effective_state`.
+    /// Modifiers are ignored by default.
+    fn apply_modifier(&mut self, _modifier: &[u8]) {}
+
+    /// The value of a named part of this state that can be revealed on its own, e.g. its cost, see `CardGame::reveal_projection`.
+    /// The value is serialized however the game's clients expect, e.g. with `serde_cbor`.
+    /// Returning `None` means the projection can't be revealed on its own, which is the default.
+    fn project(&self, _projection: &str) -> Option<Vec<u8>> {
+        None
+    }
 }
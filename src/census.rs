@@ -0,0 +1,70 @@
+use crate::{Card, InstanceID, Player, ZoneSizes};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Every zone of a player at one point in time, from `CardGame::census`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Census {
+    pub player: Player,
+
+    /// Pointers to the cards in the deck, in order.
+    pub deck: Vec<Card>,
+
+    /// The hand in order, with pointers to the cards in the secret hand.
+    pub hand: Vec<Card>,
+
+    pub field: Vec<InstanceID>,
+    pub graveyard: Vec<InstanceID>,
+
+    pub public_dust: Vec<InstanceID>,
+    pub secret_dust: usize,
+
+    pub public_limbo: Vec<InstanceID>,
+    pub secret_limbo: usize,
+
+    pub casting: Vec<InstanceID>,
+
+    /// Pointers to the cards in the card selection, in order.
+    pub card_selection: Vec<Card>,
+
+    /// The number of cards in each pile.
+    pub piles: Vec<usize>,
+
+    pub hero_ability: Vec<InstanceID>,
+    pub boon: Vec<InstanceID>,
+}
+
+impl Census {
+    /// The number of cards in the public part of the hand.
+    pub fn public_hand(&self) -> usize {
+        self.hand.iter().filter(|card| card.id().is_some()).count()
+    }
+
+    /// The number of cards in the secret part of the hand.
+    pub fn secret_hand(&self) -> usize {
+        self.hand.len() - self.public_hand()
+    }
+
+    /// The total number of cards in each zone, counting both public and secret cards.
+    pub fn sizes(&self) -> ZoneSizes {
+        ZoneSizes {
+            deck: self.deck.len(),
+            hand: self.hand.len(),
+            field: self.field.len(),
+            graveyard: self.graveyard.len(),
+            dust: self.public_dust.len() + self.secret_dust,
+            limbo: self.public_limbo.len() + self.secret_limbo,
+            casting: self.casting.len(),
+            card_selection: self.card_selection.len(),
+            piles: self.piles.iter().sum(),
+            hero_ability: self.hero_ability.len(),
+            boon: self.boon.len(),
+        }
+    }
+}
@@ -1,9 +1,187 @@
-use crate::{Card, Player, Zone};
+use crate::{Card, GameResult, InstanceID, PileID, Player, Zone};
+
+/// Any error from this crate, so games can use `?` on every operation.
+///
+/// Each variant displays what failed, and the wrapped error is its `std::error::Error::source`,
+/// so error reporters print each message once.
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("cannot move a card")]
+    MoveCard(#[from] MoveCardError),
+    #[error("cannot move a secret card")]
+    SecretMoveCard(#[from] SecretMoveCardError),
+    #[error("cannot modify a secret card")]
+    SecretModifyCard(#[from] SecretModifyCardError),
+    #[error("cannot reveal a card")]
+    RevealCard(#[from] RevealCardError),
+    #[error("commitment failed")]
+    Commitment(#[from] CommitmentError),
+    #[error("cannot link cards")]
+    LinkCards(#[from] LinkCardsError),
+    #[error("invalid catalog card")]
+    CatalogCard(#[from] CatalogCardError),
+    #[error("cannot compare cards")]
+    CardEquality(#[from] CardEqualityError),
+    #[error("cannot compare zones")]
+    ZoneEquality(#[from] ZoneEqualityError),
+    #[error("cannot check a zone")]
+    ZoneAttachment(#[from] ZoneAttachmentError),
+    #[error("invalid prompt response")]
+    Prompt(#[from] PromptError),
+    #[error("invalid targets")]
+    Target(#[from] TargetError),
+    #[error("turn change failed")]
+    Turn(#[from] TurnError),
+    #[error("game states diverge")]
+    Divergence(#[from] Divergence),
+    #[error("game state is inconsistent")]
+    InvariantViolation(#[from] InvariantViolation),
+    #[error("game state is inconsistent")]
+    RevealOk(#[from] RevealOkError),
+    #[error("replay failed")]
+    Replay(#[from] ReplayError),
+    #[error("illegal card")]
+    IllegalCard(#[from] IllegalCardError),
+    #[error("fuzzing failed")]
+    Fuzz(#[from] FuzzError),
+    #[error("limbo policy violated")]
+    Limbo(#[from] LimboError),
+}
+
+impl Error {
+    /// The card the error is about, if any.
+    pub fn card(&self) -> Option<Card> {
+        match self {
+            Self::MoveCard(
+                MoveCardError::DustedCard { card }
+                | MoveCardError::CardDoesNotExist { card }
+                | MoveCardError::CannotAttachToSelf { card }
+                | MoveCardError::AttachmentCycle { card, .. }
+                | MoveCardError::Unauthorized { card, .. }
+                | MoveCardError::NotOnStack { card }
+                | MoveCardError::NotInDust { card }
+                | MoveCardError::NotFaceDown { card }
+                | MoveCardError::NotInSecretHand { card, .. }
+                | MoveCardError::NotInPublicHand { card, .. }
+                | MoveCardError::SwapAttachment { card },
+            )
+            | Self::SecretMoveCard(
+                SecretMoveCardError::MissingPointer { card, .. }
+                | SecretMoveCardError::MissingInstance { card, .. }
+                | SecretMoveCardError::DustedCard { card },
+            )
+            | Self::SecretModifyCard(SecretModifyCardError::MissingInstance { card, .. })
+            | Self::RevealCard(RevealCardError::CardDoesNotExist { card })
+            | Self::LinkCards(LinkCardsError::SameCard { card })
+            | Self::Target(
+                TargetError::Duplicate { card }
+                | TargetError::Stale { card }
+                | TargetError::Illegal { card, .. },
+            ) => Some(*card),
+            _ => None,
+        }
+    }
+
+    /// The player the error is about, if any.
+    pub fn player(&self) -> Option<Player> {
+        match self {
+            Self::MoveCard(
+                MoveCardError::ZoneFull { player, .. }
+                | MoveCardError::Unauthorized { player, .. }
+                | MoveCardError::NotInSecretHand { player, .. }
+                | MoveCardError::NotInPublicHand { player, .. },
+            )
+            | Self::SecretMoveCard(
+                SecretMoveCardError::MissingPointer { player, .. }
+                | SecretMoveCardError::MissingInstance { player, .. },
+            )
+            | Self::SecretModifyCard(SecretModifyCardError::MissingInstance { player, .. })
+            | Self::Commitment(
+                CommitmentError::AlreadyCommitted { player, .. }
+                | CommitmentError::NotCommitted { player, .. },
+            )
+            | Self::Prompt(PromptError::WrongPlayer { player, .. })
+            | Self::Limbo(LimboError::NotEmpty { player }) => Some(*player),
+            _ => None,
+        }
+    }
+
+    /// The zone the error is about, if any.
+    pub fn zone(&self) -> Option<Zone> {
+        match self {
+            Self::MoveCard(
+                MoveCardError::ZoneFull { zone, .. } | MoveCardError::NotPublicZone { zone },
+            )
+            | Self::ZoneAttachment(ZoneAttachmentError::AttachmentSecrecyIndeterminate {
+                a: zone,
+            }) => Some(*zone),
+            _ => None,
+        }
+    }
+}
 
 #[derive(thiserror::Error, Debug)]
 pub enum MoveCardError {
     #[error("cannot move dusted {card:?}")]
     DustedCard { card: Card },
+    #[error("{card:?} does not exist")]
+    CardDoesNotExist { card: Card },
+    #[error("cannot attach {card:?} to itself")]
+    CannotAttachToSelf { card: Card },
+    #[error("cannot attach {card:?} to its own attachment {parent:?}")]
+    AttachmentCycle { card: Card, parent: Card },
+    #[error("cannot create an attachment for {parent:?} with its own attachment")]
+    NestedAttachment { parent: Card },
+    #[error("player {player}'s {zone} is full: it can hold at most {capacity} cards")]
+    ZoneFull {
+        player: Player,
+        zone: Zone,
+        capacity: usize,
+    },
+    #[error("player {player} is not allowed to move {card:?}")]
+    Unauthorized { player: Player, card: Card },
+    #[error("cannot change cards after the game is over: {result}")]
+    GameOver { result: GameResult },
+    #[error("cannot counter {card:?}: it isn't on the stack")]
+    NotOnStack { card: Card },
+    #[error("cannot recover {card:?}: it isn't in dust")]
+    NotInDust { card: Card },
+    #[error("cannot place a card face-down in {zone}: it isn't a public zone")]
+    NotPublicZone { zone: Zone },
+    #[error("cannot turn {card:?} face-up: it isn't face-down")]
+    NotFaceDown { card: Card },
+    #[error("cannot reveal {card:?}: it isn't in player {player}'s secret hand")]
+    NotInSecretHand { player: Player, card: Card },
+    #[error("cannot conceal {card:?}: it isn't in player {player}'s public hand")]
+    NotInPublicHand { player: Player, card: Card },
+    #[error("cannot swap {card:?}: it's an attachment")]
+    SwapAttachment { card: Card },
+    #[error(transparent)]
+    IllegalCard(#[from] IllegalCardError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CommitmentError {
+    #[error("player {player} already committed to {key:?}")]
+    AlreadyCommitted { player: Player, key: String },
+    #[error("player {player} has no commitment to {key:?}")]
+    NotCommitted { player: Player, key: String },
+    #[error("cannot commit after the game is over: {result}")]
+    GameOver { result: GameResult },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum LinkCardsError {
+    #[error("cannot link {card:?} to itself")]
+    SameCard { card: Card },
+    #[error("cannot link cards after the game is over: {result}")]
+    GameOver { result: GameResult },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CatalogCardError {
+    #[error("cannot find card {id:?} in the catalog")]
+    UnknownCard { id: String },
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -22,6 +200,12 @@ pub enum SecretModifyCardError {
     MissingInstance { card: Card, player: Player },
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum RevealCardError {
+    #[error("{card:?} does not exist")]
+    CardDoesNotExist { card: Card },
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum CardEqualityError {
     #[error("cannot determine if {a:?} and {b:?} are equal")]
@@ -40,9 +224,228 @@ pub enum ZoneAttachmentError {
     AttachmentSecrecyIndeterminate { a: Zone },
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum PromptError {
+    #[error("no prompt is waiting for a response")]
+    NoPrompt,
+    #[error("player {player} can't respond to player {prompted}'s prompt")]
+    WrongPlayer { player: Player, prompted: Player },
+    #[error("the response doesn't match the kind of choice prompted")]
+    WrongKind,
+    #[error("{count} cards were chosen, but {expected} must be")]
+    WrongCount { count: usize, expected: usize },
+    #[error("card {index} can't be chosen")]
+    InvalidCard { index: usize },
+    #[error("{number} is not between {min} and {max}")]
+    OutOfRange {
+        number: usize,
+        min: usize,
+        max: usize,
+    },
+    #[error("{count} cards must be chosen, but there are only {cards}")]
+    TooFewCards { count: usize, cards: usize },
+    #[error("no number is between {min} and {max}")]
+    EmptyRange { min: usize, max: usize },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TurnError {
+    #[error("no turn to set a phase in")]
+    NoTurn,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum TargetError {
+    #[error("{count} targets were chosen, but {expected} must be")]
+    WrongCount { count: usize, expected: usize },
+    #[error("{card:?} is targeted more than once")]
+    Duplicate { card: Card },
+    #[error("{card:?} no longer exists")]
+    Stale { card: Card },
+    #[error("{card:?} is not a legal target {index}")]
+    Illegal { index: usize, card: Card },
+}
+
+/// A difference between two game states, see `GameState::divergence_report`.
+#[derive(thiserror::Error, Clone, Debug)]
+pub enum Divergence {
+    #[error("{this} instances, but the other state has {other}")]
+    InstanceCount { this: usize, other: usize },
+    #[error("{id:?} is {this}, but {other} in the other state")]
+    Instance {
+        id: InstanceID,
+        this: String,
+        other: String,
+    },
+    #[error("player {player}'s {zone} has {this} cards, but {other} in the other state")]
+    ZoneSize {
+        player: Player,
+        zone: Zone,
+        this: usize,
+        other: usize,
+    },
+    #[error("player {player}'s {zone} has {this:?} at {index}, but {other:?} in the other state")]
+    ZoneCard {
+        player: Player,
+        zone: Zone,
+        index: usize,
+        this: Option<InstanceID>,
+        other: Option<InstanceID>,
+    },
+    #[error("player {player} has {this} pointers, but {other} in the other state")]
+    PointerCount {
+        player: Player,
+        this: usize,
+        other: usize,
+    },
+    #[error("the game state is {this}, but {other} in the other state")]
+    State { this: String, other: String },
+}
+
+#[derive(thiserror::Error, Clone, Eq, PartialEq, Debug)]
+pub enum InvariantViolation {
+    #[error("player {player}'s pointer {index} points to nonexistent {id:?}")]
+    PointerOutOfBounds {
+        player: Player,
+        index: usize,
+        id: InstanceID,
+    },
+    #[error("{id:?} should be in player {player}'s secret")]
+    MissingFromSecret { id: InstanceID, player: Player },
+    #[error("{id:?} belongs to player {owner}, but is in player {player}'s secret")]
+    InOtherSecret {
+        id: InstanceID,
+        owner: Player,
+        player: Player,
+    },
+    #[error("{id:?} is public, but is in player {player}'s secret")]
+    PublicInSecret { id: InstanceID, player: Player },
+    #[error("{id:?} was destroyed, but is in player {player}'s secret")]
+    DestroyedInSecret { id: InstanceID, player: Player },
+    #[error("{id:?} and its attachment {attachment:?} are in different buckets")]
+    AttachmentBucket {
+        id: InstanceID,
+        attachment: InstanceID,
+    },
+    #[error("{id:?} occurs {count} times in public and secret zones")]
+    DuplicateID { id: InstanceID, count: usize },
+    #[error("{id:?} is in player {player}'s secret, but not in any of their zones")]
+    NotInZone { id: InstanceID, player: Player },
+    #[error("{id:?} is public, but not in any public zone or attached to a public card")]
+    PublicNotInZone { id: InstanceID },
+    #[error(
+        "player {player}'s public deck size is {public}, but their secret deck size is {secret}"
+    )]
+    DeckSize {
+        player: Player,
+        public: usize,
+        secret: usize,
+    },
+    #[error("player {player}'s public card selection size is {public}, but their secret card selection size is {secret}")]
+    CardSelectionSize {
+        player: Player,
+        public: usize,
+        secret: usize,
+    },
+    #[error("player {player}'s public pile {pile} size is {public}, but their secret pile {pile} size is {secret}")]
+    PileSize {
+        player: Player,
+        pile: PileID,
+        public: usize,
+        secret: usize,
+    },
+    #[error("player {player}'s hand position {index} is in both public and secret state")]
+    HandInBoth { player: Player, index: usize },
+    #[error("player {player}'s hand position {index} is in neither public nor secret state")]
+    HandInNeither { player: Player, index: usize },
+}
+
+#[derive(thiserror::Error, Eq, PartialEq, Debug)]
+pub enum LimboError {
+    #[error("player {player}'s limbo is not empty")]
+    NotEmpty { player: Player },
+}
+
 #[doc(hidden)]
 #[derive(thiserror::Error, Eq, PartialEq, Debug)]
 pub enum RevealOkError {
     #[error("err:?")]
     Error { err: String },
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReplayError {
+    #[error("action {index} failed")]
+    Action {
+        index: usize,
+        #[source]
+        error: ActionError,
+    },
+    #[error(
+        "action {index} logged different events or revealed different values than were recorded"
+    )]
+    Desync { index: usize },
+    #[error("cannot serialize an event")]
+    Serialize {
+        #[source]
+        error: serde_cbor::Error,
+    },
+}
+
+/// An error from applying an action through an `arcadeum::store::Tester`, which only reports messages.
+#[derive(thiserror::Error, Debug)]
+#[error("{message}")]
+pub struct ActionError {
+    pub message: String,
+}
+
+impl From<String> for ActionError {
+    fn from(message: String) -> Self {
+        Self { message }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum IllegalCardError {
+    #[error("card {card} is not in the card database")]
+    UnknownCard { card: String },
+    #[error("card {card} is not legal in format {format}")]
+    NotInFormat { card: String, format: String },
+    #[error("card {card} is banned")]
+    Banned { card: String },
+    #[error("deck has {count} copies of card {card}, but at most {limit} are allowed")]
+    TooManyCopies {
+        card: String,
+        count: usize,
+        limit: usize,
+    },
+    #[error("deck has {size} cards, but must have between {min} and {max}")]
+    DeckSize { size: usize, min: usize, max: usize },
+    #[error("cannot add cards after the game is over: {result}")]
+    GameOver { result: GameResult },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum FuzzError {
+    #[error("no valid action after {attempts} attempts at step {step}")]
+    NoValidAction { step: usize, attempts: usize },
+    #[error("action {step} failed")]
+    Action {
+        step: usize,
+        #[source]
+        error: ActionError,
+    },
+    #[error("action {step} broke an invariant")]
+    NotOk {
+        step: usize,
+        #[source]
+        error: InvariantViolation,
+    },
+    #[error("action {step} leaked {id:?} from player {owner}'s secret to player {player}")]
+    Leak {
+        step: usize,
+        id: InstanceID,
+        owner: Player,
+        player: Player,
+    },
+}
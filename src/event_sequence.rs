@@ -0,0 +1,24 @@
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A global ordering token attached to events when strict event ordering is enabled.
+///
+/// Every public log and every secret mutation takes the next `step`.
+/// Events logged within a single secret mutation share its `step` and are numbered by `index`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(
+    serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash,
+)]
+pub struct EventSequence {
+    pub step: usize,
+    pub index: usize,
+}
+
+impl std::fmt::Display for EventSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.step, self.index)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{Card, Player, Zone};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A card placed face-down in a public zone by `CardGame::place_face_down`.
+///
+/// The card's instance stays in its owner's secret limbo until `CardGame::turn_face_up` moves it into the zone.
+/// It counts towards the zone's capacity, but not its size.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug)]
+pub struct FaceDownCard {
+    pub(crate) player: Player,
+    pub(crate) card: Card,
+    pub(crate) zone: Zone,
+}
+
+impl FaceDownCard {
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// The card as it was placed, usually a pointer.
+    pub fn card(&self) -> Card {
+        self.card
+    }
+
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+}
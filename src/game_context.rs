@@ -0,0 +1,776 @@
+use {
+    crate::{
+        card_game::MutateSecretInfo,
+        replay::{TranscribedRng, Transcriber, TranscriptRun},
+        testing::Reveal,
+        trace::traced,
+        ActionCorrelation, ActionMetrics, CardEvent, Context, EventSequence, Player, PlayerSecret,
+        State, Turn,
+    },
+    arcadeum::store::Secret,
+    rand::RngCore,
+    std::{
+        cell::{Cell, RefCell},
+        future::Future,
+        ops::{Deref, DerefMut, Range},
+        rc::Rc,
+    },
+};
+
+/// The arcadeum context used by a `CardGame`.
+///
+/// All events logged through `GameContext::log`, `GameContext::mutate_secret` and
+/// `GameContext::mutate_secret_or_log` pass through here, so they can be stamped with an
+/// `EventSequence` when strict event ordering is enabled, coalesced when event coalescing is enabled,
+/// stamped with an `ActionCorrelation` when action correlation is enabled, marked as secret when
+/// spectator events are enabled, wrapped in `CardEvent::InPhase` once turns have started, redacted
+/// for each player by `State::redact_event`, and held until the action finishes under `PanicPolicy::Lenient`.
+///
+/// Values revealed through `GameContext::reveal` and `GameContext::reveal_unique` are recorded when reveal recording is enabled.
+/// Randomness is drawn through the transcript, and revealed values are added to it, when transcript recording is enabled.
+pub struct GameContext<S: State> {
+    context: Context<S>,
+
+    pub(crate) next_step: Option<usize>,
+
+    action: Option<usize>,
+
+    next_index: usize,
+
+    spectator: bool,
+
+    /// The current turn, if turns have started, see `CardGame::start_turn`.
+    turn: Option<Turn<S>>,
+
+    /// Public events held for coalescing, if enabled.
+    pending: Option<Vec<CardEvent<S>>>,
+
+    /// Events held until the action finishes, while the panic policy is `PanicPolicy::Lenient`.
+    held: Option<Vec<Held<S>>>,
+
+    /// Values revealed during the action, if reveal recording is enabled.
+    reveals: Option<Rc<RefCell<Vec<Reveal>>>>,
+
+    /// This run of the action through the transcript, if transcript recording is enabled.
+    transcript: Option<Rc<TranscriptRun>>,
+
+    /// What the action has revealed and mutated so far, shared with pending reveals.
+    metrics: Rc<Cell<ActionMetrics>>,
+
+    /// Whether revealed values are measured for `ActionMetrics::revealed_bytes`.
+    measuring: bool,
+
+    players: Range<Player>,
+
+    /// The player who conceded during the action, whose secret can't be revealed anymore.
+    pub(crate) conceded: Option<Player>,
+
+    /// Whether a reveal of the conceded player's secret was skipped.
+    skipped_reveals: Rc<Cell<bool>>,
+}
+
+impl<S: State> Deref for GameContext<S> {
+    type Target = Context<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.context
+    }
+}
+
+impl<S: State> DerefMut for GameContext<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.context
+    }
+}
+
+impl<S: State> GameContext<S> {
+    pub(crate) fn new(
+        context: Context<S>,
+        next_step: Option<usize>,
+        coalesce: bool,
+        action: Option<usize>,
+        spectator: bool,
+        record_reveals: bool,
+        players: Range<Player>,
+    ) -> Self {
+        Self {
+            context,
+            next_step,
+            action,
+            next_index: 0,
+            spectator,
+            turn: None,
+            pending: coalesce.then(Vec::new),
+            held: None,
+            reveals: record_reveals.then(Default::default),
+            transcript: None,
+            metrics: Default::default(),
+            measuring: false,
+            players,
+            conceded: None,
+            skipped_reveals: Default::default(),
+        }
+    }
+
+    pub(crate) fn into_inner(mut self) -> Context<S> {
+        self.flush();
+
+        self.context
+    }
+
+    pub(crate) fn set_coalescing(&mut self, enabled: bool) {
+        if enabled {
+            self.pending.get_or_insert_with(Vec::new);
+        } else {
+            self.flush();
+
+            self.pending = None;
+        }
+    }
+
+    /// Holds every event until `GameContext::release`, so an aborted action logs nothing.
+    ///
+    /// Events logged within secret mutations are held in the player's secret, see `PlayerSecret::held_logs`.
+    pub(crate) fn hold(&mut self) {
+        self.held.get_or_insert_with(Vec::new);
+    }
+
+    /// Logs every held event in order, and stops holding events.
+    pub(crate) fn release(&mut self) {
+        self.flush();
+
+        for held in self.held.take().into_iter().flatten() {
+            match held {
+                Held::Public(event) => self.context.log(event),
+                Held::Secret(player) => self.context.mutate_secret(player, release_held_logs),
+                Held::SecretOrLog(player, event) => {
+                    self.context
+                        .mutate_secret_or_log(player, release_held_logs, event)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn set_action(&mut self, action: Option<usize>) {
+        self.action = action;
+    }
+
+    pub(crate) fn set_spectator(&mut self, enabled: bool) {
+        self.spectator = enabled;
+    }
+
+    pub(crate) fn set_turn(&mut self, turn: Option<Turn<S>>) {
+        self.turn = turn;
+    }
+
+    pub(crate) fn set_transcriber(&mut self, transcriber: Option<Rc<RefCell<Transcriber>>>) {
+        self.transcript = transcriber.map(|transcriber| Rc::new(TranscriptRun::new(transcriber)));
+    }
+
+    pub(crate) fn set_reveal_recording(&mut self, enabled: bool) {
+        if !enabled {
+            self.reveals = None;
+        } else if self.reveals.is_none() {
+            self.reveals = Some(Default::default());
+        }
+    }
+
+    pub(crate) fn set_measuring(&mut self, enabled: bool) {
+        self.measuring = enabled;
+    }
+
+    pub(crate) fn metrics(&self) -> ActionMetrics {
+        self.metrics.get()
+    }
+
+    fn count(&self, f: impl FnOnce(&mut ActionMetrics)) {
+        let mut metrics = self.metrics.get();
+        f(&mut metrics);
+        self.metrics.set(metrics);
+    }
+
+    /// The values revealed so far, if reveal recording is enabled.
+    pub(crate) fn reveals(&self) -> Option<Vec<Reveal>> {
+        self.reveals
+            .as_ref()
+            .map(|reveals| reveals.borrow().clone())
+    }
+
+    pub fn reveal<T: Secret>(
+        &mut self,
+        player: Player,
+        reveal: impl Fn(&PlayerSecret<S>) -> T + 'static,
+        verify: impl Fn(&T) -> bool + 'static,
+    ) -> impl Future<Output = T> {
+        let revealed = (!self.skip_if_conceded(player)).then(|| {
+            self.count(|metrics| metrics.reveals += 1);
+
+            traced!(
+                recorded(
+                    self.reveals.clone(),
+                    self.transcript.clone(),
+                    self.measuring.then(|| self.metrics.clone()),
+                    player,
+                    self.context.reveal(player, reveal, verify),
+                ),
+                tracing::debug_span!("reveal", player)
+            )
+        });
+
+        skippable(revealed)
+    }
+
+    pub fn reveal_unique<T: Secret>(
+        &mut self,
+        player: Player,
+        reveal: impl Fn(&PlayerSecret<S>) -> T + 'static,
+        verify: impl Fn(&T) -> bool + 'static,
+    ) -> impl Future<Output = T> {
+        let revealed = (!self.skip_if_conceded(player)).then(|| {
+            self.count(|metrics| metrics.reveals += 1);
+
+            traced!(
+                recorded(
+                    self.reveals.clone(),
+                    self.transcript.clone(),
+                    self.measuring.then(|| self.metrics.clone()),
+                    player,
+                    self.context.reveal_unique(player, reveal, verify),
+                ),
+                tracing::debug_span!("reveal_unique", player)
+            )
+        });
+
+        skippable(revealed)
+    }
+
+    /// Draws from the shared randomness.
+    ///
+    /// When transcript recording is enabled, the values drawn from the returned generator are recorded in, or replayed from,
+    /// the transcript.
+    /// Recording draws the same values as not recording.
+    pub fn random(&mut self) -> impl Future<Output = impl RngCore> + '_ {
+        let transcript = self.transcript.clone();
+        let random = self.context.random();
+
+        async move {
+            let random = random.await;
+
+            match transcript {
+                Some(transcript) => SharedRandom::Transcribed(transcript.random(random)),
+                None => SharedRandom::Shared(random),
+            }
+        }
+    }
+
+    /// Whether a reveal of a player's secret is skipped, because they conceded during the action, see `CardGame::concede`.
+    ///
+    /// The player may have left, so rather than waiting for their secret, the action's effect ends at the skipped reveal.
+    /// See `GameContext::skipped_reveals`.
+    fn skip_if_conceded(&self, player: Player) -> bool {
+        if self.conceded != Some(player) {
+            return false;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(player, "skipped a reveal of a conceded player's secret");
+
+        self.skipped_reveals.set(true);
+
+        true
+    }
+
+    /// Set once a reveal is skipped, which ends the action's effect, see `GameContext::skip_if_conceded`.
+    pub(crate) fn skipped_reveals(&self) -> Rc<Cell<bool>> {
+        self.skipped_reveals.clone()
+    }
+
+    pub fn log(&mut self, event: CardEvent<S>) {
+        let (public, entitled) = match self.redacted(&event, None) {
+            Some(redacted) => redacted,
+            None => return self.log_public(event),
+        };
+
+        match entitled[..] {
+            [player] => {
+                self.secret_or_log(player, move |mut secret| secret.log(event.clone()), public)
+            }
+            _ => {
+                self.log_public(public);
+
+                for &player in &entitled {
+                    self.log_secret(player, event.clone());
+                }
+            }
+        }
+    }
+
+    /// Logs a public event without redacting it.
+    fn log_public(&mut self, event: CardEvent<S>) {
+        let event = in_phase(self.turn.as_ref(), event);
+
+        let event = match self.step() {
+            Some(step) => sequenced(step, 0, event),
+            None => event,
+        };
+
+        match &mut self.pending {
+            Some(pending) => coalesce(pending, event),
+            None => self.emit(event),
+        }
+    }
+
+    /// Logs any public events held for coalescing.
+    pub fn flush(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            for event in pending {
+                self.emit(event);
+            }
+
+            self.pending = Some(Vec::new());
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, mutate))
+    )]
+    pub fn mutate_secret(&mut self, player: Player, mutate: impl Fn(MutateSecretInfo<S>)) {
+        self.flush();
+
+        self.count(|metrics| metrics.mutations += 1);
+
+        let step = self.step();
+        let owner = self.spectator.then_some((player, None));
+        let action = self.action;
+        let turn = self.turn.as_ref();
+        let transcript = self.transcript.as_deref();
+
+        let run = |secret: MutateSecretInfo<'_, S>| match (step, action, owner, turn, transcript) {
+            (None, None, None, None, None) => mutate(secret),
+            (step, action, owner, turn, transcript) => mutate_stamped(
+                step, action, owner, turn, transcript, player, secret, &mutate,
+            ),
+        };
+
+        match &mut self.held {
+            Some(held) => {
+                self.context
+                    .mutate_secret(player, |secret| hold_logs(secret, &run));
+
+                held.push(Held::Secret(player));
+            }
+            None => self.context.mutate_secret(player, run),
+        }
+    }
+
+    /// Saves a copy of each player's secret, for `GameContext::restore_secrets`.
+    pub(crate) fn checkpoint_secrets(&mut self) {
+        for player in self.players.clone() {
+            self.context
+                .mutate_secret(player, |secret| secret.secret.checkpoint());
+        }
+    }
+
+    /// Restores each player's secret to its last checkpoint, and drops the events held for coalescing
+    /// or until the action finishes.
+    pub(crate) fn restore_secrets(&mut self) {
+        if let Some(pending) = &mut self.pending {
+            pending.clear();
+        }
+
+        if let Some(held) = &mut self.held {
+            held.clear();
+        }
+
+        for player in self.players.clone() {
+            self.context
+                .mutate_secret(player, |secret| secret.secret.restore());
+        }
+    }
+
+    pub(crate) fn discard_checkpoints(&mut self) {
+        for player in self.players.clone() {
+            self.context
+                .mutate_secret(player, |secret| secret.secret.checkpoint = None);
+        }
+    }
+
+    pub fn mutate_secret_or_log(
+        &mut self,
+        player: Player,
+        mutate: impl Fn(MutateSecretInfo<S>),
+        event: CardEvent<S>,
+    ) {
+        match self.redacted(&event, Some(player)) {
+            Some((public, entitled)) => {
+                self.secret_or_log(player, mutate, public);
+
+                for player in entitled {
+                    self.log_secret(player, event.clone());
+                }
+            }
+            None => self.secret_or_log(player, mutate, event),
+        }
+    }
+
+    /// Logs an event to one player from their own secret, e.g. an event redacted for everyone else.
+    fn log_secret(&mut self, player: Player, event: CardEvent<S>) {
+        self.mutate_secret(player, move |mut secret| secret.log(event.clone()));
+    }
+
+    /// Mutates a player's secret, and logs a public event to everyone else without redacting it.
+    fn secret_or_log(
+        &mut self,
+        player: Player,
+        mutate: impl Fn(MutateSecretInfo<S>),
+        event: CardEvent<S>,
+    ) {
+        self.flush();
+
+        self.count(|metrics| metrics.mutations += 1);
+
+        let event = in_phase(self.turn.as_ref(), event);
+        let public = self.spectator.then(|| event.clone());
+        let step = self.step();
+        let owner = public.as_ref().map(|public| (player, Some(public)));
+        let action = self.action;
+        let turn = self.turn.as_ref();
+        let transcript = self.transcript.as_deref();
+
+        let run = |secret: MutateSecretInfo<'_, S>| match (step, action, owner, turn, transcript) {
+            (None, None, None, None, None) => mutate(secret),
+            (step, action, owner, turn, transcript) => mutate_stamped(
+                step, action, owner, turn, transcript, player, secret, &mutate,
+            ),
+        };
+
+        let event = stamped(step, 0, action, None, event);
+
+        match &mut self.held {
+            Some(held) => {
+                self.context
+                    .mutate_secret(player, |secret| hold_logs(secret, &run));
+
+                held.push(Held::SecretOrLog(player, event));
+            }
+            None => self.context.mutate_secret_or_log(player, run, event),
+        }
+    }
+
+    /// The public view of an event, and the players other than the owner it isn't redacted for,
+    /// if `State::redact_event` redacts it for any player.
+    ///
+    /// The public view is the view of the first player it's redacted for.
+    fn redacted(
+        &self,
+        event: &CardEvent<S>,
+        owner: Option<Player>,
+    ) -> Option<(CardEvent<S>, Vec<Player>)> {
+        let views: Vec<_> = self
+            .players
+            .clone()
+            .filter(|player| Some(*player) != owner)
+            .map(|player| (player, S::redact_event(event, player)))
+            .collect();
+
+        let public = views.iter().find_map(|(_, view)| view.clone())?;
+
+        Some((
+            public,
+            views
+                .into_iter()
+                .filter(|(_, view)| view.is_none())
+                .map(|(player, _)| player)
+                .collect(),
+        ))
+    }
+
+    /// Logs a public event, numbering it within its action if action correlation is enabled.
+    fn emit(&mut self, event: CardEvent<S>) {
+        let event = match self.action {
+            Some(action) => {
+                self.next_index += 1;
+
+                correlated(action, Some(self.next_index - 1), event)
+            }
+            None => event,
+        };
+
+        match &mut self.held {
+            Some(held) => held.push(Held::Public(event)),
+            None => self.context.log(event),
+        }
+    }
+
+    /// Takes the next step, if strict event ordering is enabled.
+    fn step(&mut self) -> Option<usize> {
+        self.next_step.as_mut().map(|next_step| {
+            *next_step += 1;
+
+            *next_step - 1
+        })
+    }
+}
+
+/// An event held until the action finishes, see `GameContext::hold`.
+enum Held<S: State> {
+    /// A public event.
+    Public(CardEvent<S>),
+
+    /// The events logged by the next held secret mutation of a player.
+    Secret(Player),
+
+    /// The events logged by the next held secret mutation of a player, and the public event everyone else is sent instead.
+    SecretOrLog(Player, CardEvent<S>),
+}
+
+/// The shared randomness, drawn through the transcript or not, see `GameContext::random`.
+enum SharedRandom<R> {
+    Shared(R),
+    Transcribed(TranscribedRng<Rc<TranscriptRun>, R>),
+}
+
+impl<R: RngCore> RngCore for SharedRandom<R> {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            Self::Shared(random) => random.next_u32(),
+            Self::Transcribed(random) => random.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            Self::Shared(random) => random.next_u64(),
+            Self::Transcribed(random) => random.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Shared(random) => random.fill_bytes(dest),
+            Self::Transcribed(random) => random.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            Self::Shared(random) => random.try_fill_bytes(dest),
+            Self::Transcribed(random) => random.try_fill_bytes(dest),
+        }
+    }
+}
+
+/// Runs a secret mutation, holding the events it logs in the secret, see `GameContext::hold`.
+fn hold_logs<S: State>(secret: MutateSecretInfo<'_, S>, mutate: &impl Fn(MutateSecretInfo<'_, S>)) {
+    let MutateSecretInfo {
+        secret,
+        random,
+        log: _,
+    } = secret;
+
+    let mut logs = Vec::new();
+
+    mutate(MutateSecretInfo {
+        secret: &mut *secret,
+        random,
+        log: &mut |event| logs.push(event),
+    });
+
+    secret.held_logs.push_back(logs);
+}
+
+/// Logs the events held by a player's next held secret mutation, see `GameContext::release`.
+fn release_held_logs<S: State>(secret: MutateSecretInfo<'_, S>) {
+    let MutateSecretInfo { secret, log, .. } = secret;
+
+    for event in secret.held_logs.pop_front().unwrap_or_default() {
+        log(event);
+    }
+}
+
+/// Records the value a reveal resolves to, if reveal or transcript recording is enabled, and counts its size, if measuring is enabled.
+async fn recorded<T: Secret>(
+    reveals: Option<Rc<RefCell<Vec<Reveal>>>>,
+    transcript: Option<Rc<TranscriptRun>>,
+    metrics: Option<Rc<Cell<ActionMetrics>>>,
+    player: Player,
+    reveal: impl Future<Output = T>,
+) -> T {
+    let value = reveal.await;
+
+    if let Some(metrics) = metrics {
+        let mut counted = metrics.get();
+        counted.revealed_bytes += serde_cbor::to_vec(&value).map_or(0, |bytes| bytes.len());
+        metrics.set(counted);
+    }
+
+    if let Some(reveals) = reveals {
+        reveals.borrow_mut().push(Reveal::new(player, &value));
+    }
+
+    if let Some(transcript) = transcript {
+        transcript.reveal(player, &value);
+    }
+
+    value
+}
+
+/// A reveal, or a skipped reveal that never finishes, see `GameContext::skip_if_conceded`.
+async fn skippable<T>(revealed: Option<impl Future<Output = T>>) -> T {
+    match revealed {
+        Some(revealed) => revealed.await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Adds a public event to the pending events, dropping any events it makes redundant.
+fn coalesce<S: State>(pending: &mut Vec<CardEvent<S>>, event: CardEvent<S>) {
+    match unsequenced(&event) {
+        CardEvent::SortField { player, real, .. } => {
+            if let Some(CardEvent::SortField {
+                player: last_player,
+                real: last_real,
+                ..
+            }) = pending.last().map(unsequenced)
+            {
+                if last_player == player && last_real == real {
+                    pending.pop();
+                }
+            }
+        }
+        CardEvent::ModifyCard { instance } => {
+            // Field sorts don't depend on what the client knows about a card, so look past them.
+            let mut i = pending.len();
+
+            while i > 0 {
+                match unsequenced(&pending[i - 1]) {
+                    CardEvent::SortField { .. } => i -= 1,
+                    CardEvent::ModifyCard { instance: previous } if previous.id == instance.id => {
+                        pending.remove(i - 1);
+
+                        i -= 1;
+                    }
+                    CardEvent::ModifyCardDelta { id, .. } if *id == instance.id => {
+                        pending.remove(i - 1);
+
+                        i -= 1;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        _ => (),
+    }
+
+    pending.push(event);
+}
+
+fn correlated<S: State>(action: usize, index: Option<usize>, event: CardEvent<S>) -> CardEvent<S> {
+    CardEvent::Correlated {
+        correlation: ActionCorrelation { action, index },
+        event: Box::new(event),
+    }
+}
+
+/// Stamps an event logged within a secret mutation.
+///
+/// If an owner is given, the event is marked as secret to them, and the first event is paired with
+/// the public event other players were sent instead, if any.
+fn stamped<S: State>(
+    step: Option<usize>,
+    index: usize,
+    action: Option<usize>,
+    owner: Option<(Player, Option<&CardEvent<S>>)>,
+    event: CardEvent<S>,
+) -> CardEvent<S> {
+    let event = match owner {
+        Some((player, public)) => CardEvent::Secret {
+            player,
+            event: Box::new(event),
+            public: public
+                .filter(|_| index == 0)
+                .map(|public| Box::new(public.clone())),
+        },
+        None => event,
+    };
+
+    let event = match step {
+        Some(step) => sequenced(step, index, event),
+        None => event,
+    };
+
+    match action {
+        Some(action) => correlated(action, None, event),
+        None => event,
+    }
+}
+
+fn unsequenced<S: State>(event: &CardEvent<S>) -> &CardEvent<S> {
+    match event {
+        CardEvent::Sequenced { event, .. } | CardEvent::InPhase { event, .. } => unsequenced(event),
+        event => event,
+    }
+}
+
+fn sequenced<S: State>(step: usize, index: usize, event: CardEvent<S>) -> CardEvent<S> {
+    CardEvent::Sequenced {
+        sequence: EventSequence { step, index },
+        event: Box::new(event),
+    }
+}
+
+/// Wraps an event in the phase it happened in, if turns have started.
+///
+/// `CardEvent::PhaseChange` already names its phase, so it's never wrapped.
+fn in_phase<S: State>(turn: Option<&Turn<S>>, event: CardEvent<S>) -> CardEvent<S> {
+    match (turn, event) {
+        (_, event @ CardEvent::PhaseChange { .. }) | (None, event) => event,
+        (Some(turn), event) => CardEvent::InPhase {
+            turn: turn.clone(),
+            event: Box::new(event),
+        },
+    }
+}
+
+/// Stamps the events logged within a secret mutation, and draws its randomness through the transcript, if any.
+#[allow(clippy::too_many_arguments)]
+fn mutate_stamped<S: State>(
+    step: Option<usize>,
+    action: Option<usize>,
+    owner: Option<(Player, Option<&CardEvent<S>>)>,
+    turn: Option<&Turn<S>>,
+    transcript: Option<&TranscriptRun>,
+    player: Player,
+    secret: MutateSecretInfo<S>,
+    mutate: &impl Fn(MutateSecretInfo<S>),
+) {
+    let MutateSecretInfo {
+        secret,
+        random,
+        log,
+    } = secret;
+
+    let mut transcribed;
+
+    let random: &mut dyn rand::RngCore = match transcript {
+        Some(transcript) => {
+            transcribed = transcript.secret_random(player, random);
+
+            &mut transcribed
+        }
+        None => random,
+    };
+
+    let mut index = 0;
+
+    mutate(MutateSecretInfo {
+        secret,
+        random,
+        log: &mut |event| {
+            log(stamped(step, index, action, owner, in_phase(turn, event)));
+
+            index += 1;
+        },
+    });
+}
@@ -1,13 +1,22 @@
 use {
     crate::{
-        error, Address, Card, CardEvent, CardGame, CardInstance, CardLocation, Context, InstanceID,
-        OpaquePointer, Player, PlayerCards, PlayerSecret, State, Zone,
+        card_modifier::Inverse, error, error::Divergence, invariants, replay::Transcriber,
+        replay::Transcript, testing::Reveal, trace::traced, ActionMetrics, ActionSummary, Address,
+        BaseCard, Card, CardDatabase, CardEvent, CardGame, CardInfo, CardInstance, CardLink,
+        CardLocation, CardModifier, CardState, Context, FaceDownCard, InstanceID, KnownProjection,
+        MatchConfig, ModifierID, OpaquePointer, PileID, Player, PlayerCards, PlayerSecret, Prompt,
+        RedactedAttachmentPolicy, State, TargetRequirement, Turn, Zone,
     },
     std::{
+        cell::{Cell, RefCell},
         convert::TryInto,
         future::Future,
-        ops::{Deref, DerefMut},
-        pin::Pin,
+        iter::repeat_n,
+        ops::{Deref, DerefMut, Range},
+        panic::AssertUnwindSafe,
+        pin::{pin, Pin},
+        rc::Rc,
+        sync::Arc,
     },
 };
 
@@ -18,20 +27,96 @@ use wasm_bindgen::prelude::wasm_bindgen;
     feature = "bindings",
     derive(typescript_definitions::TypescriptDefinition)
 )]
-#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct GameState<S: State> {
+    /// Only changed through `GameState::set_instance` and `GameState::reserve_instances`, to keep `buckets` in sync.
     #[serde(bound = "S: State")]
     pub(crate) instances: Vec<InstanceOrPlayer<S>>,
 
-    player_cards: [PlayerCards; 2],
+    /// The bucket holding each instance, shared with reveal closures by `GameState::buckets`.
+    #[serde(skip)]
+    buckets: Rc<Vec<Bucket>>,
+
+    /// One per player, see `MatchConfig::player_count`.
+    #[serde(bound = "S: State")]
+    player_cards: Vec<PlayerCards<S>>,
+
+    pub(crate) config: MatchConfig,
+
+    pub(crate) next_event_step: Option<usize>,
+
+    pub(crate) links: Vec<CardLink>,
+
+    pub(crate) modifiers: Vec<CardModifier>,
 
-    pub(crate) shuffle_deck_on_insert: bool,
+    pub(crate) next_modifier: usize,
+
+    #[serde(bound = "S: State")]
+    pub(crate) turn: Option<Turn<S>>,
+
+    pub(crate) stack: Vec<Card>,
+
+    pub(crate) face_down: Vec<FaceDownCard>,
+
+    /// The keys of the commitments that haven't been revealed yet, see `CardGame::commit`.
+    pub(crate) commitments: Vec<(Player, String)>,
+
+    /// How to undo the temporary modifications of public cards, see `CardGame::modify_card_until`.
+    #[serde(bound = "S: State")]
+    pub(crate) inverses: Vec<Inverse<S>>,
+
+    /// The publicly known IDs of destroyed cards that haven't been reused yet, see `CardGame::destroy_instance`.
+    pub(crate) destroyed: Vec<InstanceID>,
+
+    /// In the same order as each player's secret projections.
+    pub(crate) projections: Vec<KnownProjection>,
+
+    pub(crate) next_action: Option<usize>,
+
+    /// How the game ended, see `CardGame::end_game`.
+    pub(crate) result: Option<GameResult>,
+
+    /// The prompt waiting for a response, see `CardGame::prompt`.
+    pub(crate) prompt: Option<Prompt>,
+
+    /// Each player's zone versions, indexed by `zone_version_index`, if zone watermarks are enabled.
+    zone_versions: Option<Vec<Vec<u64>>>,
+
+    /// Only held on the server, so it's never serialized.
+    #[serde(skip, default = "Default::default", bound = "S: State")]
+    card_database: Option<Arc<dyn CardDatabase<S>>>,
+
+    /// The values revealed during the last action, if reveal recording is enabled.
+    #[serde(skip)]
+    pub(crate) reveals: Option<Vec<Reveal>>,
+
+    /// The transcript being recorded or replayed, if transcript recording is enabled.
+    ///
+    /// Clones share it, so every client a `Tester` runs an action on agrees on the transcript.
+    #[serde(skip)]
+    pub(crate) transcriber: Option<Rc<RefCell<Transcriber>>>,
+
+    /// What the last action revealed and mutated, see `CardGame::action_metrics`.
+    #[serde(skip)]
+    pub(crate) last_action_metrics: ActionMetrics,
+
+    /// Whether revealed values are measured, see `CardGame::set_measure_reveals`.
+    ///
+    /// Measuring doesn't change the game, so it's left out of `GameState::checksum`.
+    #[serde(skip)]
+    measure_reveals: bool,
 
     #[serde(bound = "S: State")]
     state: S,
 }
 
+impl<S: State + Default> Default for GameState<S> {
+    fn default() -> Self {
+        Self::with_config(Default::default(), Default::default())
+    }
+}
+
 impl<S: State> Deref for GameState<S> {
     type Target = S;
 
@@ -48,35 +133,567 @@ impl<S: State> DerefMut for GameState<S> {
 
 impl<S: State> GameState<S> {
     pub fn new(state: S, shuffle_deck_on_insert: bool) -> Self {
+        Self::with_config(
+            state,
+            MatchConfig {
+                shuffle_deck_on_insert,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn with_config(state: S, config: MatchConfig) -> Self {
         Self {
             instances: Default::default(),
-            player_cards: Default::default(),
-            shuffle_deck_on_insert,
+            buckets: Default::default(),
+            player_cards: (0..config.player_count())
+                .map(|_| Default::default())
+                .collect(),
+            config,
+            next_event_step: None,
+            links: Default::default(),
+            modifiers: Default::default(),
+            next_modifier: 0,
+            inverses: Default::default(),
+            destroyed: Default::default(),
+            projections: Default::default(),
+            turn: None,
+            stack: Default::default(),
+            face_down: Default::default(),
+            commitments: Default::default(),
+            next_action: None,
+            result: None,
+            prompt: None,
+            zone_versions: None,
+            card_database: None,
+            reveals: None,
+            transcriber: None,
+            last_action_metrics: Default::default(),
+            measure_reveals: false,
             state,
         }
     }
 
-    pub fn all_player_cards(&self) -> &[PlayerCards] {
+    /// A new game with the same config and settings, for the next game of a `MatchState`.
+    pub(crate) fn next_game(&self, state: S) -> Self {
+        let mut game = Self::with_config(state, self.config.clone());
+
+        game.set_strict_event_ordering(self.strict_event_ordering());
+        game.set_zone_watermarks(self.zone_watermarks());
+        game.set_reveal_recording(self.reveal_recording());
+        game.set_measure_reveals(self.measure_reveals());
+        game.card_database = self.card_database.clone();
+
+        game
+    }
+
+    /// A hash of the public state, for detecting clients that have diverged from the server.
+    ///
+    /// The hash is stable across platforms, as long as `S` serializes deterministically.
+    pub fn checksum(&self) -> u64 {
+        fnv1a(FNV_OFFSET, &serde_cbor::to_vec(self).unwrap())
+    }
+
+    /// A hash of the public state and a player's secret, see `GameState::checksum`.
+    pub fn player_checksum(&self, secret: &PlayerSecret<S>) -> u64 {
+        fnv1a(self.checksum(), &serde_cbor::to_vec(secret).unwrap())
+    }
+
+    /// How the game ended, once it's over.
+    pub fn result(&self) -> Option<GameResult> {
+        self.result
+    }
+
+    /// The winner of the game, once it's over, unless it was a draw.
+    ///
+    /// A player conceding only makes another player the winner if they're the only one left.
+    pub fn winner(&self) -> Option<Player> {
+        match self.result? {
+            GameResult::Win(winner) => Some(winner),
+            GameResult::Draw => None,
+            GameResult::Conceded(player) => {
+                let mut others = self.players().filter(|other| *other != player);
+
+                match (others.next(), others.next()) {
+                    (Some(winner), None) => Some(winner),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The prompt waiting for a response, if any, see `CardGame::prompt`.
+    ///
+    /// Only the prompted player's response to it can be applied until then.
+    pub fn prompt(&self) -> Option<&Prompt> {
+        self.prompt.as_ref()
+    }
+
+    /// Fails once the game is over, see `CardGame::end_game`.
+    pub(crate) fn check_in_progress(&self) -> Result<(), error::MoveCardError> {
+        match self.result {
+            Some(result) => Err(error::MoveCardError::GameOver { result }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn config(&self) -> &MatchConfig {
+        &self.config
+    }
+
+    /// The match configuration, for changing several settings at once.
+    ///
+    /// Event settings changed through this only take effect from the next action.
+    /// Use the `CardGame` setters to change them immediately.
+    pub fn config_mut(&mut self) -> &mut MatchConfig {
+        &mut self.config
+    }
+
+    pub fn secret_card_padding(&self) -> usize {
+        self.config.secret_card_padding
+    }
+
+    /// Pads every secret card creation up to the next multiple of `multiple` instances.
+    ///
+    /// `CardGame::new_secret_cards` and `CardGame::new_secret_cards_with_fakes` reserve unused
+    /// instance IDs so that the number of instances they create doesn't reveal how many fake cards
+    /// and attachments were made, and pad the pointers they return with fake pointers so that their
+    /// number doesn't reveal how many real cards were made. Fake pointers don't point to any card,
+    /// so moving or revealing from them fails like for destroyed cards. A `multiple` of 0 or 1 disables padding.
+    pub fn set_secret_card_padding(&mut self, multiple: usize) {
+        self.config.secret_card_padding = multiple;
+    }
+
+    pub fn redacted_attachment_policy(&self) -> RedactedAttachmentPolicy {
+        self.config.redacted_attachment_policy
+    }
+
+    pub fn set_redacted_attachment_policy(&mut self, policy: RedactedAttachmentPolicy) {
+        self.config.redacted_attachment_policy = policy;
+    }
+
+    pub fn strict_event_ordering(&self) -> bool {
+        self.next_event_step.is_some()
+    }
+
+    /// Enables or disables strict event ordering for subsequent actions.
+    ///
+    /// See `CardGame::set_strict_event_ordering`.
+    pub fn set_strict_event_ordering(&mut self, enabled: bool) {
+        self.next_event_step = match (enabled, self.next_event_step) {
+            (true, next_step) => Some(next_step.unwrap_or_default()),
+            (false, _) => None,
+        };
+    }
+
+    pub fn action_correlation(&self) -> bool {
+        self.next_action.is_some()
+    }
+
+    /// Enables or disables action correlation, starting with the next action.
+    ///
+    /// While enabled, every event is wrapped in a `CardEvent::Correlated` identifying the action that logged it.
+    pub fn set_action_correlation(&mut self, enabled: bool) {
+        self.next_action = match (enabled, self.next_action) {
+            (true, next_action) => Some(next_action.unwrap_or_default()),
+            (false, _) => None,
+        };
+    }
+
+    pub fn coalesce_events(&self) -> bool {
+        self.config.coalesce_events
+    }
+
+    /// Enables or disables coalescing of redundant public events for subsequent actions.
+    ///
+    /// See `CardGame::set_coalesce_events`.
+    pub fn set_coalesce_events(&mut self, enabled: bool) {
+        self.config.coalesce_events = enabled;
+    }
+
+    pub fn spectator_events(&self) -> bool {
+        self.config.spectator_events
+    }
+
+    /// Enables or disables marking of secret events for subsequent actions.
+    ///
+    /// See `CardGame::set_spectator_events`.
+    pub fn set_spectator_events(&mut self, enabled: bool) {
+        self.config.spectator_events = enabled;
+    }
+
+    pub fn measure_reveals(&self) -> bool {
+        self.measure_reveals
+    }
+
+    /// Enables or disables measuring the size of revealed values for subsequent actions.
+    ///
+    /// See `CardGame::set_measure_reveals`.
+    pub fn set_measure_reveals(&mut self, enabled: bool) {
+        self.measure_reveals = enabled;
+    }
+
+    pub fn zone_watermarks(&self) -> bool {
+        self.zone_versions.is_some()
+    }
+
+    /// Enables or disables zone watermarks.
+    ///
+    /// While enabled, each of a player's zones has a version, starting at 0 when enabled, that's bumped whenever a card
+    /// publicly moves into or out of it, or it's reordered or replaced.
+    /// Every bump is logged as a `CardEvent::ZoneVersion`, so clients can detect missed updates and resync with `GameState::zone_snapshot`.
+    /// The public and secret parts of a hand are the same zone, and each pile is a separate zone.
+    pub fn set_zone_watermarks(&mut self, enabled: bool) {
+        self.zone_versions = match (enabled, self.zone_versions.take()) {
+            (true, Some(versions)) => Some(versions),
+            (true, None) => Some(vec![
+                vec![0; ZONE_VERSION_COUNT];
+                self.all_player_cards().len()
+            ]),
+            (false, _) => None,
+        };
+    }
+
+    /// The version of a player's zone, if zone watermarks are enabled.
+    pub fn zone_version(&self, player: Player, zone: Zone) -> Option<u64> {
+        let index = zone_version_index(zone)?;
+
+        // A pile's version is only stored once it's bumped.
+        self.zone_versions.as_ref().map(|versions| {
+            versions[usize::from(player)]
+                .get(index)
+                .copied()
+                .unwrap_or_default()
+        })
+    }
+
+    /// Bumps the version of a player's zone, if zone watermarks are enabled.
+    pub(crate) fn bump_zone_version(&mut self, player: Player, zone: Zone) -> Option<u64> {
+        let index = zone_version_index(zone)?;
+        let versions = &mut self.zone_versions.as_mut()?[usize::from(player)];
+
+        if versions.len() <= index {
+            versions.resize(index + 1, 0);
+        }
+
+        versions[index] += 1;
+
+        Some(versions[index])
+    }
+
+    /// The public contents of a player's zone at its current version, if zone watermarks are enabled.
+    ///
+    /// Cards in secret slots are `None`.
+    /// Returns `None` for secret dust and secret limbo, whose sizes aren't public.
+    pub fn zone_snapshot(&self, player: Player, zone: Zone) -> Option<ZoneSnapshot> {
+        let version = self.zone_version(player, zone)?;
+        let cards = self.public_zone(player, zone)?;
+
+        Some(ZoneSnapshot {
+            player,
+            zone,
+            version,
+            cards,
+        })
+    }
+
+    /// The public contents of a player's zone, with `None` for cards in secret slots.
+    ///
+    /// Returns `None` for secret dust and secret limbo, whose sizes aren't public.
+    fn public_zone(&self, player: Player, zone: Zone) -> Option<Vec<Option<InstanceID>>> {
+        let player_cards = self.player_cards(player);
+
+        Some(match zone {
+            Zone::Deck => vec![None; player_cards.deck()],
+            Zone::Hand { .. } => player_cards.hand().clone(),
+            Zone::CardSelection => vec![None; player_cards.card_selection()],
+            Zone::Pile { pile } => vec![None; player_cards.pile(pile)],
+            Zone::Dust { public: false } | Zone::Limbo { public: false } => return None,
+            zone => player_cards
+                .zone_cards(zone)
+                .into_iter()
+                .map(Some)
+                .collect(),
+        })
+    }
+
+    /// The differences between two game states, e.g. a client's and the server's after a desync, for bug reports.
+    ///
+    /// Reports a different number of instances, each differing instance, each differing zone and pointer count,
+    /// and finally whether the game-specific states differ.
+    pub fn divergence_report(&self, other: &Self) -> Vec<Divergence> {
+        let mut report = Vec::new();
+
+        if self.instances.len() != other.instances.len() {
+            report.push(Divergence::InstanceCount {
+                this: self.instances.len(),
+                other: other.instances.len(),
+            });
+        }
+
+        for (index, (this, other)) in self.instances.iter().zip(&other.instances).enumerate() {
+            let (this, other) = (format!("{:?}", this), format!("{:?}", other));
+
+            if this != other {
+                report.push(Divergence::Instance {
+                    id: InstanceID(index),
+                    this,
+                    other,
+                });
+            }
+        }
+
+        for player in self.players() {
+            let (this_cards, other_cards) = (self.player_cards(player), other.player_cards(player));
+
+            let piles = this_cards.piles().len().max(other_cards.piles().len());
+
+            let zones = [
+                Zone::Deck,
+                Zone::Hand { public: true },
+                Zone::Field,
+                Zone::Graveyard,
+                Zone::Dust { public: true },
+                Zone::Limbo { public: true },
+                Zone::Casting,
+                Zone::CardSelection,
+                Zone::HeroAbility,
+                Zone::Boon,
+            ]
+            .into_iter()
+            .chain((0..piles).map(|pile| Zone::Pile { pile: PileID(pile) }));
+
+            for zone in zones {
+                let (this, other) = (
+                    self.public_zone(player, zone).unwrap_or_default(),
+                    other.public_zone(player, zone).unwrap_or_default(),
+                );
+
+                if this.len() != other.len() {
+                    report.push(Divergence::ZoneSize {
+                        player,
+                        zone,
+                        this: this.len(),
+                        other: other.len(),
+                    });
+                } else if let Some(index) = (0..this.len()).find(|i| this[*i] != other[*i]) {
+                    report.push(Divergence::ZoneCard {
+                        player,
+                        zone,
+                        index,
+                        this: this[index],
+                        other: other[index],
+                    });
+                }
+            }
+
+            if this_cards.pointers != other_cards.pointers {
+                report.push(Divergence::PointerCount {
+                    player,
+                    this: this_cards.pointers,
+                    other: other_cards.pointers,
+                });
+            }
+        }
+
+        let (this, other) = (format!("{:?}", self.state), format!("{:?}", other.state));
+
+        if this != other {
+            report.push(Divergence::State { this, other });
+        }
+
+        report
+    }
+
+    pub fn reveal_recording(&self) -> bool {
+        self.reveals.is_some()
+    }
+
+    /// Enables or disables recording of the values revealed during each action, for tests.
+    ///
+    /// See `GameState::reveals` and `testing::assert_no_reveals`.
+    pub fn set_reveal_recording(&mut self, enabled: bool) {
+        self.reveals = enabled.then(|| self.reveals.take().unwrap_or_default());
+    }
+
+    /// The values revealed during the last action, in order, if reveal recording is enabled.
+    pub fn reveals(&self) -> Option<&[Reveal]> {
+        self.reveals.as_deref()
+    }
+
+    pub fn transcript_recording(&self) -> bool {
+        self.transcriber.is_some()
+    }
+
+    /// Enables or disables recording the randomness each action draws and the values it reveals, for replays.
+    ///
+    /// See `GameState::take_transcript` and `replay::ReplayRecorder::record_transcript`.
+    pub fn set_transcript_recording(&mut self, enabled: bool) {
+        if !enabled {
+            self.transcriber = None;
+        } else if self.transcriber.is_none() {
+            self.transcriber = Some(Rc::new(RefCell::new(Transcriber::recording())));
+        }
+    }
+
+    /// Takes the transcript recorded since it was last taken, if transcript recording is enabled.
+    pub fn take_transcript(&self) -> Option<Transcript> {
+        self.transcriber
+            .as_ref()
+            .map(|transcriber| transcriber.borrow_mut().take())
+    }
+
+    /// How many reveals and secret mutations the last action performed, and how many bytes it revealed.
+    pub fn last_action_metrics(&self) -> ActionMetrics {
+        self.last_action_metrics
+    }
+
+    pub fn card_database(&self) -> Option<&dyn CardDatabase<S>> {
+        self.card_database.as_deref()
+    }
+
+    /// Sets the database used to validate new cards and decks.
+    ///
+    /// The database isn't serialized, so it has to be set again on a deserialized game state.
+    pub fn set_card_database(&mut self, database: Option<Arc<dyn CardDatabase<S>>>) {
+        self.card_database = database;
+    }
+
+    /// Checks that a card can be created, according to the card database, if any.
+    pub fn validate_card(&self, base: &S::BaseCard) -> Result<(), error::IllegalCardError> {
+        match &self.card_database {
+            Some(database) => database.validate_card(base),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that a deck can be registered, according to the match config's deck size bounds and the card database, if any.
+    pub fn validate_deck(&self, deck: &[S::BaseCard]) -> Result<(), error::IllegalCardError> {
+        if let Some((min, max)) = self.config.deck_size {
+            if !(min..=max).contains(&deck.len()) {
+                return Err(error::IllegalCardError::DeckSize {
+                    size: deck.len(),
+                    min,
+                    max,
+                });
+            }
+        }
+
+        match &self.card_database {
+            Some(database) => database.validate_deck(deck),
+            None => Ok(()),
+        }
+    }
+
+    pub fn limbo_policy(&self) -> LimboPolicy {
+        self.config.limbo_policy
+    }
+
+    pub fn set_limbo_policy(&mut self, policy: LimboPolicy) {
+        self.config.limbo_policy = policy;
+    }
+
+    pub fn panic_policy(&self) -> PanicPolicy {
+        self.config.panic_policy
+    }
+
+    pub fn set_panic_policy(&mut self, policy: PanicPolicy) {
+        self.config.panic_policy = policy;
+    }
+
+    /// Checks that a player's zone has room for another card, according to the match config's zone capacities.
+    ///
+    /// A card already in the zone still counts.
+    pub fn check_zone_capacity(
+        &self,
+        player: Player,
+        zone: Zone,
+    ) -> Result<(), error::MoveCardError> {
+        let capacity = match self.config.zone_capacity(zone) {
+            Some(capacity) => capacity,
+            None => return Ok(()),
+        };
+
+        let face_down = self
+            .face_down
+            .iter()
+            .filter(|card| card.player == player && card.zone.eq(zone).unwrap_or(false))
+            .count();
+
+        match self.player_cards(player).zone_size(zone) {
+            Some(size) if size + face_down >= capacity => Err(error::MoveCardError::ZoneFull {
+                player,
+                zone,
+                capacity,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that every player's limbo is empty.
+    ///
+    /// Secret limbo is only checked for the given secrets.
+    pub fn limbo_ok(
+        &self,
+        secrets: &[Option<&PlayerSecret<S>>],
+    ) -> Result<(), error::RevealOkError> {
+        for player in self.players() {
+            if !self.player_cards(player).limbo.is_empty() {
+                return Err(error::RevealOkError::Error {
+                    err: format!("Player {}'s public limbo is not empty", player),
+                });
+            }
+
+            if let Some(Some(secret)) = secrets.get(usize::from(player)) {
+                if !secret.limbo.is_empty() {
+                    return Err(error::RevealOkError::Error {
+                        err: format!("Player {}'s secret limbo is not empty", player),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the players in this game.
+    pub fn players(&self) -> Range<Player> {
+        0..self
+            .player_cards
+            .len()
+            .try_into()
+            .expect("more than 255 players")
+    }
+
+    pub fn all_player_cards(&self) -> &[PlayerCards<S>] {
         &self.player_cards
     }
 
-    pub fn all_player_cards_mut(&mut self) -> &mut [PlayerCards] {
+    pub fn all_player_cards_mut(&mut self) -> &mut [PlayerCards<S>] {
         &mut self.player_cards
     }
 
-    pub fn player_cards(&self, player: Player) -> &PlayerCards {
+    pub fn player_cards(&self, player: Player) -> &PlayerCards<S> {
         &self.player_cards[usize::from(player)]
     }
 
-    pub fn player_cards_mut(&mut self, player: Player) -> &mut PlayerCards {
+    pub fn player_cards_mut(&mut self, player: Player) -> &mut PlayerCards<S> {
         &mut self.player_cards[usize::from(player)]
     }
 
+    /// The actions a given player can currently take, see `State::legal_actions`.
+    pub fn legal_actions(&self, player: Option<Player>) -> Vec<S::Action> {
+        S::legal_actions(self, player)
+    }
+
+    /// Whether a card exists: destroyed cards don't, see `CardGame::destroy_instance`.
+    ///
+    /// A pointer exists once it's been created, even if its card has since been destroyed, since only its player knows.
     pub fn exists(&self, card: impl Into<Card>) -> bool {
         let card = card.into();
 
         match card {
-            Card::ID(id) => id.0 < self.instances.len(),
+            Card::ID(id) => id.0 < self.instances.len() && !self.is_destroyed(id),
             Card::Pointer(OpaquePointer { player, index }) => {
                 usize::from(player) < self.player_cards.len()
                     && index < self.player_cards(player).pointers
@@ -84,6 +701,129 @@ impl<S: State> GameState<S> {
         }
     }
 
+    pub fn links(&self) -> &Vec<CardLink> {
+        &self.links
+    }
+
+    pub fn link(&self, id: InstanceID) -> Option<&CardLink> {
+        self.links.iter().find(|link| link.contains(id))
+    }
+
+    /// The current turn, if turns have started.
+    pub fn turn(&self) -> Option<&Turn<S>> {
+        self.turn.as_ref()
+    }
+
+    /// The player whose turn it is, if turns have started.
+    pub fn active_player(&self) -> Option<Player> {
+        self.turn.as_ref().map(|turn| turn.player)
+    }
+
+    pub fn phase(&self) -> Option<&S::Phase> {
+        self.turn.as_ref().map(|turn| &turn.phase)
+    }
+
+    /// The cards cast with `CardGame::cast` that haven't resolved yet, from bottom to top.
+    pub fn stack(&self) -> &Vec<Card> {
+        &self.stack
+    }
+
+    /// The cards placed face-down with `CardGame::place_face_down` that haven't been turned face-up or moved yet.
+    pub fn face_down_cards(&self) -> &Vec<FaceDownCard> {
+        &self.face_down
+    }
+
+    /// Whether a player has committed to a value with `key` that hasn't been revealed yet.
+    pub fn has_commitment(&self, player: Player, key: &str) -> bool {
+        self.commitments
+            .iter()
+            .any(|(committer, committed)| *committer == player && committed == key)
+    }
+
+    /// Whether a card with a publicly known ID was destroyed by `CardGame::destroy_instance`, and its ID hasn't been reused since.
+    ///
+    /// Secret cards destroyed without revealing their IDs are only gone from their owner's secret.
+    pub fn is_destroyed(&self, id: InstanceID) -> bool {
+        matches!(
+            self.instances.get(id.0),
+            Some(InstanceOrPlayer::Destroyed(..))
+        )
+    }
+
+    /// The revealed projections of secret cards, see `CardGame::reveal_projection`.
+    pub fn known_projections(&self) -> &Vec<KnownProjection> {
+        &self.projections
+    }
+
+    /// The publicly known value of a projection of a card.
+    ///
+    /// Public cards' projections are always known.
+    /// Secret cards' known projections are refreshed at the end of each action.
+    pub fn known_projection(&self, card: impl Into<Card>, projection: &str) -> Option<Vec<u8>> {
+        let card = card.into();
+
+        if let Some(id) = card.id() {
+            if let InstanceOrPlayer::Instance(instance) = &self.instances[id.0] {
+                return instance.state.project(projection);
+            }
+        }
+
+        self.projections
+            .iter()
+            .find(|known| known.card.eq(card).unwrap_or(false) && known.projection == projection)
+            .map(|known| known.value.clone())
+    }
+
+    pub fn modifiers(&self) -> &Vec<CardModifier> {
+        &self.modifiers
+    }
+
+    pub fn modifier(&self, id: ModifierID) -> Option<&CardModifier> {
+        self.modifiers.iter().find(|modifier| modifier.id == id)
+    }
+
+    /// The modifiers that apply to a card, in the order they're applied.
+    pub fn card_modifiers(&self, id: InstanceID) -> Vec<&CardModifier> {
+        let mut modifiers: Vec<_> = self
+            .modifiers
+            .iter()
+            .filter(|modifier| modifier.applies_to(id))
+            .collect();
+
+        modifiers.sort_by_key(|modifier| (modifier.layer, modifier.id.0));
+
+        modifiers
+    }
+
+    /// A card's state with every modifier that applies to it.
+    pub fn effective_state(
+        &self,
+        instance: &CardInstance<S>,
+    ) -> <S::BaseCard as BaseCard>::CardState {
+        CardModifier::apply_all(&self.modifiers, instance)
+    }
+
+    /// Gets the attachment of a public card, if any.
+    pub fn attachment_of(&self, id: InstanceID) -> Option<InstanceID> {
+        self.instances.get(id.0)?.instance_ref()?.attachment
+    }
+
+    /// Gets the public card that a public attachment is attached to, if any.
+    pub fn parent_of(&self, id: InstanceID) -> Option<InstanceID> {
+        let mut parents = self.instances.iter().filter_map(|instance| {
+            instance
+                .instance_ref()
+                .filter(|instance| instance.attachment == Some(id))
+                .map(|instance| instance.id())
+        });
+
+        let parent = parents.next();
+
+        assert!(parents.next().is_none());
+
+        parent
+    }
+
     pub fn owner(&self, id: InstanceID) -> Player {
         self.location(id).player
     }
@@ -91,40 +831,24 @@ impl<S: State> GameState<S> {
     pub fn location(&self, id: InstanceID) -> CardLocation {
         match &self.instances[id.0] {
             InstanceOrPlayer::Instance(..) => {
-                let mut locations = (0u8..self
-                    .player_cards
-                    .len()
-                    .try_into()
-                    .expect("more than 255 players"))
-                    .filter_map(|player| {
-                        self.player_cards(player)
-                            .location(id)
-                            .map(|location| CardLocation {
-                                player,
-                                location: Some((location.0, Some(location.1))),
-                            })
-                    });
+                let mut locations = self.players().filter_map(|player| {
+                    self.player_cards(player)
+                        .location(id)
+                        .map(|location| CardLocation {
+                            player,
+                            location: Some((location.0, Some(location.1))),
+                        })
+                });
 
                 if let Some(location) = locations.next() {
                     assert!(locations.next().is_none());
 
                     location
                 } else {
-                    let mut parents = self.instances.iter().filter_map(|instance| {
-                        instance.instance_ref().and_then(|instance| {
-                            if instance.attachment == Some(id) {
-                                Some(instance.id())
-                            } else {
-                                None
-                            }
-                        })
-                    });
-                    let parent = parents
-                        .next()
+                    let parent = self
+                        .parent_of(id)
                         .unwrap_or_else(|| panic!("{:?} has no owner or public parent", id));
 
-                    assert!(parents.next().is_none());
-
                     CardLocation {
                         player: self.owner(parent),
                         location: Some((
@@ -136,293 +860,177 @@ impl<S: State> GameState<S> {
                     }
                 }
             }
-            InstanceOrPlayer::Player(owner) => CardLocation {
+            // A destroyed card has no location, only its last owner.
+            InstanceOrPlayer::Player(owner) | InstanceOrPlayer::Destroyed(owner) => CardLocation {
                 player: *owner,
                 location: None,
             },
         }
     }
 
+    /// Checks the game state and known secrets against every rule in `invariants`, returning the first violation.
     #[cfg(debug_assertions)]
     #[doc(hidden)]
     pub fn ok(&self, secrets: &[Option<&PlayerSecret<S>>]) -> Result<(), error::RevealOkError> {
-        let any_pointer_out_of_bounds = secrets.iter().flatten().any(|secret| {
-            secret
-                .pointers
-                .iter()
-                .any(|pointer| pointer.0 >= self.instances.len())
-        });
-        if any_pointer_out_of_bounds {
+        if let Some(violation) = invariants::check(self, secrets).into_iter().next() {
             return Err(error::RevealOkError::Error {
-                err: "pointer out of bounds".into(),
+                err: violation.to_string(),
             });
         }
 
-        // Only one bucket may contain the CardInstance for an InstanceID.
-        // If a CardInstance has an attachment, the attachment must be in the same Bucket.
-        let real_instance_ids = self
-            .instances
-            .iter()
-            .flat_map(|card| card.instance_ref().map(|instance| instance.id))
-            .chain(
-                secrets
-                    .iter()
-                    .flatten()
-                    .flat_map(|secret| secret.instances.keys().copied()),
-            );
-
-        for id in real_instance_ids.clone() {
-            match &self.instances[id.0] {
-                InstanceOrPlayer::Player(player) => {
-                    // The card must be in that player's secret cards
-
-                    if let Some(secret) = secrets[usize::from(*player)] {
-                        if !secret.instances.contains_key(&id) {
-                            return Err(error::RevealOkError::Error {
-                                err: format!("Card should have been in player {}'s secret", player),
-                            });
-                        }
-                    }
+        Ok(())
+    }
 
-                    // The card must not be in the other player's secret cards
+    /// Replaces an instance or the bucket holding it, returning what it replaced.
+    pub(crate) fn set_instance(
+        &mut self,
+        id: InstanceID,
+        instance: impl Into<InstanceOrPlayer<S>>,
+    ) -> InstanceOrPlayer<S> {
+        let instance = instance.into();
 
-                    if let Some(secret) = secrets[usize::from(1 - *player)] {
-                        if secret.instances.contains_key(&id) {
-                            return Err(error::RevealOkError::Error {
-                                err: format!(
-                                    "Card should not have been in player {}'s secret",
-                                    1 - player
-                                ),
-                            });
-                        }
-                    }
+        if self.buckets.len() != self.instances.len() {
+            self.buckets = self.buckets();
+        }
 
-                    // The instance's attachment, if any, should also be in this player's secret.
-
-                    if let Some(secret) = secrets[usize::from(*player)] {
-                        if let Some(attachment_id) = secret.instance(id).unwrap().attachment {
-                            if !secret.instances.contains_key(&attachment_id) {
-                                return Err(error::RevealOkError::Error {
-                                    err: format!(
-                                        "Card's attachment should have been in player {}'s secret",
-                                        player
-                                    ),
-                                });
-                            }
-                        }
-                    }
-                }
-                InstanceOrPlayer::Instance(instance) => {
-                    // The card shouldn't be in either player's secret cards
-
-                    for (player, secret) in secrets
-                        .iter()
-                        .enumerate()
-                        .filter_map(|(player, secret)| secret.map(|secret| (player, secret)))
-                    {
-                        if secret.instances.contains_key(&id) {
-                            return Err(error::RevealOkError::Error {
-                                err: format!(
-                                    "InstanceID {:?} is both public and in player {:?}'s secret",
-                                    id, player
-                                ),
-                            });
-                        }
-                    }
+        Rc::make_mut(&mut self.buckets)[id.0] = instance.bucket();
 
-                    // The instance's attachment, if any, should also be public.
+        std::mem::replace(&mut self.instances[id.0], instance)
+    }
 
-                    if let Some(attachment) = instance.attachment {
-                        if let InstanceOrPlayer::Player(player) = self.instances[attachment.0] {
-                            return Err(error::RevealOkError::Error {
-                                err: format!("The instance for card {} is public, but its attachment {} is in player {}'s secret", id.0, attachment.0, player)});
-                        }
-                    }
-                }
-            }
-        }
+    /// Adds a new public instance.
+    pub(crate) fn push_instance(&mut self, instance: CardInstance<S>) {
+        assert_eq!(instance.id.0, self.instances.len());
 
-        // An InstanceID must occur in all zones combined at most once.
-        // It can be 0, because some InstanceIDs correspond to non-existent attachments.
-        for id in 0..self.instances.len() {
-            let id = InstanceID(id);
+        if self.buckets.len() != self.instances.len() {
+            self.buckets = self.buckets();
+        }
 
-            // Count the number of times id occurs in public and secret state.
+        Rc::make_mut(&mut self.buckets).push(Bucket::Public);
+        self.instances.push(instance.into());
+    }
 
-            let mut count = 0;
+    /// Adds `count` instances to a player's secret.
+    pub(crate) fn reserve_instances(&mut self, player: Player, count: usize) {
+        if self.buckets.len() != self.instances.len() {
+            self.buckets = self.buckets();
+        }
 
-            for (player_id, player) in self.all_player_cards().iter().enumerate() {
-                let player_id: Player =
-                    player_id
-                        .try_into()
-                        .map_err(|error| error::RevealOkError::Error {
-                            err: format!("{}", error),
-                        })?;
+        Rc::make_mut(&mut self.buckets).extend(repeat_n(Bucket::Secret(player), count));
+        self.instances
+            .extend(repeat_n(InstanceOrPlayer::Player(player), count));
+    }
 
-                count += player
-                    .hand
-                    .iter()
-                    .filter(|hand_id| **hand_id == Some(id))
-                    .count();
-                count += player
-                    .field
-                    .iter()
-                    .filter(|field_id| **field_id == id)
-                    .count();
-                count += player
-                    .graveyard
+    /// The bucket holding each instance, without copying it, for reveal closures.
+    pub(crate) fn buckets(&self) -> Rc<Vec<Bucket>> {
+        if self.buckets.len() == self.instances.len() {
+            self.buckets.clone()
+        } else {
+            // The index isn't serialized, so it's rebuilt after deserializing.
+            Rc::new(
+                self.instances
                     .iter()
-                    .filter(|graveyard_id| **graveyard_id == id)
-                    .count();
-                count += player
-                    .limbo
-                    .iter()
-                    .filter(|limbo_id| **limbo_id == id)
-                    .count();
-                count += player
-                    .casting
-                    .iter()
-                    .filter(|casting_id| **casting_id == id)
-                    .count();
-                count += player.dust.iter().filter(|dust_id| **dust_id == id).count();
-                count += self
-                    .instances
-                    .iter()
-                    .filter(|card| {
-                        if let InstanceOrPlayer::Instance(instance) = card {
-                            instance.attachment == Some(id) && self.owner(instance.id) == player_id
-                        } else {
-                            false
-                        }
-                    })
-                    .count();
-            }
+                    .map(InstanceOrPlayer::bucket)
+                    .collect(),
+            )
+        }
+    }
 
-            for secret in secrets.iter().flatten() {
-                count += secret.deck.iter().filter(|deck_id| **deck_id == id).count();
-                count += secret
-                    .hand
-                    .iter()
-                    .filter(|hand_id| **hand_id == Some(id))
-                    .count();
-                count += secret
-                    .limbo
-                    .iter()
-                    .filter(|limbo_id| **limbo_id == id)
-                    .count();
-                count += secret.dust.iter().filter(|dust_id| **dust_id == id).count();
-                count += secret
-                    .card_selection
-                    .iter()
-                    .filter(|card_selection_id| **card_selection_id == id)
-                    .count();
-                count += secret
-                    .instances
-                    .values()
-                    .filter(|instance| instance.attachment == Some(id))
-                    .count();
-            }
+    /// The player whose secret holds an instance, or `None` if it's public or destroyed.
+    pub fn instance_bucket(&self, id: InstanceID) -> Option<Player> {
+        self.instances[id.0].player()
+    }
 
-            if count > 1 {
-                return Err(error::RevealOkError::Error {
-                    err: format!(
-                        "Instance ID {} occurs {} times in public and secret state",
-                        id.0, count
-                    ),
-                });
+    /// An instance, from the public state or whichever of the given secrets holds it.
+    ///
+    /// `secrets` are indexed by player, like those of `invariants::check`.
+    /// Returns `None` if the instance is in a secret that isn't given.
+    pub fn find_instance<'a>(
+        &'a self,
+        id: InstanceID,
+        secrets: &[Option<&'a PlayerSecret<S>>],
+    ) -> Option<&'a CardInstance<S>> {
+        match self.instance_bucket(id) {
+            None => id.instance(self, None),
+            Some(owner) => {
+                let secret = secrets.get(usize::from(owner)).copied().flatten()?;
+
+                id.instance(self, Some(secret))
             }
         }
+    }
 
-        // If an instance is public, it should be in a public zone.
-        // If an instance is secret, it should be in a secret zone.
-
-        for id in real_instance_ids {
-            match self.instances[id.0] {
-                InstanceOrPlayer::Player(player) => {
-                    if let Some(secret) = secrets[usize::from(player)] {
-                        if secret.location(id).location.is_none() {
-                            return Err(error::RevealOkError::Error {
-                                err: format!(
-                            "{:?} is in player {}'s secret bucket, but not in any of their zzones",
-                            id, player
-                        ),
-                            });
-                        }
-                    }
-                }
-                InstanceOrPlayer::Instance(..) => {
-                    self.owner(id);
-                }
-            }
+    /// Checks an action's targets against their requirements, e.g. in `State::verify`.
+    ///
+    /// Public cards are checked fully, and targets that are publicly the same card are rejected.
+    /// Secret cards only have to exist and may still be the same card as another target,
+    /// since checking them needs their owner's secret, see `CardGame::check_targets`.
+    pub fn check_targets(
+        &self,
+        requirements: &[TargetRequirement<S>],
+        targets: &[Card],
+    ) -> Result<(), error::TargetError> {
+        if targets.len() != requirements.len() {
+            return Err(error::TargetError::WrongCount {
+                count: targets.len(),
+                expected: requirements.len(),
+            });
         }
 
-        // Public state deck must match secret state deck length.
-        for (player_id, player) in self.all_player_cards().iter().enumerate() {
-            if let Some(secret) = secrets[player_id] {
-                if secret.deck.len() != player.deck {
-                    return Err(error::RevealOkError::Error {
-                        err: format!(
-                        "Player {}'s public deck size is {}, but their private deck size is {}.",
-                        player_id,
-                        player.deck,
-                        secret.deck.len()
-                    ),
-                    });
-                }
+        for (index, (requirement, card)) in requirements.iter().zip(targets).enumerate() {
+            let card = *card;
+
+            if targets[..index]
+                .iter()
+                .any(|other| other.eq(card).unwrap_or(false))
+            {
+                return Err(error::TargetError::Duplicate { card });
             }
-        }
 
-        // Public state card selection must match secret state card selection length.
-        for (player_id, player) in self.all_player_cards().iter().enumerate() {
-            if let Some(secret) = secrets[player_id] {
-                if secret.card_selection.len() != player.card_selection {
-                    return Err(error::RevealOkError::Error {
-                    err: format!("Player {}'s public card selection size is {}, but their private card selection size is {}.", player_id, player.card_selection, secret.card_selection.len())
-                }
-                );
-                }
+            if !self.exists(card) {
+                return Err(error::TargetError::Stale { card });
             }
-        }
 
-        // For each card in Public & Secret hand, if one Bucket has None, the other must have Some(ID).
-        for (player, secret) in self
-            .all_player_cards()
-            .iter()
-            .zip(secrets.iter())
-            .filter_map(|(player, secret)| secret.map(|secret| (player, secret)))
-        {
-            for (index, (public_hand, secret_hand)) in
-                player.hand.iter().zip(secret.hand.iter()).enumerate()
-            {
-                match (public_hand, secret_hand) {
-                    (Some(_), None) | (None, Some(_)) => {
-                        // ok! only one state has it
-                    }
-                    (Some(public_some), Some(private_some)) => {
-                        return Err(error::RevealOkError::Error {
-                            err: format!("Both public state & private state({:?}) have Some(_) at hand position {:?} .\nPublic: Some({:?}), Private: Some({:?})", player, index, public_some, private_some)});
+            if let Card::ID(id) = card {
+                if self.instances[id.0].instance_ref().is_some() {
+                    if self.location(id).location.is_none() {
+                        return Err(error::TargetError::Stale { card });
                     }
-                    (None, None) => {
-                        return Err(error::RevealOkError::Error {
-                            err: "Both public & private state have None at this hand position."
-                                .to_string(),
-                        });
+
+                    if !requirement.accepts(self.card_info(id)) {
+                        return Err(error::TargetError::Illegal { index, card });
                     }
                 }
             }
         }
 
-        for id in self
-            .instances
-            .iter()
-            .flat_map(|card| card.instance_ref().map(|instance| instance.id))
-        {
-            self.owner(id); // should be able to call owner for each public id
-        }
-
         Ok(())
     }
 
+    pub(crate) fn card_info(&self, pub_id: InstanceID) -> CardInfo<'_, S> {
+        let CardLocation {
+            player: owner,
+            location,
+        } = self.location(pub_id);
+        let location = location.unwrap_or_else(|| panic!("public {:?} has no zone", pub_id));
+
+        let instance = &self.instances[pub_id.0].instance_ref().unwrap();
+        let attachment = instance.attachment().map(|attachment| {
+            self.instances[attachment.0]
+                .instance_ref()
+                .unwrap_or_else(|| {
+                    panic!("public {:?} attachment {:?} not public", pub_id, attachment)
+                })
+        });
+
+        CardInfo {
+            instance,
+            owner,
+            zone: location.0,
+            attachment,
+        }
+    }
+
     #[cfg(debug_assertions)]
     #[doc(hidden)]
     pub fn instances(&self) -> usize {
@@ -458,7 +1066,36 @@ impl<S: State> arcadeum::store::State for GameState<S> {
     }
 
     fn verify(&self, player: Option<Player>, action: &Self::Action) -> Result<(), String> {
-        S::verify(self, player, action)
+        if self.result.is_some() {
+            return Err("the game is over".to_string());
+        }
+
+        if let Some(conceding) = S::concession(action) {
+            return match player {
+                Some(player) if player != conceding => Err(format!(
+                    "player {} can't concede for player {}",
+                    player, conceding
+                )),
+                _ => Ok(()),
+            };
+        }
+
+        match (&self.prompt, S::prompt_response(action)) {
+            (Some(prompt), Some(response)) => match player {
+                Some(player) if player != prompt.player => Err(error::PromptError::WrongPlayer {
+                    player,
+                    prompted: prompt.player,
+                }
+                .to_string()),
+                _ => prompt.check(&response).map_err(|error| error.to_string()),
+            },
+            (Some(prompt), None) => Err(format!(
+                "waiting for player {} to respond to {:?}",
+                prompt.player, prompt.key
+            )),
+            (None, Some(..)) => Err(error::PromptError::NoPrompt.to_string()),
+            (None, None) => S::verify(self, player, action),
+        }
     }
 
     fn apply(
@@ -469,27 +1106,314 @@ impl<S: State> arcadeum::store::State for GameState<S> {
     ) -> Pin<Box<dyn Future<Output = (Self, Context<S>)>>> {
         let action = action.clone();
 
-        Box::pin(async move {
-            let mut game = CardGame {
-                state: self,
-                context,
+        let future = async move {
+            let key = S::action_key(&action);
+
+            let lenient = self.config.panic_policy == PanicPolicy::Lenient;
+
+            // Only actions that can be undone need a snapshot.
+            let snapshot =
+                (lenient || self.config.limbo_policy == LimboPolicy::Forbid).then(|| self.clone());
+
+            let mut game = CardGame::new(self, context);
+
+            game.set_acting_player(player);
+
+            if snapshot.is_some() {
+                game.context.checkpoint_secrets();
+                game.context.hold();
+            }
+
+            let skipped_reveals = game.context.skipped_reveals();
+
+            let run = async {
+                {
+                    let effect = pin!(async {
+                        match (S::concession(&action), S::prompt_response(&action)) {
+                            (Some(conceding), _) => game.concede(conceding),
+                            (None, Some(response)) => game.respond(response).await,
+                            (None, None) => S::apply(&mut game, player, action).await,
+                        }
+                    });
+
+                    // An effect revealing a conceded player's secret ends there.
+                    UntilSkipped {
+                        effect,
+                        skipped_reveals,
+                    }
+                    .await;
+                }
+
+                if game.result.is_none() {
+                    game.refresh_projections().await;
+
+                    if let Some(result) = S::check_game_over(&game) {
+                        game.end_game(result);
+                    }
+                }
+
+                game.enforce_limbo_policy().await
             };
 
-            S::apply(&mut game, player, action).await;
+            // `None` if the action panicked.
+            // The panic message isn't logged, since it may describe secret state.
+            let outcome = if lenient {
+                CatchUnwind(Box::pin(run)).await.ok()
+            } else {
+                Some(run.await)
+            };
+
+            match (snapshot, outcome) {
+                (None, Some(Err(error::LimboError::NotEmpty { player }))) => {
+                    // The policy was only forbidden during the action, so it can't be undone.
+                    game.context.log(CardEvent::LimboNotEmpty { player });
+                }
+                (None, _) => (),
+                (Some(_), Some(Ok(()))) => {
+                    game.context.discard_checkpoints();
+                    game.context.release();
+                }
+                (Some(snapshot), outcome) => {
+                    game.state = snapshot;
+                    game.context.restore_secrets();
+                    game.context.set_turn(game.state.turn.clone());
+
+                    if let Some(Err(error::LimboError::NotEmpty { player })) = outcome {
+                        game.context.log(CardEvent::LimboNotEmpty { player });
+                    }
+
+                    game.context.log(CardEvent::ActionAborted);
+                    game.context.release();
+                }
+            }
 
             // workaround bug https://github.com/horizon-games/issue-tracker/issues/4888
             // TODO figure out the actual cause of this issue
-            for player in 0..2 {
+            for player in game.players() {
                 game.context
                     .mutate_secret(player, |mut s| s.deferred_locations.clear());
             }
-            let CardGame { state, context } = game;
 
-            (state, context)
-        })
+            if game.config.checksum_events {
+                let checksum = game.checksum();
+
+                game.context.log(CardEvent::Checksum { checksum });
+            }
+
+            if game.config.action_summaries {
+                let summary = ActionSummary {
+                    key,
+                    ..game.action_summary()
+                };
+
+                game.context.log(CardEvent::ActionResolved { summary });
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::Span::current().record("reveals", game.context.metrics().reveals);
+
+            game.into_parts()
+        };
+
+        Box::pin(traced!(
+            future,
+            tracing::info_span!("apply", ?player, reveals = tracing::field::Empty)
+        ))
     }
 }
 
+/// The public contents of a zone at a version, see `GameState::zone_snapshot`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ZoneSnapshot {
+    pub player: Player,
+    pub zone: Zone,
+    pub version: u64,
+    pub cards: Vec<Option<InstanceID>>,
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 64-bit FNV-1a, which unlike `std::hash` is the same on every platform and Rust version.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// The number of zones with a version, not counting piles, which are versioned after them.
+const ZONE_VERSION_COUNT: usize = 12;
+
+fn zone_version_index(zone: Zone) -> Option<usize> {
+    match zone {
+        Zone::Deck => Some(0),
+        Zone::Hand { .. } => Some(1),
+        Zone::Field => Some(2),
+        Zone::Graveyard => Some(3),
+        Zone::Dust { public: true } => Some(4),
+        Zone::Dust { public: false } => Some(5),
+        Zone::Attachment { .. } => None,
+        Zone::Limbo { public: true } => Some(6),
+        Zone::Limbo { public: false } => Some(7),
+        Zone::Casting => Some(8),
+        Zone::CardSelection => Some(9),
+        Zone::HeroAbility => Some(10),
+        Zone::Boon => Some(11),
+
+        Zone::Pile { pile } => Some(ZONE_VERSION_COUNT + pile.0),
+    }
+}
+
+/// What happens when cards are left in limbo at the end of an action.
+///
+/// Checking a player's secret limbo reveals whether it's empty.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum LimboPolicy {
+    /// Cards may stay in limbo between actions.
+    #[default]
+    Allow,
+
+    /// A `CardEvent::LimboNotEmpty` is logged for each player with cards in limbo.
+    /// Face-down cards and cards cast face-down aren't counted while they wait in secret limbo.
+    Flag,
+
+    /// An action leaving cards in limbo is aborted like a panic under `PanicPolicy::Lenient`:
+    /// the game state and every player's secret are restored to how they were before the action,
+    /// and a `CardEvent::LimboNotEmpty` and a `CardEvent::ActionAborted` are logged.
+    ///
+    /// The game state is cloned before every action to undo it, see `PanicPolicy::Lenient`.
+    /// If the policy is only set during an action, that action can't be undone, so its cards in limbo are flagged instead.
+    Forbid,
+}
+
+/// What happens when an action panics, e.g. because of a broken invariant.
+///
+/// Panics can only be caught in builds where they unwind, not with `panic = "abort"`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum PanicPolicy {
+    /// The panic propagates, ending the match, which is what tests want.
+    #[default]
+    Strict,
+
+    /// The action is aborted: the game state and every player's secret are restored to how they were before the action,
+    /// and a `CardEvent::ActionAborted` is logged.
+    /// The panic message is only reported by the panic hook, since it may describe secret state.
+    ///
+    /// Only panics outside `mutate_secret` and `reveal` closures are safe to abort.
+    /// Those closures only run where the secret is held, so a panic inside one aborts the action there
+    /// while the other peers carry on, and the match diverges.
+    ///
+    /// Every event the action logs, public or secret, is held until the action finishes,
+    /// so clients never see the events of an aborted action, only the `CardEvent::ActionAborted`.
+    ///
+    /// Panics can't be caught where they abort instead of unwinding, e.g. on `wasm32` targets,
+    /// which always abort, so there this behaves like `PanicPolicy::Strict`, except for the cost below.
+    ///
+    /// Every action clones the whole game state, so the cost grows with the number of card instances,
+    /// and checkpoints and then discards each player's secret with two extra `mutate_secret` calls per player.
+    /// Releasing the held events takes another `mutate_secret` call for every secret mutation of the action.
+    /// Prefer `PanicPolicy::Strict` for large games or where panics abort.
+    Lenient,
+}
+
+/// Polls a future, catching its panics, see `PanicPolicy::Lenient`.
+///
+/// The future is asserted to be unwind safe, since nothing it touched survives a panic unrestored:
+/// it's dropped without being polled again, `GameState::apply` replaces the game state with its pre-action
+/// snapshot and restores each player's secret from its checkpoint, and the held and pending events are dropped.
+/// Any `RefCell` borrows it held are released while unwinding.
+///
+/// Values already revealed can't be taken back, so they stay in the reveal record and the transcript,
+/// but every client panics at the same point and restores the same snapshot, so they stay consistent.
+/// This doesn't hold for panics inside `mutate_secret` and `reveal` closures, which only run on one client.
+struct CatchUnwind<F>(F);
+
+impl<F: Future + Unpin> Future for CatchUnwind<F> {
+    type Output = std::thread::Result<F::Output>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        let future = &mut self.0;
+
+        match std::panic::catch_unwind(AssertUnwindSafe(|| Pin::new(future).poll(cx))) {
+            Ok(poll) => poll.map(Ok),
+            Err(payload) => std::task::Poll::Ready(Err(payload)),
+        }
+    }
+}
+
+/// Polls an action's effect until it finishes, or until it waits for a skipped reveal, see `GameContext::skip_if_conceded`.
+struct UntilSkipped<F> {
+    effect: F,
+    skipped_reveals: Rc<Cell<bool>>,
+}
+
+impl<F: Future<Output = ()> + Unpin> Future for UntilSkipped<F> {
+    type Output = ();
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        match Pin::new(&mut self.effect).poll(cx) {
+            std::task::Poll::Pending if self.skipped_reveals.get() => std::task::Poll::Ready(()),
+            poll => poll,
+        }
+    }
+}
+
+/// How a game ended, see `CardGame::end_game`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GameResult {
+    Win(Player),
+    Draw,
+
+    /// The player conceded, so the other player won, see `CardGame::concede`.
+    Conceded(Player),
+}
+
+impl std::fmt::Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameResult::Win(winner) => write!(f, "player {} won", winner),
+            GameResult::Draw => write!(f, "draw"),
+            GameResult::Conceded(player) => write!(f, "player {} conceded", player),
+        }
+    }
+}
+
+/// What happens to tokens that leave the field, see `CardInstance::is_token`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum TokenPolicy {
+    /// Tokens are destroyed by `CardGame::destroy_instance` right after they're moved off the field.
+    #[default]
+    Destroy,
+
+    /// Tokens move like any other card.
+    Keep,
+}
+
 #[cfg_attr(
     feature = "bindings",
     derive(typescript_definitions::TypescriptDefinition)
@@ -501,6 +1425,10 @@ pub(crate) enum InstanceOrPlayer<S: State> {
 
     #[serde(rename = "player")]
     Player(Player),
+
+    /// A card destroyed by `CardGame::destroy_instance`, with its last owner, until its ID is reused.
+    #[serde(rename = "destroyed")]
+    Destroyed(Player),
 }
 
 impl<S: State> InstanceOrPlayer<S> {
@@ -531,6 +1459,41 @@ impl<S: State> InstanceOrPlayer<S> {
             _ => None,
         }
     }
+
+    pub(crate) fn bucket(&self) -> Bucket {
+        match self {
+            Self::Instance(..) => Bucket::Public,
+            Self::Player(player) => Bucket::Secret(*player),
+            Self::Destroyed(..) => Bucket::Destroyed,
+        }
+    }
+}
+
+/// Where an instance is held, see `GameState::buckets`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Bucket {
+    Public,
+
+    /// A player's secret.
+    Secret(Player),
+
+    /// Neither: the instance was destroyed, see `CardGame::destroy_instance`.
+    Destroyed,
+}
+
+impl Bucket {
+    /// The bucket of an instance a pointer resolved to, including the `InstanceID::DESTROYED` of an invalidated pointer.
+    pub(crate) fn of(buckets: &[Self], id: InstanceID) -> Self {
+        buckets.get(id.0).copied().unwrap_or(Self::Destroyed)
+    }
+
+    /// The player whose secret holds the instance, if it's secret.
+    pub(crate) fn secret(self) -> Option<Player> {
+        match self {
+            Self::Secret(player) => Some(player),
+            _ => None,
+        }
+    }
 }
 
 impl<S: State> From<CardInstance<S>> for InstanceOrPlayer<S> {
@@ -14,6 +14,9 @@ use wasm_bindgen::prelude::wasm_bindgen;
 pub struct InstanceID(pub(crate) usize);
 
 impl InstanceID {
+    /// What pointers to destroyed cards point to, see `PlayerSecret::destroy`.
+    pub(crate) const DESTROYED: InstanceID = InstanceID(usize::MAX);
+
     #[doc(hidden)]
     /// Internal-only API! Creates an instance ID from a usize.
     /// Never use this in prod-facing code.
@@ -28,6 +31,7 @@ impl InstanceID {
     ) -> Option<&'a CardInstance<S>> {
         match &state.instances[self.0] {
             InstanceOrPlayer::Instance(instance) => Some(instance),
+            InstanceOrPlayer::Destroyed(..) => None,
             InstanceOrPlayer::Player(owner) => secret.and_then(|secret| {
                 if secret.player() == *owner {
                     Some(&secret.instances[self])
@@ -0,0 +1,339 @@
+//! The consistency rules of a game state and its player secrets.
+//!
+//! Each check takes the game state and whichever player secrets are known, indexed by player,
+//! skips the parts of its rule that need an unknown secret, and returns every violation it finds.
+//! `GameState::ok` fails on the first violation of any of them.
+
+use crate::{
+    error::InvariantViolation, GameState, InstanceID, InstanceOrPlayer, PileID, Player,
+    PlayerSecret, State,
+};
+
+/// Runs every check.
+pub fn check<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    [
+        pointers_in_bounds,
+        single_bucket,
+        single_zone,
+        secret_instances_in_zones,
+        public_instances_in_zones,
+        deck_sizes_match,
+        card_selection_sizes_match,
+        pile_sizes_match,
+        hands_paired,
+    ]
+    .iter()
+    .flat_map(|check| check(state, secrets))
+    .collect()
+}
+
+/// Every secret pointer points to an existing instance ID, unless it was invalidated by `CardGame::destroy_instance`.
+pub fn pointers_in_bounds<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    known_secrets(state, secrets)
+        .flat_map(|(player, secret)| {
+            secret
+                .pointers
+                .iter()
+                .enumerate()
+                .filter(|(_, id)| **id != InstanceID::DESTROYED && id.0 >= state.instances.len())
+                .map(move |(index, id)| InvariantViolation::PointerOutOfBounds {
+                    player,
+                    index,
+                    id: *id,
+                })
+        })
+        .collect()
+}
+
+/// Every instance is in exactly one bucket: the public state, or its owner's secret.
+/// A destroyed instance is in neither.
+/// An instance's attachment is in the same bucket as it.
+pub fn single_bucket<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    let mut violations = Vec::new();
+
+    let public_ids = state
+        .instances
+        .iter()
+        .filter_map(|card| card.instance_ref().map(|instance| instance.id));
+
+    let secret_ids =
+        known_secrets(state, secrets).flat_map(|(_, secret)| secret.instances.keys().copied());
+
+    // A secret instance can be listed in more than one secret, so only check it once.
+    let mut ids: Vec<_> = public_ids.chain(secret_ids).collect();
+
+    ids.sort_unstable_by_key(|id| id.0);
+    ids.dedup();
+
+    for id in ids {
+        match state.instances.get(id.0) {
+            Some(InstanceOrPlayer::Player(owner)) => {
+                for player in state.players() {
+                    if let Some(secret) = secret(secrets, player) {
+                        match (secret.instances.get(&id), player == *owner) {
+                            (None, true) => violations
+                                .push(InvariantViolation::MissingFromSecret { id, player }),
+                            (Some(_), false) => {
+                                violations.push(InvariantViolation::InOtherSecret {
+                                    id,
+                                    owner: *owner,
+                                    player,
+                                })
+                            }
+                            (Some(instance), true) => {
+                                if let Some(attachment) = instance.attachment {
+                                    if !secret.instances.contains_key(&attachment) {
+                                        violations.push(InvariantViolation::AttachmentBucket {
+                                            id,
+                                            attachment,
+                                        });
+                                    }
+                                }
+                            }
+                            (None, false) => (),
+                        }
+                    }
+                }
+            }
+            Some(InstanceOrPlayer::Instance(instance)) => {
+                for (player, secret) in known_secrets(state, secrets) {
+                    if secret.instances.contains_key(&id) {
+                        violations.push(InvariantViolation::PublicInSecret { id, player });
+                    }
+                }
+
+                if let Some(attachment) = instance.attachment {
+                    if let Some(InstanceOrPlayer::Player(..)) = state.instances.get(attachment.0) {
+                        violations.push(InvariantViolation::AttachmentBucket { id, attachment });
+                    }
+                }
+            }
+            Some(InstanceOrPlayer::Destroyed(..)) => {
+                for (player, secret) in known_secrets(state, secrets) {
+                    if secret.instances.contains_key(&id) {
+                        violations.push(InvariantViolation::DestroyedInSecret { id, player });
+                    }
+                }
+            }
+            None => (),
+        }
+    }
+
+    violations
+}
+
+/// Every instance ID occurs at most once across all public and secret zones, counting attachments.
+///
+/// An ID can occur nowhere, since some IDs are reserved for attachments that don't exist.
+pub fn single_zone<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    let mut counts = vec![0; state.instances.len()];
+
+    let public_ids = state.all_player_cards().iter().flat_map(|cards| {
+        cards
+            .hand
+            .iter()
+            .flatten()
+            .chain(cards.field.iter())
+            .chain(cards.graveyard.iter())
+            .chain(cards.dust.iter())
+            .chain(cards.limbo.iter())
+            .chain(cards.casting.iter())
+            .chain(cards.hero_ability.iter())
+            .chain(cards.boon.iter())
+    });
+
+    let public_attachments = state
+        .instances
+        .iter()
+        .filter_map(|card| card.instance_ref()?.attachment.as_ref());
+
+    let secret_ids = known_secrets(state, secrets).flat_map(|(_, secret)| {
+        secret
+            .deck
+            .iter()
+            .chain(secret.hand.iter().flatten())
+            .chain(secret.dust.iter())
+            .chain(secret.limbo.iter())
+            .chain(secret.card_selection.iter())
+            .chain(secret.piles.iter().flatten())
+            .chain(
+                secret
+                    .instances
+                    .values()
+                    .filter_map(|instance| instance.attachment.as_ref()),
+            )
+    });
+
+    for id in public_ids.chain(public_attachments).chain(secret_ids) {
+        if let Some(count) = counts.get_mut(id.0) {
+            *count += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .enumerate()
+        .filter(|(_, count)| *count > 1)
+        .map(|(id, count)| InvariantViolation::DuplicateID {
+            id: InstanceID(id),
+            count,
+        })
+        .collect()
+}
+
+/// Every instance in a player's secret is in one of their zones.
+pub fn secret_instances_in_zones<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    known_secrets(state, secrets)
+        .flat_map(|(player, secret)| {
+            secret
+                .instances
+                .keys()
+                .filter(move |id| {
+                    matches!(
+                        state.instances.get(id.0),
+                        Some(InstanceOrPlayer::Player(owner)) if *owner == player
+                    )
+                })
+                .filter(|id| secret.location(**id).location.is_none())
+                .map(move |id| InvariantViolation::NotInZone { id: *id, player })
+        })
+        .collect()
+}
+
+/// Every public instance is in one of its owner's public zones, or attached to a public card.
+pub fn public_instances_in_zones<S: State>(
+    state: &GameState<S>,
+    _secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    state
+        .instances
+        .iter()
+        .filter_map(|card| card.instance_ref().map(|instance| instance.id))
+        .filter(|id| {
+            state
+                .players()
+                .all(|player| state.player_cards(player).location(*id).is_none())
+                && state.parent_of(*id).is_none()
+        })
+        .map(|id| InvariantViolation::PublicNotInZone { id })
+        .collect()
+}
+
+/// Each player's public deck size matches their secret deck.
+pub fn deck_sizes_match<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    known_secrets(state, secrets)
+        .filter_map(|(player, secret)| {
+            let public = state.player_cards(player).deck;
+
+            (public != secret.deck.len()).then_some(InvariantViolation::DeckSize {
+                player,
+                public,
+                secret: secret.deck.len(),
+            })
+        })
+        .collect()
+}
+
+/// Each player's public card selection size matches their secret card selection.
+pub fn card_selection_sizes_match<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    known_secrets(state, secrets)
+        .filter_map(|(player, secret)| {
+            let public = state.player_cards(player).card_selection;
+
+            (public != secret.card_selection.len()).then_some(
+                InvariantViolation::CardSelectionSize {
+                    player,
+                    public,
+                    secret: secret.card_selection.len(),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Each of a player's public pile sizes matches their secret pile.
+pub fn pile_sizes_match<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    known_secrets(state, secrets)
+        .flat_map(|(player, secret)| {
+            let piles = state
+                .player_cards(player)
+                .piles
+                .len()
+                .max(secret.piles.len());
+
+            (0..piles).map(PileID).filter_map(move |pile| {
+                let public = state.player_cards(player).pile(pile);
+
+                (public != secret.pile(pile).len()).then_some(InvariantViolation::PileSize {
+                    player,
+                    pile,
+                    public,
+                    secret: secret.pile(pile).len(),
+                })
+            })
+        })
+        .collect()
+}
+
+/// Each position in a player's hand is filled in exactly one of their public and secret hands.
+pub fn hands_paired<S: State>(
+    state: &GameState<S>,
+    secrets: &[Option<&PlayerSecret<S>>],
+) -> Vec<InvariantViolation> {
+    known_secrets(state, secrets)
+        .flat_map(|(player, secret)| {
+            state
+                .player_cards(player)
+                .hand
+                .iter()
+                .zip(secret.hand.iter())
+                .enumerate()
+                .filter_map(move |(index, hands)| match hands {
+                    (Some(_), None) | (None, Some(_)) => None,
+                    (Some(_), Some(_)) => Some(InvariantViolation::HandInBoth { player, index }),
+                    (None, None) => Some(InvariantViolation::HandInNeither { player, index }),
+                })
+        })
+        .collect()
+}
+
+fn secret<'a, S: State>(
+    secrets: &[Option<&'a PlayerSecret<S>>],
+    player: Player,
+) -> Option<&'a PlayerSecret<S>> {
+    secrets.get(usize::from(player)).copied().flatten()
+}
+
+/// The known secrets, with the players they belong to.
+fn known_secrets<'a, S: State>(
+    state: &GameState<S>,
+    secrets: &'a [Option<&'a PlayerSecret<S>>],
+) -> impl Iterator<Item = (Player, &'a PlayerSecret<S>)> + 'a {
+    state
+        .players()
+        .filter_map(move |player| Some((player, secret(secrets, player)?)))
+}
@@ -1,42 +1,95 @@
 use std::fmt::Debug;
 
+mod action_correlation;
+mod action_metrics;
+mod action_summary;
 mod base_card;
 mod bind;
 mod card;
+mod card_catalog;
+mod card_database;
 mod card_event;
 mod card_game;
 mod card_instance;
+mod card_link;
 mod card_location;
+mod card_modifier;
 mod card_state;
+mod census;
+mod event_sequence;
+mod face_down;
+mod game_context;
 mod game_state;
 mod instance_id;
+mod match_config;
+mod match_state;
 mod opaque_pointer;
+mod pile_id;
 mod player_cards;
 mod player_secret;
+mod projection;
+mod prompt;
+mod reveal_estimate;
+mod secret_mutations;
+mod simulation;
 mod state;
+mod targeting;
+mod trace;
+mod turn;
 mod zone;
+mod zone_set;
+mod zone_sizes;
 
 pub mod error;
+pub mod invariants;
+pub mod prelude;
+pub mod replay;
+pub mod testing;
 
 pub use {
+    action_correlation::ActionCorrelation,
+    action_metrics::ActionMetrics,
+    action_summary::ActionSummary,
     arcadeum::{crypto::Address, Nonce, Player, ID},
     base_card::BaseCard,
     card::Card,
-    card_event::CardEvent,
-    card_game::{CardGame, CardInfo, CardInfoMut, SecretCardsInfo},
+    card_catalog::{CardCatalog, CardDefinition, Catalog, CatalogCard},
+    card_database::CardDatabase,
+    card_event::{CardEvent, RedactedAttachmentPolicy},
+    card_game::{
+        AttachResult, BatchChunk, CardGame, CardInfo, CardInfoMut, CardPredicate, MoveOptions,
+        PlayerSecretMut, Quantifier, SecretCardsInfo, TransformPolicy,
+    },
     card_instance::CardInstance,
+    card_link::{CardLink, LinkPolicy},
     card_location::{CardLocation, ExactCardLocation},
+    card_modifier::{CardModifier, ModifierID, ModifierTarget},
     card_state::CardState,
-    game_state::GameState,
+    census::Census,
+    event_sequence::EventSequence,
+    face_down::FaceDownCard,
+    game_context::GameContext,
+    game_state::{GameResult, GameState, LimboPolicy, PanicPolicy, TokenPolicy, ZoneSnapshot},
     instance_id::InstanceID,
+    match_config::MatchConfig,
+    match_state::MatchState,
     opaque_pointer::OpaquePointer,
+    pile_id::PileID,
     player_cards::PlayerCards,
     player_secret::PlayerSecret,
+    projection::KnownProjection,
+    prompt::{Choice, Prompt, PromptResponse},
+    reveal_estimate::RevealEstimate,
+    simulation::Simulation,
     state::State,
+    targeting::TargetRequirement,
+    turn::Turn,
     zone::Zone,
+    zone_set::ZoneSet,
+    zone_sizes::ZoneSizes,
 };
 
-pub(crate) use game_state::InstanceOrPlayer;
+pub(crate) use game_state::{Bucket, InstanceOrPlayer};
 
 pub use arcadeum;
 
@@ -51,7 +104,14 @@ pub trait Secret<T: BaseCard>: serde::Serialize + serde::de::DeserializeOwned +
     fn attachment(&self, id: &InstanceID, base: T) -> Option<T>;
 
     fn reset_card(&self, id: &InstanceID, parent: T) -> T::CardState;
-} //TODO: add a fn that takes the secret self, and old card info, returns default card info
+
+    /// Returns the state a card should be reset to, given its current state.
+    ///
+    /// Defaults to [Secret::reset_card], ignoring the current state.
+    fn default_card_state(&self, id: &InstanceID, base: T, _state: &T::CardState) -> T::CardState {
+        self.reset_card(id, base)
+    }
+}
 
 pub type Context<S> = arcadeum::store::Context<
     <GameState<S> as arcadeum::store::State>::Secret,
@@ -0,0 +1,96 @@
+use crate::{LimboPolicy, PanicPolicy, RedactedAttachmentPolicy, TokenPolicy, Zone, ZoneSet};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Per-match configuration, stored in `GameState`.
+///
+/// `State::verify` and `State::apply` can read it with `GameState::config`.
+/// The engine enforces everything here except the starting hand size and mulligans, which are up to the game's own rules.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchConfig {
+    /// The number of players, or two if unset, see `MatchConfig::player_count`.
+    pub player_count: Option<usize>,
+
+    /// The number of cards each player starts with in hand.
+    pub starting_hand_size: usize,
+
+    /// The number of mulligans each player may take.
+    pub mulligans: usize,
+
+    /// The inclusive bounds on the number of cards in a deck, checked by `GameState::validate_deck`.
+    pub deck_size: Option<(usize, usize)>,
+
+    /// The most cards a player's zones may hold, checked by `CardGame::move_card`.
+    ///
+    /// Capacities of dust and limbo are ignored, since their total sizes aren't public.
+    pub zone_capacities: Vec<(ZoneSet, usize)>,
+
+    /// Checks moves touching another player's secret with `State::authorize_move`, see `CardGame::acting_player`.
+    pub authorize_moves: bool,
+
+    /// Shuffles a player's deck whenever a card is moved into it.
+    pub shuffle_deck_on_insert: bool,
+
+    /// See `GameState::set_secret_card_padding`.
+    pub secret_card_padding: usize,
+
+    pub redacted_attachment_policy: RedactedAttachmentPolicy,
+
+    pub limbo_policy: LimboPolicy,
+
+    pub token_policy: TokenPolicy,
+
+    pub panic_policy: PanicPolicy,
+
+    /// See `CardGame::set_coalesce_events`.
+    pub coalesce_events: bool,
+
+    /// See `CardGame::set_spectator_events`.
+    pub spectator_events: bool,
+
+    /// Skips the public `CardEvent::NewPointer` events of `CardGame::deck_card`, `CardGame::hand_card` and friends.
+    ///
+    /// Pointers are still created and usable, clients just aren't told where they point.
+    /// Pointers to new secret cards are still logged to their owners.
+    pub suppress_pointer_events: bool,
+
+    /// Lets the game position cards on the field, instead of sorting it with `State::field_order`.
+    ///
+    /// Cards moved to the field go at the end, unless placed with `CardGame::move_card_to_index`,
+    /// and can be moved with `CardGame::reposition_card`.
+    /// No `CardEvent::SortField` events are logged, only `CardEvent::FieldPosition` events.
+    pub positioned_field: bool,
+
+    /// Logs a `CardEvent::ActionResolved` at the end of each action.
+    pub action_summaries: bool,
+
+    /// Logs a `CardEvent::Checksum` at the end of each action.
+    pub checksum_events: bool,
+
+    /// The most cards that chunked batch operations like `CardGame::move_cards_chunk` process per call, or all of them if unset.
+    ///
+    /// Each chunk can be processed in its own action, keeping each message within the store's compute budget.
+    pub batch_chunk_size: Option<usize>,
+}
+
+impl MatchConfig {
+    /// The number of players in a game with this config.
+    pub fn player_count(&self) -> usize {
+        self.player_count.unwrap_or(2)
+    }
+
+    /// The most cards a zone may hold, if it's limited.
+    pub fn zone_capacity(&self, zone: Zone) -> Option<usize> {
+        self.zone_capacities
+            .iter()
+            .filter(|(zones, _)| zones.contains(zone))
+            .map(|(_, capacity)| *capacity)
+            .min()
+    }
+}
@@ -0,0 +1,185 @@
+use {
+    crate::{CardEvent, Context, GameResult, GameState, Player, PlayerSecret, State},
+    std::{future::Future, pin::Pin},
+};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A best-of-`n` match of consecutive games, played over one channel.
+///
+/// When a game ends with `CardGame::end_game`, the next one starts from `State::next_game`,
+/// and each player's secret is carried over with `State::next_game_secret`.
+/// The loser of each game goes first in the next, and a draw keeps the same first player.
+/// In games of more than two players, the player after the winner goes first,
+/// and a concession without a single remaining player counts as a draw.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchState<S: State> {
+    #[serde(bound = "S: State")]
+    game: GameState<S>,
+
+    best_of: usize,
+    /// One per player of `MatchState::game`.
+    wins: Vec<usize>,
+    draws: usize,
+    first_player: Player,
+}
+
+impl<S: State> MatchState<S> {
+    pub fn new(game: GameState<S>, best_of: usize) -> Self {
+        assert!(best_of > 0, "a match needs at least one game");
+
+        let wins = vec![0; game.players().len()];
+
+        Self {
+            game,
+            best_of,
+            wins,
+            draws: 0,
+            first_player: 0,
+        }
+    }
+
+    /// The game in progress, or the last game if the match is over.
+    pub fn game(&self) -> &GameState<S> {
+        &self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut GameState<S> {
+        &mut self.game
+    }
+
+    pub fn best_of(&self) -> usize {
+        self.best_of
+    }
+
+    /// The number of games each player has won.
+    pub fn wins(&self) -> &[usize] {
+        &self.wins
+    }
+
+    pub fn draws(&self) -> usize {
+        self.draws
+    }
+
+    /// The number of games played so far.
+    pub fn games(&self) -> usize {
+        self.wins.iter().sum::<usize>() + self.draws
+    }
+
+    /// The player going first in the current game.
+    pub fn first_player(&self) -> Player {
+        self.first_player
+    }
+
+    /// The winner of the match, once a player has won more than half of its games.
+    pub fn winner(&self) -> Option<Player> {
+        self.game
+            .players()
+            .find(|player| self.wins[usize::from(*player)] > self.best_of / 2)
+    }
+
+    /// Whether the match has a winner, or all of its games were played.
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some() || self.games() >= self.best_of
+    }
+}
+
+impl<S: State> arcadeum::store::State for MatchState<S> {
+    type ID = S::ID;
+    type Nonce = S::Nonce;
+    type Action = S::Action;
+    type Event = CardEvent<S>;
+    type Secret = PlayerSecret<S>;
+
+    fn version() -> &'static [u8] {
+        S::version()
+    }
+
+    fn challenge(address: &crate::Address) -> String {
+        S::challenge(address)
+    }
+
+    fn deserialize(data: &[u8]) -> Result<Self, String> {
+        serde_cbor::from_slice(data).map_err(|error| error.to_string())
+    }
+
+    fn is_serializable(&self) -> bool {
+        true
+    }
+
+    fn serialize(&self) -> Option<Vec<u8>> {
+        Some(serde_cbor::to_vec(self).unwrap())
+    }
+
+    fn verify(&self, player: Option<Player>, action: &Self::Action) -> Result<(), String> {
+        if self.is_over() {
+            return Err("the match is over".to_string());
+        }
+
+        arcadeum::store::State::verify(&self.game, player, action)
+    }
+
+    fn apply(
+        mut self,
+        player: Option<Player>,
+        action: &Self::Action,
+        context: Context<S>,
+    ) -> Pin<Box<dyn Future<Output = (Self, Context<S>)>>> {
+        let action = action.clone();
+
+        Box::pin(async move {
+            let (game, mut context) =
+                arcadeum::store::State::apply(self.game, player, &action, context).await;
+
+            self.game = game;
+
+            if let Some(result) = self.game.result() {
+                let players = self.game.players().end;
+
+                match (result, self.game.winner()) {
+                    (GameResult::Win(winner), _) => {
+                        self.wins[usize::from(winner)] += 1;
+                        self.first_player = (winner + 1) % players;
+                    }
+                    (GameResult::Conceded(loser), Some(winner)) => {
+                        self.wins[usize::from(winner)] += 1;
+                        self.first_player = loser;
+                    }
+                    (GameResult::Conceded(loser), None) => {
+                        self.draws += 1;
+                        self.first_player = loser;
+                    }
+                    (GameResult::Draw, _) => self.draws += 1,
+                }
+
+                if !self.is_over() {
+                    let state = S::next_game(&self.game, self.first_player);
+
+                    self.game = self.game.next_game(state);
+
+                    for player in self.game.players() {
+                        context.mutate_secret(player, |secret| {
+                            let next = S::next_game_secret(secret.secret);
+
+                            *secret.secret = next;
+                        });
+                    }
+
+                    context.log(CardEvent::NextGame {
+                        wins: self.wins.clone(),
+                        draws: self.draws,
+                        first_player: self.first_player,
+                    });
+                }
+            }
+
+            (self, context)
+        })
+    }
+}
@@ -0,0 +1,22 @@
+use std::fmt::{Display, Error, Formatter};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Identifies one of a player's piles, see `Zone::Pile`.
+///
+/// A player's piles are numbered from 0, and exist once a card is moved into them.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(
+    serde::Serialize, serde::Deserialize, Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd,
+)]
+pub struct PileID(pub usize);
+
+impl Display for PileID {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{}", self.0)
+    }
+}
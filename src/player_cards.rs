@@ -1,4 +1,7 @@
-use crate::{InstanceID, Zone};
+use {
+    crate::{InstanceID, PileID, State, Zone},
+    std::collections::BTreeMap,
+};
 
 #[cfg(feature = "bindings")]
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -7,8 +10,8 @@ use wasm_bindgen::prelude::wasm_bindgen;
     feature = "bindings",
     derive(typescript_definitions::TypescriptDefinition)
 )]
-#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
-pub struct PlayerCards {
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct PlayerCards<S: State> {
     pub(crate) deck: usize,
     pub(crate) hand: Vec<Option<InstanceID>>,
     pub(crate) field: Vec<InstanceID>,
@@ -22,10 +25,52 @@ pub struct PlayerCards {
     pub(crate) hero_ability: Vec<InstanceID>,
     pub(crate) boon: Vec<InstanceID>,
 
+    /// The size of each pile.
+    pub(crate) piles: Vec<usize>,
+
     pub(crate) pointers: usize,
+
+    /// Whether any of the player's pointers may point to a destroyed card, see `CardGame::destroy_instance`.
+    #[serde(rename = "destroyedPointers")]
+    pub(crate) destroyed_pointers: bool,
+
+    /// Public resources like mana or health, see `CardGame::adjust_resource`.
+    #[serde(bound = "S: State")]
+    pub(crate) counters: BTreeMap<S::ResourceKind, i32>,
+}
+
+impl<S: State> Default for PlayerCards<S> {
+    fn default() -> Self {
+        Self {
+            deck: Default::default(),
+            hand: Default::default(),
+            field: Default::default(),
+            graveyard: Default::default(),
+            dust: Default::default(),
+            limbo: Default::default(),
+            casting: Default::default(),
+            card_selection: Default::default(),
+            hero_ability: Default::default(),
+            boon: Default::default(),
+            piles: Default::default(),
+            pointers: Default::default(),
+            destroyed_pointers: Default::default(),
+            counters: Default::default(),
+        }
+    }
 }
 
-impl PlayerCards {
+impl<S: State> PlayerCards<S> {
+    /// The player's resources, see `CardGame::adjust_resource`.
+    pub fn counters(&self) -> &BTreeMap<S::ResourceKind, i32> {
+        &self.counters
+    }
+
+    /// The amount of a resource the player has, which is 0 until it's adjusted.
+    pub fn resource(&self, kind: &S::ResourceKind) -> i32 {
+        self.counters.get(kind).copied().unwrap_or_default()
+    }
+
     pub fn deck(&self) -> usize {
         self.deck
     }
@@ -64,6 +109,57 @@ impl PlayerCards {
         &self.boon
     }
 
+    /// The number of cards in a pile, see `Zone::Pile`.
+    pub fn pile(&self, pile: PileID) -> usize {
+        self.piles.get(pile.0).copied().unwrap_or(0)
+    }
+
+    /// The size of each of this player's piles.
+    pub fn piles(&self) -> &Vec<usize> {
+        &self.piles
+    }
+
+    pub(crate) fn grow_pile(&mut self, pile: PileID) {
+        if self.piles.len() <= pile.0 {
+            self.piles.resize(pile.0 + 1, 0);
+        }
+
+        self.piles[pile.0] += 1;
+    }
+
+    /// The IDs of the cards in one of this player's public zones, in order.
+    pub fn zone_cards(&self, zone: Zone) -> Vec<InstanceID> {
+        match zone {
+            Zone::Hand { public: true } => self.hand.iter().flatten().copied().collect(),
+            Zone::Field => self.field.clone(),
+            Zone::Graveyard => self.graveyard.clone(),
+            Zone::Dust { public: true } => self.dust.clone(),
+            Zone::Limbo { public: true } => self.limbo.clone(),
+            Zone::Casting => self.casting.clone(),
+            Zone::HeroAbility => self.hero_ability.clone(),
+            Zone::Boon => self.boon.clone(),
+            _ => panic!("{:?} is not a public zone", zone),
+        }
+    }
+
+    /// The total number of cards in one of this player's zones, counting both public and secret cards.
+    ///
+    /// Returns `None` for dust, limbo and attachments, whose total sizes aren't public.
+    pub fn zone_size(&self, zone: Zone) -> Option<usize> {
+        match zone {
+            Zone::Deck => Some(self.deck),
+            Zone::Hand { .. } => Some(self.hand.len()),
+            Zone::Field => Some(self.field.len()),
+            Zone::Graveyard => Some(self.graveyard.len()),
+            Zone::Casting => Some(self.casting.len()),
+            Zone::CardSelection => Some(self.card_selection),
+            Zone::HeroAbility => Some(self.hero_ability.len()),
+            Zone::Boon => Some(self.boon.len()),
+            Zone::Pile { pile } => Some(self.pile(pile)),
+            Zone::Dust { .. } | Zone::Limbo { .. } | Zone::Attachment { .. } => None,
+        }
+    }
+
     pub fn zone(&self, id: InstanceID) -> Option<Zone> {
         self.location(id).map(|(zone, ..)| zone)
     }
@@ -118,6 +214,21 @@ impl PlayerCards {
             })
     }
 
+    /// Puts a card in a public slot in place of the card there, keeping every other card's index.
+    pub(crate) fn replace(&mut self, zone: Zone, index: usize, id: InstanceID) {
+        match zone {
+            Zone::Hand { public: true } => self.hand[index] = Some(id),
+            Zone::Field => self.field[index] = id,
+            Zone::Graveyard => self.graveyard[index] = id,
+            Zone::Dust { public: true } => self.dust[index] = id,
+            Zone::Limbo { public: true } => self.limbo[index] = id,
+            Zone::Casting => self.casting[index] = id,
+            Zone::HeroAbility => self.hero_ability[index] = id,
+            Zone::Boon => self.boon[index] = id,
+            _ => panic!("{:?} is not a public zone", zone),
+        }
+    }
+
     pub(crate) fn remove_from(&mut self, zone: Zone, index: Option<usize>) {
         match zone {
             Zone::Deck => self.deck -= 1,
@@ -147,6 +258,7 @@ impl PlayerCards {
                     .remove(index.expect("no index for casting zone"));
             }
             Zone::CardSelection => self.card_selection -= 1,
+            Zone::Pile { pile } => self.piles[pile.0] -= 1,
             Zone::HeroAbility => {
                 self.hero_ability
                     .remove(index.expect("no index for hero ability zone"));
@@ -1,10 +1,14 @@
 use {
     crate::{
-        card_state::CardState, error, Card, CardEvent, CardInfo, CardInfoMut, CardInstance,
-        CardLocation, ExactCardLocation, GameState, InstanceID, OpaquePointer, Player, State, Zone,
+        card_modifier::Inverse, card_state::CardState, error, AnySecretData, BaseCard, Card,
+        CardEvent, CardInfo, CardInfoMut, CardInstance, CardLocation, ExactCardLocation, GameState,
+        InstanceID, OpaquePointer, PileID, Player, State, Zone,
     },
     rand::seq::SliceRandom,
-    std::ops::{Deref, DerefMut},
+    std::{
+        collections::VecDeque,
+        ops::{Deref, DerefMut},
+    },
 };
 
 #[cfg(feature = "bindings")]
@@ -30,14 +34,35 @@ pub struct PlayerSecret<S: State> {
     pub(crate) dust: Vec<InstanceID>,
     pub(crate) limbo: Vec<InstanceID>,
     pub(crate) card_selection: Vec<InstanceID>,
+    pub(crate) piles: Vec<Vec<InstanceID>>,
 
-    /// Used for deferred ModifyCard events when attachments are detached.
-    /// Internal use only.
+    /// Events logged to this player at the next `CardGame::flush_deferred_logs`,
+    /// e.g. ModifyCard events when attachments are detached.
+    /// Only added to through `PlayerSecret::defer_log` and `PlayerSecret::with_deferred_log`.
     #[serde(bound = "S: State")]
     pub(crate) deferred_logs: Vec<CardEvent<S>>,
 
     pub(crate) deferred_locations: Vec<(Zone, Option<usize>)>,
 
+    /// How to undo the temporary modifications of secret cards, see `CardGame::modify_card_until`.
+    #[serde(bound = "S: State")]
+    pub(crate) inverses: Vec<Inverse<S>>,
+
+    /// CBOR-encoded values committed to with `CardGame::commit`, by key.
+    pub(crate) commitments: indexmap::IndexMap<String, Vec<u8>>,
+
+    /// The revealed projections of cards in this secret, see `CardGame::reveal_projection`.
+    pub(crate) projections: Vec<(InstanceID, String)>,
+
+    /// The secret as it was before the current action, while the panic policy is `PanicPolicy::Lenient`.
+    #[serde(skip, default = "Option::default")]
+    pub(crate) checkpoint: Option<Box<PlayerSecret<S>>>,
+
+    /// The events logged to this player by each secret mutation of the current action,
+    /// held until the action finishes while the panic policy is `PanicPolicy::Lenient`.
+    #[serde(skip, default = "VecDeque::new")]
+    pub(crate) held_logs: VecDeque<Vec<CardEvent<S>>>,
+
     player: Player,
 }
 
@@ -69,18 +94,146 @@ impl<S: State> PlayerSecret<S> {
             dust: Default::default(),
             limbo: Default::default(),
             card_selection: Default::default(),
+            piles: Default::default(),
 
             deferred_logs: Default::default(),
             deferred_locations: Default::default(),
 
+            inverses: Default::default(),
+
+            commitments: Default::default(),
+
+            projections: Default::default(),
+
+            checkpoint: Default::default(),
+            held_logs: Default::default(),
+
             player,
         }
     }
 
+    /// A guess at a player's secret that is consistent with the public game state, for `Simulation`.
+    ///
+    /// Each of the player's secret deck, hand, card selection and pile positions gets a new card of the base card `base` picks for it,
+    /// using the instance IDs the public state has reserved for the player's secret.
+    /// The secret dust and limbo are empty, since their sizes aren't public, and every pointer points to one of the new cards.
+    pub fn hypothesis(
+        state: &GameState<S>,
+        player: Player,
+        secret: S::Secret,
+        mut base: impl FnMut(Zone, usize) -> S::BaseCard,
+    ) -> Self {
+        let mut hypothesis = Self::new(player, secret);
+
+        let mut ids = state
+            .instances
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| card.player() == Some(player))
+            .map(|(id, _)| InstanceID(id));
+
+        let cards = state.player_cards(player);
+
+        let mut new_card = |zone, index| {
+            let id = ids.next()?;
+            let base = base(zone, index);
+
+            hypothesis.instances.insert(
+                id,
+                CardInstance {
+                    id,
+                    state: base.new_card_state(None),
+                    base,
+                    attachment: None,
+                    token: false,
+                    original_owner: player,
+                },
+            );
+
+            Some(id)
+        };
+
+        let deck: Vec<_> = (0..cards.deck)
+            .filter_map(|index| new_card(Zone::Deck, index))
+            .collect();
+
+        let hand: Vec<_> = cards
+            .hand
+            .iter()
+            .enumerate()
+            .map(|(index, public)| match public {
+                Some(_) => None,
+                None => new_card(Zone::Hand { public: false }, index),
+            })
+            .collect();
+
+        let card_selection: Vec<_> = (0..cards.card_selection)
+            .filter_map(|index| new_card(Zone::CardSelection, index))
+            .collect();
+
+        let piles: Vec<Vec<_>> = cards
+            .piles
+            .iter()
+            .enumerate()
+            .map(|(pile, size)| {
+                (0..*size)
+                    .filter_map(|index| new_card(Zone::Pile { pile: PileID(pile) }, index))
+                    .collect()
+            })
+            .collect();
+
+        hypothesis.deck = deck;
+        hypothesis.hand = hand;
+        hypothesis.card_selection = card_selection;
+        hypothesis.piles = piles;
+
+        let ids: Vec<_> = hypothesis.instances.keys().copied().collect();
+
+        if !ids.is_empty() {
+            hypothesis.pointers = (0..cards.pointers).map(|i| ids[i % ids.len()]).collect();
+        }
+
+        hypothesis
+    }
+
     pub fn player(&self) -> Player {
         self.player
     }
 
+    pub(crate) fn checkpoint(&mut self) {
+        self.checkpoint = None;
+        self.checkpoint = Some(Box::new(self.clone()));
+    }
+
+    /// Panics if there's no checkpoint, e.g. because the secret was serialized since, see `PlayerSecret::checkpoint`.
+    pub(crate) fn restore(&mut self) {
+        let checkpoint = self
+            .checkpoint
+            .take()
+            .unwrap_or_else(|| panic!("player {} secret has no checkpoint", self.player));
+
+        *self = *checkpoint;
+    }
+
+    /// Logs an event to this player at the next `CardGame::flush_deferred_logs`.
+    pub fn defer_log(&mut self, event: CardEvent<S>) {
+        self.deferred_logs.push(event);
+    }
+
+    /// Runs `f` with a logger whose events are deferred like those of `PlayerSecret::defer_log`,
+    /// for secret operations that take a logger.
+    pub fn with_deferred_log<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self, &mut dyn FnMut(CardEvent<S>)) -> T,
+    ) -> T {
+        let mut deferred = Vec::new();
+        let result = f(self, &mut |event| deferred.push(event));
+
+        self.deferred_logs.extend(deferred);
+
+        result
+    }
+
     pub fn deck(&self) -> &Vec<InstanceID> {
         &self.deck
     }
@@ -101,10 +254,52 @@ impl<S: State> PlayerSecret<S> {
         &self.card_selection
     }
 
+    /// The IDs of the cards in a pile, in order, see `Zone::Pile`.
+    pub fn pile(&self, pile: PileID) -> &[InstanceID] {
+        self.piles.get(pile.0).map_or(&[], Vec::as_slice)
+    }
+
+    /// The IDs of the cards in one of this player's secret zones, in order.
+    pub fn zone_cards(&self, zone: Zone) -> Vec<InstanceID> {
+        match zone {
+            Zone::Deck => self.deck.clone(),
+            Zone::Hand { public: false } => self.hand.iter().flatten().copied().collect(),
+            Zone::Dust { public: false } => self.dust.clone(),
+            Zone::Limbo { public: false } => self.limbo.clone(),
+            Zone::CardSelection => self.card_selection.clone(),
+            Zone::Pile { pile } => self.pile(pile).to_vec(),
+            _ => panic!("{:?} is not a secret zone", zone),
+        }
+    }
+
     pub fn instance(&self, card: impl Into<Card>) -> Option<&CardInstance<S>> {
         self.id(card).and_then(|id| self.instances.get(&id))
     }
 
+    /// Gets the attachment of a card in this secret, if any.
+    pub fn attachment_of(&self, card: impl Into<Card>) -> Option<InstanceID> {
+        self.instance(card)?.attachment
+    }
+
+    /// Gets the card in this secret that an attachment is attached to, if any.
+    pub fn parent_of(&self, card: impl Into<Card>) -> Option<InstanceID> {
+        let id = self.id(card)?;
+
+        let mut parents = self.instances.values().filter_map(|instance| {
+            if instance.attachment == Some(id) {
+                Some(instance.id())
+            } else {
+                None
+            }
+        });
+
+        let parent = parents.next();
+
+        assert!(parents.next().is_none());
+
+        parent
+    }
+
     pub fn zone(&self, card: impl Into<Card>) -> Option<Zone> {
         self.location(card).location.map(|(zone, ..)| zone)
     }
@@ -156,26 +351,24 @@ impl<S: State> PlayerSecret<S> {
                             })
                     })
                     .or_else(|| {
-                        let mut parents = self.instances.values().filter_map(|instance| {
-                            if instance.attachment == Some(id) {
-                                Some(instance.id())
-                            } else {
-                                None
-                            }
-                        });
-
-                        parents.next().map(|parent| {
-                            assert!(parents.next().is_none());
-
-                            CardLocation {
-                                player: self.player,
-                                location: Some((
-                                    Zone::Attachment {
-                                        parent: parent.into(),
-                                    },
-                                    None,
-                                )),
-                            }
+                        self.piles.iter().enumerate().find_map(|(pile, ids)| {
+                            ids.iter()
+                                .position(|pile_id| *pile_id == id)
+                                .map(|i| CardLocation {
+                                    player: self.player,
+                                    location: Some((Zone::Pile { pile: PileID(pile) }, Some(i))),
+                                })
+                        })
+                    })
+                    .or_else(|| {
+                        self.parent_of(id).map(|parent| CardLocation {
+                            player: self.player,
+                            location: Some((
+                                Zone::Attachment {
+                                    parent: parent.into(),
+                                },
+                                None,
+                            )),
                         })
                     })
             })
@@ -331,6 +524,7 @@ impl<S: State> PlayerSecret<S> {
                         0,
                     ),
                 },
+                has_attachment: None,
             });
             S::on_attach(parent, &new_attach);
         });
@@ -368,6 +562,48 @@ impl<S: State> PlayerSecret<S> {
         self.pointers.extend(&self.card_selection);
     }
 
+    pub(crate) fn push_pile(&mut self, pile: PileID, id: InstanceID) {
+        if self.piles.len() <= pile.0 {
+            self.piles.resize_with(pile.0 + 1, Default::default);
+        }
+
+        self.piles[pile.0].push(id);
+    }
+
+    /// The deck and pile after moving `out` from the deck to the pile and `into` from the pile to the deck.
+    ///
+    /// Cards that aren't in the deck or the pile are ignored.
+    pub(crate) fn sideboarded(
+        &self,
+        pile: PileID,
+        out: &[InstanceID],
+        into: &[InstanceID],
+    ) -> (Vec<InstanceID>, Vec<InstanceID>) {
+        let mut deck: Vec<_> = self
+            .deck
+            .iter()
+            .filter(|id| !out.contains(id))
+            .copied()
+            .collect();
+        let mut sideboard: Vec<_> = self
+            .pile(pile)
+            .iter()
+            .filter(|id| !into.contains(id))
+            .copied()
+            .collect();
+
+        deck.extend(self.pile(pile).iter().filter(|id| into.contains(id)));
+        sideboard.extend(self.deck.iter().filter(|id| out.contains(id)));
+
+        (deck, sideboard)
+    }
+
+    pub(crate) fn append_pile_to_pointers(&mut self, pile: PileID) {
+        if let Some(ids) = self.piles.get(pile.0) {
+            self.pointers.extend(ids);
+        }
+    }
+
     pub(crate) fn dust_card(
         &mut self,
         card: impl Into<Card>,
@@ -401,6 +637,7 @@ impl<S: State> PlayerSecret<S> {
                 player: self.player,
                 location: (Zone::Dust { public: false }, self.dust.len()),
             },
+            has_attachment: None,
         });
 
         // Finally, move the card from its current zone to Dust.
@@ -436,10 +673,155 @@ impl<S: State> PlayerSecret<S> {
             .instance(card)
             .unwrap_or_else(|| panic!("{:?} vanished", card));
 
-        if !before.eq(after) {
-            log(CardEvent::ModifyCard {
-                instance: after.clone(),
+        if let Some(event) = CardEvent::modify_card(&before, after) {
+            log(event);
+        }
+    }
+
+    /// Like `PlayerSecret::modify_card`, but remembers how to undo the modification
+    /// until `PlayerSecret::expire` is called with `key`.
+    pub(crate) fn modify_card_until(
+        &mut self,
+        card: impl Into<Card>,
+        key: &str,
+        log: &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
+        f: impl FnOnce(CardInfoMut<S>),
+    ) -> Result<(), error::SecretModifyCardError> {
+        let card = card.into();
+
+        let before = self.instance(card).map(|instance| instance.state.clone());
+
+        self.modify_card(card, log, f)?;
+
+        let after = self
+            .instance(card)
+            .unwrap_or_else(|| panic!("{:?} vanished", card));
+
+        let inverse = before.and_then(|before| Inverse::new(key, after.id, &before, &after.state));
+
+        self.inverses.extend(inverse);
+
+        Ok(())
+    }
+
+    /// Undoes the modifications made with `key` to cards in this secret, latest first.
+    pub(crate) fn expire(
+        &mut self,
+        key: &str,
+        log: &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
+    ) {
+        let expired = Inverse::take(&mut self.inverses, |inverse| inverse.key == key);
+
+        for inverse in expired.into_iter().rev() {
+            self.modify_card(inverse.id, &mut *log, |info| {
+                inverse.apply(&mut info.instance.state)
+            })
+            .unwrap_or_else(|_| {
+                panic!(
+                    "player {} secret {:?} not in secret",
+                    self.player, inverse.id
+                )
+            });
+        }
+    }
+
+    /// Removes destroyed cards from this secret's dust, see `CardGame::destroy_instance`.
+    ///
+    /// Pointers to them are invalidated, so moving or revealing from them fails.
+    pub(crate) fn destroy(&mut self, ids: &[InstanceID]) {
+        self.dust.retain(|id| !ids.contains(id));
+
+        for pointer in &mut self.pointers {
+            if ids.contains(pointer) {
+                *pointer = InstanceID::DESTROYED;
+            }
+        }
+
+        for id in ids {
+            self.instances.shift_remove(id);
+        }
+
+        self.inverses.retain(|inverse| !ids.contains(&inverse.id));
+    }
+
+    /// The current values of the revealed projections, or `None` for cards that aren't in this secret anymore.
+    pub(crate) fn projection_values(&self) -> Vec<Option<Vec<u8>>> {
+        self.projections
+            .iter()
+            .map(|(id, projection)| self.instances.get(id)?.state.project(projection))
+            .collect()
+    }
+
+    /// Forgets the revealed projections that don't have values anymore.
+    pub(crate) fn forget_projections(&mut self) {
+        let values = self.projection_values();
+        let mut values = values.iter();
+
+        self.projections
+            .retain(|_| values.next().unwrap().is_some());
+    }
+
+    /// The value committed to with `key`, if it hasn't been revealed yet.
+    pub fn commitment<T: AnySecretData>(&self, key: &str) -> Option<T> {
+        self.commitments.get(key).map(|value| {
+            serde_cbor::from_slice(value).unwrap_or_else(|error| {
+                panic!(
+                    "player {} commitment {:?} has the wrong type: {}",
+                    self.player, key, error
+                )
             })
+        })
+    }
+
+    /// Modifies every card in this secret, including attachments, and logs the changes in a single `CardEvent::ModifyCards`.
+    /// Internal API only.
+    pub(crate) fn modify_all_cards(
+        &mut self,
+        log: &mut dyn FnMut(<GameState<S> as arcadeum::store::State>::Event),
+        f: impl Fn(CardInfoMut<S>),
+    ) {
+        let owner = self.player;
+        let ids: Vec<_> = self.instances.keys().copied().collect();
+        let mut events = vec![];
+
+        for id in ids {
+            let zone = self
+                .zone(id)
+                .unwrap_or_else(|| panic!("player {} secret {:?} has no zone", owner, id));
+
+            let attachment = self.instances[&id].attachment.map(|attachment| {
+                self.instance(attachment)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "player {} secret {:?} attachment {:?} not secret",
+                            owner, id, attachment
+                        )
+                    })
+                    .clone()
+            });
+
+            let instance = self
+                .instances
+                .get_mut(&id)
+                .unwrap_or_else(|| panic!("{:?} vanished", id));
+
+            let before = instance.clone();
+
+            f(CardInfoMut {
+                instance,
+                owner,
+                zone,
+                attachment: attachment.as_ref(),
+                log,
+            });
+
+            if let Some(event) = CardEvent::modify_card(&before, &self.instances[&id]) {
+                events.push(event);
+            }
+        }
+
+        if !events.is_empty() {
+            log(CardEvent::ModifyCards { events });
         }
     }
 
@@ -457,6 +839,10 @@ impl<S: State> PlayerSecret<S> {
         self.card_selection
             .retain(|card_selection_id| *card_selection_id != id);
 
+        for pile in &mut self.piles {
+            pile.retain(|pile_id| *pile_id != id);
+        }
+
         for parent_id in self.instances.keys().copied().collect::<Vec<_>>() {
             if let Some(attach_id) = self.instance(parent_id).unwrap().attachment {
                 if attach_id == id {
@@ -470,6 +856,31 @@ impl<S: State> PlayerSecret<S> {
         }
     }
 
+    /// Swap two cards' slots in this secret's zones, keeping every other card's index.
+    /// A card that isn't in this secret takes the other card's slot.
+    /// Internal API only.
+    pub(crate) fn swap_ids(&mut self, a: Card, b: Card) {
+        let (a, b) = (self.id(a), self.id(b));
+
+        let swap = |id: &mut InstanceID| {
+            if Some(*id) == a {
+                *id = b.expect("A card without an ID in this secret isn't in its zones.");
+            } else if Some(*id) == b {
+                *id = a.expect("A card without an ID in this secret isn't in its zones.");
+            }
+        };
+
+        self.deck.iter_mut().for_each(swap);
+        self.hand.iter_mut().flatten().for_each(swap);
+        self.dust.iter_mut().for_each(swap);
+        self.limbo.iter_mut().for_each(swap);
+        self.card_selection.iter_mut().for_each(swap);
+
+        for pile in &mut self.piles {
+            pile.iter_mut().for_each(swap);
+        }
+    }
+
     pub(crate) fn id(&self, card: impl Into<Card>) -> Option<InstanceID> {
         let card = card.into();
 
@@ -477,7 +888,7 @@ impl<S: State> PlayerSecret<S> {
             Card::ID(id) => Some(id),
             Card::Pointer(OpaquePointer { player, index }) => {
                 if player == self.player {
-                    Some(self.pointers[index])
+                    Some(self.pointers[index]).filter(|id| *id != InstanceID::DESTROYED)
                 } else {
                     None
                 }
@@ -0,0 +1,13 @@
+//! The types most games need, for glob importing.
+//!
+//! The prelude is the supported API.
+//! The crate root still exports every type that appears in a public signature, e.g. `GameContext` through `CardGame::context`,
+//! but types that are only exported from the crate root may change between minor versions.
+//! Internals are sealed where existing games don't depend on them:
+//! `SecretCardsInfo` only exposes its `PlayerSecret` and its own methods, and only `GameState` creates `CardGame`s.
+
+pub use crate::{
+    BaseCard, Card, CardEvent, CardGame, CardInfo, CardInfoMut, CardInstance, CardLocation,
+    CardState, ExactCardLocation, GameState, InstanceID, OpaquePointer, PileID, Player,
+    PlayerCards, PlayerSecret, Secret, SecretCardsInfo, State, Zone,
+};
@@ -0,0 +1,38 @@
+use crate::{Card, Player};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A projection of a secret card that was revealed by `CardGame::reveal_projection`.
+///
+/// The value is kept up to date while the card stays in its player's secret.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct KnownProjection {
+    pub(crate) player: Player,
+    pub(crate) card: Card,
+    pub(crate) projection: String,
+    pub(crate) value: Vec<u8>,
+}
+
+impl KnownProjection {
+    /// The player whose secret holds the card.
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn card(&self) -> Card {
+        self.card
+    }
+
+    pub fn projection(&self) -> &str {
+        &self.projection
+    }
+
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{error, Card, Player};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A decision a player has to make before an action can continue, see `CardGame::prompt`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct Prompt {
+    pub(crate) player: Player,
+    pub(crate) key: String,
+    pub(crate) choice: Choice,
+}
+
+impl Prompt {
+    /// The player who has to respond.
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// Identifies what the prompt is for, so `State::resume` knows how to continue.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn choice(&self) -> &Choice {
+        &self.choice
+    }
+
+    /// Checks that a response answers the prompt.
+    pub fn check(&self, response: &PromptResponse) -> Result<(), error::PromptError> {
+        match (&self.choice, response) {
+            (Choice::Cards { cards, count }, PromptResponse::Cards(indices)) => {
+                if indices.len() != *count {
+                    return Err(error::PromptError::WrongCount {
+                        count: indices.len(),
+                        expected: *count,
+                    });
+                }
+
+                for (i, index) in indices.iter().enumerate() {
+                    if *index >= cards.len() || indices[..i].contains(index) {
+                        return Err(error::PromptError::InvalidCard { index: *index });
+                    }
+                }
+
+                Ok(())
+            }
+            (Choice::Number { min, max }, PromptResponse::Number(number)) => {
+                if number < min || number > max {
+                    Err(error::PromptError::OutOfRange {
+                        number: *number,
+                        min: *min,
+                        max: *max,
+                    })
+                } else {
+                    Ok(())
+                }
+            }
+            (Choice::YesNo, PromptResponse::YesNo(..)) => Ok(()),
+            _ => Err(error::PromptError::WrongKind),
+        }
+    }
+
+    /// The cards chosen by a response to a `Choice::Cards` prompt, in the order they were chosen.
+    pub fn chosen_cards(&self, response: &PromptResponse) -> Vec<Card> {
+        match (&self.choice, response) {
+            (Choice::Cards { cards, .. }, PromptResponse::Cards(indices)) => {
+                indices.iter().map(|index| cards[*index]).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "event-eq")]
+impl PartialEq for Prompt {
+    fn eq(&self, other: &Self) -> bool {
+        self.player == other.player
+            && self.key == other.key
+            && match (&self.choice, &other.choice) {
+                (
+                    Choice::Cards { cards, count },
+                    Choice::Cards {
+                        cards: other_cards,
+                        count: other_count,
+                    },
+                ) => {
+                    count == other_count
+                        && cards.len() == other_cards.len()
+                        && cards
+                            .iter()
+                            .zip(other_cards)
+                            .all(|(card, other_card)| card.eq(*other_card).unwrap_or(false))
+                }
+                (
+                    Choice::Number { min, max },
+                    Choice::Number {
+                        min: other_min,
+                        max: other_max,
+                    },
+                ) => min == other_min && max == other_max,
+                (Choice::YesNo, Choice::YesNo) => true,
+                _ => false,
+            }
+    }
+}
+
+/// The kinds of decisions a `Prompt` can ask for.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub enum Choice {
+    /// Choose exactly `count` distinct cards, usually opaque pointers.
+    Cards {
+        cards: Vec<Card>,
+        count: usize,
+    },
+
+    /// Choose a number between `min` and `max`, inclusive.
+    Number {
+        min: usize,
+        max: usize,
+    },
+
+    YesNo,
+}
+
+impl Choice {
+    /// Checks that at least one response satisfies the choice.
+    pub fn check(&self) -> Result<(), error::PromptError> {
+        match self {
+            Self::Cards { cards, count } if *count > cards.len() => {
+                Err(error::PromptError::TooFewCards {
+                    count: *count,
+                    cards: cards.len(),
+                })
+            }
+            Self::Number { min, max } if min > max => Err(error::PromptError::EmptyRange {
+                min: *min,
+                max: *max,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A player's answer to a `Prompt`, see `State::prompt_response`.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Eq, PartialEq)]
+pub enum PromptResponse {
+    /// Indices into the cards of a `Choice::Cards`.
+    Cards(Vec<usize>),
+
+    Number(usize),
+    YesNo(bool),
+}
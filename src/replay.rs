@@ -0,0 +1,622 @@
+//! Client-side helpers for consuming logged events, and test helpers for recording, replaying and rewinding games.
+
+use {
+    crate::{error, CardEvent, GameState, Player, PlayerSecret, State},
+    arcadeum::store::Tester,
+    rand::RngCore,
+    std::{cell::RefCell, collections::BTreeMap, ops::Deref, rc::Rc},
+};
+
+/// Restores the authoritative order of events logged with strict event ordering enabled.
+///
+/// Public and secret events are delivered through different channels, so a client may observe
+/// them interleaved differently than they were logged.
+/// Collect the events received for an action and pass them here to recover the logged order.
+/// The `CardEvent::Sequenced` wrappers are removed.
+///
+/// Events without an `EventSequence` keep their relative order and come first.
+pub fn order_events<S: State>(events: impl IntoIterator<Item = CardEvent<S>>) -> Vec<CardEvent<S>> {
+    let mut events: Vec<_> = events.into_iter().collect();
+
+    events.sort_by_key(CardEvent::sequence);
+
+    events.into_iter().map(CardEvent::unsequenced).collect()
+}
+
+/// A recorded game: the players' starting secrets, and every action applied since, with the events each logged
+/// and the `Transcript` of the randomness it drew and the values it revealed.
+///
+/// The events are kept serialized, and are only used to detect desyncs.
+/// See `Replay::apply_to`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct Replay<S: State> {
+    #[serde(bound = "S: State")]
+    secrets: Vec<PlayerSecret<S>>,
+
+    #[serde(bound = "S: State")]
+    steps: Vec<ReplayStep<S>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+struct ReplayStep<S: State> {
+    player: Option<Player>,
+
+    #[serde(bound = "S: State")]
+    action: S::Action,
+
+    /// Each logged event's recipient and serialized event, if recorded.
+    events: Option<SerializedEvents>,
+
+    /// The randomness the action drew and the values it revealed, if recorded.
+    #[serde(default)]
+    transcript: Option<Transcript>,
+}
+
+/// The randomness an action drew and the values it revealed, in order, see `GameState::set_transcript_recording`.
+///
+/// Replaying an action from its transcript draws the same randomness, so games that use randomness replay deterministically.
+/// Recording doesn't change the randomness an action draws.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    /// The bytes drawn by each use of the shared randomness.
+    random: Vec<Vec<u8>>,
+
+    /// The bytes drawn by each secret mutation that used a player's secret randomness.
+    secret_random: BTreeMap<Player, Vec<Vec<u8>>>,
+
+    /// Each value revealed from each player's secret, serialized as CBOR.
+    reveals: BTreeMap<Player, Vec<Vec<u8>>>,
+}
+
+impl Transcript {
+    pub fn is_empty(&self) -> bool {
+        self.random.is_empty() && self.secret_random.is_empty() && self.reveals.is_empty()
+    }
+
+    /// The draws from the shared randomness (`None`), or from a player's secret randomness.
+    fn draws(&mut self, player: Option<Player>) -> &mut Vec<Vec<u8>> {
+        match player {
+            None => &mut self.random,
+            Some(player) => self.secret_random.entry(player).or_default(),
+        }
+    }
+}
+
+/// The transcript of the action being applied, shared by every clone of a `GameState`.
+///
+/// A `Tester` applies each action once per client, so every run reads the entries the first run recorded.
+#[derive(Debug, Default)]
+pub(crate) struct Transcriber {
+    transcript: Transcript,
+
+    /// Whether the transcript is being replayed, so it's never extended.
+    replaying: bool,
+
+    /// Whether a run drew more than the replayed transcript holds, or revealed a different value.
+    diverged: bool,
+}
+
+impl Transcriber {
+    pub(crate) fn recording() -> Self {
+        Default::default()
+    }
+
+    /// Takes the recorded transcript, starting a new one.
+    pub(crate) fn take(&mut self) -> Transcript {
+        std::mem::take(&mut self.transcript)
+    }
+
+    /// Replays `transcript`, or records a new one if there isn't one.
+    pub(crate) fn replay(&mut self, transcript: Option<Transcript>) {
+        self.replaying = transcript.is_some();
+        self.transcript = transcript.unwrap_or_default();
+        self.diverged = false;
+    }
+
+    pub(crate) fn diverged(&self) -> bool {
+        self.diverged
+    }
+
+    fn entry<T: Clone>(
+        &mut self,
+        entries: impl FnOnce(&mut Transcript) -> &mut Vec<T>,
+        index: usize,
+        draw: impl FnOnce() -> T,
+    ) -> T {
+        let replaying = self.replaying;
+        let entries = entries(&mut self.transcript);
+
+        if let Some(entry) = entries.get(index) {
+            return entry.clone();
+        }
+
+        let entry = draw();
+
+        if replaying {
+            self.diverged = true;
+        } else if index == entries.len() {
+            entries.push(entry.clone());
+        }
+
+        entry
+    }
+}
+
+/// One run of an action against the shared `Transcriber`, tracking how far into the transcript it is.
+#[derive(Debug)]
+pub(crate) struct TranscriptRun {
+    transcriber: Rc<RefCell<Transcriber>>,
+
+    /// The number of draws so far from the shared randomness (`None`) and from each player's secret randomness.
+    next_draw: RefCell<BTreeMap<Option<Player>, usize>>,
+
+    next_reveal: RefCell<BTreeMap<Player, usize>>,
+}
+
+impl TranscriptRun {
+    pub(crate) fn new(transcriber: Rc<RefCell<Transcriber>>) -> Self {
+        Self {
+            transcriber,
+            next_draw: Default::default(),
+            next_reveal: Default::default(),
+        }
+    }
+
+    /// Wraps a draw from the shared randomness, so it's recorded in, or replayed from, the transcript.
+    pub(crate) fn random<R: RngCore>(self: Rc<Self>, random: R) -> TranscribedRng<Rc<Self>, R> {
+        TranscribedRng {
+            run: self,
+            player: None,
+            source: random,
+            draw: None,
+        }
+    }
+
+    /// Wraps a player's secret randomness, so it's only drawn through the transcript.
+    pub(crate) fn secret_random<'a>(
+        &'a self,
+        player: Player,
+        random: &'a mut dyn RngCore,
+    ) -> TranscribedRng<&'a Self, &'a mut dyn RngCore> {
+        TranscribedRng {
+            run: self,
+            player: Some(player),
+            source: random,
+            draw: None,
+        }
+    }
+
+    /// Records a value revealed from a player's secret, or checks it against the replayed transcript.
+    pub(crate) fn reveal(&self, player: Player, value: &impl serde::Serialize) {
+        let mut next_reveal = self.next_reveal.borrow_mut();
+        let index = next_reveal.entry(player).or_default();
+
+        let value = serde_cbor::to_vec(value).unwrap_or_default();
+        let mut transcriber = self.transcriber.borrow_mut();
+
+        let recorded = transcriber.entry(
+            |transcript| transcript.reveals.entry(player).or_default(),
+            *index,
+            || value.clone(),
+        );
+
+        if recorded != value {
+            transcriber.diverged = true;
+        }
+
+        *index += 1;
+    }
+}
+
+/// The shared randomness (`player` is `None`) or a player's secret randomness, drawn through the transcript.
+///
+/// While recording, every value is drawn from `source` as is, so recording doesn't change the outcome of the action,
+/// and the drawn bytes are recorded.
+/// While replaying, the recorded bytes are returned instead.
+pub(crate) struct TranscribedRng<Run, R> {
+    run: Run,
+    player: Option<Player>,
+    source: R,
+
+    /// The index of this draw in the transcript, and the number of bytes drawn so far, once it's first used.
+    draw: Option<(usize, usize)>,
+}
+
+impl<Run: Deref<Target = TranscriptRun>, R: RngCore> TranscribedRng<Run, R> {
+    /// Fills `dest` from the transcript if it's being replayed, or with `draw` from the source, recording it otherwise.
+    fn transcribe(&mut self, dest: &mut [u8], draw: impl FnOnce(&mut R, &mut [u8])) {
+        let run = &*self.run;
+        let player = self.player;
+
+        let (index, position) = *self.draw.get_or_insert_with(|| {
+            let mut next_draw = run.next_draw.borrow_mut();
+            let index = next_draw.entry(player).or_default();
+
+            *index += 1;
+
+            (*index - 1, 0)
+        });
+
+        let end = position + dest.len();
+        self.draw = Some((index, end));
+
+        let mut transcriber = run.transcriber.borrow_mut();
+
+        let Transcriber {
+            transcript,
+            replaying,
+            diverged,
+        } = &mut *transcriber;
+
+        let draws = transcript.draws(player);
+
+        if *replaying {
+            match draws.get(index).and_then(|bytes| bytes.get(position..end)) {
+                Some(bytes) => dest.copy_from_slice(bytes),
+                None => {
+                    draw(&mut self.source, dest);
+
+                    *diverged = true;
+                }
+            }
+        } else {
+            draw(&mut self.source, dest);
+
+            if index == draws.len() {
+                draws.push(Vec::new());
+            }
+
+            // Only the first run of the action records, later runs draw the same values.
+            if let Some(bytes) = draws.get_mut(index).filter(|bytes| bytes.len() == position) {
+                bytes.extend_from_slice(dest);
+            }
+        }
+    }
+}
+
+impl<Run: Deref<Target = TranscriptRun>, R: RngCore> RngCore for TranscribedRng<Run, R> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0; 4];
+
+        self.transcribe(&mut bytes, |source, bytes| {
+            bytes.copy_from_slice(&source.next_u32().to_le_bytes())
+        });
+
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0; 8];
+
+        self.transcribe(&mut bytes, |source, bytes| {
+            bytes.copy_from_slice(&source.next_u64().to_le_bytes())
+        });
+
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.transcribe(dest, |source, dest| source.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+
+        Ok(())
+    }
+}
+
+impl<S: State> Replay<S> {
+    pub fn new(secrets: Vec<PlayerSecret<S>>) -> Self {
+        Self {
+            secrets,
+            steps: Default::default(),
+        }
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        player: Option<Player>,
+        action: S::Action,
+        transcript: Option<Transcript>,
+    ) {
+        self.steps.push(ReplayStep {
+            player,
+            action,
+            events: None,
+            transcript,
+        });
+    }
+
+    /// The number of recorded actions.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Re-applies the recorded actions to `state`, which should be the state the recording started from.
+    ///
+    /// Actions with a recorded transcript draw their randomness from it, and their reveals, which are re-derived
+    /// from the recorded secrets, are checked against it.
+    /// Actions without one draw fresh randomness from an `arcadeum::store::Tester`.
+    /// Returns the events logged by each action, with their recipients: `None` for the owner, `Some(player)` for a player.
+    /// Fails with `error::ReplayError::Desync` at the first action whose events or reveals differ from the recorded ones.
+    pub fn apply_to(&self, mut state: GameState<S>) -> Result<Vec<Events<S>>, error::ReplayError> {
+        let logs = Rc::new(RefCell::new(Vec::new()));
+        let logs_clone = logs.clone();
+
+        let transcriber = Rc::new(RefCell::new(Transcriber::recording()));
+        state.transcriber = Some(transcriber.clone());
+
+        let mut tester = Tester::new(
+            state,
+            tester_secrets(self.secrets.clone()).map_err(|error| error::ReplayError::Action {
+                index: 0,
+                error: error.into(),
+            })?,
+            Default::default(),
+            |_, _, _| {},
+            move |player, _, event| logs_clone.borrow_mut().push((player, event)),
+            false,
+        )
+        .map_err(|error| error::ReplayError::Action {
+            index: 0,
+            error: error.into(),
+        })?;
+
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(index, step)| {
+                transcriber.borrow_mut().replay(step.transcript.clone());
+
+                tester.apply(step.player, &step.action).map_err(|error| {
+                    error::ReplayError::Action {
+                        index,
+                        error: error.into(),
+                    }
+                })?;
+
+                let events = logs.replace(Vec::new());
+
+                if transcriber.borrow().diverged() {
+                    return Err(error::ReplayError::Desync { index });
+                }
+
+                if let Some(recorded) = &step.events {
+                    if *recorded != serialize_events(&events)? {
+                        return Err(error::ReplayError::Desync { index });
+                    }
+                }
+
+                Ok(events)
+            })
+            .collect()
+    }
+}
+
+/// Records a `Replay` of a game as it's played.
+///
+/// Call `ReplayRecorder::record_action` before applying each action, `ReplayRecorder::record_event` from the logger,
+/// and `ReplayRecorder::record_transcript` after applying each action if the game records transcripts.
+/// Events logged before the first action aren't recorded.
+pub struct ReplayRecorder<S: State> {
+    replay: Replay<S>,
+    record_events: bool,
+}
+
+impl<S: State> ReplayRecorder<S> {
+    /// Starts recording from the players' current secrets.
+    ///
+    /// If `record_events` is false, only the actions are recorded, and the replay can't detect desyncs.
+    pub fn new(secrets: Vec<PlayerSecret<S>>, record_events: bool) -> Self {
+        Self {
+            replay: Replay::new(secrets),
+            record_events,
+        }
+    }
+
+    pub fn record_action(&mut self, player: Option<Player>, action: S::Action) {
+        self.replay.steps.push(ReplayStep {
+            player,
+            action,
+            events: self.record_events.then(Vec::new),
+            transcript: None,
+        });
+    }
+
+    /// Fails if the event can't be serialized, e.g. because of its `CardEvent::GameEvent`.
+    pub fn record_event(
+        &mut self,
+        player: Option<Player>,
+        event: &CardEvent<S>,
+    ) -> Result<(), error::ReplayError> {
+        if let Some(ReplayStep {
+            events: Some(events),
+            ..
+        }) = self.replay.steps.last_mut()
+        {
+            events.push((player, serialize_event(event)?));
+        }
+
+        Ok(())
+    }
+
+    /// Records the transcript of the last action, taken from `state`, if it records transcripts.
+    ///
+    /// See `GameState::set_transcript_recording`.
+    pub fn record_transcript(&mut self, state: &GameState<S>) {
+        let transcript = state.take_transcript();
+
+        if let Some(step) = self.replay.steps.last_mut() {
+            step.transcript = transcript;
+        }
+    }
+
+    pub fn replay(&self) -> &Replay<S> {
+        &self.replay
+    }
+
+    pub fn into_replay(self) -> Replay<S> {
+        self.replay
+    }
+}
+
+/// A `Tester`-driven game that can be rewound to before any applied action, and resumed with different actions.
+///
+/// Keeps a snapshot of the game state and secrets before every action, and records each action's transcript.
+pub struct Timeline<S: State> {
+    tester: Tester<GameState<S>>,
+    logger: Logger<S>,
+    snapshots: Vec<(GameState<S>, Vec<PlayerSecret<S>>)>,
+    actions: Vec<(Option<Player>, S::Action)>,
+    transcripts: Vec<Option<Transcript>>,
+}
+
+type Logger<S> = Rc<RefCell<dyn FnMut(Option<Player>, CardEvent<S>)>>;
+
+type Events<S> = Vec<(Option<Player>, CardEvent<S>)>;
+
+type SerializedEvents = Vec<(Option<Player>, Vec<u8>)>;
+
+impl<S: State> Timeline<S> {
+    /// Starts a game, logging events to `log` as they would be logged by a `Tester`.
+    pub fn new(
+        mut state: GameState<S>,
+        secrets: Vec<PlayerSecret<S>>,
+        log: impl FnMut(Option<Player>, CardEvent<S>) + 'static,
+    ) -> Result<Self, String> {
+        let logger: Logger<S> = Rc::new(RefCell::new(log));
+
+        state.set_transcript_recording(true);
+
+        Ok(Self {
+            tester: new_tester(state, secrets, logger.clone())?,
+            logger,
+            snapshots: Default::default(),
+            actions: Default::default(),
+            transcripts: Default::default(),
+        })
+    }
+
+    pub fn state(&self) -> &GameState<S> {
+        self.tester.state()
+    }
+
+    pub fn secret(&self, player: Player) -> PlayerSecret<S> {
+        self.tester.secret(player)
+    }
+
+    /// Every player's secret, in player order.
+    pub fn secrets(&self) -> Vec<PlayerSecret<S>> {
+        self.state()
+            .players()
+            .map(|player| self.secret(player))
+            .collect()
+    }
+
+    /// The actions applied so far, in order.
+    pub fn actions(&self) -> &[(Option<Player>, S::Action)] {
+        &self.actions
+    }
+
+    pub fn apply(&mut self, player: Option<Player>, action: S::Action) -> Result<(), String> {
+        let snapshot = (self.tester.state().clone(), self.secrets());
+
+        let result = self.tester.apply(player, &action);
+
+        // A failed action's transcript is dropped, so it doesn't leak into the next action's.
+        let transcript = self.tester.state().take_transcript();
+
+        result?;
+
+        self.snapshots.push(snapshot);
+        self.actions.push((player, action));
+        self.transcripts.push(transcript);
+
+        Ok(())
+    }
+
+    /// Rewinds the game to before action `index`, discarding it and every later action.
+    ///
+    /// Events aren't logged again, and the discarded events aren't retracted.
+    pub fn rewind(&mut self, index: usize) -> Result<(), String> {
+        if index >= self.actions.len() {
+            return Err(format!(
+                "cannot rewind to action {} of {}",
+                index,
+                self.actions.len()
+            ));
+        }
+
+        let (state, secrets) = self.snapshots[index].clone();
+
+        self.tester = new_tester(state, secrets, self.logger.clone())?;
+        self.snapshots.truncate(index);
+        self.actions.truncate(index);
+        self.transcripts.truncate(index);
+
+        Ok(())
+    }
+
+    /// A replay of the actions applied so far, from the secrets the game started with.
+    pub fn replay(&self) -> Replay<S> {
+        let secrets = match self.snapshots.first() {
+            Some((_, secrets)) => secrets.clone(),
+            None => self.secrets(),
+        };
+
+        let mut replay = Replay::new(secrets);
+
+        for ((player, action), transcript) in self.actions.iter().zip(&self.transcripts) {
+            replay.push(*player, action.clone(), transcript.clone());
+        }
+
+        replay
+    }
+}
+
+fn new_tester<S: State>(
+    state: GameState<S>,
+    secrets: Vec<PlayerSecret<S>>,
+    logger: Logger<S>,
+) -> Result<Tester<GameState<S>>, String> {
+    Tester::new(
+        state,
+        tester_secrets(secrets)?,
+        Default::default(),
+        |_, _, _| {},
+        move |player, _, event| (logger.borrow_mut())(player, event),
+        false,
+    )
+}
+
+/// The secrets for an `arcadeum::store::Tester`, which only runs two-player games.
+pub(crate) fn tester_secrets<S: State>(
+    secrets: Vec<PlayerSecret<S>>,
+) -> Result<[PlayerSecret<S>; 2], String> {
+    let count = secrets.len();
+
+    secrets.try_into().map_err(|_| {
+        format!(
+            "{} secrets were given, but testers only run two players",
+            count
+        )
+    })
+}
+
+fn serialize_events<S: State>(
+    events: &[(Option<Player>, CardEvent<S>)],
+) -> Result<SerializedEvents, error::ReplayError> {
+    events
+        .iter()
+        .map(|(player, event)| Ok((*player, serialize_event(event)?)))
+        .collect()
+}
+
+fn serialize_event<S: State>(event: &CardEvent<S>) -> Result<Vec<u8>, error::ReplayError> {
+    serde_cbor::to_vec(event).map_err(|error| error::ReplayError::Serialize { error })
+}
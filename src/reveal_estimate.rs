@@ -0,0 +1,34 @@
+use crate::{testing::Reveal, Player};
+
+/// The reveals a card effect needs, per player, see `Simulation::estimate_reveals`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RevealEstimate {
+    reveals: Vec<usize>,
+}
+
+impl RevealEstimate {
+    pub(crate) fn new(players: usize, reveals: &[Reveal]) -> Self {
+        let mut estimate = Self {
+            reveals: vec![0; players],
+        };
+
+        for reveal in reveals {
+            estimate.reveals[usize::from(reveal.player)] += 1;
+        }
+
+        estimate
+    }
+
+    /// The number of reveals of the player's secret.
+    pub fn reveals(&self, player: Player) -> usize {
+        self.reveals
+            .get(usize::from(player))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The number of reveals across all players.
+    pub fn total(&self) -> usize {
+        self.reveals.iter().sum()
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{
+    card_game::MutateSecretInfo, card_modifier::Inverse, Card, CardInstance, GameContext,
+    InstanceID, Player, State, Zone,
+};
+
+/// A mutation of a player's secret queued by an engine operation, see `SecretMutations`.
+///
+/// Mutations are plain data rather than closures, so queueing one doesn't allocate.
+pub(crate) enum SecretMutation<S: State> {
+    /// Removes a card from the secret dust.
+    RemoveDust(Card),
+
+    /// Appends a card to a secret zone.
+    Push { card: Card, zone: Zone },
+
+    /// Inserts a card into the hand at an index, or a placeholder for a public card.
+    InsertHand {
+        index: usize,
+        id: Option<InstanceID>,
+    },
+
+    /// Removes the card or placeholder at an index of the hand.
+    RemoveHand(usize),
+
+    /// Swaps two cards' IDs, see `PlayerSecret::swap_ids`.
+    SwapIds(Card, Card),
+
+    /// Takes a card's ID out of any zones in the secret, see `PlayerSecret::remove_id`.
+    RemoveId(InstanceID),
+
+    /// Removes a card leaving the secret from its zone and its parent,
+    /// then removes it, its attachment and their inverses.
+    Take(InstanceID),
+
+    /// Removes a card, its attachment and their inverses.
+    RemoveInstance {
+        id: InstanceID,
+        attachment: Option<InstanceID>,
+    },
+
+    /// Adds a card instance.
+    InsertInstance(CardInstance<S>),
+
+    /// Adds inverses following their cards into the secret.
+    ExtendInverses(Vec<Inverse<S>>),
+}
+
+impl<S: State> SecretMutation<S> {
+    fn apply(&self, secret: &mut MutateSecretInfo<S>) {
+        match self {
+            Self::RemoveDust(card) => {
+                let id = secret.id(*card).expect("The card is in secret dust.");
+
+                secret.dust.retain(|dust_id| *dust_id != id);
+            }
+            Self::Push { card, zone } => {
+                let id = secret.id(*card).expect("The card is in this secret.");
+
+                match *zone {
+                    Zone::Deck => secret.deck.push(id),
+                    Zone::Hand { .. } => secret.hand.push(Some(id)),
+                    Zone::Limbo { .. } => secret.limbo.push(id),
+                    Zone::CardSelection => secret.card_selection.push(id),
+                    Zone::Pile { pile } => secret.push_pile(pile, id),
+                    Zone::Dust { .. } => secret.dust.push(id),
+                    zone => unreachable!("{:?} is a secret zone", zone),
+                }
+            }
+            Self::InsertHand { index, id } => secret.hand.insert(*index, *id),
+            Self::RemoveHand(index) => {
+                secret.hand.remove(*index);
+            }
+            Self::SwapIds(a, b) => secret.swap_ids(*a, *b),
+            Self::RemoveId(id) => secret.secret.remove_id(secret.log, *id),
+            Self::Take(id) => {
+                let id = *id;
+
+                // find what collection id is in and remove it
+                secret.deck.retain(|i| *i != id);
+                secret.hand.retain(|i| *i != Some(id));
+                secret.limbo.retain(|i| *i != id);
+                secret.card_selection.retain(|i| *i != id);
+                secret.dust.retain(|i| *i != id);
+                for pile in &mut secret.piles {
+                    pile.retain(|i| *i != id);
+                }
+
+                let parent_id = secret
+                    .instances
+                    .values()
+                    .find(|c| c.attachment == Some(id))
+                    .map(|c| c.id);
+                // We're removing the attachment from a card in the secret
+                if let Some(parent_id) = parent_id {
+                    let attach_clone = secret.instance(id).unwrap().clone();
+                    secret.with_deferred_log(|secret, log| {
+                        secret.modify_card_internal(parent_id, log, |parent, _| {
+                            parent.attachment = None;
+                            S::on_detach(parent, &attach_clone);
+                        });
+                    });
+                }
+                let attachment = secret.instance(id).unwrap().attachment;
+
+                Self::RemoveInstance { id, attachment }.apply(secret);
+            }
+            Self::RemoveInstance { id, attachment } => {
+                secret
+                    .inverses
+                    .retain(|inverse| inverse.id != *id && Some(inverse.id) != *attachment);
+
+                // We're removing a card with an attachment from the secret
+                if let Some(attachment) = attachment {
+                    secret.instances.remove(attachment);
+                }
+
+                secret.instances.remove(id);
+            }
+            Self::InsertInstance(instance) => {
+                secret.instances.insert(instance.id, instance.clone());
+            }
+            Self::ExtendInverses(inverses) => secret.inverses.extend(inverses.iter().cloned()),
+        }
+    }
+}
+
+/// Queues secret mutations within a single engine operation.
+///
+/// Consecutive mutations of the same player's secret are coalesced into a single
+/// `mutate_secret` call when the queue is flushed, preserving their relative order.
+/// Each `mutate_secret` call is counted in `ActionMetrics::mutations`, so the savings show there,
+/// e.g. moving a card and its attachment into a secret costs no more than moving the card alone.
+pub(crate) struct SecretMutations<S: State> {
+    pending: Vec<(Player, SecretMutation<S>)>,
+}
+
+impl<S: State> SecretMutations<S> {
+    pub fn new() -> Self {
+        Self {
+            pending: Default::default(),
+        }
+    }
+
+    pub fn push(&mut self, player: Player, mutation: SecretMutation<S>) {
+        self.pending.push((player, mutation));
+    }
+
+    pub fn flush(self, context: &mut GameContext<S>) {
+        let mut pending = self.pending.into_iter().peekable();
+
+        while let Some((player, mutation)) = pending.next() {
+            let mut batch = vec![mutation];
+
+            while let Some((_, mutation)) = pending.next_if(|(next, _)| *next == player) {
+                batch.push(mutation);
+            }
+
+            context.mutate_secret(player, move |mut secret| {
+                for mutation in &batch {
+                    mutation.apply(&mut secret);
+                }
+            });
+        }
+    }
+}
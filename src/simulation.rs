@@ -0,0 +1,98 @@
+use {
+    crate::{
+        replay::tester_secrets, ActionMetrics, GameState, Player, PlayerSecret, RevealEstimate,
+        State,
+    },
+    arcadeum::store::Tester,
+};
+
+/// A local fork of a game, for bots to evaluate candidate actions without affecting the real match.
+///
+/// Reveals are answered from the secrets the simulation was given,
+/// e.g. the bot's own secret and a hypothesis of its opponent's from `PlayerSecret::hypothesis`.
+/// Events aren't logged anywhere.
+pub struct Simulation<S: State> {
+    tester: Tester<GameState<S>>,
+}
+
+impl<S: State> Simulation<S> {
+    pub fn new(state: GameState<S>, secrets: Vec<PlayerSecret<S>>) -> Result<Self, String> {
+        Ok(Self {
+            tester: Tester::new(
+                state,
+                tester_secrets(secrets)?,
+                Default::default(),
+                |_, _, _| {},
+                |_, _, _| {},
+                false,
+            )?,
+        })
+    }
+
+    pub fn state(&self) -> &GameState<S> {
+        self.tester.state()
+    }
+
+    pub fn secret(&self, player: Player) -> PlayerSecret<S> {
+        self.tester.secret(player)
+    }
+
+    pub fn apply(&mut self, player: Option<Player>, action: &S::Action) -> Result<(), String> {
+        self.tester.apply(player, action).map(|_| ())
+    }
+
+    /// Copies the simulation, so several candidate actions can be tried from the same position.
+    pub fn fork(&self) -> Result<Self, String> {
+        self.fork_with(self.state().clone())
+    }
+
+    fn fork_with(&self, state: GameState<S>) -> Result<Self, String> {
+        let secrets = self
+            .state()
+            .players()
+            .map(|player| self.secret(player))
+            .collect();
+
+        Self::new(state, secrets)
+    }
+
+    /// How many reveals and secret mutations an action performs, without applying it to the simulation.
+    ///
+    /// The action's real effect is run on a fork, so the count can't drift from what the action does.
+    pub fn measure(
+        &self,
+        player: Option<Player>,
+        action: &S::Action,
+    ) -> Result<ActionMetrics, String> {
+        let mut fork = self.fork()?;
+
+        fork.apply(player, action)?;
+
+        Ok(fork.state().last_action_metrics())
+    }
+
+    /// Estimates the reveals of each player's secret that a card effect needs, e.g. to warn designers about reveal-heavy effects,
+    /// without applying it to the simulation.
+    ///
+    /// The action carrying the effect is run through the real `CardGame` operations on a fork,
+    /// where reveals are answered from the simulation's secrets and recorded instead of being sent to other players.
+    /// So the estimate follows cards that change buckets during the effect, and can't drift from what the effect does,
+    /// but it's only exact for the secrets the simulation was given.
+    pub fn estimate_reveals(
+        &self,
+        player: Option<Player>,
+        action: &S::Action,
+    ) -> Result<RevealEstimate, String> {
+        let mut state = self.state().clone();
+        state.set_reveal_recording(true);
+
+        let mut fork = self.fork_with(state)?;
+
+        fork.apply(player, action)?;
+
+        Ok(RevealEstimate::new(
+            fork.state().all_player_cards().len(),
+            fork.state().reveals().unwrap_or_default(),
+        ))
+    }
+}
@@ -1,7 +1,8 @@
 use {
     crate::{
-        Action, Address, BaseCard, CardGame, CardInfo, CardInstance, GameState, Nonce, Player,
-        Secret, ID,
+        Action, Address, BaseCard, Card, CardEvent, CardGame, CardInfo, CardInstance, GameResult,
+        GameState, InstanceID, Nonce, Player, PlayerSecret, Prompt, PromptResponse, Secret, Zone,
+        ID,
     },
     std::{cmp::Ordering, fmt::Debug, future::Future, pin::Pin},
 };
@@ -25,6 +26,12 @@ pub trait State: serde::Serialize + serde::de::DeserializeOwned + Clone + Debug
     /// Base card type
     type BaseCard: BaseCard;
 
+    /// Public resource type, e.g. mana or health, see `CardGame::adjust_resource`
+    type ResourceKind: serde::Serialize + serde::de::DeserializeOwned + Clone + Debug + Ord;
+
+    /// Turn phase type, see `CardGame::start_turn`
+    type Phase: serde::Serialize + serde::de::DeserializeOwned + Clone + PartialEq + Debug;
+
     /// Gets the ABI version of this implementation.
     ///
     /// See [arcadeum::tag] and [arcadeum::version::version] for potentially helpful utilities.
@@ -45,6 +52,83 @@ pub trait State: serde::Serialize + serde::de::DeserializeOwned + Clone + Debug
         action: &Self::Action,
     ) -> Result<(), String>;
 
+    /// Enumerates the actions a given player can currently take, for bots and UI affordances.
+    ///
+    /// Every action returned should pass [State::verify].
+    /// See `testing::assert_legal_actions` for checking the two agree.
+    fn legal_actions(_state: &GameState<Self>, _player: Option<Player>) -> Vec<Self::Action> {
+        Vec::new()
+    }
+
+    /// Decides whether a player may move a card out of or into another player's secret, while `MatchConfig::authorize_moves` is enabled.
+    ///
+    /// Defaults to forbidding every such move.
+    fn authorize_move(
+        _state: &GameState<Self>,
+        _player: Player,
+        _card: Card,
+        _to_player: Player,
+        _to_zone: Zone,
+    ) -> bool {
+        false
+    }
+
+    /// Redacts a public event for one player, e.g. to hide which card was played face-down from a game-specific event.
+    ///
+    /// Returns `None` to send the event unchanged, which is the default.
+    /// If any player's view of an event is redacted, the view of the first player it's redacted for is logged
+    /// publicly in its place, so spectators and every player it's redacted for see that view,
+    /// and each player it isn't redacted for is sent the event unchanged from their own secret instead.
+    /// The public side of events logged with `GameContext::mutate_secret_or_log` is redacted too,
+    /// but events logged from a player's secret aren't.
+    fn redact_event(_event: &CardEvent<Self>, _viewer: Player) -> Option<CardEvent<Self>> {
+        None
+    }
+
+    /// Decides whether the game is over after each action, e.g. when a player's life total reaches zero.
+    ///
+    /// Returning a result ends the game with `CardGame::end_game`, unless the action already ended it.
+    fn check_game_over(_state: &GameState<Self>) -> Option<GameResult> {
+        None
+    }
+
+    /// The state of the next game of a `MatchState`, after the previous game was won.
+    ///
+    /// Defaults to the previous game's state.
+    fn next_game(previous: &GameState<Self>, _first_player: Player) -> Self {
+        Self::clone(previous)
+    }
+
+    /// A player's secret for the next game of a `MatchState`, e.g. keeping their sideboard decisions.
+    ///
+    /// The new game's cards are created from scratch, so this defaults to a new secret with the previous `State::Secret`.
+    fn next_game_secret(previous: &PlayerSecret<Self>) -> PlayerSecret<Self> {
+        PlayerSecret::new(previous.player(), previous.secret.clone())
+    }
+
+    /// Identifies a concession action and the player conceding, see `CardGame::concede`.
+    ///
+    /// Concessions skip [State::verify] and [State::apply], so they're accepted whoever's turn it is.
+    /// Only the conceding player or the owner can concede.
+    fn concession(_action: &Self::Action) -> Option<Player> {
+        None
+    }
+
+    /// Identifies an action responding to the pending prompt, see `CardGame::prompt`.
+    ///
+    /// While a prompt is pending, only the prompted player's responses pass verification,
+    /// and they skip [State::verify] and [State::apply] in favour of [State::resume].
+    fn prompt_response(_action: &Self::Action) -> Option<PromptResponse> {
+        None
+    }
+
+    /// Identifies an action for deduplication, e.g. by a client-generated idempotency key.
+    ///
+    /// Reported in `CardEvent::ActionResolved` events.
+    fn action_key(_action: &Self::Action) -> Option<String> {
+        None
+    }
+
     /// Applies an action by a given player to the state.
     fn apply<'a>(
         game: &'a mut CardGame<Self>,
@@ -57,9 +141,112 @@ pub trait State: serde::Serialize + serde::de::DeserializeOwned + Clone + Debug
         Ordering::Equal
     }
 
+    /// Used to sort the field instead of [State::field_order], for orders that depend on the rest of the game,
+    /// e.g. putting taunts first unless an aura is out.
+    ///
+    /// Defaults to [State::field_order].
+    fn field_order_with(
+        _state: &GameState<Self>,
+        a: CardInfo<Self>,
+        b: CardInfo<Self>,
+    ) -> Ordering {
+        Self::field_order(a, b)
+    }
+
+    /// Orders the field when [State::field_order] isn't a total order on it, with the `field-order-check` feature.
+    ///
+    /// Defaults to ordering by instance ID, so every client agrees on the result.
+    fn resolve_field_order(field: &mut [InstanceID]) {
+        field.sort_by_key(|id| id.0);
+    }
+
     /// A callback that lets you modify a parent card right after it gets a new attachment.
+    ///
+    /// Follow-up operations on the game, like drawing a card when equipped, belong in [State::after_attach].
     fn on_attach(_parent: &mut CardInstance<Self>, _new_attach: &CardInstance<Self>) {}
 
     /// A callback that lets you modify a parent card right before it loses an attachment.
+    ///
+    /// Follow-up operations on the game belong in [State::after_detach].
     fn on_detach(_parent: &mut CardInstance<Self>, _old_attach: &CardInstance<Self>) {}
+
+    /// An async callback that runs after a card gets a new attachment.
+    ///
+    /// Unlike [State::on_attach], this can reveal and move cards.
+    /// See [State::after_sort_field] for when async callbacks run.
+    fn after_attach<'a>(
+        _game: &'a mut CardGame<Self>,
+        _parent: Card,
+        _attachment: Card,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
+
+    /// An async callback that runs after a card loses an attachment.
+    ///
+    /// For an attachment in a player's secret, the parent is a new pointer in that secret, so its ID isn't revealed.
+    ///
+    /// Unlike [State::on_detach], this can reveal and move cards.
+    /// See [State::after_sort_field] for when async callbacks run.
+    fn after_detach<'a>(
+        _game: &'a mut CardGame<Self>,
+        _parent: Card,
+        _attachment: Card,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
+
+    /// An async callback that resolves a card popped off the stack by `CardGame::resolve_top`.
+    ///
+    /// The card is still in its owner's casting zone, or their secret limbo if it was cast face-down,
+    /// so this should move it somewhere else, e.g. to the graveyard.
+    fn resolve<'a>(
+        _game: &'a mut CardGame<Self>,
+        _card: Card,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
+
+    /// An async callback that continues an action suspended by `CardGame::prompt`, once the player responds.
+    ///
+    /// The response has already been checked with `Prompt::check`.
+    fn resume<'a>(
+        _game: &'a mut CardGame<Self>,
+        _prompt: Prompt,
+        _response: PromptResponse,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
+
+    /// An async callback that runs when a phase starts, see `CardGame::start_turn` and `CardGame::set_phase`.
+    ///
+    /// Unlike the other async callbacks, this runs immediately, and can start other phases.
+    fn on_phase_start<'a>(
+        _game: &'a mut CardGame<Self>,
+        _player: Player,
+        _phase: Self::Phase,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
+
+    /// An async callback that runs when a phase ends, before the next phase starts.
+    fn on_phase_end<'a>(
+        _game: &'a mut CardGame<Self>,
+        _player: Player,
+        _phase: Self::Phase,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
+
+    /// An async callback that runs after a player's field is re-ordered.
+    ///
+    /// Async callbacks run once the outermost `CardGame::move_card` or `CardGame::modify_card` call has finished,
+    /// in the order they were triggered.
+    /// Async callbacks triggered while an async callback is running are queued, and run after the ones already queued.
+    fn after_sort_field<'a>(
+        _game: &'a mut CardGame<Self>,
+        _player: Player,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async {})
+    }
 }
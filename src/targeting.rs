@@ -0,0 +1,47 @@
+use {
+    crate::{CardInfo, CardPredicate, Player, State, ZoneSet},
+    std::rc::Rc,
+};
+
+/// What one target of an action must be, see `GameState::check_targets` and `CardGame::check_targets`.
+#[derive(Clone)]
+pub struct TargetRequirement<S: State> {
+    /// The zones the target may be in.
+    pub zones: ZoneSet,
+
+    /// The player whose zones the target must be in, if it matters.
+    pub owner: Option<Player>,
+
+    /// Extra conditions on the target, e.g. its cost.
+    pub predicate: Option<CardPredicate<S>>,
+}
+
+impl<S: State> TargetRequirement<S> {
+    pub fn new(zones: ZoneSet) -> Self {
+        Self {
+            zones,
+            owner: None,
+            predicate: None,
+        }
+    }
+
+    pub fn owned_by(mut self, player: Player) -> Self {
+        self.owner = Some(player);
+        self
+    }
+
+    pub fn matching(mut self, f: impl Fn(CardInfo<S>) -> bool + 'static) -> Self {
+        self.predicate = Some(Rc::new(f));
+        self
+    }
+
+    /// Whether a card satisfies the requirement.
+    pub fn accepts(&self, info: CardInfo<S>) -> bool {
+        self.owner.is_none_or(|owner| owner == info.owner)
+            && self.zones.contains(info.zone)
+            && self
+                .predicate
+                .as_ref()
+                .is_none_or(|predicate| predicate(info))
+    }
+}
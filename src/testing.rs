@@ -0,0 +1,446 @@
+//! Helpers for testing `State` implementations.
+
+use {
+    crate::{
+        card_game::Either, error, invariants, replay::Replay, replay::Timeline, CardEvent,
+        CardLocation, Choice, ExactCardLocation, GameState, InstanceID, InstanceOrPlayer, Player,
+        PlayerSecret, Prompt, PromptResponse, State, Zone,
+    },
+    rand::{rngs::StdRng, SeedableRng},
+    std::{any, cell::RefCell, fs, io, path::Path, rc::Rc},
+};
+
+/// A sequence of actions for `fuzz_from_seed` to start from.
+pub type CorpusSeed<S> = Vec<(Option<Player>, <S as State>::Action)>;
+
+/// How long `fuzz_actions` runs for.
+#[derive(Clone, Debug)]
+pub struct FuzzConfig {
+    /// Seeds the generator's randomness, so runs are reproducible.
+    pub seed: u64,
+
+    /// The number of actions to generate and apply.
+    pub steps: usize,
+
+    /// The number of actions to generate at each step before giving up if none pass `State::verify`.
+    pub attempts: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            steps: 100,
+            attempts: 100,
+        }
+    }
+}
+
+/// A failed `fuzz_actions` run.
+#[derive(Debug)]
+pub struct FuzzFailure<S: State> {
+    /// The actions applied, ending with the failing one.
+    /// Use `Timeline` to rewind and inspect them.
+    pub replay: Replay<S>,
+
+    pub error: error::FuzzError,
+}
+
+/// Applies randomly generated actions to a game, checking invariants after each one.
+///
+/// `generate` proposes an action for the current state, or `None` to end the run early.
+/// Proposals the game rejects are discarded, including anything but a response while a prompt is pending.
+/// After each action, `GameState::ok` must pass with every secret, and no event sent to a player may
+/// contain a card that was in another player's secret both before and after the action.
+///
+/// Returns a replay of the applied actions.
+pub fn fuzz_actions<S: State>(
+    state: GameState<S>,
+    secrets: Vec<PlayerSecret<S>>,
+    config: FuzzConfig,
+    generate: impl FnMut(&GameState<S>, &mut StdRng) -> Option<(Option<Player>, S::Action)>,
+) -> Result<Replay<S>, Box<FuzzFailure<S>>> {
+    fuzz_from_seed(state, secrets, config, Vec::new(), generate)
+}
+
+/// Like `fuzz_actions`, but applies the actions of a corpus seed before generating any.
+///
+/// The seed's actions are checked like generated ones, and count as steps in errors.
+pub fn fuzz_from_seed<S: State>(
+    state: GameState<S>,
+    secrets: Vec<PlayerSecret<S>>,
+    config: FuzzConfig,
+    seed: CorpusSeed<S>,
+    mut generate: impl FnMut(&GameState<S>, &mut StdRng) -> Option<(Option<Player>, S::Action)>,
+) -> Result<Replay<S>, Box<FuzzFailure<S>>> {
+    let steps = seed.len() + config.steps;
+    let mut seed = seed.into_iter();
+    let mut random = StdRng::seed_from_u64(config.seed);
+    let initial_secrets = secrets.clone();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let events_clone = events.clone();
+
+    let mut timeline = Timeline::new(state, secrets, move |player, event| {
+        events_clone.borrow_mut().push((player, event))
+    })
+    .map_err(|error| {
+        Box::new(FuzzFailure {
+            replay: Replay::new(initial_secrets),
+            error: error::FuzzError::Action {
+                step: 0,
+                error: error.into(),
+            },
+        })
+    })?;
+
+    for step in 0..steps {
+        let proposal = match seed.next() {
+            Some(action) => Some(Some(action)),
+            None => (0..config.attempts)
+                .map(|_| generate(timeline.state(), &mut random))
+                .find(|proposal| match proposal {
+                    Some((player, action)) => {
+                        arcadeum::store::State::verify(timeline.state(), *player, action).is_ok()
+                    }
+                    None => true,
+                }),
+        };
+
+        let (player, action) = match proposal {
+            Some(Some(proposal)) => proposal,
+            Some(None) => break,
+            None => {
+                return Err(Box::new(FuzzFailure {
+                    replay: timeline.replay(),
+                    error: error::FuzzError::NoValidAction {
+                        step,
+                        attempts: config.attempts,
+                    },
+                }))
+            }
+        };
+
+        let before = timeline.state().clone();
+
+        events.borrow_mut().clear();
+
+        if let Err(error) = timeline.apply(player, action.clone()) {
+            let mut replay = timeline.replay();
+            replay.push(player, action, None);
+
+            return Err(Box::new(FuzzFailure {
+                replay,
+                error: error::FuzzError::Action {
+                    step,
+                    error: error.into(),
+                },
+            }));
+        }
+
+        let fail = |error| {
+            Box::new(FuzzFailure {
+                replay: timeline.replay(),
+                error,
+            })
+        };
+
+        let secrets = timeline.secrets();
+        let known: Vec<_> = secrets.iter().map(Some).collect();
+
+        if let Some(error) = invariants::check(timeline.state(), &known)
+            .into_iter()
+            .next()
+        {
+            return Err(fail(error::FuzzError::NotOk { step, error }));
+        }
+
+        for (recipient, event) in events.borrow().iter() {
+            if let Some(player) = recipient {
+                for id in revealed_instances(event) {
+                    let owner = |state: &GameState<S>| match state.instances.get(id.0) {
+                        Some(InstanceOrPlayer::Player(owner)) => Some(*owner),
+                        _ => None,
+                    };
+
+                    match (owner(&before), owner(timeline.state())) {
+                        (Some(a), Some(b)) if a == b && a != *player => {
+                            return Err(fail(error::FuzzError::Leak {
+                                step,
+                                id,
+                                owner: a,
+                                player: *player,
+                            }))
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(timeline.replay())
+}
+
+/// Writes corpus seeds to `dir` as CBOR, one file per seed named `<name>.cbor`, creating `dir` if needed.
+pub fn write_corpus<S: State>(
+    dir: impl AsRef<Path>,
+    seeds: impl IntoIterator<Item = (impl AsRef<str>, CorpusSeed<S>)>,
+) -> io::Result<()> {
+    let dir = dir.as_ref();
+
+    fs::create_dir_all(dir)?;
+
+    for (name, seed) in seeds {
+        let bytes = serde_cbor::to_vec(&seed)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        fs::write(dir.join(format!("{}.cbor", name.as_ref())), bytes)?;
+    }
+
+    Ok(())
+}
+
+/// Reads the corpus seeds in `dir` written by `write_corpus`, in file name order.
+///
+/// Files without a `.cbor` extension are ignored.
+pub fn read_corpus<S: State>(dir: impl AsRef<Path>) -> io::Result<Vec<CorpusSeed<S>>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    paths.retain(|path| {
+        path.extension()
+            .is_some_and(|extension| extension == "cbor")
+    });
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            serde_cbor::from_slice(&fs::read(path)?)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+        })
+        .collect()
+}
+
+/// The IDs of the card instances an event reveals.
+fn revealed_instances<S: State>(event: &CardEvent<S>) -> Vec<InstanceID> {
+    match event {
+        CardEvent::ModifyCard { instance } | CardEvent::PeekCard { instance, .. } => {
+            vec![instance.id()]
+        }
+        CardEvent::ModifyCards { events } => events.iter().flat_map(revealed_instances).collect(),
+        CardEvent::MoveCard {
+            instance: Some((instance, attachment)),
+            ..
+        }
+        | CardEvent::NewCard {
+            instance: Some((instance, attachment)),
+            ..
+        }
+        | CardEvent::CopyCard {
+            instance: Some((instance, attachment)),
+            ..
+        }
+        | CardEvent::TransformCard {
+            instance: Some((instance, attachment)),
+            ..
+        } => std::iter::once(instance.id())
+            .chain(attachment.as_ref().map(|attachment| attachment.id()))
+            .collect(),
+        CardEvent::Sequenced { event, .. }
+        | CardEvent::Correlated { event, .. }
+        | CardEvent::Secret { event, .. }
+        | CardEvent::InPhase { event, .. } => revealed_instances(event),
+        _ => vec![],
+    }
+}
+
+/// A value revealed from a player's secret, recorded while reveal recording is enabled.
+///
+/// See `GameState::set_reveal_recording`.
+#[derive(Clone, Debug)]
+pub struct Reveal {
+    /// The player whose secret was revealed.
+    pub player: Player,
+
+    /// The revealed value, serialized as CBOR.
+    value: Vec<u8>,
+
+    type_name: &'static str,
+}
+
+impl Reveal {
+    pub(crate) fn new<T: serde::Serialize>(player: Player, value: &T) -> Self {
+        Self {
+            player,
+            value: serde_cbor::to_vec(value).unwrap(),
+            type_name: any::type_name::<T>(),
+        }
+    }
+
+    /// Whether the revealed value is a `T`, going by its type name.
+    ///
+    /// Revealing from a pointer instead reveals either a `T`, or the ID of the card it points to outside its player's secret,
+    /// with the index of the pointer for reveals about two cards, so this also matches those reveals.
+    /// `CardGame::reveal_zone` reveals either a `Zone` or the owner of a secret parent, which this also matches.
+    pub fn is<T>(&self) -> bool {
+        self.type_name == any::type_name::<T>()
+            || self.is_wrapped::<T>()
+            || self.is_zone_or_owner::<T>()
+    }
+
+    fn is_wrapped<T>(&self) -> bool {
+        self.type_name == any::type_name::<Either<T, InstanceID>>()
+            || self.type_name == any::type_name::<Either<T, (usize, InstanceID)>>()
+            || self.type_name == any::type_name::<Either<T, Player>>()
+    }
+
+    fn is_zone_or_owner<T>(&self) -> bool {
+        self.type_name == any::type_name::<Either<Either<T, Player>, InstanceID>>()
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// The revealed value, if it's a `T`.
+    ///
+    /// Returns `None` for a reveal from a pointer to a card outside its player's secret, or of an owner instead of a zone.
+    pub fn value<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+        if self.type_name == any::type_name::<T>() {
+            serde_cbor::from_slice(&self.value).ok()
+        } else if self.is_wrapped::<T>() {
+            match serde_cbor::from_slice(&self.value).ok()? {
+                Either::A(value) => Some(value),
+                Either::B(serde::de::IgnoredAny) => None,
+            }
+        } else if self.is_zone_or_owner::<T>() {
+            match serde_cbor::from_slice(&self.value).ok()? {
+                Either::A(Either::A(value)) => Some(value),
+                Either::A(Either::B(serde::de::IgnoredAny)) => None,
+                Either::B(serde::de::IgnoredAny) => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Whether only zone information was revealed, i.e. a `Zone`, `CardLocation` or `ExactCardLocation`.
+    pub fn is_zone_information(&self) -> bool {
+        self.is::<Zone>() || self.is::<CardLocation>() || self.is::<ExactCardLocation>()
+    }
+}
+
+/// Asserts that the last action revealed nothing.
+///
+/// Panics if reveal recording isn't enabled.
+pub fn assert_no_reveals<S: State>(state: &GameState<S>) {
+    let reveals = recorded_reveals(state);
+
+    assert!(reveals.is_empty(), "unexpected reveals: {:#?}", reveals);
+}
+
+/// Asserts that the last action revealed only zone information, see `Reveal::is_zone_information`.
+///
+/// Panics if reveal recording isn't enabled.
+pub fn assert_only_zone_reveals<S: State>(state: &GameState<S>) {
+    let reveals: Vec<_> = recorded_reveals(state)
+        .iter()
+        .filter(|reveal| !reveal.is_zone_information())
+        .collect();
+
+    assert!(reveals.is_empty(), "unexpected reveals: {:#?}", reveals);
+}
+
+/// Asserts that `State::legal_actions` agrees with `State::verify` for a player.
+///
+/// Every legal action must verify, and every candidate action that verifies must be legal.
+/// Actions are compared by their serialization.
+pub fn assert_legal_actions<S: State>(
+    state: &GameState<S>,
+    player: Option<Player>,
+    candidates: impl IntoIterator<Item = S::Action>,
+) {
+    let legal = state.legal_actions(player);
+
+    for action in &legal {
+        assert_eq!(
+            S::verify(state, player, action),
+            Ok(()),
+            "legal action {:?} doesn't verify for {:?}",
+            action,
+            player
+        );
+    }
+
+    let legal: Vec<_> = legal
+        .iter()
+        .map(|action| serde_cbor::to_vec(action).unwrap())
+        .collect();
+
+    for action in candidates {
+        if S::verify(state, player, &action).is_ok() {
+            assert!(
+                legal.contains(&serde_cbor::to_vec(&action).unwrap()),
+                "action {:?} verifies for {:?}, but isn't legal",
+                action,
+                player
+            );
+        }
+    }
+}
+
+/// The first `limit` valid responses to a prompt, e.g. for generating responses in `fuzz_actions` or `State::legal_actions`.
+///
+/// Cards are chosen in increasing index order, since the order of choices rarely matters.
+/// The number of responses grows combinatorially with the cards, so `limit` bounds the enumeration itself.
+pub fn prompt_responses(prompt: &Prompt, limit: usize) -> Vec<PromptResponse> {
+    if limit == 0 || prompt.choice().check().is_err() {
+        return Vec::new();
+    }
+
+    match prompt.choice() {
+        Choice::Cards { cards, count } => {
+            let (cards, count) = (cards.len(), *count);
+
+            let mut chosen: Vec<usize> = (0..count).collect();
+            let mut responses = Vec::new();
+
+            loop {
+                responses.push(PromptResponse::Cards(chosen.clone()));
+
+                if responses.len() == limit {
+                    return responses;
+                }
+
+                // The last choice that can still move to a later card, in lexicographic order.
+                match (0..count).rev().find(|i| chosen[*i] < cards - count + i) {
+                    Some(i) => {
+                        chosen[i] += 1;
+
+                        for j in i + 1..count {
+                            chosen[j] = chosen[j - 1] + 1;
+                        }
+                    }
+                    None => return responses,
+                }
+            }
+        }
+        Choice::Number { min, max } => (*min..=*max)
+            .take(limit)
+            .map(PromptResponse::Number)
+            .collect(),
+        Choice::YesNo => [PromptResponse::YesNo(true), PromptResponse::YesNo(false)]
+            .into_iter()
+            .take(limit)
+            .collect(),
+    }
+}
+
+fn recorded_reveals<S: State>(state: &GameState<S>) -> &[Reveal] {
+    state
+        .reveals()
+        .expect("reveal recording isn't enabled, see `GameState::set_reveal_recording`")
+}
@@ -0,0 +1,19 @@
+/// Instruments a future with a span when the `tracing` feature is enabled, e.g.
+/// `traced!(future, tracing::debug_span!("reveal", player))`.
+///
+/// Without the feature, the span is never built.
+#[cfg(feature = "tracing")]
+macro_rules! traced {
+    ($future:expr, $span:expr) => {
+        tracing::Instrument::instrument($future, $span)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! traced {
+    ($future:expr, $span:expr) => {
+        $future
+    };
+}
+
+pub(crate) use traced;
@@ -0,0 +1,31 @@
+use crate::{Player, State};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The current turn, once `CardGame::start_turn` has been called.
+///
+/// Turns are opt-in: games that never start a turn never see one.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Turn<S: State> {
+    /// The player whose turn it is.
+    pub player: Player,
+
+    /// The turn number, starting from 1.
+    pub number: usize,
+
+    /// The current phase.
+    #[serde(bound = "S: State")]
+    pub phase: S::Phase,
+}
+
+impl<S: State> PartialEq for Turn<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.player == other.player && self.number == other.number && self.phase == other.phase
+    }
+}
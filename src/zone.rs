@@ -1,4 +1,4 @@
-use crate::{error, Card};
+use crate::{error, Card, PileID};
 
 #[cfg(feature = "bindings")]
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -21,6 +21,7 @@ pub enum Zone {
     CardSelection,
     HeroAbility,
     Boon,
+    Pile { pile: PileID },
 }
 
 impl Zone {
@@ -90,6 +91,13 @@ impl Zone {
         matches!(self, Self::Boon)
     }
 
+    /// Whether this is one of a player's extra secret ordered piles, e.g. an extra deck or a resource pile.
+    ///
+    /// A player's piles are numbered from 0, and exist once a card is moved into them.
+    pub fn is_pile(&self) -> bool {
+        matches!(self, Self::Pile { .. })
+    }
+
     pub fn is_public(&self) -> Result<bool, error::ZoneAttachmentError> {
         match self {
             Self::Deck => Ok(false),
@@ -106,6 +114,7 @@ impl Zone {
             Self::CardSelection => Ok(false),
             Self::HeroAbility => Ok(true),
             Self::Boon => Ok(true),
+            Self::Pile { .. } => Ok(false),
         }
     }
 
@@ -134,6 +143,9 @@ impl Zone {
             Self::CardSelection => Ok(other.is_card_selection()),
             Self::HeroAbility => Ok(other.is_hero_ability()),
             Self::Boon => Ok(other.is_boon()),
+            Self::Pile { pile } => {
+                Ok(matches!(other, Self::Pile { pile: other_pile } if other_pile == *pile))
+            }
         }
     }
 
@@ -162,6 +174,7 @@ impl std::fmt::Display for Zone {
             Zone::CardSelection => write!(f, "card selection"),
             Zone::HeroAbility => write!(f, "hero ability"),
             Zone::Boon => write!(f, "boon"),
+            Zone::Pile { pile } => write!(f, "pile {}", pile),
         }
     }
 }
@@ -0,0 +1,143 @@
+use crate::{PileID, Zone};
+
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// A set of a player's zones.
+///
+/// Public and secret variants of a zone are the same zone in a set, so a set with `Zone::Hand` contains the whole hand.
+/// Attachments aren't in any zone of their own, so adding or removing `Zone::Attachment` leaves a set unchanged.
+/// All of a player's piles are a single zone.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Default, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneSet {
+    deck: bool,
+    hand: bool,
+    field: bool,
+    graveyard: bool,
+    dust: bool,
+    limbo: bool,
+    casting: bool,
+    card_selection: bool,
+    hero_ability: bool,
+    boon: bool,
+    piles: bool,
+}
+
+impl ZoneSet {
+    pub fn all() -> Self {
+        Self {
+            deck: true,
+            hand: true,
+            field: true,
+            graveyard: true,
+            dust: true,
+            limbo: true,
+            casting: true,
+            card_selection: true,
+            hero_ability: true,
+            boon: true,
+            piles: true,
+        }
+    }
+
+    pub fn with(mut self, zone: Zone) -> Self {
+        if let Some(flag) = self.flag_mut(zone) {
+            *flag = true;
+        }
+
+        self
+    }
+
+    pub fn without(mut self, zone: Zone) -> Self {
+        if let Some(flag) = self.flag_mut(zone) {
+            *flag = false;
+        }
+
+        self
+    }
+
+    pub fn contains(&self, zone: Zone) -> bool {
+        match zone {
+            Zone::Deck => self.deck,
+            Zone::Hand { .. } => self.hand,
+            Zone::Field => self.field,
+            Zone::Graveyard => self.graveyard,
+            Zone::Dust { .. } => self.dust,
+            Zone::Attachment { .. } => false,
+            Zone::Limbo { .. } => self.limbo,
+            Zone::Casting => self.casting,
+            Zone::CardSelection => self.card_selection,
+            Zone::HeroAbility => self.hero_ability,
+            Zone::Boon => self.boon,
+            Zone::Pile { .. } => self.piles,
+        }
+    }
+
+    /// The public zones in this set, as accepted by `PlayerCards::zone_cards`.
+    pub fn public_zones(&self) -> Vec<Zone> {
+        [
+            Zone::Hand { public: true },
+            Zone::Field,
+            Zone::Graveyard,
+            Zone::Dust { public: true },
+            Zone::Limbo { public: true },
+            Zone::Casting,
+            Zone::HeroAbility,
+            Zone::Boon,
+        ]
+        .into_iter()
+        .filter(|zone| self.contains(*zone))
+        .collect()
+    }
+
+    /// The secret zones in this set, as accepted by `PlayerSecret::zone_cards`.
+    ///
+    /// `piles` is the number of piles the player has, see `PlayerCards::piles`.
+    pub fn secret_zones(&self, piles: usize) -> Vec<Zone> {
+        [
+            Zone::Deck,
+            Zone::Hand { public: false },
+            Zone::Dust { public: false },
+            Zone::Limbo { public: false },
+            Zone::CardSelection,
+        ]
+        .into_iter()
+        .chain((0..piles).map(|pile| Zone::Pile { pile: PileID(pile) }))
+        .filter(|zone| self.contains(*zone))
+        .collect()
+    }
+
+    fn flag_mut(&mut self, zone: Zone) -> Option<&mut bool> {
+        match zone {
+            Zone::Deck => Some(&mut self.deck),
+            Zone::Hand { .. } => Some(&mut self.hand),
+            Zone::Field => Some(&mut self.field),
+            Zone::Graveyard => Some(&mut self.graveyard),
+            Zone::Dust { .. } => Some(&mut self.dust),
+            Zone::Attachment { .. } => None,
+            Zone::Limbo { .. } => Some(&mut self.limbo),
+            Zone::Casting => Some(&mut self.casting),
+            Zone::CardSelection => Some(&mut self.card_selection),
+            Zone::HeroAbility => Some(&mut self.hero_ability),
+            Zone::Boon => Some(&mut self.boon),
+            Zone::Pile { .. } => Some(&mut self.piles),
+        }
+    }
+}
+
+impl From<Zone> for ZoneSet {
+    fn from(zone: Zone) -> Self {
+        Self::default().with(zone)
+    }
+}
+
+impl FromIterator<Zone> for ZoneSet {
+    fn from_iter<T: IntoIterator<Item = Zone>>(zones: T) -> Self {
+        zones.into_iter().fold(Self::default(), Self::with)
+    }
+}
@@ -0,0 +1,25 @@
+#[cfg(feature = "bindings")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// The total number of cards in each of a player's zones, counting both public and secret cards.
+#[cfg_attr(
+    feature = "bindings",
+    derive(typescript_definitions::TypescriptDefinition)
+)]
+#[derive(serde::Serialize, serde::Deserialize, Copy, Clone, Default, Debug, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoneSizes {
+    pub deck: usize,
+    pub hand: usize,
+    pub field: usize,
+    pub graveyard: usize,
+    pub dust: usize,
+    pub limbo: usize,
+    pub casting: usize,
+    pub card_selection: usize,
+    pub hero_ability: usize,
+    pub boon: usize,
+
+    /// The total number of cards in all piles.
+    pub piles: usize,
+}
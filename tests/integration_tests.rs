@@ -1,13 +1,66 @@
 use arcadeum::store::Tester;
 use card_movement_simulator::{
-    Card, CardEvent, CardGame, CardInstance, CardLocation, ExactCardLocation, GameState,
-    InstanceID, Player, PlayerSecret, Zone,
+    error::{
+        CommitmentError, Divergence, Error, IllegalCardError, InvariantViolation, MoveCardError,
+        ReplayError, RevealCardError, TargetError,
+    },
+    invariants,
+    replay::{Replay, ReplayRecorder, Timeline},
+    testing::{self, FuzzConfig},
+    ActionMetrics, Card, CardCatalog, CardDatabase, CardDefinition, CardEvent, CardGame, CardInfo,
+    CardInstance, CardLocation, CardPredicate, Catalog, CatalogCard, Choice, ExactCardLocation,
+    GameResult, GameState, InstanceID, LimboPolicy, LinkPolicy, MatchState, MoveOptions,
+    PanicPolicy, PileID, Player, PlayerSecret, Prompt, PromptResponse, Quantifier,
+    RedactedAttachmentPolicy, Simulation, TargetRequirement, TokenPolicy, TransformPolicy, Zone,
+    ZoneSet,
 };
 use pretty_assertions::{assert_eq, assert_ne};
-use std::{cell::RefCell, convert::TryInto, future::Future, pin::Pin, rc::Rc};
+use rand::Rng;
+use std::{
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    convert::TryInto,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+};
+
+thread_local! {
+    // Makes `State::field_order` inconsistent while set.
+    static INCONSISTENT_FIELD_ORDER: Cell<bool> = const { Cell::new(false) };
+
+    // Makes `State::field_order` put cloned cards first while set.
+    static CLONED_FIRST_FIELD_ORDER: Cell<bool> = const { Cell::new(false) };
+
+    // Makes `State::field_order` put cards with attachments by base first while set.
+    static WITH_ATTACHMENT_FIRST_FIELD_ORDER: Cell<bool> = const { Cell::new(false) };
+}
 
 #[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
-struct State;
+struct State {
+    // Makes the async hooks log and act while set.
+    hooks: bool,
+
+    // The batch moved a chunk per action by `Action::ContinueBatch`, and where its next chunk starts.
+    batch: Vec<Card>,
+    next_chunk: Option<usize>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+enum Event {
+    Custom,
+    PhaseStart(Phase),
+    PhaseEnd(Phase),
+    AfterAttach,
+    AfterDetach { secret: bool },
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+enum Phase {
+    Draw,
+    Main,
+}
 
 impl card_movement_simulator::State for State {
     type ID = ID;
@@ -16,22 +69,38 @@ impl card_movement_simulator::State for State {
 
     type Action = Action;
 
-    type Event = ();
+    type Event = Event;
 
     type Secret = Secret;
 
     type BaseCard = BaseCard;
 
+    type ResourceKind = Resource;
+
+    type Phase = Phase;
+
     fn version() -> &'static [u8] {
         b"Test"
     }
 
     fn verify(
-        _game: &GameState<Self>,
-        _player: Option<Player>,
-        _action: &Self::Action,
+        game: &GameState<Self>,
+        player: Option<Player>,
+        action: &Self::Action,
     ) -> Result<(), String> {
-        Ok(())
+        match action {
+            Action::PlayTopCard if game.player_cards(player.unwrap_or(0)).deck() == 0 => {
+                Err("deck is empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn legal_actions(game: &GameState<Self>, player: Option<Player>) -> Vec<Self::Action> {
+        (game.player_cards(player.unwrap_or(0)).deck() > 0)
+            .then_some(Action::PlayTopCard)
+            .into_iter()
+            .collect()
     }
 
     fn apply<'a>(
@@ -61,7 +130,10 @@ impl card_movement_simulator::State for State {
 
                     eprintln!("and then...");
 
-                    let card_id = live_game.new_card(from_player, base_card_type, None).await;
+                    let card_id = live_game
+                        .new_card(from_player, base_card_type, None)
+                        .await
+                        .unwrap();
 
                     assert_eq!(live_game.reveal_ok().await, Ok(()));
 
@@ -110,7 +182,8 @@ impl card_movement_simulator::State for State {
                             BaseCard::WithAttachment,
                             BaseCard::WithAttachment.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
 
                     live_game
                         .move_card(parent_id, 0, parent_zone)
@@ -185,7 +258,8 @@ impl card_movement_simulator::State for State {
                             parent_base_card,
                             parent_base_card.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
                     live_game
                         .move_card(parent_id, parent_owner, parent_zone)
                         .await
@@ -221,7 +295,8 @@ impl card_movement_simulator::State for State {
                             BaseCard::Attachment,
                             BaseCard::Attachment.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
                     live_game
                         .move_card(card_id, card_owner, card_zone)
                         .await
@@ -300,7 +375,8 @@ impl card_movement_simulator::State for State {
                             parent_base_card,
                             parent_base_card.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
 
                     live_game
                         .move_card(parent_id, parent_owner, parent_zone)
@@ -337,7 +413,8 @@ impl card_movement_simulator::State for State {
                             BaseCard::WithAttachment,
                             BaseCard::WithAttachment.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
                     live_game
                         .move_card(starting_parent_id, card_owner, card_zone)
                         .await
@@ -434,7 +511,8 @@ impl card_movement_simulator::State for State {
                             BaseCard::WithAttachment,
                             BaseCard::WithAttachment.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
                     live_game
                         .move_card(parent, 0, Zone::Hand { public: false })
                         .await
@@ -442,7 +520,8 @@ impl card_movement_simulator::State for State {
 
                     let card = live_game
                         .new_card(0, BaseCard::Attachment, BaseCard::Attachment.attachment())
-                        .await;
+                        .await
+                        .unwrap();
                     live_game
                         .move_card(
                             card,
@@ -455,10 +534,19 @@ impl card_movement_simulator::State for State {
                         .unwrap();
                 }
 
+                Action::PlayTopCard => {
+                    let card = live_game
+                        .draw_card(0)
+                        .await
+                        .expect("Player should have drawn a card.");
+
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                }
                 Action::OpaquePointerAssociationDoesntHoldThroughDraw => {
                     let card_id = live_game
                         .new_card(0, BaseCard::Basic, BaseCard::Basic.attachment())
-                        .await;
+                        .await
+                        .unwrap();
                     assert_eq!(live_game.player_cards(0).deck(), 0);
                     live_game.move_card(card_id, 0, Zone::Deck).await.unwrap();
 
@@ -490,7 +578,8 @@ impl card_movement_simulator::State for State {
                             BaseCard::WithAttachment,
                             BaseCard::WithAttachment.attachment(),
                         )
-                        .await;
+                        .await
+                        .unwrap();
                     live_game.move_card(card, 0, Zone::Field).await.unwrap();
 
                     // Public Attachment
@@ -540,7 +629,8 @@ impl card_movement_simulator::State for State {
                     let owner = 0;
                     let card_id = live_game
                         .new_card(owner, base_card_type, base_card_type.attachment())
-                        .await;
+                        .await
+                        .unwrap();
 
                     live_game
                         .move_card(card_id, owner, card_zone)
@@ -559,7 +649,7 @@ impl card_movement_simulator::State for State {
 
                     assert_eq!(live_game.reveal_ok().await, Ok(()));
 
-                    let copy = live_game.copy_card(card, deep).await;
+                    let copy = live_game.copy_card(card, deep).await.unwrap();
 
                     assert_eq!(live_game.reveal_ok().await, Ok(()));
 
@@ -598,7 +688,8 @@ impl card_movement_simulator::State for State {
                     let owner = 0;
                     let card_id = live_game
                         .new_card(owner, base_card_type, base_card_type.attachment())
-                        .await;
+                        .await
+                        .unwrap();
 
                     let starting_attach = live_game
                         .reveal_from_card(card_id, |c| {
@@ -608,7 +699,7 @@ impl card_movement_simulator::State for State {
                         .await;
 
                     if let Some(new_attach) = attachment_type {
-                        let attach = live_game.new_card(owner, new_attach, None).await;
+                        let attach = live_game.new_card(owner, new_attach, None).await.unwrap();
                         live_game
                             .move_card(
                                 attach,
@@ -736,1030 +827,6822 @@ impl card_movement_simulator::State for State {
 
                     assert_eq!(live_game.reveal_ok().await, Ok(()));
                 }
-            }
-        })
-    }
-    fn on_attach(parent: &mut CardInstance<Self>, new_attach: &CardInstance<Self>) {
-        assert_eq!(parent.attachment(), Some(new_attach.id()));
-        parent.attachment_was_attached += 1;
-    }
-    fn on_detach(parent: &mut CardInstance<Self>, _old_attach: &CardInstance<Self>) {
-        assert!(parent.attachment().is_none());
-        parent.attachment_was_detached += 1;
-    }
-}
+                Action::SecretCardPadding { created } => {
+                    live_game.set_secret_card_padding(4);
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
-struct Secret;
+                    let cards = live_game
+                        .new_secret_cards(0, move |mut secret| {
+                            for _ in 0..created {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
 
-impl card_movement_simulator::Secret<BaseCard> for Secret {
-    fn reset_card(&self, _id: &InstanceID, _base: BaseCard) -> CardState {
-        <BaseCard as card_movement_simulator::BaseCard>::new_card_state(&BaseCard::Basic, None)
-    }
-    fn attachment(&self, _id: &InstanceID, _base: BaseCard) -> Option<BaseCard> {
-        None
-    }
-}
+                    // The real count only shows in the player's secret.
+                    assert_eq!(cards.len(), 4);
+                    assert!(live_game.exists(InstanceID::from_raw(3)));
+                    assert!(!live_game.exists(InstanceID::from_raw(4)));
 
-#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
-enum BaseCard {
-    Basic,
-    WithAttachment,
-    Attachment,
-}
-impl BaseCard {
-    pub fn attachment(&self) -> Option<Self> {
-        match self {
-            Self::Basic => None,
-            Self::WithAttachment => Some(Self::Attachment),
-            Self::Attachment => None,
-        }
-    }
-}
-impl card_movement_simulator::BaseCard for BaseCard {
-    type CardState = CardState;
+                    for (index, card) in cards.iter().enumerate() {
+                        assert_eq!(
+                            live_game.move_card(*card, 0, Zone::Graveyard).await.is_ok(),
+                            index < created
+                        );
+                    }
 
-    fn new_card_state(&self, _parent: Option<&Self::CardState>) -> Self::CardState {
-        CardState {
-            attachment_was_detached: 0,
-            attachment_was_attached: 0,
-            was_cloned: false,
-        }
-    }
+                    let cards = live_game
+                        .new_secret_cards_with_fakes(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
 
-    // fn reset_card(&self, _card: &Self::CardState) -> Self::CardState {
-    //     self.new_card_state(None)
-    // }
-}
+                            for _ in 0..4 {
+                                secret.new_fake_card();
+                            }
+                        })
+                        .await;
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
-struct CardState {
-    attachment_was_detached: usize,
-    attachment_was_attached: usize,
-    was_cloned: bool,
-}
+                    assert_eq!(cards.len(), 4);
+                    assert!(live_game.exists(InstanceID::from_raw(11)));
+                    assert!(!live_game.exists(InstanceID::from_raw(12)));
 
-impl card_movement_simulator::CardState for CardState {
-    fn eq(&self, other: &Self) -> bool {
-        self.was_cloned == other.was_cloned
-            && self.attachment_was_attached == other.attachment_was_attached
-            && self.attachment_was_detached == other.attachment_was_detached
-    }
-    fn copy_card(&self) -> CardState {
-        let mut copy = self.clone();
-        copy.was_cloned = true;
-        copy
-    }
-}
+                    assert_eq!(live_game.reveal_ok().await, Ok(()));
+                }
+                Action::SecretZoneSize => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..3 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
-enum Action {
-    Move {
-        card_ptr_bucket: Option<Player>, // 3
-        base_card_type: BaseCard,        // 2
-        from_player: Player,             // 2
-        to_player: Player,               // 2
-        from_zone: Zone,                 // 11
-        to_zone: Zone,                   // 11
-    },
-    Detach {
-        parent_zone: Zone,                     // 11
-        attachment_ptr_bucket: Option<Player>, // 3
-        to_player: Player,                     // 2
-        to_zone: Zone,                         // 11
-    },
-    Attach {
-        parent_base_card: BaseCard,        // 2
-        parent_ptr_bucket: Option<Player>, // 3
-        parent_zone: Zone,                 // 11
-        card_ptr_bucket: Option<Player>,   // 3
-        card_owner: Player,                // 2
-        card_zone: Zone,                   // 11
-    },
-    AttachFromAttached {
-        parent_base_card: BaseCard,        // 2
-        parent_ptr_bucket: Option<Player>, // 3
-        parent_zone: Zone,                 // 11
-        card_ptr_bucket: Option<Player>,   // 3
-        card_owner: Player,                // 2
-        card_zone: Zone,                   // 11
-    },
-    CopyCard {
-        card_ptr_bucket: Option<Player>, // 3
-        card_zone: Zone,                 // 11
-        base_card_type: BaseCard,        // 2
-        deep: bool,                      // 2
-    },
-    ResetCard {
-        card_ptr_bucket: Option<Player>,
-        base_card_type: BaseCard,
-        attachment_type: Option<BaseCard>,
-        card_zone: Zone,
-    },
-    ReplacingAttachOnSecretCardDoesNotLeakInfo,
-    OpaquePointerAssociationDoesntHoldThroughDraw,
-    InstanceFromIDSetup,
-    RevealSecretHandCard,
-}
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(0, Zone::Limbo { public: false })
+                            .await,
+                        Some(3)
+                    );
 
-#[derive(Clone, Copy, Eq, PartialEq, Default)]
-pub struct ID([u8; 8]);
+                    live_game
+                        .move_card(cards[0], 0, Zone::Dust { public: false })
+                        .await
+                        .unwrap();
+                    live_game
+                        .move_card(cards[1], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
 
-impl card_movement_simulator::ID for ID {
-    fn deserialize(data: &mut &[u8]) -> Result<Self, String> {
-        if data.len() < 8 {
-            return Err("data.len() < 8".to_string());
-        }
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(0, Zone::Limbo { public: false })
+                            .await,
+                        Some(1)
+                    );
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(0, Zone::Dust { public: false })
+                            .await,
+                        Some(1)
+                    );
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(0, Zone::Hand { public: false })
+                            .await,
+                        Some(1)
+                    );
+                    assert_eq!(live_game.secret_zone_size(0, Zone::Deck).await, Some(0));
+                    assert_eq!(live_game.secret_zone_size(0, Zone::Field).await, None);
 
-        let id = data[..8].try_into().map_err(|error| format!("{}", error))?;
-        *data = &data[8..];
-        Ok(Self(id))
-    }
+                    live_game
+                        .move_card(cards[2], 0, Zone::Hand { public: true })
+                        .await
+                        .unwrap();
 
-    fn serialize(&self) -> Vec<u8> {
-        self.0.to_vec()
-    }
-}
+                    assert_eq!(
+                        live_game.zone_size(0, Zone::Hand { public: false }).await,
+                        Some(2)
+                    );
+                    assert_eq!(
+                        live_game.zone_size(0, Zone::Dust { public: true }).await,
+                        Some(1)
+                    );
+                    assert_eq!(
+                        live_game
+                            .zone_size(0, Zone::Attachment { parent: cards[2] })
+                            .await,
+                        None
+                    );
 
-#[test]
-fn replacing_attach_on_secret_card_does_not_leak_existence_of_current_attachment() {
-    let reveals = Tester::new(
-        GameState::<State>::default(),
-        [
-            PlayerSecret::new(0, Default::default()),
-            PlayerSecret::new(1, Default::default()),
-        ],
-        Default::default(),
-        |_, _, _| {},
-        |_, _, _| {},
-        false,
-    )
-    .unwrap()
-    .apply(Some(0), &Action::ReplacingAttachOnSecretCardDoesNotLeakInfo)
+                    let sizes = live_game.zone_sizes(0).await;
+
+                    assert_eq!(sizes.hand, 2);
+                    assert_eq!(sizes.dust, 1);
+                    assert_eq!(sizes.limbo, 0);
+                }
+                Action::StrictEventOrdering => {
+                    live_game.set_strict_event_ordering(true);
+
+                    let hand = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..3 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
+
+                    for card in &hand {
+                        live_game
+                            .move_card(card, 0, Zone::Hand { public: false })
+                            .await
+                            .unwrap();
+                    }
+
+                    live_game
+                        .move_card(hand[1], 0, Zone::Hand { public: true })
+                        .await
+                        .unwrap();
+                }
+                Action::LinkCards => {
+                    let host = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let spawn = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await[0];
+
+                    live_game
+                        .link_cards(host, spawn, LinkPolicy::DustTogether)
+                        .await
+                        .unwrap();
+
+                    assert!(live_game.link(host).is_some());
+
+                    live_game
+                        .move_card(host, 0, Zone::Dust { public: true })
+                        .await
+                        .unwrap();
+
+                    assert!(live_game.links().is_empty());
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(1, Zone::Dust { public: false })
+                            .await,
+                        Some(1)
+                    );
+
+                    let leader = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let follower = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+
+                    live_game
+                        .link_cards(leader, follower, LinkPolicy::MoveTogether)
+                        .await
+                        .unwrap();
+
+                    live_game.move_card(follower, 1, Zone::Field).await.unwrap();
+
+                    assert!(matches!(
+                        live_game.location(leader),
+                        CardLocation {
+                            player: 1,
+                            location: Some((Zone::Field, _))
+                        }
+                    ));
+                    assert!(live_game.link(leader).is_some());
+
+                    assert!(live_game
+                        .link_cards(leader, leader, LinkPolicy::DustTogether)
+                        .await
+                        .is_err());
+
+                    assert_eq!(live_game.reveal_ok().await, Ok(()));
+                }
+                Action::RedactedAttachmentPresence => {
+                    live_game.set_redacted_attachment_policy(RedactedAttachmentPolicy::Presence);
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(
+                                BaseCard::WithAttachment,
+                                BaseCard::WithAttachment.attachment(),
+                            );
+                            secret.new_card(BaseCard::Basic, BaseCard::Basic.attachment());
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game
+                            .move_card(card, 0, Zone::Hand { public: false })
+                            .await
+                            .unwrap();
+                    }
+                }
+                Action::RedactedAttachmentAcrossBuckets => {
+                    live_game.set_redacted_attachment_policy(RedactedAttachmentPolicy::Presence);
+
+                    // Public to secret.
+                    let card = live_game
+                        .new_card(
+                            0,
+                            BaseCard::WithAttachment,
+                            BaseCard::WithAttachment.attachment(),
+                        )
+                        .await
+                        .unwrap();
+
+                    live_game
+                        .move_card(card, 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    // Secret to another secret.
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(
+                                BaseCard::WithAttachment,
+                                BaseCard::WithAttachment.attachment(),
+                            );
+                        })
+                        .await;
+
+                    live_game
+                        .move_card(cards[0], 1, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                }
+                Action::RevealLocation => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game
+                        .move_card(cards[1], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    // Only the zones of secret cards are revealed.
+                    assert!(matches!(
+                        live_game.reveal_location(cards[0]).await,
+                        CardLocation {
+                            player: 0,
+                            location: Some((Zone::Limbo { public: false }, None))
+                        }
+                    ));
+                    assert!(matches!(
+                        live_game.reveal_location(cards[1]).await,
+                        CardLocation {
+                            player: 0,
+                            location: Some((Zone::Hand { public: false }, None))
+                        }
+                    ));
+
+                    assert!(
+                        live_game
+                            .is_in_zone(cards[1], Zone::Hand { public: false })
+                            .await
+                    );
+                    assert!(
+                        !live_game
+                            .is_in_zone(cards[0], Zone::Hand { public: false })
+                            .await
+                    );
+                    assert!(matches!(
+                        live_game.reveal_zone(cards[0]).await,
+                        Zone::Limbo { public: false }
+                    ));
+                    assert_eq!(live_game.reveal_owner(cards[0]).await, Some(0));
+
+                    // A secret parent is revealed as a new pointer, not by ID.
+                    let parent = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(
+                                BaseCard::WithAttachment,
+                                BaseCard::WithAttachment.attachment(),
+                            );
+                        })
+                        .await[0];
+                    live_game
+                        .move_card(parent, 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                    let attachment = live_game.reveal_attachment(parent).await.unwrap();
+                    let location = live_game.reveal_location(attachment).await;
+                    assert!(matches!(
+                        location,
+                        CardLocation {
+                            player: 0,
+                            location: Some((
+                                Zone::Attachment {
+                                    parent: Card::Pointer(..)
+                                },
+                                None
+                            ))
+                        }
+                    ));
+                    if let Some((Zone::Attachment { parent: revealed }, _)) = location.location {
+                        assert!(live_game.reveal_if_cards_eq(revealed, parent).await);
+                    }
+
+                    let (_, id) = live_game.move_card(cards[1], 1, Zone::Field).await.unwrap();
+
+                    // A pointer to another player's secret card only reveals its owner.
+                    live_game
+                        .move_card(cards[0], 1, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                    assert!(matches!(
+                        live_game.reveal_location(cards[0]).await,
+                        CardLocation {
+                            player: 1,
+                            location: None
+                        }
+                    ));
+                    assert_eq!(live_game.reveal_owner(cards[0]).await, Some(1));
+
+                    assert!(matches!(
+                        live_game.reveal_location(cards[1]).await,
+                        CardLocation {
+                            player: 1,
+                            location: Some((Zone::Field, Some(0)))
+                        }
+                    ));
+                    assert_eq!(live_game.reveal_owner(cards[1]).await, Some(1));
+                    assert_eq!(live_game.reveal_owner(id.unwrap()).await, Some(1));
+                    assert!(matches!(
+                        live_game.reveal_location(id.unwrap()).await,
+                        CardLocation {
+                            player: 1,
+                            location: Some((Zone::Field, Some(0)))
+                        }
+                    ));
+                }
+                Action::FindSecretCard => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(
+                                BaseCard::WithAttachment,
+                                BaseCard::WithAttachment.attachment(),
+                            );
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game
+                            .move_card(card, 0, Zone::Hand { public: false })
+                            .await
+                            .unwrap();
+                    }
+
+                    let card = live_game
+                        .find_secret_hand_card(0, |card| *card.base() == BaseCard::WithAttachment)
+                        .await
+                        .expect("secret hand should have a card with an attachment");
+
+                    assert!(
+                        live_game
+                            .reveal_from_card(card, |card| card.attachment().is_some())
+                            .await
+                    );
+
+                    let first = live_game
+                        .find_secret_card(0, Zone::Hand { public: false }, |card| {
+                            *card.base() == BaseCard::Basic
+                        })
+                        .await
+                        .unwrap();
+                    let last = live_game
+                        .find_last_secret_card(0, Zone::Hand { public: false }, |card| {
+                            *card.base() == BaseCard::Basic
+                        })
+                        .await
+                        .unwrap();
+
+                    assert!(!live_game.reveal_if_cards_eq(first, last).await);
+                    assert!(live_game
+                        .find_deck_card(0, |card| *card.base() == BaseCard::Basic)
+                        .await
+                        .is_none());
+                }
+                Action::ReplaceDeck => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards.iter().copied() {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    live_game
+                        .link_cards(cards[0], cards[1], LinkPolicy::DustTogether)
+                        .await
+                        .unwrap();
+
+                    live_game
+                        .replace_deck(
+                            0,
+                            vec![BaseCard::WithAttachment, BaseCard::Basic, BaseCard::Basic],
+                        )
+                        .await
+                        .unwrap();
+
+                    assert!(live_game.links().is_empty());
+                    assert_eq!(live_game.player_cards(0).deck(), 3);
+                    assert_eq!(live_game.secret_zone_size(0, Zone::Deck).await, Some(3));
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(0, Zone::Dust { public: false })
+                            .await,
+                        Some(2)
+                    );
+
+                    let card = live_game
+                        .find_deck_card(0, |card| *card.base() == BaseCard::WithAttachment)
+                        .await
+                        .expect("replaced deck should have a card with an attachment");
+
+                    assert!(
+                        live_game
+                            .reveal_from_card(card, |card| card.attachment().is_none())
+                            .await
+                    );
+                }
+                Action::NonAdjacentAttachment => {
+                    let parent = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(parent, 0, Zone::Field).await.unwrap();
+
+                    // keep the attachment's ID from being adjacent to its parent's
+                    live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let attachment = live_game
+                        .new_card(0, BaseCard::Attachment, None)
+                        .await
+                        .unwrap();
+
+                    live_game
+                        .move_card(
+                            attachment,
+                            0,
+                            Zone::Attachment {
+                                parent: parent.into(),
+                            },
+                        )
+                        .await
+                        .unwrap();
+
+                    assert_eq!(live_game.attachment_of(parent), Some(attachment));
+                    assert_eq!(live_game.parent_of(attachment), Some(parent));
+                    assert_eq!(live_game.parent_of(parent), None);
+                    assert!(matches!(
+                        live_game.location(attachment).location,
+                        Some((Zone::Attachment { parent: Card::ID(id) }, None)) if id == parent
+                    ));
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Attachment, None);
+                        })
+                        .await;
+
+                    live_game
+                        .move_card(cards[2], 0, Zone::Attachment { parent: cards[0] })
+                        .await
+                        .unwrap();
+
+                    let (parent, attachment) = (cards[0], cards[2]);
+
+                    assert!(
+                        live_game
+                            .context
+                            .reveal_unique(
+                                0,
+                                move |secret| {
+                                    let parent_id = secret.instance(parent).unwrap().id();
+                                    let attachment_id = secret.instance(attachment).unwrap().id();
+
+                                    secret.attachment_of(parent) == Some(attachment_id)
+                                        && secret.parent_of(attachment) == Some(parent_id)
+                                        && secret.parent_of(parent).is_none()
+                                },
+                                |_| true,
+                            )
+                            .await
+                    );
+                }
+                Action::AsyncHooks => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    let parent = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(parent, 0, Zone::Field).await.unwrap();
+
+                    let attachment = live_game
+                        .new_card(0, BaseCard::Attachment, None)
+                        .await
+                        .unwrap();
+
+                    live_game.hooks = true;
+
+                    live_game
+                        .move_card(
+                            attachment,
+                            0,
+                            Zone::Attachment {
+                                parent: parent.into(),
+                            },
+                        )
+                        .await
+                        .unwrap();
+
+                    assert_eq!(live_game.player_cards(0).deck(), 1);
+                    assert_eq!(live_game.player_cards(0).hand().len(), 1);
+
+                    // The re-attachment from `State::after_detach` runs `State::after_attach` after it.
+                    live_game
+                        .move_card(attachment, 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+
+                    assert!(live_game.attachment_of(parent).is_some());
+                    assert_eq!(live_game.player_cards(0).deck(), 0);
+                    assert_eq!(live_game.player_cards(0).hand().len(), 2);
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Attachment, None);
+                        })
+                        .await;
+
+                    live_game
+                        .move_card(cards[1], 0, Zone::Attachment { parent: cards[0] })
+                        .await
+                        .unwrap();
+
+                    // A secret attachment's parent is passed to `State::after_detach` as a new pointer.
+                    live_game
+                        .move_card(cards[1], 0, Zone::Dust { public: false })
+                        .await
+                        .unwrap();
+
+                    // Creating a public card with an attachment runs `State::after_attach` too.
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.move_card(cards[0], 0, Zone::Deck).await.unwrap();
+
+                    let hand = live_game.player_cards(0).hand().len();
+
+                    let card = live_game
+                        .new_card_in(0, BaseCard::Basic, Some(BaseCard::Attachment), Zone::Field)
+                        .await
+                        .unwrap()
+                        .id()
+                        .unwrap();
+
+                    assert_ne!(live_game.attachment_of(card), Some(card));
+                    assert!(live_game.attachment_of(card).is_some());
+                    assert_eq!(live_game.player_cards(0).deck(), 0);
+                    assert_eq!(live_game.player_cards(0).hand().len(), hand + 1);
+
+                    live_game.hooks = false;
+                }
+                Action::ModifyCardDelta => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    live_game
+                        .modify_card(card, |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    live_game
+                        .modify_card(card, |mut card| {
+                            card.attachment_was_attached += 1;
+                        })
+                        .await;
+                }
+                Action::ModifyAllCards => {
+                    let public = live_game
+                        .new_card(0, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+                    live_game.move_card(public, 0, Zone::Field).await.unwrap();
+
+                    let mut secret = vec![];
+
+                    for player in live_game.players() {
+                        let cards = live_game
+                            .new_secret_cards(player, |mut secret| {
+                                secret.new_card(BaseCard::Basic, None);
+                            })
+                            .await;
+
+                        live_game
+                            .move_card(cards[0], player, Zone::Deck)
+                            .await
+                            .unwrap();
+
+                        secret.extend(cards);
+                    }
+
+                    live_game
+                        .modify_all_cards(|mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    for card in std::iter::once(Card::from(public)).chain(secret) {
+                        assert!(
+                            live_game
+                                .reveal_from_card(card, |info| info.instance.was_cloned)
+                                .await
+                        );
+                    }
+
+                    let attachment = live_game.attachment_of(public).unwrap();
+
+                    assert!(
+                        live_game
+                            .reveal_from_card(attachment, |info| info.instance.was_cloned)
+                            .await
+                    );
+                }
+                Action::FieldOrderConflict => {
+                    let mut cards = vec![];
+
+                    for _ in 0..3 {
+                        cards.push(live_game.new_card(0, BaseCard::Basic, None).await.unwrap());
+                    }
+
+                    for card in cards.iter().rev() {
+                        live_game.move_card(*card, 0, Zone::Field).await.unwrap();
+                    }
+
+                    assert_eq!(live_game.player_cards(0).field(), &cards);
+                }
+                Action::MatchConfig => {
+                    let config = live_game.config_mut();
+
+                    config.deck_size = Some((1, 2));
+                    config.zone_capacities = vec![(Zone::Field.into(), 1)];
+
+                    let a = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let b = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    live_game.move_card(a, 0, Zone::Field).await.unwrap();
+
+                    assert!(matches!(
+                        live_game.move_card(b, 0, Zone::Field).await,
+                        Err(MoveCardError::ZoneFull {
+                            player: 0,
+                            zone: Zone::Field,
+                            capacity: 1,
+                        })
+                    ));
+
+                    // Other players' fields and other zones are unaffected.
+                    live_game.move_card(b, 1, Zone::Field).await.unwrap();
+                    live_game.move_card(a, 0, Zone::Graveyard).await.unwrap();
+
+                    assert!(matches!(
+                        live_game.replace_deck(0, vec![BaseCard::Basic; 3]).await,
+                        Err(IllegalCardError::DeckSize {
+                            size: 3,
+                            min: 1,
+                            max: 2,
+                        })
+                    ));
+                    assert_eq!(live_game.player_cards(0).deck(), 0);
+
+                    live_game
+                        .replace_deck(0, vec![BaseCard::Basic; 2])
+                        .await
+                        .unwrap();
+                }
+                Action::Census => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..4 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
+
+                    live_game.move_card(cards[0], 0, Zone::Deck).await.unwrap();
+                    live_game.move_card(cards[1], 0, Zone::Deck).await.unwrap();
+                    live_game
+                        .move_card(cards[2], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    let field = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(field, 0, Zone::Field).await.unwrap();
+
+                    let hand = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game
+                        .move_card(hand, 0, Zone::Hand { public: true })
+                        .await
+                        .unwrap();
+
+                    let census = live_game.census(0).await;
+
+                    assert_eq!(census.deck.len(), 2);
+                    assert_eq!(census.public_hand(), 1);
+                    assert_eq!(census.secret_hand(), 1);
+                    assert_eq!(census.field, vec![field]);
+                    assert_eq!(census.secret_limbo, 1);
+                    assert!(census.public_limbo.is_empty());
+                    assert_eq!(census.sizes(), live_game.zone_sizes(0).await);
+
+                    // The deck pointers point to the cards moved into the deck.
+                    for (pointer, card) in census.deck.into_iter().zip(&cards) {
+                        assert!(live_game.reveal_if_cards_eq(pointer, *card).await);
+                    }
+                }
+                Action::AuthorizeMoves => {
+                    assert_eq!(live_game.acting_player(), Some(0));
+
+                    live_game.config_mut().authorize_moves = true;
+
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    assert!(matches!(
+                        live_game.move_card(cards[0], 1, Zone::Deck).await,
+                        Err(MoveCardError::Unauthorized { player: 0, .. })
+                    ));
+
+                    // Public moves don't need authorization.
+                    let public = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(public, 1, Zone::Field).await.unwrap();
+
+                    // `State::authorize_move` allows moves to the graveyard.
+                    live_game
+                        .move_card(cards[0], 1, Zone::Graveyard)
+                        .await
+                        .unwrap();
+
+                    live_game.set_acting_player(Some(1));
+                    live_game.move_card(cards[1], 1, Zone::Deck).await.unwrap();
+
+                    live_game.set_acting_player(Some(0));
+                    assert!(live_game.move_card(public, 1, Zone::Deck).await.is_err());
+                    assert_eq!(live_game.player_cards(1).deck(), 1);
+                }
+                Action::AuthorizePointerMoves => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    // Player 0's pointer follows its card into player 1's secret.
+                    live_game.move_card(cards[0], 1, Zone::Deck).await.unwrap();
+
+                    live_game.config_mut().authorize_moves = true;
+
+                    assert!(matches!(
+                        live_game
+                            .move_card(cards[0], 0, Zone::Hand { public: false })
+                            .await,
+                        Err(MoveCardError::Unauthorized { player: 0, .. })
+                    ));
+                    assert_eq!(live_game.player_cards(1).deck(), 1);
+                    assert_eq!(live_game.player_cards(0).hand().len(), 0);
+                }
+                Action::RevealAfterConcession => {
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.concede(1);
+
+                    // Player 1 may have left, so the effect ends here instead of waiting for their secret.
+                    live_game
+                        .reveal_from_card(cards[0], |info| info.owner)
+                        .await;
+
+                    panic!("the reveal of player 1's secret should end the effect");
+                }
+                Action::RevealAttachment => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::WithAttachment, Some(BaseCard::Attachment));
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let attachment = live_game
+                        .reveal_attachment(cards[0])
+                        .await
+                        .expect("secret card should have an attachment");
+
+                    assert!(attachment.id().is_none());
+                    assert!(live_game.reveal_attachment(cards[1]).await.is_none());
+
+                    let parent = live_game.reveal_parent(attachment).await.unwrap();
+                    assert!(live_game.reveal_if_cards_eq(parent, cards[0]).await);
+
+                    // Once the card is public, so is its attachment.
+                    live_game.move_card(cards[0], 0, Zone::Field).await.unwrap();
+
+                    let id = live_game
+                        .reveal_from_card(cards[0], |info| info.instance.id())
+                        .await;
+
+                    assert!(matches!(
+                        live_game.reveal_attachment(cards[0]).await,
+                        Some(Card::ID(attachment)) if Some(attachment) == live_game.attachment_of(id)
+                    ));
+                }
+                Action::Modifiers => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let aura = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let other = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+
+                    for id in [card, aura, other] {
+                        live_game
+                            .move_card(id, live_game.owner(id), Zone::Field)
+                            .await
+                            .unwrap();
+                    }
+
+                    fn was_cloned(live_game: &CardGame<State>, id: InstanceID) -> bool {
+                        live_game
+                            .effective_state(id.instance(&live_game.state, None).unwrap())
+                            .was_cloned
+                    }
+
+                    // Layers are applied in order, regardless of the order they're added in.
+                    let id = live_game
+                        .add_modifier(card, Some(aura.into()), 1, modifier(false))
+                        .await
+                        .unwrap();
+                    live_game.add_global_modifier(None, 0, modifier(true)).await;
+
+                    assert!(!was_cloned(live_game, card));
+                    assert!(was_cloned(live_game, other));
+                    assert_eq!(live_game.card_modifiers(card).len(), 2);
+
+                    // Resetting a card keeps its modifiers.
+                    live_game.reset_card(card).await;
+                    assert!(live_game.modifier(id).is_some());
+
+                    // Moving the source removes its modifiers.
+                    live_game.move_card(aura, 0, Zone::Graveyard).await.unwrap();
+                    assert!(live_game.modifier(id).is_none());
+                    assert!(was_cloned(live_game, card));
+
+                    // Dusting the target removes its modifiers.
+                    let id = live_game
+                        .add_modifier(other, None, 0, modifier(false))
+                        .await
+                        .unwrap();
+                    live_game
+                        .move_card(other, 1, Zone::Dust { public: true })
+                        .await
+                        .unwrap();
+                    assert!(live_game.modifier(id).is_none());
+                    assert_eq!(live_game.modifiers().len(), 1);
+
+                    // Moving the target to a secret removes its modifiers, so they never involve secret cards.
+                    let id = live_game
+                        .add_modifier(card, None, 0, modifier(false))
+                        .await
+                        .unwrap();
+                    live_game
+                        .move_card(card, 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                    assert!(live_game.modifier(id).is_none());
+
+                    // Secret cards can't be modified, and their IDs aren't revealed trying.
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    assert_eq!(
+                        live_game
+                            .add_modifier(cards[0], None, 0, modifier(false))
+                            .await,
+                        None
+                    );
+                    assert_eq!(live_game.modifiers().len(), 1);
+                }
+                Action::TemporaryModifications => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in [card.into(), cards[0], cards[1]] {
+                        live_game
+                            .modify_card_until(card, "turn", |mut card| {
+                                card.was_cloned = true;
+                            })
+                            .await;
+                    }
+
+                    // This modification can't be sent as a delta, so expiring restores the whole state from before it.
+                    live_game
+                        .modify_card_until(card, "turn", |mut card| {
+                            card.attachment_was_attached += 1;
+                        })
+                        .await;
+
+                    live_game
+                        .modify_card_until(cards[2], "combat", |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    // Changes that aren't temporary are kept when a patchable modification expires.
+                    live_game
+                        .modify_card(cards[0], |mut card| {
+                            card.attachment_was_detached += 1;
+                        })
+                        .await;
+
+                    // This card leaves its owner's secret before it expires.
+                    live_game.move_card(cards[1], 0, Zone::Field).await.unwrap();
+
+                    live_game.expire_modifications("turn").await;
+
+                    for card in [card.into(), cards[0], cards[1]] {
+                        assert!(
+                            !live_game
+                                .reveal_from_card(card, |info| info.instance.was_cloned)
+                                .await
+                        );
+                    }
+
+                    assert_eq!(
+                        live_game
+                            .reveal_from_card(card, |info| info.instance.attachment_was_attached)
+                            .await,
+                        0
+                    );
+                    assert_eq!(
+                        live_game
+                            .reveal_from_card(cards[0], |info| info
+                                .instance
+                                .attachment_was_detached)
+                            .await,
+                        1
+                    );
+                    assert!(
+                        live_game
+                            .reveal_from_card(cards[2], |info| info.instance.was_cloned)
+                            .await
+                    );
+                }
+                Action::Turns => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    assert!(live_game.turn().is_none());
+
+                    // There's no turn to set a phase in yet.
+                    assert!(live_game.set_phase(Phase::Main).await.is_err());
+
+                    live_game.hooks = true;
+
+                    live_game.start_turn(0, Phase::Draw).await;
+
+                    assert_eq!(live_game.player_cards(0).hand().len(), 1);
+                    assert_eq!(live_game.active_player(), Some(0));
+                    assert_eq!(live_game.phase(), Some(&Phase::Main));
+
+                    live_game.start_turn(1, Phase::Main).await;
+
+                    assert_eq!(live_game.turn().map(|turn| turn.number), Some(2));
+                    assert_eq!(live_game.active_player(), Some(1));
+
+                    live_game.hooks = false;
+                }
+                Action::Stack => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let countered = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.cast(card, false).await.unwrap();
+                    assert_eq!(live_game.player_cards(0).casting(), &vec![card]);
+
+                    // Face-down casts stay secret.
+                    let face_down = live_game.cast(cards[0], true).await.unwrap();
+                    assert!(face_down.id().is_none());
+                    assert!(live_game.player_cards(1).casting().is_empty());
+
+                    live_game.cast(countered, false).await.unwrap();
+                    assert_eq!(live_game.stack().len(), 3);
+
+                    live_game.counter(countered, Zone::Graveyard).await.unwrap();
+                    assert!(live_game.is_in_zone(countered, Zone::Graveyard).await);
+                    assert!(matches!(
+                        live_game.counter(countered, Zone::Graveyard).await,
+                        Err(MoveCardError::NotOnStack { .. })
+                    ));
+
+                    // The stack resolves from the top.
+                    let top = live_game.resolve_top().await.unwrap();
+                    assert!(live_game.reveal_if_cards_eq(top, cards[0]).await);
+                    assert!(live_game.is_in_zone(cards[0], Zone::Graveyard).await);
+
+                    assert_eq!(
+                        live_game.resolve_top().await.and_then(|card| card.id()),
+                        Some(card)
+                    );
+                    assert!(live_game.resolve_top().await.is_none());
+
+                    // Moving a card some other way takes it off the stack.
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.cast(card, false).await.unwrap();
+                    live_game
+                        .move_card(card, 0, Zone::Dust { public: true })
+                        .await
+                        .unwrap();
+                    assert!(live_game.stack().is_empty());
+
+                    // Moving a face-down card through another pointer takes it off the stack too.
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    live_game.cast(cards[0], true).await.unwrap();
+                    let limbo = live_game.secret_limbo_cards(1).await;
+                    live_game
+                        .move_card(limbo[0], 1, Zone::Graveyard)
+                        .await
+                        .unwrap();
+                    assert!(live_game.stack().is_empty());
+
+                    // Cards cast face-down don't count against the limbo policy while they're on the stack.
+                    live_game.set_limbo_policy(LimboPolicy::Forbid);
+
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    live_game.cast(cards[0], true).await.unwrap();
+                    assert_eq!(live_game.stack().len(), 1);
+                }
+                Action::Commitments => {
+                    live_game
+                        .commit(0, "type", |_, random| random.gen_range(0..3usize))
+                        .await
+                        .unwrap();
+                    live_game
+                        .commit(0, "other", |secret, _| secret.deck().len())
+                        .await
+                        .unwrap();
+
+                    assert!(live_game.has_commitment(0, "type"));
+                    assert!(!live_game.has_commitment(1, "type"));
+                    assert!(matches!(
+                        live_game.commit(0, "type", |_, _| 0usize).await,
+                        Err(CommitmentError::AlreadyCommitted { .. })
+                    ));
+
+                    let value: usize = live_game.reveal_commitment(0, "type").await.unwrap();
+                    assert!(value < 3);
+                    assert!(!live_game.has_commitment(0, "type"));
+                    assert!(matches!(
+                        live_game.reveal_commitment::<usize>(0, "type").await,
+                        Err(CommitmentError::NotCommitted { .. })
+                    ));
+                }
+                Action::RandomCardsWeighted => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::WithAttachment, Some(BaseCard::Attachment));
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::WithAttachment, Some(BaseCard::Attachment));
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    let weight = |info: CardInfo<State>| match info.base() {
+                        BaseCard::WithAttachment => 1,
+                        _ => 0,
+                    };
+
+                    let picked = live_game
+                        .random_cards_weighted(0, Zone::Deck, 3, weight)
+                        .await;
+
+                    // Only the two cards with weights are picked, and only their pointers are revealed.
+                    assert_eq!(picked.len(), 2);
+                    assert!(picked.iter().all(|card| card.id().is_none()));
+                    assert!(live_game.reveal_if_cards_ne(picked[0], picked[1]).await);
+
+                    for card in picked {
+                        assert_eq!(
+                            live_game.reveal_from_card(card, |info| *info.base()).await,
+                            BaseCard::WithAttachment
+                        );
+                    }
+
+                    let card = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+                    let other = live_game
+                        .new_card(1, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+
+                    for card in [card, other] {
+                        live_game.move_card(card, 1, Zone::Field).await.unwrap();
+                    }
+
+                    let picked = live_game
+                        .random_cards_weighted(1, Zone::Field, 2, weight)
+                        .await;
+
+                    assert_eq!(
+                        picked.iter().map(|card| card.id()).collect::<Vec<_>>(),
+                        vec![Some(other)]
+                    );
+                }
+                Action::RandomCard => {
+                    assert!(live_game.random_card(0, Zone::Deck).await.is_none());
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards.iter() {
+                        live_game.move_card(*card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    let card = live_game.random_card(0, Zone::Deck).await.unwrap();
+                    assert!(card.id().is_none());
+                    assert!(live_game.is_in_zone(card, Zone::Deck).await);
+
+                    let field = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(field, 1, Zone::Field).await.unwrap();
+
+                    assert_eq!(
+                        live_game
+                            .random_card(1, Zone::Field)
+                            .await
+                            .and_then(|card| card.id()),
+                        Some(field)
+                    );
+                }
+                Action::RandomOutcomes => {
+                    for _ in 0..20 {
+                        assert!((1..=6).contains(&live_game.roll(6).await));
+                    }
+
+                    live_game.flip_coin().await;
+                }
+                Action::PeekTopOfDeck => {
+                    assert!(live_game.peek_top_of_deck(0).is_none());
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    let card = live_game.peek_top_of_deck(0).unwrap();
+                    assert!(card.id().is_none());
+                    assert!(live_game.is_in_zone(card, Zone::Deck).await);
+                }
+                Action::RevealProjection => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let card = cards[0];
+                    live_game
+                        .move_card(card, 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    let value = |value: bool| serde_cbor::to_vec(&value).ok();
+
+                    assert_eq!(live_game.known_projection(card, "wasCloned"), None);
+                    assert_eq!(live_game.reveal_projection(card, "cost").await, None);
+                    assert_eq!(
+                        live_game.reveal_projection(card, "wasCloned").await,
+                        value(false)
+                    );
+                    assert_eq!(live_game.known_projection(card, "wasCloned"), value(false));
+
+                    live_game
+                        .modify_card(card, |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    // Known projections are refreshed at the end of the action.
+                    assert_eq!(live_game.known_projection(card, "wasCloned"), value(false));
+                }
+                Action::ForgetProjection => {
+                    let card = live_game.known_projections()[0].card();
+
+                    let (_, id) = live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    assert_eq!(
+                        live_game.known_projection(id.unwrap(), "wasCloned"),
+                        serde_cbor::to_vec(&true).ok()
+                    );
+                }
+                Action::RedactEvents => {
+                    live_game
+                        .commit(0, "secret-bid", |_, _| 3usize)
+                        .await
+                        .unwrap();
+                    live_game.commit(0, "bid", |_, _| 3usize).await.unwrap();
+                }
+                Action::SuppressPointerEvents => {
+                    live_game.config_mut().suppress_pointer_events = true;
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    for card in cards {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    for card in live_game.deck_cards(0) {
+                        assert!(live_game.is_in_zone(card, Zone::Deck).await);
+                    }
+
+                    let card = live_game.deck_card(0, 1);
+                    assert!(live_game.is_in_zone(card, Zone::Deck).await);
+                }
+                Action::SkipSort => {
+                    let skip_sort = MoveOptions { skip_sort: true };
+
+                    let a = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let b = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let c = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    live_game
+                        .modify_card(b, |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    for card in [a, b] {
+                        live_game
+                            .move_card_with(card, 0, Zone::Field, skip_sort)
+                            .await
+                            .unwrap();
+                    }
+
+                    assert_eq!(live_game.player_cards(0).field(), &vec![a, b]);
+
+                    live_game.resort_field(0).await;
+                    assert_eq!(live_game.player_cards(0).field(), &vec![b, a]);
+
+                    live_game.move_card(c, 0, Zone::Field).await.unwrap();
+                    assert_eq!(live_game.player_cards(0).field(), &vec![b, a, c]);
+                }
+                Action::FieldOrderWithState => {
+                    let a = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let b = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    live_game
+                        .modify_card(b, |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    for card in [a, b] {
+                        live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                    }
+
+                    assert_eq!(live_game.player_cards(0).field(), &vec![b, a]);
+
+                    live_game
+                        .add_global_modifier(None, 0, modifier(false))
+                        .await;
+                    live_game.resort_field(0).await;
+
+                    assert_eq!(live_game.player_cards(0).field(), &vec![a, b]);
+                }
+                Action::PositionedField => {
+                    live_game.config_mut().positioned_field = true;
+
+                    let a = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let b = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let c = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    live_game
+                        .modify_card(b, |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    for card in [a, b] {
+                        live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                    }
+
+                    assert_eq!(live_game.player_cards(0).field(), &vec![a, b]);
+
+                    live_game.move_card_to_index(c, 0, 1).await.unwrap();
+                    assert_eq!(live_game.player_cards(0).field(), &vec![a, c, b]);
+
+                    live_game.reposition_card(b, 0);
+                    assert_eq!(live_game.player_cards(0).field(), &vec![b, a, c]);
+                }
+                Action::RecoverFromDust => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    assert!(matches!(
+                        live_game
+                            .recover_from_dust(card, 0, Zone::Hand { public: true })
+                            .await,
+                        Err(MoveCardError::NotInDust { .. })
+                    ));
+
+                    live_game
+                        .move_card(card, 0, Zone::Dust { public: true })
+                        .await
+                        .unwrap();
+                    live_game
+                        .recover_from_dust(card, 0, Zone::Hand { public: true })
+                        .await
+                        .unwrap();
+
+                    assert!(
+                        live_game
+                            .is_in_zone(card, Zone::Hand { public: true })
+                            .await
+                    );
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game
+                        .move_card(cards[0], 0, Zone::Dust { public: false })
+                        .await
+                        .unwrap();
+                    live_game
+                        .recover_from_dust(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    assert!(
+                        live_game
+                            .is_in_zone(cards[0], Zone::Hand { public: false })
+                            .await
+                    );
+                }
+                Action::DestroyInstance => {
+                    let card = live_game
+                        .new_card(0, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+                    let attachment = live_game.attachment_of(card).unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    assert_eq!(live_game.destroy_instance(card).await.unwrap(), Some(card));
+                    assert!(live_game.is_destroyed(card));
+                    assert!(live_game.is_destroyed(attachment));
+                    assert!(live_game.player_cards(0).field().is_empty());
+                    assert!(live_game.player_cards(0).dust().is_empty());
+
+                    // Destroyed IDs are reused, latest first.
+                    let new = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+                    assert_eq!(new, attachment);
+                    assert!(!live_game.is_destroyed(new));
+                    assert!(live_game.is_destroyed(card));
+                    assert!(live_game.location(card).location.is_none());
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    live_game
+                        .move_card(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    // A secret card's ID isn't revealed.
+                    assert_eq!(live_game.destroy_instance(cards[0]).await.unwrap(), None);
+                    assert!(live_game.player_cards(0).hand().is_empty());
+                    assert!(matches!(
+                        live_game
+                            .try_reveal_from_card(cards[0], |info| info.owner)
+                            .await,
+                        Err(RevealCardError::CardDoesNotExist { .. })
+                    ));
+
+                    // Destroyed cards and invalidated pointers to them fail instead of panicking.
+                    let dead = cards[0];
+                    assert!(matches!(
+                        live_game.destroy_instance(dead).await,
+                        Err(MoveCardError::CardDoesNotExist { .. })
+                    ));
+                    assert!(matches!(
+                        live_game.destroy_instance(card).await,
+                        Err(MoveCardError::CardDoesNotExist { .. })
+                    ));
+                    assert_eq!(live_game.reveal_owner(dead).await, None);
+                    assert_eq!(live_game.reveal_owner(card).await, None);
+                    assert!(!live_game.exists(card));
+                    assert!(live_game.exists(new));
+                    // A destroyed card isn't public, and has no location.
+                    assert!(!live_game.is_public(dead).await);
+                    assert!(live_game.reveal_location(dead).await.location.is_none());
+                    assert!(live_game.reveal_attachment(dead).await.is_none());
+                    assert!(live_game.reveal_attachment(card).await.is_none());
+                    assert_eq!(live_game.reveal_projection(dead, "wasCloned").await, None);
+                    assert_eq!(
+                        live_game
+                            .count_matching(vec![dead, card.into(), new.into()], |_| true)
+                            .await,
+                        1
+                    );
+                    live_game
+                        .transform_card(dead, BaseCard::WithAttachment, TransformPolicy::Keep)
+                        .await;
+                    live_game.modify_card(dead, |_| {}).await;
+                    live_game.modify_card_until(dead, "turn", |_| {}).await;
+                    assert!(matches!(
+                        live_game
+                            .new_card_in(
+                                0,
+                                BaseCard::Attachment,
+                                None,
+                                Zone::Attachment { parent: dead }
+                            )
+                            .await,
+                        Err(MoveCardError::CardDoesNotExist { .. })
+                    ));
+                }
+                Action::AttachResult => {
+                    let parent = live_game
+                        .new_card(0, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+                    let old = live_game.attachment_of(parent).unwrap();
+                    live_game.move_card(parent, 0, Zone::Field).await.unwrap();
+
+                    let card = live_game
+                        .new_card(0, BaseCard::Attachment, None)
+                        .await
+                        .unwrap();
+                    let result = live_game.attach(card, parent).await.unwrap();
+
+                    assert_eq!(result.previous.and_then(|card| card.id()), Some(old));
+                    assert!(matches!(
+                        result.parent_location.location,
+                        Some((Zone::Field, _))
+                    ));
+                    assert!(matches!(
+                        result.from.location,
+                        Some((Zone::Limbo { public: true }, _))
+                    ));
+                    assert_eq!(live_game.attachment_of(parent), Some(card));
+                    assert!(live_game.is_in_zone(old, Zone::Dust { public: true }).await);
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::WithAttachment, Some(BaseCard::Attachment));
+                        })
+                        .await;
+                    live_game
+                        .move_card(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    let card = live_game
+                        .new_card(0, BaseCard::Attachment, None)
+                        .await
+                        .unwrap();
+                    let result = live_game.attach(card, cards[0]).await.unwrap();
+
+                    assert!(result.previous.unwrap().id().is_none());
+                    assert!(result.parent_location.location.is_none());
+                }
+                Action::SwapAttachments => {
+                    let parent = live_game
+                        .new_card(0, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+                    let old = live_game.attachment_of(parent).unwrap();
+                    live_game.move_card(parent, 0, Zone::Field).await.unwrap();
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::WithAttachment, Some(BaseCard::Attachment));
+                        })
+                        .await;
+                    live_game
+                        .move_card(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    let counts = |info: CardInfo<State>| {
+                        (info.attachment_was_attached, info.attachment_was_detached)
+                    };
+                    let (attached, detached) = live_game.reveal_from_card(parent, counts).await;
+                    let (secret_attached, secret_detached) =
+                        live_game.reveal_from_card(cards[0], counts).await;
+
+                    live_game.swap_attachments(parent, cards[0]).await.unwrap();
+
+                    let new = live_game.attachment_of(parent).unwrap();
+                    assert_ne!(new, old);
+                    assert!(
+                        live_game
+                            .is_in_zone(
+                                new,
+                                Zone::Attachment {
+                                    parent: parent.into()
+                                }
+                            )
+                            .await
+                    );
+                    assert!(matches!(
+                        live_game.reveal_from_card(old, |info| info.zone).await,
+                        Zone::Attachment { .. }
+                    ));
+                    assert!(!live_game.is_in_zone(old, Zone::Dust { public: true }).await);
+
+                    assert_eq!(
+                        live_game.reveal_from_card(parent, counts).await,
+                        (attached + 1, detached + 1)
+                    );
+                    assert_eq!(
+                        live_game.reveal_from_card(cards[0], counts).await,
+                        (secret_attached + 1, secret_detached + 1)
+                    );
+                }
+                Action::SwapCards => {
+                    live_game.config_mut().zone_capacities = vec![(Zone::Field.into(), 1)];
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    live_game
+                        .move_card(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    live_game.swap_cards(card, cards[0]).await.unwrap();
+
+                    assert!(
+                        live_game
+                            .is_in_zone(card, Zone::Hand { public: false })
+                            .await
+                    );
+                    assert!(matches!(
+                        live_game.reveal_from_card(cards[0], |info| info.zone).await,
+                        Zone::Field
+                    ));
+                    assert_eq!(live_game.player_cards(0).field().len(), 1);
+
+                    let field = live_game.player_cards(0).field()[0];
+                    let hand = [
+                        live_game.new_card(0, BaseCard::Basic, None).await.unwrap(),
+                        live_game.new_card(0, BaseCard::Basic, None).await.unwrap(),
+                    ];
+
+                    for card in hand {
+                        live_game
+                            .move_card(card, 0, Zone::Hand { public: true })
+                            .await
+                            .unwrap();
+                    }
+
+                    live_game.swap_cards(hand[0], field).await.unwrap();
+
+                    assert_eq!(
+                        live_game.player_cards(0).hand(),
+                        &vec![None, Some(field), Some(hand[1])]
+                    );
+                    assert_eq!(live_game.player_cards(0).field(), &vec![hand[0]]);
+
+                    let attachment = live_game
+                        .new_card(0, BaseCard::Attachment, None)
+                        .await
+                        .unwrap();
+
+                    live_game
+                        .move_card(
+                            attachment,
+                            0,
+                            Zone::Attachment {
+                                parent: field.into(),
+                            },
+                        )
+                        .await
+                        .unwrap();
+
+                    assert!(matches!(
+                        live_game.swap_cards(attachment, hand[1]).await,
+                        Err(MoveCardError::SwapAttachment { .. })
+                    ));
+                }
+                Action::NewCardIn => {
+                    let card = live_game
+                        .new_card_in(0, BaseCard::Basic, None, Zone::Field)
+                        .await
+                        .unwrap();
+
+                    assert!(card.id().is_some());
+                    assert!(live_game.is_in_zone(card, Zone::Field).await);
+
+                    live_game
+                        .new_card_in(1, BaseCard::Basic, None, Zone::Attachment { parent: card })
+                        .await
+                        .unwrap();
+
+                    assert!(
+                        live_game
+                            .reveal_from_card(card, |info| info.attachment.is_some())
+                            .await
+                    );
+
+                    let card = live_game
+                        .new_card_in(0, BaseCard::Basic, Some(BaseCard::Basic), Zone::Deck)
+                        .await
+                        .unwrap();
+
+                    assert!(card.id().is_none());
+                    assert!(live_game.is_in_zone(card, Zone::Deck).await);
+                    assert!(
+                        live_game
+                            .reveal_from_card(card, |info| info.attachment.is_some())
+                            .await
+                    );
+                    assert!(live_game.player_cards(0).limbo().is_empty());
+                }
+                Action::Tokens => {
+                    let token = live_game.new_token(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(token, 0, Zone::Field).await.unwrap();
+
+                    let copy = live_game.copy_card(token, false).await.unwrap();
+                    assert!(
+                        live_game
+                            .reveal_from_card(copy, |info| info.is_token())
+                            .await
+                    );
+
+                    live_game.move_card(copy, 0, Zone::Field).await.unwrap();
+                    live_game
+                        .move_card(copy, 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                    assert!(live_game.is_destroyed(copy.id().unwrap()));
+
+                    live_game
+                        .move_card(token, 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+                    assert!(live_game.is_destroyed(token));
+                    assert!(live_game.player_cards(0).graveyard().is_empty());
+
+                    live_game.config_mut().token_policy = TokenPolicy::Keep;
+
+                    let token = live_game.new_token(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(token, 0, Zone::Field).await.unwrap();
+                    live_game
+                        .move_card(token, 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+                    assert!(!live_game.is_destroyed(token));
+                }
+                Action::TransformCard => {
+                    let card = live_game
+                        .new_card(0, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                    let attachment = live_game.attachment_of(card).unwrap();
+
+                    live_game
+                        .transform_card(card, BaseCard::Basic, TransformPolicy::Keep)
+                        .await;
+                    assert_eq!(
+                        live_game.reveal_from_card(card, |info| *info.base()).await,
+                        BaseCard::Basic
+                    );
+                    assert_eq!(live_game.attachment_of(card), Some(attachment));
+
+                    live_game
+                        .transform_card(card, BaseCard::WithAttachment, TransformPolicy::Replace)
+                        .await;
+                    assert_eq!(live_game.attachment_of(card), None);
+                    assert!(
+                        live_game
+                            .is_in_zone(attachment, Zone::Dust { public: true })
+                            .await
+                    );
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::WithAttachment, Some(BaseCard::Attachment));
+                        })
+                        .await;
+                    live_game
+                        .move_card(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    let card = live_game
+                        .transform_card(cards[0], BaseCard::Basic, TransformPolicy::Dust)
+                        .await;
+                    assert!(card.id().is_none());
+                    assert_eq!(
+                        live_game
+                            .reveal_from_card(card, |info| (*info.base(), info.attachment()))
+                            .await,
+                        (BaseCard::Basic, None)
+                    );
+                }
+                Action::TransformFieldCard => {
+                    let cards = [
+                        live_game.new_card(0, BaseCard::Basic, None).await.unwrap(),
+                        live_game.new_card(0, BaseCard::Basic, None).await.unwrap(),
+                    ];
+
+                    for card in cards {
+                        live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                    }
+
+                    assert_eq!(live_game.player_cards(0).field(), &cards.to_vec());
+
+                    live_game
+                        .transform_card(cards[1], BaseCard::WithAttachment, TransformPolicy::Keep)
+                        .await;
+
+                    assert_eq!(live_game.player_cards(0).field(), &vec![cards[1], cards[0]]);
+                }
+                Action::FaceDown => {
+                    live_game.config_mut().zone_capacities = vec![(Zone::Field.into(), 1)];
+                    live_game.config_mut().limbo_policy = LimboPolicy::Forbid;
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    for card in &cards {
+                        live_game
+                            .move_card(*card, 0, Zone::Hand { public: false })
+                            .await
+                            .unwrap();
+                    }
+
+                    assert!(matches!(
+                        live_game.place_face_down(cards[0], Zone::Deck).await,
+                        Err(MoveCardError::NotPublicZone { .. })
+                    ));
+
+                    let card = live_game
+                        .place_face_down(cards[0], Zone::Field)
+                        .await
+                        .unwrap();
+                    assert_eq!(live_game.face_down_cards().len(), 1);
+                    assert!(live_game.player_cards(0).field().is_empty());
+
+                    let other = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    assert!(matches!(
+                        live_game.move_card(other, 0, Zone::Field).await,
+                        Err(MoveCardError::ZoneFull { .. })
+                    ));
+                    live_game
+                        .move_card(other, 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+
+                    // A card that can't be turned face-up stays face-down.
+                    live_game.config_mut().zone_capacities = vec![(Zone::Field.into(), 0)];
+                    assert!(matches!(
+                        live_game.turn_face_up(card).await,
+                        Err(MoveCardError::ZoneFull { .. })
+                    ));
+                    assert_eq!(live_game.face_down_cards().len(), 1);
+                    live_game.config_mut().zone_capacities = vec![(Zone::Field.into(), 1)];
+
+                    let (_, id) = live_game.turn_face_up(card).await.unwrap();
+                    assert!(live_game.is_in_zone(id.unwrap(), Zone::Field).await);
+                    assert!(live_game.face_down_cards().is_empty());
+                    assert!(matches!(
+                        live_game.turn_face_up(card).await,
+                        Err(MoveCardError::NotFaceDown { .. })
+                    ));
+
+                    // Left face-down at the end of the action, without violating the limbo policy.
+                    live_game
+                        .place_face_down(cards[1], Zone::Casting)
+                        .await
+                        .unwrap();
+                }
+                Action::RevealHandCard => {
+                    let hand = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..5 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
+
+                    for card in &hand {
+                        live_game
+                            .move_card(card, 0, Zone::Hand { public: false })
+                            .await
+                            .unwrap();
+                    }
+
+                    let id = live_game.reveal_hand_card(0, hand[2]).await.unwrap();
+                    assert_eq!(live_game.player_cards(0).hand()[2], Some(id));
+                    assert_eq!(live_game.player_cards(0).hand().len(), 5);
+
+                    assert!(matches!(
+                        live_game.reveal_hand_card(0, id).await,
+                        Err(MoveCardError::NotInSecretHand { .. })
+                    ));
+                    assert!(matches!(
+                        live_game.reveal_hand_card(1, hand[3]).await,
+                        Err(MoveCardError::NotInSecretHand { .. })
+                    ));
+
+                    live_game.destroy_instance(hand[4]).await.unwrap();
+                    assert!(matches!(
+                        live_game.reveal_hand_card(0, hand[4]).await,
+                        Err(MoveCardError::NotInSecretHand { .. })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .reveal_hand_card(0, InstanceID::from_raw(1000))
+                            .await,
+                        Err(MoveCardError::NotInSecretHand { .. })
+                    ));
+
+                    assert_eq!(live_game.reveal_ok().await, Ok(()));
+                }
+                Action::ConcealHandCard => {
+                    let hand = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..5 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
+
+                    for card in &hand {
+                        live_game
+                            .move_card(card, 0, Zone::Hand { public: false })
+                            .await
+                            .unwrap();
+                    }
+
+                    let id = live_game.reveal_hand_card(0, hand[2]).await.unwrap();
+                    let other = live_game.reveal_hand_card(0, hand[4]).await.unwrap();
+
+                    live_game.conceal_hand_card(0, id).await.unwrap();
+
+                    let public_hand = live_game.player_cards(0).hand();
+                    assert_eq!(public_hand, &vec![None, None, None, None, Some(other)]);
+
+                    assert!(matches!(
+                        live_game.conceal_hand_card(0, id).await,
+                        Err(MoveCardError::NotInPublicHand { .. })
+                    ));
+                    assert!(
+                        live_game
+                            .is_in_zone(hand[2], Zone::Hand { public: false })
+                            .await
+                    );
+
+                    assert_eq!(live_game.reveal_ok().await, Ok(()));
+                }
+                Action::Piles => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..5 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
+
+                    for card in &cards[..3] {
+                        live_game
+                            .move_card(card, 0, Zone::Pile { pile: PileID(1) })
+                            .await
+                            .unwrap();
+                    }
+
+                    live_game
+                        .move_card(cards[3], 0, Zone::Pile { pile: PileID(0) })
+                        .await
+                        .unwrap();
+
+                    assert_eq!(live_game.player_cards(0).piles(), &vec![1, 3]);
+                    assert_eq!(
+                        live_game.zone_size(0, Zone::Pile { pile: PileID(1) }).await,
+                        Some(3)
+                    );
+                    assert_eq!(live_game.zone_sizes(0).await.piles, 4);
+
+                    let basic: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::Basic);
+                    let piles = ZoneSet::from(Zone::Pile { pile: PileID(0) })
+                        .with(Zone::Attachment { parent: cards[4] });
+
+                    assert!(
+                        live_game
+                            .reveal_if(vec![(0, piles, basic)], Quantifier::Any)
+                            .await
+                    );
+
+                    live_game.shuffle_pile(0, PileID(1));
+
+                    let top = live_game.peek_top_of_pile(0, PileID(1)).unwrap();
+                    let top = live_game
+                        .reveal_from_card(top, |info| info.instance.id())
+                        .await;
+
+                    let drawn = live_game.draw_from_pile(0, PileID(1), 2).await;
+                    assert_eq!(drawn.len(), 2);
+                    assert_eq!(
+                        live_game
+                            .reveal_from_card(drawn[0], |info| info.instance.id())
+                            .await,
+                        top
+                    );
+                    assert_eq!(live_game.player_cards(0).pile(PileID(1)), 1);
+                    assert_eq!(live_game.player_cards(0).hand().len(), 2);
+                    assert_eq!(live_game.player_cards(0).deck(), 0);
+
+                    assert_eq!(live_game.draw_from_pile(0, PileID(2), 1).await.len(), 0);
+                }
+                Action::Sideboard => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            for _ in 0..6 {
+                                secret.new_card(BaseCard::Basic, None);
+                            }
+                        })
+                        .await;
+
+                    for card in &cards[..4] {
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+                    }
+
+                    for card in &cards[4..] {
+                        live_game
+                            .move_card(card, 0, Zone::Pile { pile: PileID(0) })
+                            .await
+                            .unwrap();
+                    }
+
+                    live_game.config_mut().deck_size = Some((4, 4));
+
+                    // Moving one card out without one in is rejected.
+                    assert!(matches!(
+                        live_game
+                            .sideboard(0, PileID(0), |secret| (secret.deck()[..1].to_vec(), vec![]))
+                            .await,
+                        Err(IllegalCardError::DeckSize { size: 3, .. })
+                    ));
+                    assert_eq!(live_game.player_cards(0).deck(), 4);
+
+                    live_game
+                        .sideboard(0, PileID(0), |secret| {
+                            (secret.deck()[..2].to_vec(), secret.pile(PileID(0)).to_vec())
+                        })
+                        .await
+                        .unwrap();
+
+                    assert_eq!(live_game.player_cards(0).deck(), 4);
+                    assert_eq!(live_game.player_cards(0).pile(PileID(0)), 2);
+                }
+                Action::EndGame { winner } => {
+                    let card = live_game
+                        .new_card(winner, BaseCard::Basic, None)
+                        .await
+                        .unwrap();
+
+                    live_game
+                        .move_card(card, winner, Zone::Field)
+                        .await
+                        .unwrap();
+                    live_game.end_game(GameResult::Win(winner));
+
+                    assert_eq!(live_game.winner(), Some(winner));
+                    assert!(matches!(
+                        live_game.move_card(card, winner, Zone::Graveyard).await,
+                        Err(MoveCardError::GameOver {
+                            result: GameResult::Win(_)
+                        })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .new_card_in(winner, BaseCard::Basic, None, Zone::Field)
+                            .await,
+                        Err(MoveCardError::GameOver { .. })
+                    ));
+                    assert!(matches!(
+                        live_game.attach(card, card).await,
+                        Err(MoveCardError::GameOver { .. })
+                    ));
+
+                    // Operations that can't fail leave cards untouched instead of panicking.
+                    assert!(matches!(
+                        live_game
+                            .modify_card(card, |mut c| c.was_cloned = true)
+                            .await,
+                        Card::ID(id) if id == card
+                    ));
+                    assert!(
+                        !live_game
+                            .reveal_from_card(card, |info| info.instance.was_cloned)
+                            .await
+                    );
+                    assert_eq!(
+                        live_game.add_global_modifier(None, 0, modifier(true)).await,
+                        None
+                    );
+                    assert!(live_game.draw_cards(winner, 1).await.is_empty());
+                    assert!(matches!(
+                        live_game.new_card(winner, BaseCard::Basic, None).await,
+                        Err(IllegalCardError::GameOver { .. })
+                    ));
+                    assert!(matches!(
+                        live_game.new_token(winner, BaseCard::Basic, None).await,
+                        Err(IllegalCardError::GameOver { .. })
+                    ));
+                }
+                Action::Concede { .. } => unreachable!("concessions are handled by the crate"),
+                Action::Checksum => {
+                    live_game.config_mut().checksum_events = true;
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                }
+                Action::CompareCards => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    let a = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let b = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.set_reveal_recording(true);
+
+                    assert!(!live_game.reveal_if_cards_eq(card, a[0]).await);
+                    assert!(!live_game.reveal_if_cards_eq(a[0], b[0]).await);
+                }
+                Action::SameZone => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    let a = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let b = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    // A pointer in player 1's secret to a card in player 0's secret.
+                    let id = live_game
+                        .reveal_from_card(a[0], |info| info.instance.id())
+                        .await;
+                    let alias = live_game
+                        .new_secret_pointers(1, move |mut secret| secret.new_pointer(id))
+                        .await[0];
+
+                    live_game.set_reveal_recording(true);
+
+                    assert!(live_game.reveal_if_same_zone(a[0], a[1]).await);
+                    assert!(!live_game.reveal_if_same_zone(card, a[0]).await);
+                    assert!(!live_game.reveal_if_same_zone(a[0], b[0]).await);
+                    assert!(live_game.reveal_if_same_zone(a[1], alias).await);
+                }
+                Action::CountMatching => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    let mut cards = vec![card.into()];
+
+                    cards.extend(
+                        live_game
+                            .new_secret_cards(0, |mut secret| {
+                                secret.new_card(BaseCard::Basic, None);
+                                secret.new_card(BaseCard::WithAttachment, None);
+                                secret.new_card(BaseCard::Basic, None);
+                            })
+                            .await,
+                    );
+
+                    cards.extend(
+                        live_game
+                            .new_secret_cards(1, |mut secret| {
+                                secret.new_card(BaseCard::Basic, None);
+                            })
+                            .await,
+                    );
+
+                    live_game.set_reveal_recording(true);
+
+                    assert_eq!(
+                        live_game
+                            .count_matching(cards, |card| *card.base() == BaseCard::Basic)
+                            .await,
+                        4
+                    );
+                }
+                Action::ChooseRandomMatching => {
+                    live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::WithAttachment, None);
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let is_basic = |card: CardInfo<State>| *card.base() == BaseCard::Basic;
+
+                    let cards = live_game
+                        .choose_random_matching(0, Zone::Limbo { public: false }, is_basic, 2)
+                        .await;
+
+                    assert_eq!(cards.len(), 2);
+                    assert!(cards.iter().all(|card| card.id().is_none()));
+                    assert!(!live_game.reveal_if_cards_eq(cards[0], cards[1]).await);
+
+                    for card in cards {
+                        assert!(live_game.reveal_from_card(card, is_basic).await);
+                    }
+
+                    let cards = live_game
+                        .choose_random_matching(
+                            0,
+                            Zone::Limbo { public: false },
+                            |card| *card.base() == BaseCard::WithAttachment,
+                            2,
+                        )
+                        .await;
+
+                    assert_eq!(cards.len(), 1);
+                }
+                Action::Targets => {
+                    let basic = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(basic, 0, Zone::Field).await.unwrap();
+
+                    let other = live_game
+                        .new_card(0, BaseCard::WithAttachment, None)
+                        .await
+                        .unwrap();
+                    live_game.move_card(other, 0, Zone::Field).await.unwrap();
+
+                    let discarded = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game
+                        .move_card(discarded, 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+
+                    let destroyed = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game
+                        .move_card(destroyed, 0, Zone::Field)
+                        .await
+                        .unwrap();
+                    live_game.destroy_instance(destroyed).await.unwrap();
+
+                    let secret = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let requirement = TargetRequirement::new(ZoneSet::default().with(Zone::Field))
+                        .owned_by(0)
+                        .matching(|card: CardInfo<State>| *card.base() == BaseCard::Basic);
+                    let requirements = vec![requirement.clone()];
+
+                    assert!(live_game
+                        .check_targets(&requirements, &[basic.into()])
+                        .await
+                        .is_ok());
+                    assert!(matches!(
+                        live_game.check_targets(&requirements, &[]).await,
+                        Err(TargetError::WrongCount {
+                            count: 0,
+                            expected: 1
+                        })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .check_targets(
+                                &[requirement.clone(), requirement],
+                                &[basic.into(), basic.into()]
+                            )
+                            .await,
+                        Err(TargetError::Duplicate { .. })
+                    ));
+
+                    for card in [other, discarded] {
+                        assert!(matches!(
+                            live_game.check_targets(&requirements, &[card.into()]).await,
+                            Err(TargetError::Illegal { index: 0, .. })
+                        ));
+                    }
+
+                    assert!(matches!(
+                        live_game
+                            .check_targets(&requirements, &[destroyed.into()])
+                            .await,
+                        Err(TargetError::Stale { .. })
+                    ));
+
+                    // Secret targets can only be rejected once their owner reveals whether they're legal.
+                    assert!(live_game
+                        .state
+                        .check_targets(&requirements, &secret)
+                        .is_ok());
+                    assert!(matches!(
+                        live_game.check_targets(&requirements, &secret).await,
+                        Err(TargetError::Illegal { index: 0, .. })
+                    ));
+
+                    // Two pointers to the same secret card are the same target.
+                    let id = live_game
+                        .reveal_from_card(secret[0], |info| info.instance.id())
+                        .await;
+                    let alias = live_game
+                        .new_secret_pointers(0, move |mut secret| secret.new_pointer(id))
+                        .await[0];
+
+                    let pair = [requirements[0].clone(), requirements[0].clone()];
+
+                    assert!(live_game
+                        .state
+                        .check_targets(&pair, &[secret[0], alias])
+                        .is_ok());
+                    assert!(matches!(
+                        live_game.check_targets(&pair, &[secret[0], alias]).await,
+                        Err(TargetError::Duplicate { .. })
+                    ));
+                }
+                Action::Resources => {
+                    assert_eq!(live_game.adjust_resource(0, Resource::Health, 20), 20);
+                    assert_eq!(live_game.adjust_resource(0, Resource::Health, -3), 17);
+                    assert_eq!(live_game.adjust_resource(1, Resource::Mana, -1), -1);
+                }
+                Action::ModifyPlayerSecret => {
+                    live_game.modify_player_secret(0, |mut secret| {
+                        secret.charges += 1;
+
+                        (secret.log)(CardEvent::GameEvent {
+                            event: Event::Custom,
+                        });
+
+                        (secret.charges > 1).then_some(Event::Custom)
+                    });
+                }
+                Action::ReturnToOwner => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 1, Zone::Field).await.unwrap();
+
+                    assert_eq!(live_game.owner(card), 1);
+                    assert_eq!(
+                        live_game
+                            .reveal_from_card(card, |info| info.original_owner())
+                            .await,
+                        0
+                    );
+
+                    let secret = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await[0];
+                    let (_, id) = live_game.move_card(secret, 0, Zone::Field).await.unwrap();
+                    let secret = id.unwrap();
+
+                    live_game
+                        .return_to_owner(card, Zone::Hand { public: true })
+                        .await
+                        .unwrap();
+                    live_game
+                        .return_to_owner(secret, Zone::Graveyard)
+                        .await
+                        .unwrap();
+
+                    assert_eq!(live_game.player_cards(0).hand(), &vec![Some(card)]);
+                    assert_eq!(live_game.player_cards(1).graveyard(), &vec![secret]);
+                }
+                Action::DeferLogs => {
+                    live_game.context.mutate_secret(0, |secret| {
+                        secret.secret.defer_log(CardEvent::GameEvent {
+                            event: Event::Custom,
+                        });
+                    });
+
+                    live_game.roll(6).await;
+                    live_game.flush_deferred_logs();
+                }
+                Action::SourceLocations => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.move_card(cards[2], 0, Zone::Field).await.unwrap();
+                    live_game
+                        .move_card(cards[1], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                    live_game
+                        .move_card(cards[1], 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+                }
+                Action::CanMoveCard => {
+                    live_game.config_mut().zone_capacities = vec![(Zone::Field.into(), 1)];
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    let other = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    assert!(live_game.can_move_card(card, 0, Zone::Field).is_ok());
+                    assert!(matches!(
+                        live_game.can_move_card(
+                            card,
+                            0,
+                            Zone::Attachment {
+                                parent: card.into()
+                            }
+                        ),
+                        Err(MoveCardError::CannotAttachToSelf { .. })
+                    ));
+
+                    // Checking doesn't move anything.
+                    assert!(live_game.player_cards(0).field().is_empty());
+
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    assert!(matches!(
+                        live_game.can_move_card(other, 0, Zone::Field),
+                        Err(MoveCardError::ZoneFull { .. })
+                    ));
+
+                    live_game.destroy_instance(card).await.unwrap();
+
+                    assert!(matches!(
+                        live_game.can_move_card(card, 0, Zone::Graveyard),
+                        Err(MoveCardError::DustedCard { .. })
+                    ));
+                    assert!(matches!(
+                        live_game.move_card(card, 0, Zone::Graveyard).await,
+                        Err(MoveCardError::DustedCard { .. })
+                    ));
+                }
+                Action::MoveCardErrors => {
+                    let card = live_game
+                        .new_card(
+                            0,
+                            BaseCard::WithAttachment,
+                            BaseCard::WithAttachment.attachment(),
+                        )
+                        .await
+                        .unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    let attachment = live_game.attachment_of(card).unwrap();
+
+                    assert!(matches!(
+                        live_game
+                            .move_card(
+                                card,
+                                0,
+                                Zone::Attachment {
+                                    parent: attachment.into()
+                                }
+                            )
+                            .await,
+                        Err(MoveCardError::AttachmentCycle { .. })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .move_card(InstanceID::from_raw(1000), 0, Zone::Graveyard)
+                            .await,
+                        Err(MoveCardError::CardDoesNotExist { .. })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .move_card(
+                                attachment,
+                                0,
+                                Zone::Attachment {
+                                    parent: InstanceID::from_raw(1000).into()
+                                }
+                            )
+                            .await,
+                        Err(MoveCardError::CardDoesNotExist { .. })
+                    ));
+
+                    // Nothing moved.
+                    assert_eq!(live_game.player_cards(0).field(), &vec![card]);
+                    assert_eq!(live_game.attachment_of(card), Some(attachment));
+
+                    // Cycles are found through pointers in secrets too.
+                    let secret = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(
+                                BaseCard::WithAttachment,
+                                BaseCard::WithAttachment.attachment(),
+                            );
+                        })
+                        .await;
+                    let attachment = live_game.reveal_attachment(secret[0]).await.unwrap();
+
+                    assert!(matches!(
+                        live_game
+                            .move_card(secret[0], 0, Zone::Attachment { parent: attachment })
+                            .await,
+                        Err(MoveCardError::AttachmentCycle { .. })
+                    ));
+
+                    // Nothing moved.
+                    let unmoved = live_game.reveal_attachment(secret[0]).await.unwrap();
+                    assert!(live_game.reveal_if_cards_eq(unmoved, attachment).await);
+                }
+                Action::UnifiedErrors => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    let result: Result<(), Error> = async {
+                        live_game.move_card(card, 0, Zone::Field).await?;
+                        live_game
+                            .move_card(
+                                card,
+                                0,
+                                Zone::Attachment {
+                                    parent: card.into(),
+                                },
+                            )
+                            .await?;
+
+                        Ok(())
+                    }
+                    .await;
+                    let error = result.unwrap_err();
+
+                    assert!(matches!(
+                        error,
+                        Error::MoveCard(MoveCardError::CannotAttachToSelf { .. })
+                    ));
+                    assert_eq!(error.card().and_then(|card| card.id()), Some(card));
+                    assert_eq!(error.player(), None);
+                    // Reporters print the wrapped error once, as the source.
+                    assert_eq!(error.to_string(), "cannot move a card");
+                    assert_eq!(
+                        std::error::Error::source(&error).map(|source| source.to_string()),
+                        Some(format!("cannot attach {:?} to itself", Card::from(card)))
+                    );
+                }
+                Action::TryRevealFromCard => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    // The pointer is followed to the other player's secret.
+                    live_game
+                        .move_card(cards[0], 1, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        live_game
+                            .try_reveal_from_card(cards[0], |info| info.owner)
+                            .await
+                            .unwrap(),
+                        1
+                    );
+
+                    live_game.destroy_instance(cards[1]).await.unwrap();
+                    assert!(matches!(
+                        live_game
+                            .try_reveal_from_card(cards[1], |info| info.owner)
+                            .await,
+                        Err(RevealCardError::CardDoesNotExist { .. })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .try_reveal_from_card(InstanceID::from_raw(1000), |info| info.owner)
+                            .await,
+                        Err(RevealCardError::CardDoesNotExist { .. })
+                    ));
+                }
+                Action::SetPanicPolicy(policy) => live_game.set_panic_policy(policy),
+                Action::SetLimboPolicy(policy) => live_game.set_limbo_policy(policy),
+                Action::LeaveInLimbo => {
+                    live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                }
+                Action::Violate => {
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    live_game.modify_player_secret(0, |mut secret| {
+                        secret.charges += 1;
+
+                        None
+                    });
+
+                    panic!("invariant violated");
+                }
+                Action::PanicAfterReveal => {
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game
+                        .move_card(cards[0], 1, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    let owner = live_game
+                        .reveal_from_card(cards[0], |info| info.owner)
+                        .await;
+
+                    assert_eq!(owner, 1);
+
+                    panic!("invariant violated after a reveal");
+                }
+                Action::Metrics => {
+                    live_game.set_measure_reveals(true);
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    let before = live_game.action_metrics();
+
+                    live_game
+                        .reveal_from_card(cards[0], |info| info.owner)
+                        .await;
+
+                    let after = live_game.action_metrics();
+                    assert_eq!(after.reveals, before.reveals + 1);
+                    assert!(after.revealed_bytes > before.revealed_bytes);
+                    assert_eq!(after.mutations, before.mutations);
+
+                    live_game.modify_player_secret(0, |mut secret| {
+                        secret.charges += 1;
+
+                        None
+                    });
+
+                    assert_eq!(live_game.action_metrics().mutations, after.mutations + 1);
+
+                    let before = live_game.action_metrics();
+
+                    live_game
+                        .transform_card(cards[0], BaseCard::Basic, TransformPolicy::Keep)
+                        .await;
+
+                    // Including the `GameContext::mutate_secret_or_log` logging the transformation.
+                    assert_eq!(live_game.action_metrics().mutations, before.mutations + 3);
+
+                    // Moving a card into a secret inserts it and its attachment in one mutation.
+                    let mut moved = vec![];
+
+                    for attachment in [None, Some(BaseCard::Attachment)] {
+                        let card = live_game
+                            .new_card(0, BaseCard::Basic, attachment)
+                            .await
+                            .unwrap();
+
+                        let before = live_game.action_metrics();
+
+                        live_game.move_card(card, 0, Zone::Deck).await.unwrap();
+
+                        moved.push(live_game.action_metrics().mutations - before.mutations);
+                    }
+
+                    assert_eq!(moved[0], moved[1]);
+                }
+                Action::DestroyedPointer => {
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                    live_game
+                        .move_card(cards[0], 0, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+
+                    live_game.destroy_instance(cards[0]).await.unwrap();
+
+                    // New cards get new IDs, and the old pointer stays invalid.
+                    let new = live_game.new_card(1, BaseCard::Basic, None).await.unwrap();
+
+                    assert!(matches!(
+                        live_game.move_card(cards[0], 0, Zone::Field).await,
+                        Err(MoveCardError::CardDoesNotExist { .. })
+                    ));
+                    assert!(matches!(
+                        live_game
+                            .try_reveal_from_card(cards[0], |info| info.owner)
+                            .await,
+                        Err(RevealCardError::CardDoesNotExist { .. })
+                    ));
+                    assert!(live_game.player_cards(0).field().is_empty());
+                    assert_eq!(live_game.owner(new), 1);
+                }
+                Action::RevealEachIf => {
+                    let basic: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::Basic);
+                    let with_attachment: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::WithAttachment);
+
+                    let conditions = vec![
+                        (0, ZoneSet::from(Zone::Graveyard), basic.clone()),
+                        (1, Zone::Hand { public: false }.into(), with_attachment),
+                        (1, Zone::Deck.into(), basic),
+                    ];
+
+                    assert_eq!(
+                        live_game.reveal_each_if(conditions.clone()).await,
+                        vec![true, true, false]
+                    );
+
+                    live_game.set_reveal_recording(true);
+                    assert!(!live_game.reveal_if(conditions, Quantifier::Every).await);
+                }
+                Action::Discard => {
+                    let cards = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game
+                        .prompt(1, "discard", Choice::Cards { cards, count: 1 })
+                        .unwrap();
+                }
+                Action::Respond { .. } => unreachable!("responses are handled by the crate"),
+                Action::Summarize => {
+                    live_game.config_mut().action_summaries = true;
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                    live_game
+                        .modify_card(card, |mut card| {
+                            card.was_cloned = true;
+                        })
+                        .await;
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game
+                        .reveal_from_card(cards[0], |info| info.instance.id())
+                        .await;
+
+                    let summary = live_game.action_summary();
+
+                    assert_eq!(summary.key, None);
+                    assert_eq!(summary.moved, 1);
+                    assert!(summary.created >= 2);
+                    assert_eq!(summary.modified, 1);
+                    assert!(summary.revealed > 0);
+                }
+                Action::CoalesceEvents => {
+                    live_game.set_coalesce_events(true);
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    for _ in 0..3 {
+                        live_game
+                            .modify_card(card, |mut card| {
+                                card.attachment_was_attached += 1;
+                            })
+                            .await;
+                    }
+                }
+                Action::DrainLimbo => {
+                    live_game.set_limbo_policy(LimboPolicy::Forbid);
+
+                    live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    assert!(live_game
+                        .drain_limbo(0, Zone::Graveyard)
+                        .await
+                        .into_iter()
+                        .all(|result| result.is_ok()));
+
+                    assert!(live_game.public_limbo_cards(0).is_empty());
+                    assert_eq!(
+                        live_game
+                            .secret_zone_size(0, Zone::Limbo { public: false })
+                            .await,
+                        Some(0)
+                    );
+                    assert_eq!(live_game.graveyard_cards(0).len(), 2);
+                }
+                Action::FlagLimbo => {
+                    live_game.set_limbo_policy(LimboPolicy::Flag);
+
+                    live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+                }
+                Action::CorrelateActions => {
+                    live_game.set_action_correlation(true);
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                }
+                Action::EstimateReveals => {
+                    let secret = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    // Publishing player 1's card reveals it from their secret.
+                    live_game
+                        .move_card(secret[0], 0, Zone::Field)
+                        .await
+                        .unwrap();
+                }
+                Action::RevealIfSetup => {
+                    let public = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game
+                        .move_card(public, 0, Zone::Graveyard)
+                        .await
+                        .unwrap();
+
+                    let secret = live_game
+                        .new_secret_cards(1, |mut secret| {
+                            secret.new_card(BaseCard::WithAttachment, None);
+                        })
+                        .await;
+                    live_game
+                        .move_card(secret[0], 1, Zone::Hand { public: false })
+                        .await
+                        .unwrap();
+                }
+                Action::RevealIfPublic => {
+                    let basic: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::Basic);
+                    let with_attachment: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::WithAttachment);
+
+                    // decided by player 0's graveyard, so player 1's hand isn't revealed
+                    assert!(
+                        live_game
+                            .reveal_if(
+                                vec![
+                                    (0, Zone::Graveyard.into(), basic.clone()),
+                                    (1, Zone::Hand { public: false }.into(), with_attachment),
+                                ],
+                                Quantifier::Any,
+                            )
+                            .await
+                    );
+                    assert!(
+                        !live_game
+                            .reveal_if(
+                                vec![
+                                    (0, Zone::Graveyard.into(), basic.clone()),
+                                    (1, Zone::Field.into(), basic),
+                                ],
+                                Quantifier::Every,
+                            )
+                            .await
+                    );
+                }
+                Action::RevealIfSecret => {
+                    let basic: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::Basic);
+                    let with_attachment: CardPredicate<State> =
+                        Rc::new(|card| *card.base() == BaseCard::WithAttachment);
+
+                    let hand_or_graveyard: ZoneSet =
+                        [Zone::Hand { public: false }, Zone::Graveyard]
+                            .into_iter()
+                            .collect();
+
+                    assert!(
+                        live_game
+                            .reveal_if(
+                                vec![
+                                    (0, Zone::Graveyard.into(), with_attachment.clone()),
+                                    (1, hand_or_graveyard, with_attachment.clone()),
+                                    (1, ZoneSet::all(), basic.clone()),
+                                ],
+                                Quantifier::Any,
+                            )
+                            .await
+                    );
+                    assert!(
+                        !live_game
+                            .reveal_if(
+                                vec![
+                                    (0, Zone::Graveyard.into(), basic),
+                                    (1, hand_or_graveyard, with_attachment.clone()),
+                                    (1, Zone::Deck.into(), with_attachment),
+                                ],
+                                Quantifier::Every,
+                            )
+                            .await
+                    );
+                }
+                Action::CardDatabase => {
+                    #[derive(Debug)]
+                    struct Database;
+
+                    impl CardDatabase<State> for Database {
+                        fn validate_card(&self, base: &BaseCard) -> Result<(), IllegalCardError> {
+                            match base {
+                                BaseCard::WithAttachment => Err(IllegalCardError::Banned {
+                                    card: format!("{:?}", base),
+                                }),
+                                _ => Ok(()),
+                            }
+                        }
+                    }
+
+                    live_game.set_card_database(Some(Arc::new(Database)));
+
+                    assert!(live_game.new_card(0, BaseCard::Basic, None).await.is_ok());
+                    assert!(matches!(
+                        live_game
+                            .new_card(0, BaseCard::Basic, Some(BaseCard::WithAttachment))
+                            .await,
+                        Err(IllegalCardError::Banned { .. })
+                    ));
+                    assert_eq!(live_game.public_limbo_cards(0).len(), 1);
+
+                    assert!(matches!(
+                        live_game
+                            .new_card_in(0, BaseCard::WithAttachment, None, Zone::Field)
+                            .await,
+                        Err(MoveCardError::IllegalCard(IllegalCardError::Banned { .. }))
+                    ));
+                    assert_eq!(live_game.instances(), 1);
+
+                    assert!(live_game
+                        .replace_deck(0, vec![BaseCard::Basic, BaseCard::WithAttachment])
+                        .await
+                        .is_err());
+                    assert_eq!(live_game.player_cards(0).deck(), 0);
+
+                    live_game
+                        .replace_deck(0, vec![BaseCard::Basic, BaseCard::Basic])
+                        .await
+                        .unwrap();
+                    assert_eq!(live_game.player_cards(0).deck(), 2);
+                }
+                Action::SpectatorEvents => {
+                    live_game.set_spectator_events(true);
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.move_card(cards[0], 0, Zone::Field).await.unwrap();
+                }
+                Action::BatchChunks => {
+                    live_game.config_mut().batch_chunk_size = Some(2);
+
+                    let mut cards: Vec<Card> = Vec::new();
+
+                    for _ in 0..5 {
+                        cards.push(
+                            live_game
+                                .new_card(0, BaseCard::Basic, None)
+                                .await
+                                .unwrap()
+                                .into(),
+                        );
+                    }
+
+                    let mut starts = Vec::new();
+                    let mut next = Some(0);
+
+                    while let Some(start) = next {
+                        starts.push(start);
+
+                        let chunk = live_game
+                            .move_cards_chunk(&cards, start, 0, Zone::Graveyard)
+                            .await;
+
+                        assert_eq!(chunk.results.len(), (cards.len() - start).min(2));
+                        assert!(chunk.results.iter().all(Result::is_ok));
+
+                        next = chunk.next;
+                    }
+
+                    assert_eq!(starts, vec![0, 2, 4]);
+                    assert_eq!(live_game.player_cards(0).graveyard().len(), 5);
+
+                    let chunk = live_game
+                        .reveal_from_cards_chunk(&cards, 4, |info| info.owner)
+                        .await;
+
+                    assert_eq!(chunk.results.len(), 1);
+                    assert!(chunk.next.is_none());
+
+                    // Without a chunk size, the whole batch is one chunk.
+                    live_game.config_mut().batch_chunk_size = None;
+
+                    let chunk = live_game.move_cards_chunk(&cards, 0, 0, Zone::Hand).await;
+
+                    assert_eq!(chunk.results.len(), 5);
+                    assert!(chunk.next.is_none());
+                }
+                Action::StartBatch => {
+                    live_game.config_mut().batch_chunk_size = Some(2);
+
+                    let mut cards: Vec<Card> = Vec::new();
+
+                    for _ in 0..5 {
+                        let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                        live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                        cards.push(card.into());
+                    }
+
+                    live_game.batch = cards;
+                    live_game.next_chunk = Some(0);
+                }
+                Action::ContinueBatch => {
+                    let start = live_game.next_chunk.expect("no batch to continue");
+                    let cards = live_game.batch.clone();
+
+                    let chunk = live_game
+                        .move_cards_chunk(&cards, start, 0, Zone::Graveyard)
+                        .await;
+
+                    assert!(chunk.results.iter().all(Result::is_ok));
+
+                    live_game.next_chunk = chunk.next;
+                }
+                Action::RecordReveals { secret } => {
+                    if secret {
+                        let cards = live_game
+                            .new_secret_cards(0, |mut secret| {
+                                secret.new_card(BaseCard::Basic, None);
+                            })
+                            .await;
+
+                        // Creating secret cards reveals how many were created, so only record from here.
+                        live_game.set_reveal_recording(true);
+                        live_game.reveal_zone(cards[0]).await;
+                    } else {
+                        live_game.set_reveal_recording(true);
+
+                        let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                        live_game.move_card(card, 0, Zone::Field).await.unwrap();
+                        live_game.reveal_zone(card).await;
+                    }
+                }
+                Action::CopyCardEvents => {
+                    let card = live_game
+                        .new_card(0, BaseCard::WithAttachment, Some(BaseCard::Attachment))
+                        .await
+                        .unwrap();
+
+                    live_game.copy_card(card, true).await;
+
+                    let cards = live_game
+                        .new_secret_cards(0, |mut secret| {
+                            secret.new_card(BaseCard::Basic, None);
+                        })
+                        .await;
+
+                    live_game.copy_card(cards[0], false).await;
+                }
+                Action::ZoneWatermarks => {
+                    assert_eq!(live_game.zone_version(0, Zone::Field), None);
+
+                    live_game.set_zone_watermarks(true);
+
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+
+                    assert_eq!(
+                        live_game.zone_version(0, Zone::Limbo { public: true }),
+                        Some(1)
+                    );
+
+                    live_game.move_card(card, 0, Zone::Field).await.unwrap();
+
+                    assert_eq!(
+                        live_game.zone_version(0, Zone::Limbo { public: true }),
+                        Some(2)
+                    );
+                    assert_eq!(live_game.zone_version(0, Zone::Field), Some(1));
+                    assert_eq!(live_game.zone_version(1, Zone::Field), Some(0));
+
+                    let snapshot = live_game.zone_snapshot(0, Zone::Field).unwrap();
+
+                    assert_eq!(snapshot.version, 1);
+                    assert_eq!(snapshot.cards, vec![Some(card)]);
+                    assert!(live_game
+                        .zone_snapshot(0, Zone::Limbo { public: false })
+                        .is_none());
+
+                    // Each pile has its own version.
+                    let card = live_game.new_card(0, BaseCard::Basic, None).await.unwrap();
+                    live_game
+                        .move_card(card, 0, Zone::Pile { pile: PileID(1) })
+                        .await
+                        .unwrap();
+
+                    assert_eq!(
+                        live_game.zone_version(0, Zone::Pile { pile: PileID(1) }),
+                        Some(1)
+                    );
+                    assert_eq!(
+                        live_game.zone_version(0, Zone::Pile { pile: PileID(0) }),
+                        Some(0)
+                    );
+                    assert_eq!(
+                        live_game
+                            .zone_snapshot(0, Zone::Pile { pile: PileID(1) })
+                            .unwrap()
+                            .cards,
+                        vec![None]
+                    );
+                }
+            }
+        })
+    }
+    fn on_attach(parent: &mut CardInstance<Self>, new_attach: &CardInstance<Self>) {
+        assert_eq!(parent.attachment(), Some(new_attach.id()));
+        parent.attachment_was_attached += 1;
+    }
+    fn on_detach(parent: &mut CardInstance<Self>, _old_attach: &CardInstance<Self>) {
+        assert!(parent.attachment().is_none());
+        parent.attachment_was_detached += 1;
+    }
+
+    fn action_key(action: &Self::Action) -> Option<String> {
+        matches!(action, Action::Summarize).then(|| "summary".to_string())
+    }
+
+    fn concession(action: &Self::Action) -> Option<Player> {
+        match action {
+            Action::Concede { player } => Some(*player),
+            _ => None,
+        }
+    }
+
+    fn prompt_response(action: &Self::Action) -> Option<PromptResponse> {
+        match action {
+            Action::Respond { response } => Some(response.clone()),
+            _ => None,
+        }
+    }
+
+    fn resume<'a>(
+        game: &'a mut CardGame<Self>,
+        prompt: Prompt,
+        response: PromptResponse,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async move {
+            if prompt.key() == "discard" {
+                for card in prompt.chosen_cards(&response) {
+                    game.move_card(card, prompt.player(), Zone::Graveyard)
+                        .await
+                        .unwrap();
+                }
+            }
+        })
+    }
+
+    /// Hides the keys of other players' `secret-` commitments.
+    fn redact_event(event: &CardEvent<Self>, viewer: Player) -> Option<CardEvent<Self>> {
+        match event {
+            CardEvent::Commit { player, key }
+                if *player != viewer && key.starts_with("secret-") =>
+            {
+                Some(CardEvent::Commit {
+                    player: *player,
+                    key: "secret-".to_string(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn authorize_move(
+        _game: &GameState<Self>,
+        _player: Player,
+        _card: Card,
+        _to_player: Player,
+        to_zone: Zone,
+    ) -> bool {
+        to_zone.is_graveyard()
+    }
+
+    fn field_order(a: CardInfo<Self>, b: CardInfo<Self>) -> Ordering {
+        if INCONSISTENT_FIELD_ORDER.with(Cell::get) {
+            Ordering::Less
+        } else if CLONED_FIRST_FIELD_ORDER.with(Cell::get) {
+            b.instance.was_cloned.cmp(&a.instance.was_cloned)
+        } else if WITH_ATTACHMENT_FIRST_FIELD_ORDER.with(Cell::get) {
+            let with_attachment = |info: &CardInfo<Self>| *info.base() == BaseCard::WithAttachment;
+
+            with_attachment(&b).cmp(&with_attachment(&a))
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Puts cloned cards last instead while any modifier is active.
+    fn field_order_with(state: &GameState<Self>, a: CardInfo<Self>, b: CardInfo<Self>) -> Ordering {
+        if CLONED_FIRST_FIELD_ORDER.with(Cell::get) && !state.modifiers().is_empty() {
+            a.instance.was_cloned.cmp(&b.instance.was_cloned)
+        } else {
+            Self::field_order(a, b)
+        }
+    }
+
+    fn resolve<'a>(
+        game: &'a mut CardGame<Self>,
+        card: Card,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async move {
+            let player = game.reveal_owner(card).await.unwrap();
+
+            game.move_card(card, player, Zone::Graveyard).await.unwrap();
+        })
+    }
+
+    fn on_phase_start<'a>(
+        game: &'a mut CardGame<Self>,
+        player: Player,
+        phase: Phase,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async move {
+            if game.hooks {
+                game.context.log(CardEvent::GameEvent {
+                    event: Event::PhaseStart(phase),
+                });
+
+                // the draw phase draws a card and moves on
+                if phase == Phase::Draw {
+                    game.draw_card(player).await;
+                    game.set_phase(Phase::Main).await.unwrap();
+                }
+            }
+        })
+    }
+
+    fn on_phase_end<'a>(
+        game: &'a mut CardGame<Self>,
+        _player: Player,
+        phase: Phase,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async move {
+            if game.hooks {
+                game.context.log(CardEvent::GameEvent {
+                    event: Event::PhaseEnd(phase),
+                });
+            }
+        })
+    }
+
+    fn after_attach<'a>(
+        game: &'a mut CardGame<Self>,
+        _parent: Card,
+        _attachment: Card,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async move {
+            if game.hooks {
+                game.context.log(CardEvent::GameEvent {
+                    event: Event::AfterAttach,
+                });
+
+                // when equipped, draw a card
+                game.draw_card(0).await;
+            }
+        })
+    }
+
+    fn after_detach<'a>(
+        game: &'a mut CardGame<Self>,
+        parent: Card,
+        _attachment: Card,
+    ) -> Pin<Box<dyn Future<Output = ()> + 'a>> {
+        Box::pin(async move {
+            if game.hooks {
+                game.context.log(CardEvent::GameEvent {
+                    event: Event::AfterDetach {
+                        secret: parent.pointer().is_some(),
+                    },
+                });
+
+                // re-attach to public parents, which runs `State::after_attach` once this hook is done
+                if let Card::ID(..) = parent {
+                    let attachment = game.new_card(0, BaseCard::Attachment, None).await.unwrap();
+
+                    game.move_card(attachment, 0, Zone::Attachment { parent })
+                        .await
+                        .unwrap();
+                }
+            }
+        })
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default, Debug)]
+struct Secret {
+    charges: u32,
+}
+
+impl card_movement_simulator::Secret<BaseCard> for Secret {
+    fn reset_card(&self, _id: &InstanceID, _base: BaseCard) -> CardState {
+        <BaseCard as card_movement_simulator::BaseCard>::new_card_state(&BaseCard::Basic, None)
+    }
+    fn attachment(&self, _id: &InstanceID, _base: BaseCard) -> Option<BaseCard> {
+        None
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord,
+)]
+enum Resource {
+    Mana,
+    Health,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+enum BaseCard {
+    Basic,
+    WithAttachment,
+    Attachment,
+}
+impl BaseCard {
+    pub fn attachment(&self) -> Option<Self> {
+        match self {
+            Self::Basic => None,
+            Self::WithAttachment => Some(Self::Attachment),
+            Self::Attachment => None,
+        }
+    }
+}
+impl card_movement_simulator::BaseCard for BaseCard {
+    type CardState = CardState;
+
+    fn new_card_state(&self, _parent: Option<&Self::CardState>) -> Self::CardState {
+        CardState {
+            attachment_was_detached: 0,
+            attachment_was_attached: 0,
+            was_cloned: false,
+        }
+    }
+
+    // fn reset_card(&self, _card: &Self::CardState) -> Self::CardState {
+    //     self.new_card_state(None)
+    // }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+struct CardState {
+    attachment_was_detached: usize,
+    attachment_was_attached: usize,
+    was_cloned: bool,
+}
+
+impl card_movement_simulator::CardState for CardState {
+    fn eq(&self, other: &Self) -> bool {
+        self.was_cloned == other.was_cloned
+            && self.attachment_was_attached == other.attachment_was_attached
+            && self.attachment_was_detached == other.attachment_was_detached
+    }
+
+    fn diff(&self, before: &Self) -> Option<Vec<u8>> {
+        // only changes to `was_cloned` are sent as deltas
+        if self.attachment_was_attached == before.attachment_was_attached
+            && self.attachment_was_detached == before.attachment_was_detached
+        {
+            serde_cbor::to_vec(&self.was_cloned).ok()
+        } else {
+            None
+        }
+    }
+
+    fn patch(&mut self, diff: &[u8]) -> bool {
+        serde_cbor::from_slice(diff)
+            .map(|was_cloned| self.was_cloned = was_cloned)
+            .is_ok()
+    }
+    fn copy_card(&self) -> CardState {
+        let mut copy = self.clone();
+        copy.was_cloned = true;
+        copy
+    }
+
+    /// Modifiers override `was_cloned`.
+    fn apply_modifier(&mut self, modifier: &[u8]) {
+        if let Ok(was_cloned) = serde_cbor::from_slice(modifier) {
+            self.was_cloned = was_cloned;
+        }
+    }
+
+    fn project(&self, projection: &str) -> Option<Vec<u8>> {
+        match projection {
+            "wasCloned" => serde_cbor::to_vec(&self.was_cloned).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// A modifier overriding `CardState::was_cloned`.
+fn modifier(was_cloned: bool) -> Vec<u8> {
+    serde_cbor::to_vec(&was_cloned).unwrap()
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+enum Action {
+    Move {
+        card_ptr_bucket: Option<Player>, // 3
+        base_card_type: BaseCard,        // 2
+        from_player: Player,             // 2
+        to_player: Player,               // 2
+        from_zone: Zone,                 // 11
+        to_zone: Zone,                   // 11
+    },
+    Detach {
+        parent_zone: Zone,                     // 11
+        attachment_ptr_bucket: Option<Player>, // 3
+        to_player: Player,                     // 2
+        to_zone: Zone,                         // 11
+    },
+    Attach {
+        parent_base_card: BaseCard,        // 2
+        parent_ptr_bucket: Option<Player>, // 3
+        parent_zone: Zone,                 // 11
+        card_ptr_bucket: Option<Player>,   // 3
+        card_owner: Player,                // 2
+        card_zone: Zone,                   // 11
+    },
+    AttachFromAttached {
+        parent_base_card: BaseCard,        // 2
+        parent_ptr_bucket: Option<Player>, // 3
+        parent_zone: Zone,                 // 11
+        card_ptr_bucket: Option<Player>,   // 3
+        card_owner: Player,                // 2
+        card_zone: Zone,                   // 11
+    },
+    CopyCard {
+        card_ptr_bucket: Option<Player>, // 3
+        card_zone: Zone,                 // 11
+        base_card_type: BaseCard,        // 2
+        deep: bool,                      // 2
+    },
+    ResetCard {
+        card_ptr_bucket: Option<Player>,
+        base_card_type: BaseCard,
+        attachment_type: Option<BaseCard>,
+        card_zone: Zone,
+    },
+    ReplacingAttachOnSecretCardDoesNotLeakInfo,
+    OpaquePointerAssociationDoesntHoldThroughDraw,
+    InstanceFromIDSetup,
+    RevealSecretHandCard,
+    SecretCardPadding {
+        created: usize,
+    },
+    SecretZoneSize,
+    StrictEventOrdering,
+    LinkCards,
+    RedactedAttachmentPresence,
+    RedactedAttachmentAcrossBuckets,
+    RevealLocation,
+    FindSecretCard,
+    ReplaceDeck,
+    PlayTopCard,
+    NonAdjacentAttachment,
+    AsyncHooks,
+    ModifyCardDelta,
+    ModifyAllCards,
+    FieldOrderConflict,
+    MatchConfig,
+    Census,
+    AuthorizeMoves,
+    AuthorizePointerMoves,
+    RevealAfterConcession,
+    RevealAttachment,
+    Modifiers,
+    TemporaryModifications,
+    Turns,
+    Stack,
+    Commitments,
+    RandomCardsWeighted,
+    RandomCard,
+    RandomOutcomes,
+    PeekTopOfDeck,
+    RevealProjection,
+    ForgetProjection,
+    RedactEvents,
+    SuppressPointerEvents,
+    SkipSort,
+    FieldOrderWithState,
+    PositionedField,
+    RecoverFromDust,
+    DestroyInstance,
+    AttachResult,
+    SwapAttachments,
+    SwapCards,
+    NewCardIn,
+    Tokens,
+    TransformCard,
+    TransformFieldCard,
+    FaceDown,
+    RevealHandCard,
+    ConcealHandCard,
+    Piles,
+    Sideboard,
+    EndGame {
+        winner: Player,
+    },
+    Concede {
+        player: Player,
+    },
+    Checksum,
+    CompareCards,
+    SameZone,
+    CountMatching,
+    ChooseRandomMatching,
+    Targets,
+    Resources,
+    ModifyPlayerSecret,
+    ReturnToOwner,
+    DeferLogs,
+    SourceLocations,
+    CanMoveCard,
+    MoveCardErrors,
+    UnifiedErrors,
+    TryRevealFromCard,
+    SetPanicPolicy(PanicPolicy),
+    SetLimboPolicy(LimboPolicy),
+    LeaveInLimbo,
+    Violate,
+    PanicAfterReveal,
+    Metrics,
+    DestroyedPointer,
+    RevealEachIf,
+    Discard,
+    Respond {
+        response: PromptResponse,
+    },
+    Summarize,
+    CoalesceEvents,
+    DrainLimbo,
+    FlagLimbo,
+    CorrelateActions,
+    EstimateReveals,
+    ZoneWatermarks,
+    CopyCardEvents,
+    RecordReveals {
+        secret: bool,
+    },
+    CardDatabase,
+    RevealIfSetup,
+    RevealIfPublic,
+    RevealIfSecret,
+    SpectatorEvents,
+    BatchChunks,
+    StartBatch,
+    ContinueBatch,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Default)]
+pub struct ID([u8; 8]);
+
+impl card_movement_simulator::ID for ID {
+    fn deserialize(data: &mut &[u8]) -> Result<Self, String> {
+        if data.len() < 8 {
+            return Err("data.len() < 8".to_string());
+        }
+
+        let id = data[..8].try_into().map_err(|error| format!("{}", error))?;
+        *data = &data[8..];
+        Ok(Self(id))
+    }
+
+    fn serialize(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+#[test]
+fn replacing_attach_on_secret_card_does_not_leak_existence_of_current_attachment() {
+    let reveals = Tester::new(
+        GameState::<State>::default(),
+        [
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        Default::default(),
+        |_, _, _| {},
+        |_, _, _| {},
+        false,
+    )
+    .unwrap()
+    .apply(Some(0), &Action::ReplacingAttachOnSecretCardDoesNotLeakInfo)
+    .unwrap();
+
+    println!("{:?}", reveals);
+
+    assert_eq!(reveals.len(), 0, "No reveals need to be made when attaching a public card to a secret card, even if it already has an attachment.")
+}
+
+#[test]
+fn opaque_pointer_association_does_not_hold_through_draw() {
+    Tester::new(
+        GameState::<State>::default(),
+        [
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        Default::default(),
+        |_, _, _| {},
+        |_, _, _| {},
+        false,
+    )
+    .unwrap()
+    .apply(
+        Some(0),
+        &Action::OpaquePointerAssociationDoesntHoldThroughDraw,
+    )
+    .unwrap();
+}
+
+#[test]
+fn public_instance_from_id() {
+    let mut tester = Tester::new(
+        GameState::<State>::default(),
+        [
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        Default::default(),
+        |_, _, _| {},
+        |_, _, _| {},
+        false,
+    )
+    .unwrap();
+
+    tester.apply(Some(0), &Action::InstanceFromIDSetup).unwrap();
+
+    // This is an implementation detail.
+    // Constructing a public card in public limbo gives the attachment the ID after the parent card.
+
+    let id: InstanceID = InstanceID::from_raw(1);
+
+    assert!(id.instance(tester.state(), None).is_some());
+}
+
+#[test]
+fn secret_instance_from_id() {
+    let mut tester = Tester::new(
+        GameState::<State>::default(),
+        [
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        Default::default(),
+        |_, _, _| {},
+        |_, _, _| {},
+        false,
+    )
+    .unwrap();
+
+    tester.apply(Some(0), &Action::InstanceFromIDSetup).unwrap();
+
+    // This is an implementation detail.
+    // Constructing a public card in public limbo gives the parent card the ID after the attachment.
+
+    let id: InstanceID = InstanceID::from_raw(0);
+
+    assert!(id
+        .instance(tester.state(), Some(&tester.secret(0)))
+        .is_some());
+}
+
+#[test]
+fn secret_card_creation_is_padded() {
+    // Different real counts create the same number of public instances and pointers.
+    for created in [1, 3] {
+        let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+        tester
+            .apply(Some(0), &Action::SecretCardPadding { created })
+            .unwrap();
+    }
+}
+
+#[test]
+fn secret_zone_size() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::SecretZoneSize).unwrap();
+}
+
+#[test]
+fn strict_event_ordering() {
+    let (mut tester, owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::StrictEventOrdering).unwrap();
+
+    assert!(tester.state().strict_event_ordering());
+
+    let mut logs: Vec<_> = player_logs.try_borrow().unwrap()[0].clone();
+    logs.extend(owner_logs.try_borrow().unwrap().iter().cloned());
+
+    assert!(!logs.is_empty());
+    assert!(logs.iter().all(|event| event.sequence().is_some()));
+
+    let ordered = card_movement_simulator::replay::order_events(logs.iter().rev().cloned());
+
+    assert_eq!(ordered.len(), logs.len());
+    assert!(ordered
+        .iter()
+        .all(|event| !matches!(event, CardEvent::Sequenced { .. })));
+
+    let player_sequences: Vec<_> = player_logs.try_borrow().unwrap()[0]
+        .iter()
+        .map(|event| event.sequence().unwrap())
+        .collect();
+
+    assert!(player_sequences.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn link_cards() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::LinkCards).unwrap();
+
+    assert!(player_logs.try_borrow().unwrap()[0]
+        .iter()
+        .any(|event| matches!(event, CardEvent::UnlinkCards { .. })));
+}
+
+#[test]
+fn redacted_move_reveals_attachment_presence() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::RedactedAttachmentPresence)
+        .unwrap();
+
+    let has_attachment: Vec<_> = player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .filter_map(|event| match event {
+            CardEvent::MoveCard {
+                instance: None,
+                has_attachment,
+                ..
+            } => Some(*has_attachment),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(has_attachment, vec![Some(true), Some(false)]);
+
+    assert!(player_logs.try_borrow().unwrap()[0]
+        .iter()
+        .all(|event| !matches!(
+            event,
+            CardEvent::MoveCard {
+                has_attachment: Some(_),
+                ..
+            }
+        )));
+}
+
+#[test]
+fn moves_across_buckets_reveal_attachment_presence() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::RedactedAttachmentAcrossBuckets)
+        .unwrap();
+
+    let has_attachment: Vec<_> = player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .filter_map(|event| match event {
+            CardEvent::MoveCard {
+                to, has_attachment, ..
+            } if to.location.0.is_secret_hand() => Some(*has_attachment),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(has_attachment, vec![Some(true), Some(true)]);
+}
+
+#[test]
+fn reveal_location() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RevealLocation).unwrap();
+}
+
+#[test]
+fn find_secret_card() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::FindSecretCard).unwrap();
+}
+
+#[test]
+fn replace_deck() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ReplaceDeck).unwrap();
+
+    // Only the owner sees the old deck moving to secret dust.
+    let dusted = |player: usize| {
+        player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    CardEvent::MoveCard {
+                        from: CardLocation {
+                            location: Some((Zone::Deck, _)),
+                            ..
+                        },
+                        to: ExactCardLocation {
+                            location: (Zone::Dust { public: false }, _),
+                            ..
+                        },
+                        ..
+                    }
+                )
+            })
+            .count()
+    };
+
+    assert_eq!(dusted(0), 2);
+    assert_eq!(dusted(1), 0);
+}
+
+#[test]
+fn non_adjacent_attachment() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::NonAdjacentAttachment)
+        .unwrap();
+}
+
+#[test]
+fn async_hooks() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::AsyncHooks).unwrap();
+
+    for player in 0..2 {
+        let hooks: Vec<_> = player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .filter_map(|event| match event {
+                CardEvent::GameEvent { event } => Some(event.clone()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            hooks,
+            vec![
+                Event::AfterAttach,
+                Event::AfterDetach { secret: false },
+                Event::AfterAttach,
+                Event::AfterAttach,
+                Event::AfterDetach { secret: true },
+            ]
+        );
+    }
+}
+
+#[test]
+fn modify_card_delta() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ModifyCardDelta).unwrap();
+
+    let logs = player_logs.try_borrow().unwrap()[1].clone();
+    let mut modifications = logs.iter().filter(|event| {
+        matches!(
+            event,
+            CardEvent::ModifyCard { .. } | CardEvent::ModifyCardDelta { .. }
+        )
+    });
+
+    assert!(matches!(
+        modifications.next(),
+        Some(CardEvent::ModifyCardDelta { delta, .. }) if serde_cbor::from_slice(delta).ok() == Some(true)
+    ));
+    assert!(matches!(
+        modifications.next(),
+        Some(CardEvent::ModifyCard { instance }) if instance.attachment_was_attached == 1
+    ));
+    assert!(modifications.next().is_none());
+}
+
+#[test]
+fn modify_all_cards() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ModifyAllCards).unwrap();
+
+    for player in 0..2 {
+        let logs = player_logs.try_borrow().unwrap()[player].clone();
+        let sizes: Vec<_> = logs
+            .iter()
+            .skip_while(|event| !matches!(event, CardEvent::ModifyCards { .. }))
+            .filter_map(|event| match event {
+                CardEvent::ModifyCards { events } => {
+                    assert!(events.iter().all(|event| matches!(
+                        event,
+                        CardEvent::ModifyCardDelta { delta, .. } if serde_cbor::from_slice(delta).ok() == Some(true)
+                    )));
+
+                    Some(events.len())
+                }
+                CardEvent::ModifyCard { .. } | CardEvent::ModifyCardDelta { .. } => {
+                    panic!("unbatched {}", event)
+                }
+                _ => None,
+            })
+            .collect();
+
+        // The public card and its attachment, then the player's own secret card.
+        assert_eq!(sizes, vec![2, 1]);
+    }
+}
+
+#[cfg(feature = "field-order-check")]
+#[test]
+fn field_order_conflict() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    INCONSISTENT_FIELD_ORDER.with(|cell| cell.set(true));
+    tester.apply(Some(0), &Action::FieldOrderConflict).unwrap();
+    INCONSISTENT_FIELD_ORDER.with(|cell| cell.set(false));
+
+    assert!(player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::FieldOrderConflict { player: 0, .. })));
+}
+
+#[test]
+fn match_config() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::MatchConfig).unwrap();
+
+    let config = tester.state().config();
+
+    assert_eq!(config.deck_size, Some((1, 2)));
+    assert_eq!(config.zone_capacity(Zone::Field), Some(1));
+    assert_eq!(config.zone_capacity(Zone::Hand { public: true }), None);
+    assert_eq!(tester.state().player_cards(0).deck(), 2);
+}
+
+#[test]
+fn census() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Census).unwrap();
+}
+
+#[test]
+fn authorize_moves() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::AuthorizeMoves).unwrap();
+
+    assert!(tester.state().config().authorize_moves);
+}
+
+#[test]
+fn authorize_pointer_moves() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::AuthorizePointerMoves)
+        .unwrap();
+}
+
+#[test]
+fn reveal_attachment() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RevealAttachment).unwrap();
+}
+
+#[test]
+fn modifiers() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Modifiers).unwrap();
+
+    for player in 0..2 {
+        let logs = player_logs.try_borrow().unwrap()[player].clone();
+
+        assert_eq!(
+            logs.iter()
+                .filter(|event| matches!(event, CardEvent::AddModifier { .. }))
+                .count(),
+            4
+        );
+        assert_eq!(
+            logs.iter()
+                .filter(|event| matches!(event, CardEvent::RemoveModifier { .. }))
+                .count(),
+            3
+        );
+    }
+}
+
+#[test]
+fn temporary_modifications() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::TemporaryModifications)
+        .unwrap();
+}
+
+#[test]
+fn turns() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Turns).unwrap();
+
+    for player in 0..2 {
+        let phases: Vec<_> = player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .filter_map(|event| match event {
+                CardEvent::PhaseChange { turn } => Some((turn.player, turn.number, turn.phase)),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            phases,
+            vec![
+                (0, 1, Phase::Draw),
+                (0, 1, Phase::Main),
+                (1, 2, Phase::Main)
+            ]
+        );
+
+        let hooks: Vec<_> = player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .filter_map(|event| match event.clone().unphased() {
+                CardEvent::GameEvent { event } => Some(event),
+                _ => None,
+            })
+            .collect();
+
+        // The draw phase started, then ended when the main phase started.
+        assert_eq!(
+            hooks,
+            vec![
+                Event::PhaseStart(Phase::Draw),
+                Event::PhaseEnd(Phase::Draw),
+                Event::PhaseStart(Phase::Main),
+                Event::PhaseEnd(Phase::Main),
+                Event::PhaseStart(Phase::Main),
+            ]
+        );
+
+        // Every event after the first turn started names its phase.
+        assert!(player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .skip_while(|event| !matches!(event, CardEvent::PhaseChange { .. }))
+            .all(|event| event.turn().is_some()));
+    }
+
+    // The draw happened in the draw phase.
+    assert!(player_logs.try_borrow().unwrap()[0]
+        .iter()
+        .any(|event| matches!(
+            event,
+            CardEvent::InPhase { turn, .. } if turn.phase == Phase::Draw
+        )));
+}
+
+#[test]
+fn stack() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Stack).unwrap();
+
+    for player in 0..2 {
+        let logs = player_logs.try_borrow().unwrap()[player].clone();
+
+        assert_eq!(
+            logs.iter()
+                .filter(|event| matches!(event, CardEvent::Cast { .. }))
+                .count(),
+            6
+        );
+        assert_eq!(
+            logs.iter()
+                .filter(|event| matches!(event, CardEvent::Resolve { .. }))
+                .count(),
+            2
+        );
+        assert_eq!(
+            logs.iter()
+                .filter(|event| matches!(event, CardEvent::Counter { .. }))
+                .count(),
+            1
+        );
+    }
+}
+
+#[test]
+fn commitments() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Commitments).unwrap();
+
+    assert_eq!(tester.secret(0).commitment::<usize>("type"), None);
+    assert_eq!(tester.secret(0).commitment::<usize>("other"), Some(0));
+}
+
+#[test]
+fn random_cards_weighted() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RandomCardsWeighted).unwrap();
+}
+
+#[test]
+fn random_card() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RandomCard).unwrap();
+}
+
+#[test]
+fn random_outcomes() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RandomOutcomes).unwrap();
+
+    let outcomes: Vec<Vec<_>> = player_logs.try_borrow().unwrap()[..]
+        .iter()
+        .map(|logs| {
+            logs.iter()
+                .filter_map(|event| match event {
+                    CardEvent::RandomOutcome { sides, outcome } => Some((*sides, *outcome)),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    // Both players see the same outcomes.
+    assert_eq!(outcomes[0], outcomes[1]);
+    assert_eq!(outcomes[0].len(), 21);
+    assert_eq!(outcomes[0][20].0, 2);
+    assert!(outcomes[0]
+        .iter()
+        .all(|(sides, outcome)| (1..=*sides).contains(outcome)));
+}
+
+#[test]
+fn peek_top_of_deck() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::PeekTopOfDeck).unwrap();
+
+    let top = *tester.secret(0).deck().last().unwrap();
+
+    let peeks: Vec<Vec<_>> = player_logs.try_borrow().unwrap()[..]
+        .iter()
+        .map(|logs| {
+            logs.iter()
+                .filter_map(|event| match event {
+                    CardEvent::PeekCard { instance, .. } => Some(instance.id()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    // Only the owner sees the card.
+    assert_eq!(peeks, vec![vec![top], vec![]]);
+}
+
+#[test]
+fn action_summary() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Summarize).unwrap();
+
+    for player in 0..2 {
+        let logs = player_logs.try_borrow().unwrap()[player].clone();
+
+        assert!(matches!(
+            logs.last(),
+            Some(CardEvent::ActionResolved { summary })
+                if summary.key.as_deref() == Some("summary")
+                    && summary.moved == 1
+                    && summary.created >= 2
+                    && summary.modified == 1
+        ));
+    }
+}
+
+#[test]
+fn coalesce_events() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::CoalesceEvents).unwrap();
+
+    assert!(tester.state().coalesce_events());
+
+    let logs = player_logs.try_borrow().unwrap()[1].clone();
+    let modifications: Vec<_> = logs
+        .iter()
+        .filter_map(|event| match event {
+            CardEvent::ModifyCard { instance } => Some(instance.attachment_was_attached),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(modifications, vec![3]);
+}
+
+#[test]
+fn drain_limbo() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::DrainLimbo).unwrap();
+}
+
+#[test]
+fn flag_limbo() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::FlagLimbo).unwrap();
+
+    assert!(player_logs.try_borrow().unwrap()[0]
+        .iter()
+        .any(|event| matches!(event, CardEvent::LimboNotEmpty { player: 1 })));
+
+    assert!(tester
+        .state()
+        .limbo_ok(&[Some(&tester.secret(0)), None])
+        .is_ok());
+    assert!(tester
+        .state()
+        .limbo_ok(&[Some(&tester.secret(0)), Some(&tester.secret(1))])
+        .is_err());
+}
+
+#[test]
+fn correlate_actions() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::CorrelateActions).unwrap();
+    tester.apply(Some(0), &Action::CorrelateActions).unwrap();
+
+    assert!(tester.state().action_correlation());
+
+    let logs = player_logs.try_borrow().unwrap()[1].clone();
+
+    assert!(!logs.is_empty());
+
+    for action in 0..2 {
+        let indices: Vec<_> = logs
+            .iter()
+            .filter_map(CardEvent::correlation)
+            .filter(|correlation| correlation.action == action)
+            .filter_map(|correlation| correlation.index)
+            .collect();
+
+        assert!(!indices.is_empty());
+        assert_eq!(indices, (0..indices.len()).collect::<Vec<_>>());
+    }
+
+    assert!(logs.iter().all(|event| event.correlation().is_some()));
+}
+
+#[test]
+fn estimate_reveals() {
+    let (tester, _owner_logs, _player_logs) = make_tester();
+
+    let simulation = Simulation::new(
+        tester.state().clone(),
+        vec![tester.secret(0), tester.secret(1)],
+    )
+    .unwrap();
+
+    let estimate = simulation
+        .estimate_reveals(Some(0), &Action::EstimateReveals)
+        .unwrap();
+
+    assert_eq!(estimate.reveals(0), 0);
+    assert!(estimate.reveals(1) > 0);
+    assert_eq!(
+        estimate.total(),
+        simulation
+            .measure(Some(0), &Action::EstimateReveals)
+            .unwrap()
+            .reveals
+    );
+
+    // Nothing is applied to the simulation.
+    assert!(simulation.state().player_cards(0).field().is_empty());
+    assert!(simulation.state().reveals().is_none());
+}
+
+#[test]
+fn reveal_if() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RevealIfSetup).unwrap();
+
+    assert!(tester
+        .apply(Some(0), &Action::RevealIfPublic)
+        .unwrap()
+        .is_empty());
+
+    // one reveal of player 1's secret per check
+    assert_eq!(
+        tester
+            .apply(Some(0), &Action::RevealIfSecret)
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn card_database() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::CardDatabase).unwrap();
+}
+
+#[test]
+fn timeline() {
+    let events = Rc::new(Cell::new(0));
+    let events_clone = events.clone();
+
+    let mut timeline = Timeline::new(
+        GameState::<State>::default(),
+        vec![
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        move |_, _| events_clone.set(events_clone.get() + 1),
+    )
+    .unwrap();
+
+    timeline.apply(Some(0), Action::RevealIfSetup).unwrap();
+    timeline.apply(Some(0), Action::RevealIfSetup).unwrap();
+
+    assert_eq!(timeline.state().player_cards(0).graveyard().len(), 2);
+    assert!(events.get() > 0);
+
+    // rewind to before the second action, and resume with a different one
+    timeline.rewind(1).unwrap();
+
+    assert_eq!(timeline.actions().len(), 1);
+    assert_eq!(timeline.state().player_cards(0).graveyard().len(), 1);
+    assert_eq!(timeline.secret(1).hand().len(), 1);
+
+    timeline.apply(Some(0), Action::RevealIfSecret).unwrap();
+
+    assert!(timeline.rewind(2).is_err());
+
+    let replay = timeline.replay();
+
+    assert_eq!(replay.len(), 2);
+    assert!(replay.apply_to(GameState::default()).is_ok());
+}
+
+#[test]
+fn fuzz_actions() {
+    let replay = testing::fuzz_actions(
+        GameState::<State>::default(),
+        vec![
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        FuzzConfig {
+            steps: 20,
+            ..Default::default()
+        },
+        |_, random| {
+            let action = match random.gen_range(0..3) {
+                0 => Action::RevealIfSetup,
+                1 => Action::SpectatorEvents,
+                _ => Action::CorrelateActions,
+            };
+
+            Some((Some(random.gen_range(0..2)), action))
+        },
+    )
+    .unwrap();
+
+    assert_eq!(replay.len(), 20);
+    assert!(replay.apply_to(GameState::default()).is_ok());
+}
+
+#[test]
+fn fuzz_corpus() {
+    let dir = std::env::var("FUZZ_CORPUS_DIR")
+        .unwrap_or_else(|_| concat!(env!("OUT_DIR"), "/fuzz_corpus").to_string());
+
+    testing::write_corpus::<State>(
+        &dir,
+        GENERATED_CORPUS
+            .iter()
+            .map(|(name, action)| (name, vec![(Some(0), action.clone())])),
+    )
+    .unwrap();
+
+    // The directory may already hold other seeds, e.g. ones found by fuzzing.
+    assert!(GENERATED_CORPUS.iter().all(|(name, _)| {
+        std::path::Path::new(&dir)
+            .join(format!("{}.cbor", name))
+            .is_file()
+    }));
+
+    let corpus = testing::read_corpus::<State>(&dir).unwrap();
+
+    assert!(corpus.len() >= GENERATED_CORPUS.len());
+
+    for seed in corpus.into_iter().step_by(1000) {
+        let actions = seed.len();
+        let replay = testing::fuzz_from_seed(
+            GameState::<State>::default(),
+            vec![
+                PlayerSecret::new(0, Default::default()),
+                PlayerSecret::new(1, Default::default()),
+            ],
+            FuzzConfig {
+                steps: 5,
+                ..Default::default()
+            },
+            seed,
+            |_, random| Some((Some(random.gen_range(0..2)), Action::CorrelateActions)),
+        )
+        .unwrap();
+
+        assert_eq!(replay.len(), actions + 5);
+    }
+}
+
+#[test]
+fn simulation() {
+    let (mut tester, owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ReplaceDeck).unwrap();
+
+    let events = player_logs.borrow()[1].len() + owner_logs.borrow().len();
+
+    let hypothesis = PlayerSecret::hypothesis(tester.state(), 0, Default::default(), |_, _| {
+        BaseCard::Basic
+    });
+
+    assert_eq!(
+        hypothesis.deck().len(),
+        tester.state().player_cards(0).deck()
+    );
+    assert_eq!(
+        invariants::check(
+            tester.state(),
+            &[Some(&hypothesis), Some(&tester.secret(1))]
+        ),
+        vec![]
+    );
+
+    let mut simulation =
+        Simulation::new(tester.state().clone(), vec![hypothesis, tester.secret(1)]).unwrap();
+
+    let fork = simulation.fork().unwrap();
+
+    // measuring runs the action on a copy, revealing player 0's deck to play its top card
+    assert!(
+        simulation
+            .measure(Some(0), &Action::PlayTopCard)
+            .unwrap()
+            .reveals
+            > 0
+    );
+    assert!(simulation.state().player_cards(0).field().is_empty());
+
+    simulation.apply(Some(0), &Action::PlayTopCard).unwrap();
+
+    assert_eq!(simulation.state().player_cards(0).field().len(), 1);
+    assert_eq!(simulation.state().player_cards(0).deck(), 2);
+    assert_eq!(
+        invariants::check(
+            simulation.state(),
+            &[Some(&simulation.secret(0)), Some(&simulation.secret(1))]
+        ),
+        vec![]
+    );
+
+    // Neither the fork nor the real game are affected, and nothing is logged.
+    assert!(fork.state().player_cards(0).field().is_empty());
+    assert!(tester.state().player_cards(0).field().is_empty());
+    assert_eq!(tester.state().player_cards(0).deck(), 3);
+    assert_eq!(
+        player_logs.borrow()[1].len() + owner_logs.borrow().len(),
+        events
+    );
+}
+
+#[test]
+fn legal_actions() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    assert!(tester.state().legal_actions(Some(0)).is_empty());
+    testing::assert_legal_actions(tester.state(), Some(0), vec![Action::PlayTopCard]);
+
+    tester.apply(Some(0), &Action::ReplaceDeck).unwrap();
+
+    assert!(matches!(
+        tester.state().legal_actions(Some(0))[..],
+        [Action::PlayTopCard]
+    ));
+    testing::assert_legal_actions(tester.state(), Some(0), vec![Action::PlayTopCard]);
+    assert!(tester.state().legal_actions(Some(1)).is_empty());
+}
+
+#[test]
+fn invariants() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ReplaceDeck).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
+    assert_eq!(invariants::check(tester.state(), &[None, None]), vec![]);
+
+    let stale = PlayerSecret::new(0, Default::default());
+
+    assert_eq!(
+        invariants::deck_sizes_match(tester.state(), &[Some(&stale), None]),
+        vec![InvariantViolation::DeckSize {
+            player: 0,
+            public: tester.state().player_cards(0).deck(),
+            secret: 0,
+        }]
+    );
+    assert!(
+        invariants::single_bucket(tester.state(), &[Some(&stale), None])
+            .iter()
+            .all(|violation| matches!(
+                violation,
+                InvariantViolation::MissingFromSecret { player: 0, .. }
+            ))
+    );
+    assert!(tester
+        .state()
+        .ok(&[Some(&stale), Some(&secrets[1])])
+        .is_err());
+}
+
+#[test]
+fn spectator_events() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::SpectatorEvents).unwrap();
+
+    assert!(tester.state().spectator_events());
+
+    let logs = player_logs.try_borrow().unwrap().clone();
+
+    assert!(logs[0]
+        .iter()
+        .any(|event| matches!(event, CardEvent::Secret { player: 0, .. })));
+    assert!(!logs[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::Secret { .. })));
+
+    let spectator_logs: Vec<Vec<_>> = logs
+        .iter()
+        .map(|log| {
+            log.iter()
+                .filter_map(CardEvent::redact_for_spectator)
+                .map(|event| event.to_string())
+                .collect()
+        })
+        .collect();
+
+    assert!(!spectator_logs[0].is_empty());
+    assert_eq!(spectator_logs[0], spectator_logs[1]);
+
+    assert!(logs[0]
+        .iter()
+        .cloned()
+        .map(CardEvent::unsecret)
+        .all(|event| !matches!(event, CardEvent::Secret { .. })));
+}
+
+#[test]
+fn replay() {
+    let secrets = [
+        PlayerSecret::new(0, Default::default()),
+        PlayerSecret::new(1, Default::default()),
+    ];
+
+    let recorder = Rc::new(RefCell::new(ReplayRecorder::new(secrets.to_vec(), true)));
+    let recorder_clone = recorder.clone();
+
+    let mut state = GameState::<State>::default();
+    state.set_transcript_recording(true);
+
+    let mut tester = Tester::new(
+        state,
+        secrets,
+        Default::default(),
+        |_, _, _| {},
+        move |player, _, event| {
+            recorder_clone
+                .borrow_mut()
+                .record_event(player, &event)
+                .unwrap()
+        },
+        false,
+    )
+    .unwrap();
+
+    for action in [
+        Action::SpectatorEvents,
+        Action::CorrelateActions,
+        Action::RandomOutcomes,
+    ] {
+        recorder.borrow_mut().record_action(Some(0), action.clone());
+        tester.apply(Some(0), &action).unwrap();
+        recorder.borrow_mut().record_transcript(tester.state());
+    }
+
+    let replay = recorder.borrow().replay().clone();
+
+    assert_eq!(replay.len(), 3);
+
+    // the random outcomes are replayed from the transcript, so they log the same events
+    let events = replay.apply_to(GameState::default()).unwrap();
+
+    assert_eq!(events.len(), 3);
+    assert!(events.iter().all(|events| !events.is_empty()));
+
+    // replays survive serialization
+    let replay: Replay<State> =
+        serde_cbor::from_slice(&serde_cbor::to_vec(&replay).unwrap()).unwrap();
+
+    assert!(replay.apply_to(GameState::default()).is_ok());
+
+    // starting from a different state logs different events
+    let mut state = GameState::<State>::default();
+    state.set_strict_event_ordering(true);
+
+    assert!(matches!(
+        replay.apply_to(state),
+        Err(ReplayError::Desync { index: 0 })
+    ));
+}
+
+#[test]
+fn transcript_recording_keeps_randomness() {
+    let outcomes = |recording: bool| {
+        let logs = Rc::new(RefCell::new(Vec::new()));
+        let logs_clone = logs.clone();
+
+        let mut state = GameState::<State>::default();
+        state.set_transcript_recording(recording);
+
+        let mut tester = Tester::new(
+            state,
+            [
+                PlayerSecret::new(0, Default::default()),
+                PlayerSecret::new(1, Default::default()),
+            ],
+            Default::default(),
+            |_, _, _| {},
+            move |player, _, event| {
+                logs_clone
+                    .borrow_mut()
+                    .push(format!("{:?} {:?}", player, event))
+            },
+            false,
+        )
+        .unwrap();
+
+        // shared randomness, then secret randomness
+        for action in [Action::RandomOutcomes, Action::Piles] {
+            tester.apply(Some(0), &action).unwrap();
+        }
+
+        logs.take()
+    };
+
+    let logs = outcomes(false);
+
+    assert!(logs.iter().any(|event| event.contains("RandomOutcome")));
+    assert!(logs.iter().any(|event| event.contains("ShufflePile")));
+    assert_eq!(outcomes(true), logs);
+}
+
+#[test]
+fn catalog_card() {
+    #[derive(Debug)]
+    struct Definition {
+        attachment_was_attached: usize,
+    }
+
+    impl CardDefinition for Definition {
+        type CardState = CardState;
+
+        fn new_card_state(&self, _parent: Option<&Self::CardState>) -> Self::CardState {
+            CardState {
+                attachment_was_detached: 0,
+                attachment_was_attached: self.attachment_was_attached,
+                was_cloned: false,
+            }
+        }
+    }
+
+    thread_local! {
+        static CATALOG: Catalog<Definition> = Catalog::new([
+            ("basic".to_string(), Definition { attachment_was_attached: 0 }),
+            ("attached".to_string(), Definition { attachment_was_attached: 1 }),
+        ]);
+    }
+
+    struct Cards;
+
+    impl CardCatalog for Cards {
+        type Definition = Definition;
+
+        fn definition(id: &str) -> Option<std::sync::Arc<Self::Definition>> {
+            CATALOG.with(|catalog| catalog.definition(id))
+        }
+    }
+
+    let card = CatalogCard::<Cards>::new("attached").unwrap();
+
+    assert_eq!(card.id(), "attached");
+    assert_eq!(
+        card_movement_simulator::BaseCard::new_card_state(&card, None).attachment_was_attached,
+        1
+    );
+    assert!(CatalogCard::<Cards>::new("missing").is_err());
+
+    let data = serde_cbor::to_vec(&card).unwrap();
+
+    assert_eq!(
+        serde_cbor::from_slice::<CatalogCard<Cards>>(&data).unwrap(),
+        card
+    );
+
+    let data = serde_cbor::to_vec(&"missing").unwrap();
+
+    assert!(serde_cbor::from_slice::<CatalogCard<Cards>>(&data).is_err());
+}
+
+#[test]
+fn opponent_instance_from_id() {
+    let mut tester = Tester::new(
+        GameState::<State>::default(),
+        [
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        Default::default(),
+        |_, _, _| {},
+        |_, _, _| {},
+        false,
+    )
+    .unwrap();
+
+    tester.apply(Some(0), &Action::InstanceFromIDSetup).unwrap();
+
+    // This is an implementation detail.
+    // Constructing a public card in public limbo gives the parent card the ID after the attachment.
+
+    let id: InstanceID = InstanceID::from_raw(4);
+
+    assert!(id
+        .instance(tester.state(), Some(&tester.secret(0)))
+        .is_none());
+}
+
+#[test]
+fn move_secret_hand_to_public_hand() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+    tester
+        .apply(Some(0), &Action::RevealSecretHandCard)
+        .unwrap();
+
+    println!(
+        "
+
+All Logs:"
+    );
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        println!("{}", card);
+    }
+    println!(
+        "
+"
+    );
+
+    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0].clone().into_iter();
+
+    for i in 0..5 {
+        let move_to_secret_hand_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::MoveCard), got None.");
+        if let CardEvent::MoveCard {
+            to:
+                ExactCardLocation {
+                    player: 0,
+                    location: (Zone::Hand { public: false }, index),
+                },
+            ..
+        } = move_to_secret_hand_event
+        {
+            assert_eq!(index, i);
+        } else {
+            unreachable!(
+                "Expected MoveCard to secret hand, got {:?}",
+                move_to_secret_hand_event
+            );
+        };
+    }
+
+    let reveal_hand_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(
+        matches!(
+            reveal_hand_event,
+            CardEvent::MoveCard {
+                to: ExactCardLocation {
+                    player: 0,
+                    location: (Zone::Hand { public: true }, 2)
+                },
+                ..
+            }
+        ),
+        "Expected MoveCard to public hand position 2, got {:#?}",
+        reveal_hand_event
+    );
+}
+
+fn make_tester() -> (
+    Tester<GameState<State>>,
+    Rc<RefCell<Vec<CardEvent<State>>>>,
+    Rc<RefCell<[Vec<CardEvent<State>>; 2]>>,
+) {
+    let owner_logs: Rc<RefCell<Vec<CardEvent<State>>>> = Rc::new(RefCell::new(vec![]));
+    let player_logs: Rc<RefCell<[Vec<CardEvent<State>>; 2]>> =
+        Rc::new(RefCell::new([vec![], vec![]]));
+
+    let owner_logs_clone = owner_logs.clone();
+    let player_logs_clone = player_logs.clone();
+    let tester = Tester::new(
+        GameState::<State>::default(),
+        [
+            PlayerSecret::new(0, Default::default()),
+            PlayerSecret::new(1, Default::default()),
+        ],
+        Default::default(),
+        |_, _, _| {},
+        move |player, _, message| match player {
+            None => owner_logs_clone.try_borrow_mut().unwrap().push(message),
+            Some(p) => player_logs_clone.try_borrow_mut().unwrap()[p as usize].push(message),
+        },
+        false,
+    )
     .unwrap();
 
-    println!("{:?}", reveals);
+    (tester, owner_logs, player_logs)
+}
+
+fn test_move(
+    card_ptr_bucket: Option<Player>,
+    base_card_type: BaseCard,
+    from_player: Player,
+    from_zone: Zone,
+    to_player: Player,
+    to_zone: Zone,
+) {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+    tester
+        .apply(
+            Some(0),
+            &Action::Move {
+                card_ptr_bucket,
+                base_card_type,
+                from_player,
+                to_player,
+                from_zone,
+                to_zone,
+            },
+        )
+        .unwrap();
+
+    println!("\n\nAll Logs:");
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        {
+            println!("{}", card);
+        }
+    }
+    println!("\n");
+
+    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0]
+        .clone()
+        .into_iter()
+        .peekable();
+
+    // If our BaseCard has an attachment, we'll see a MoveCard to attach it upon creation.
+    if base_card_type == BaseCard::WithAttachment {
+        let attach_event = actual_player_logs
+            .next()
+            .expect("Expected attach event, got None.");
+        assert!(
+            matches!(
+                attach_event,
+                CardEvent::MoveCard {
+                    to: ExactCardLocation {
+                        location: (
+                            Zone::Attachment {
+                                parent: Card::ID(_)
+                            },
+                            _
+                        ),
+                        ..
+                    },
+                    instance: Some(_),
+                    ..
+                }
+            ),
+            "Base card has attachment, so expected attach event.\nGot {:#?}.",
+            attach_event
+        );
+
+        // ModifyCard of parent from attach callback.
+        let modify_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::ModifyCard), got None.");
+        assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
+    };
+
+    let move_to_start_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(matches!(
+        move_to_start_zone_event,
+        CardEvent::MoveCard { .. }
+    ));
+
+    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
+        actual_player_logs.next(); // allow sort fields
+    }
+
+    // Event should fire if we moved to a different zone.
+    // Player 0 should see the card instance in every event involving it.
+    let move_to_end_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    if from_player == 0
+        || to_player == 0
+        || to_zone.is_public().unwrap()
+        || from_zone.is_public().unwrap()
+    {
+        assert!(matches!(
+            move_to_end_zone_event,
+            CardEvent::MoveCard {
+                instance: Some(_),
+                ..
+            }
+        ));
+    } else {
+        assert!(matches!(
+            move_to_end_zone_event,
+            CardEvent::MoveCard { instance: None, .. }
+        ));
+    }
+}
+
+fn test_detach(
+    parent_zone: Zone,
+    attachment_ptr_bucket: Option<Player>,
+    to_player: Player,
+    to_zone: Zone,
+) {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(
+            Some(0),
+            &Action::Detach {
+                parent_zone,
+                attachment_ptr_bucket,
+                to_player,
+                to_zone,
+            },
+        )
+        .unwrap();
+
+    println!("\n\nAll Logs:");
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        println!("{:#?}\n", card);
+    }
+    println!("\n");
+
+    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0]
+        .clone()
+        .into_iter()
+        .peekable();
+
+    // Our BaseCard has an attachment, so we'll see a MoveCard to attach it upon creation.
+    let attach_event = actual_player_logs
+        .next()
+        .expect("Expected attach event, got None.");
+    assert!(
+        matches!(
+            attach_event,
+            CardEvent::MoveCard {
+                to: ExactCardLocation {
+                    location: (
+                        Zone::Attachment {
+                            parent: Card::ID(_)
+                        },
+                        _
+                    ),
+                    ..
+                },
+                instance: Some(_),
+                ..
+            }
+        ),
+        "Base card has attachment, so expected attach event.\nGot {:#?}.",
+        attach_event
+    );
+
+    // ModifyCard of parent from attach callback.
+    let modify_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::ModifyCard), got None.");
+    assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
+
+    let move_to_start_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(matches!(
+        move_to_start_zone_event,
+        CardEvent::MoveCard { .. }
+    ));
+
+    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
+        actual_player_logs.next(); // allow sort fields
+    }
+
+    let move_to_end_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(
+        matches!(
+            move_to_end_zone_event,
+            CardEvent::MoveCard {
+                instance: Some(_),
+                ..
+            }
+        ),
+        "Expected MoveCard to End Zone, got {:#?}",
+        move_to_end_zone_event
+    );
+
+    // Player 0 should see the card instance in every event involving it.
+    // ModifyCard for parent being modified because of detach.
+    let modify_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::ModifyCard), got None.");
+    if let CardEvent::ModifyCard { instance } = modify_event {
+        assert!(instance.attachment().is_none());
+    } else {
+        unreachable!("Expected ModifyCard, got {:#?}", modify_event);
+    }
+}
+
+fn test_attach(
+    parent_base_card: BaseCard,
+    parent_ptr_bucket: Option<Player>,
+    parent_zone: Zone,
+    card_ptr_bucket: Option<Player>,
+    card_owner: Player,
+    card_zone: Zone,
+) {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(
+            Some(0),
+            &Action::Attach {
+                parent_base_card,
+                parent_ptr_bucket,
+                parent_zone,
+                card_ptr_bucket,
+                card_owner,
+                card_zone,
+            },
+        )
+        .unwrap();
+
+    println!("\n\nAll Logs:");
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        println!("{}\n", card);
+    }
+    println!("\n");
+
+    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0]
+        .clone()
+        .into_iter()
+        .peekable();
+
+    if parent_base_card == BaseCard::WithAttachment {
+        // Our BaseCard has an attachment, so we'll see a MoveCard to attach it upon creation.
+        let attach_event = actual_player_logs
+            .next()
+            .expect("Expected attach event, got None.");
+        assert!(
+            matches!(
+                attach_event,
+                CardEvent::MoveCard {
+                    to: ExactCardLocation {
+                        location: (
+                            Zone::Attachment {
+                                parent: Card::ID(_)
+                            },
+                            _
+                        ),
+                        ..
+                    },
+                    instance: Some(_),
+                    ..
+                }
+            ),
+            "Base card has attachment, so expected attach event.\nGot {:#?}.",
+            attach_event
+        );
+
+        // ModifyCard of parent from attach callback.
+        let modify_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::ModifyCard), got None.");
+        assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
+    }
+    let move_parent_to_start_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(matches!(
+        move_parent_to_start_zone_event,
+        CardEvent::MoveCard { .. }
+    ));
+
+    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
+        actual_player_logs.next(); // allow sort fields
+    }
+
+    let move_attach_to_start_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(matches!(
+        move_attach_to_start_zone_event,
+        CardEvent::MoveCard { .. }
+    ));
+
+    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
+        actual_player_logs.next(); // allow sort fields
+    }
+
+    if parent_base_card == BaseCard::WithAttachment {
+        // Dust current attach
+        let dust_current_attach = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::MoveCard), got None.");
+        assert!(
+            matches!(
+                dust_current_attach,
+                CardEvent::MoveCard {
+                    instance: Some(_),
+                    ..
+                }
+            ),
+            "Expected MoveCard from Zone::Attach to Zone::Dust, got {:#?}.",
+            dust_current_attach
+        );
+
+        // ModifyCard for parent being modified because of detach.
+        let modify_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::ModifyCard), got None.");
+        assert!(
+            matches!(modify_event, CardEvent::ModifyCard { .. }),
+            "Expected CardEvent::ModifyCard for parent because of child being detached, got {:#?}",
+            modify_event
+        );
+    }
+    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
+        actual_player_logs.next(); // allow sort fields
+    }
+
+    let attach_attachment_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+
+    let zone = card_zone;
+    let is_mine = card_owner == 0;
+    let has_public_location = match zone {
+        Zone::Deck => true,
+        Zone::Hand { .. } => true,
+        Zone::Field => true,
+        Zone::Graveyard => true,
+        Zone::Dust { .. } => true,
+        Zone::Attachment { .. } => false,
+        Zone::Limbo { .. } => true,
+        Zone::Casting => true,
+        Zone::CardSelection => true,
+        Zone::Pile { .. } => true,
+        Zone::HeroAbility => true,
+        Zone::Boon => true,
+    };
+    if matches!(zone, Zone::Limbo { public: false }) && !is_mine {
+        assert!(
+            matches!(
+                attach_attachment_event,
+                CardEvent::MoveCard {
+                    from: CardLocation {
+                        location: Some((_, None)),
+                        ..
+                    },
+                    ..
+                }
+            ),
+            "Expected MoveCard from secret limbo without an index, got {:#?}.",
+            attach_attachment_event
+        );
+    }
+    if has_public_location {
+        assert!(
+            matches!(
+                attach_attachment_event,
+                CardEvent::MoveCard {
+                    instance: Some(_),
+                    from: CardLocation {
+                        location: Some(_),
+                        ..
+                    },
+                    ..
+                }
+            ),
+            "Expected MoveCard to Zone::Attachment with Some(location), got {:#?}.",
+            attach_attachment_event
+        );
+    } else {
+        assert!(
+            matches!(
+                attach_attachment_event,
+                CardEvent::MoveCard {
+                    instance: Some(_),
+                    from: CardLocation { location: None, .. },
+                    ..
+                }
+            ),
+            "Expected MoveCard to Zone::Attachment with location: None, got {:#?}.",
+            attach_attachment_event
+        );
+    }
+
+    // Parent being modified because of new attach.
+    let modify_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::ModifyCard), got None.");
+    assert!(
+        matches!(modify_event, CardEvent::ModifyCard { .. }),
+        "Expected CardEvent::ModifyCard for parent because of new attach, got {:#?}",
+        modify_event
+    );
+}
+
+fn test_attach_from_attached(
+    parent_base_card: BaseCard,
+    parent_ptr_bucket: Option<Player>,
+    parent_zone: Zone,
+    card_ptr_bucket: Option<Player>,
+    card_owner: Player,
+    card_zone: Zone,
+) {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(
+            Some(0),
+            &Action::AttachFromAttached {
+                parent_base_card,
+                parent_ptr_bucket,
+                parent_zone,
+                card_ptr_bucket,
+                card_owner,
+                card_zone,
+            },
+        )
+        .unwrap();
+
+    println!(
+        "Action: {:#?}",
+        Action::AttachFromAttached {
+            parent_base_card,
+            parent_ptr_bucket,
+            parent_zone,
+            card_ptr_bucket,
+            card_owner,
+            card_zone,
+        }
+    );
+    println!("\n\nAll Logs:");
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        println!("{:#?}\n", card);
+    }
+    println!("\n");
+
+    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0].clone().into_iter();
+
+    let is_mine = card_owner == 0;
+
+    if parent_base_card == BaseCard::WithAttachment {
+        // Our BaseCard has an attachment, so we'll see a MoveCard to attach it upon creation.
+        let attach_event = actual_player_logs
+            .next()
+            .expect("Expected attach event, got None.");
+        assert!(
+            matches!(
+                attach_event,
+                CardEvent::MoveCard {
+                    to: ExactCardLocation {
+                        location: (
+                            Zone::Attachment {
+                                parent: Card::ID(_)
+                            },
+                            _
+                        ),
+                        ..
+                    },
+                    instance: Some(_),
+                    ..
+                }
+            ),
+            "Base card has attachment, so expected attach event.\nGot {:#?}.",
+            attach_event
+        );
+
+        // ModifyCard of parent from attach callback.
+        let modify_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::ModifyCard), got None.");
+        assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
+    }
+    let move_parent_to_start_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(matches!(
+        move_parent_to_start_zone_event,
+        CardEvent::MoveCard { .. }
+    ));
+
+    if parent_zone.is_field() {
+        let sort_field_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::SortField), got None.");
+        assert!(matches!(sort_field_event, CardEvent::SortField { .. }));
+    }
+
+    let start_parent_gains_attach = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(
+        matches!(
+            start_parent_gains_attach,
+            CardEvent::MoveCard {
+                to: ExactCardLocation {
+                    location: (Zone::Attachment { .. }, _),
+                    ..
+                },
+                ..
+            }
+        ),
+        "Expected Some(CardEvent::MoveCard) for attach coming to starting parent, got {:#?}",
+        start_parent_gains_attach
+    );
+
+    let start_parent_gains_attach_modify = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::ModifyCard), got None.");
+    assert!(
+        matches!(
+            start_parent_gains_attach_modify,
+            CardEvent::ModifyCard { .. }
+        ),
+        "Expected Some(CardEvent::ModifyCard) for attach callback on starting parent, got {:#?}",
+        start_parent_gains_attach_modify
+    );
+
+    let move_attach_to_start_zone_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    assert!(matches!(
+        move_attach_to_start_zone_event,
+        CardEvent::MoveCard { .. }
+    ));
+    if card_zone.is_field() {
+        let sort_field_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::SortField), got None.");
+        assert!(matches!(sort_field_event, CardEvent::SortField { .. }));
+    }
+
+    if parent_base_card == BaseCard::WithAttachment {
+        // Dust current attach
+        let dust_current_attach = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::MoveCard), got None.");
+        assert!(
+            matches!(
+                dust_current_attach,
+                CardEvent::MoveCard {
+                    instance: Some(_),
+                    ..
+                }
+            ),
+            "Expected MoveCard from Zone::Attach to Zone::Dust, got {:#?}.",
+            dust_current_attach
+        );
+
+        // ModifyCard for new parent being modified because of detach of existing attachment.
+        let modify_event = actual_player_logs
+            .next()
+            .expect("Expected Some(CardEvent::ModifyCard), got None.");
+        assert!(
+            matches!(modify_event, CardEvent::ModifyCard { .. }),
+            "Expected CardEvent::ModifyCard for parent because of child being detached, got {:#?}",
+            modify_event
+        );
+    }
+
+    let attach_attachment_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::MoveCard), got None.");
+
+    let zone = card_zone;
+    let has_public_location = match zone {
+        Zone::Deck => is_mine,
+        Zone::Hand { public } => is_mine || public, // attachment secrecy depends on parent secrecy
+        Zone::Field => true,
+        Zone::Graveyard => true,
+        Zone::Dust { public } => is_mine || public,
+        Zone::Attachment { .. } => unreachable!(),
+        Zone::Limbo { public } => is_mine || public,
+        Zone::Casting => true,
+        Zone::CardSelection => is_mine,
+        Zone::Pile { .. } => is_mine,
+        Zone::HeroAbility => true,
+        Zone::Boon => true,
+    };
+    if has_public_location {
+        assert!(
+            matches!(
+                attach_attachment_event,
+                CardEvent::MoveCard {
+                    instance: Some(_),
+                    from: CardLocation {
+                        location: Some(_),
+                        ..
+                    },
+                    ..
+                }
+            ),
+            "Expected MoveCard to Zone::Attachment with Some(location), got {:#?}.",
+            attach_attachment_event
+        );
+    } else {
+        assert!(
+            matches!(
+                attach_attachment_event,
+                CardEvent::MoveCard {
+                    instance: Some(_),
+                    from: CardLocation { location: None, .. },
+                    ..
+                }
+            ),
+            "Expected MoveCard to Zone::Attachment with location: None, got {:#?}.",
+            attach_attachment_event
+        );
+    }
+
+    // Parent being modified because of new attach.
+    let modify_event = actual_player_logs
+        .next()
+        .expect("Expected Some(CardEvent::ModifyCard), got None.");
+    assert!(
+        matches!(modify_event, CardEvent::ModifyCard { .. }),
+        "Expected CardEvent::ModifyCard for parent because of new attach, got {:#?}",
+        modify_event
+    );
+}
+
+fn test_copy_card(
+    card_ptr_bucket: Option<Player>,
+    base_card_type: BaseCard,
+    card_zone: Zone,
+    deep: bool,
+) {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(
+            Some(0),
+            &Action::CopyCard {
+                card_ptr_bucket,
+                base_card_type,
+                card_zone,
+                deep,
+            },
+        )
+        .unwrap();
+
+    println!("\n\nAll Logs:");
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        println!("{:#?}\n", card);
+    }
+    println!("\n");
+}
+
+fn test_reset_card(
+    card_ptr_bucket: Option<Player>,   // 3
+    attachment_type: Option<BaseCard>, //2
+    base_card_type: BaseCard,          // 2
+    card_zone: Zone,
+) {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+    tester
+        .apply(
+            Some(0),
+            &Action::ResetCard {
+                card_ptr_bucket,
+                attachment_type,
+                base_card_type,
+                card_zone,
+            },
+        )
+        .unwrap();
+
+    println!("\n\nAll Logs:");
+    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
+        println!("{:#?}\n", card);
+    }
+    println!("\n");
+}
+include!(concat!(env!("OUT_DIR"), "/generated_tests.rs"));
+include!(concat!(env!("OUT_DIR"), "/generated_corpus.rs"));
+
+#[test]
+fn zone_watermarks() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ZoneWatermarks).unwrap();
+
+    assert!(tester.state().zone_watermarks());
+
+    let logs = player_logs.try_borrow().unwrap().clone();
+
+    for log in logs.iter() {
+        assert!(log.iter().any(|event| matches!(
+            event,
+            CardEvent::ZoneVersion {
+                player: 0,
+                zone: Zone::Field,
+                version: 1
+            }
+        )));
+    }
+}
+
+#[test]
+fn record_reveals() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    assert!(tester.state().reveals().is_none());
+
+    tester
+        .apply(Some(0), &Action::RecordReveals { secret: true })
+        .unwrap();
+
+    testing::assert_only_zone_reveals(tester.state());
+
+    let reveals = tester.state().reveals().unwrap();
+
+    assert_eq!(reveals.len(), 1);
+    assert_eq!(reveals[0].player, 0);
+    assert!(reveals[0].is_zone_information());
+    assert!(matches!(
+        reveals[0].value::<Zone>(),
+        Some(Zone::Limbo { public: false })
+    ));
+    assert!(reveals[0].value::<CardLocation>().is_none());
+
+    tester
+        .apply(Some(0), &Action::RecordReveals { secret: false })
+        .unwrap();
+
+    testing::assert_no_reveals(tester.state());
+}
+
+#[test]
+fn copy_card_events() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::CopyCardEvents).unwrap();
+
+    let logs = player_logs.try_borrow().unwrap().clone();
+
+    let copies: Vec<Vec<_>> = logs
+        .iter()
+        .map(|log| {
+            log.iter()
+                .filter_map(|event| match event {
+                    CardEvent::CopyCard {
+                        source,
+                        copy,
+                        instance,
+                        deep,
+                    } => Some((*source, *copy, instance.clone(), *deep)),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    for (player, copies) in copies.iter().enumerate() {
+        // A deep copy copies the attachment first.
+        assert_eq!(copies.len(), 3);
+
+        let (source, _, instance, _) = &copies[0];
+
+        assert_eq!(source.id(), Some(InstanceID::from_raw(1)));
+        assert_eq!(*instance.as_ref().unwrap().0.base(), BaseCard::Attachment);
+
+        let (source, copy, instance, deep) = &copies[1];
+        let (instance, attachment) = instance.as_ref().unwrap();
+
+        assert!(deep);
+        assert_eq!(source.id(), Some(InstanceID::from_raw(0)));
+        assert_eq!(copy.id(), Some(instance.id()));
+        assert_eq!(*instance.base(), BaseCard::WithAttachment);
+        assert_eq!(*attachment.as_ref().unwrap().base(), BaseCard::Attachment);
+
+        let (source, copy, instance, deep) = &copies[2];
+
+        assert!(!deep);
+        assert!(source.pointer().is_some());
+        assert!(copy.pointer().is_some());
+        assert_eq!(instance.is_some(), player == 0);
+    }
+}
+
+#[test]
+fn reveal_projection() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RevealProjection).unwrap();
+
+    let values: Vec<Vec<_>> = player_logs.try_borrow().unwrap()[..]
+        .iter()
+        .map(|logs| {
+            logs.iter()
+                .filter_map(|event| match event {
+                    CardEvent::RevealProjection { value, .. } => {
+                        serde_cbor::from_slice::<bool>(value).ok()
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    // Both players see the initial value and the change.
+    assert_eq!(values[0], vec![false, true]);
+    assert_eq!(values[1], vec![false, true]);
+    assert_eq!(
+        tester.state().known_projections()[0].value(),
+        serde_cbor::to_vec(&true).unwrap()
+    );
+
+    tester.apply(Some(0), &Action::ForgetProjection).unwrap();
+
+    assert!(tester.state().known_projections().is_empty());
+}
+
+#[test]
+fn redact_events() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::RedactEvents).unwrap();
+
+    let keys: Vec<Vec<_>> = player_logs.try_borrow().unwrap()[..]
+        .iter()
+        .map(|logs| {
+            logs.iter()
+                .filter_map(|event| match event {
+                    CardEvent::Commit { key, .. } => Some(key.clone()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(keys[0], vec!["secret-bid", "bid"]);
+    assert_eq!(keys[1], vec!["secret-", "bid"]);
+}
+
+#[test]
+fn suppress_pointer_events() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::SuppressPointerEvents)
+        .unwrap();
 
-    assert_eq!(reveals.len(), 0, "No reveals need to be made when attaching a public card to a secret card, even if it already has an attachment.")
+    // The opponent never learns about any pointers.
+    assert!(!player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::NewPointer { .. })));
 }
 
 #[test]
-fn opaque_pointer_association_does_not_hold_through_draw() {
-    Tester::new(
-        GameState::<State>::default(),
-        [
-            PlayerSecret::new(0, Default::default()),
-            PlayerSecret::new(1, Default::default()),
-        ],
-        Default::default(),
-        |_, _, _| {},
-        |_, _, _| {},
-        false,
-    )
-    .unwrap()
-    .apply(
-        Some(0),
-        &Action::OpaquePointerAssociationDoesntHoldThroughDraw,
-    )
-    .unwrap();
+fn skip_sort() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    CLONED_FIRST_FIELD_ORDER.with(|cell| cell.set(true));
+    let result = tester.apply(Some(0), &Action::SkipSort);
+    CLONED_FIRST_FIELD_ORDER.with(|cell| cell.set(false));
+
+    result.unwrap();
 }
 
 #[test]
-fn public_instance_from_id() {
-    let mut tester = Tester::new(
-        GameState::<State>::default(),
-        [
-            PlayerSecret::new(0, Default::default()),
-            PlayerSecret::new(1, Default::default()),
-        ],
-        Default::default(),
-        |_, _, _| {},
-        |_, _, _| {},
-        false,
-    )
-    .unwrap();
+fn field_order_with_state() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    tester.apply(Some(0), &Action::InstanceFromIDSetup).unwrap();
+    CLONED_FIRST_FIELD_ORDER.with(|cell| cell.set(true));
+    let result = tester.apply(Some(0), &Action::FieldOrderWithState);
+    CLONED_FIRST_FIELD_ORDER.with(|cell| cell.set(false));
 
-    // This is an implementation detail.
-    // Constructing a public card in public limbo gives the attachment the ID after the parent card.
+    result.unwrap();
+}
 
-    let id: InstanceID = InstanceID::from_raw(1);
+#[test]
+fn positioned_field() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
 
-    assert!(id.instance(tester.state(), None).is_some());
+    CLONED_FIRST_FIELD_ORDER.with(|cell| cell.set(true));
+    let result = tester.apply(Some(0), &Action::PositionedField);
+    CLONED_FIRST_FIELD_ORDER.with(|cell| cell.set(false));
+
+    result.unwrap();
+
+    let logs = &player_logs.try_borrow().unwrap()[1];
+
+    assert!(!logs
+        .iter()
+        .any(|event| matches!(event, CardEvent::SortField { .. })));
+    assert_eq!(
+        logs.iter()
+            .filter(|event| matches!(event, CardEvent::FieldPosition { .. }))
+            .count(),
+        2
+    );
 }
 
 #[test]
-fn secret_instance_from_id() {
-    let mut tester = Tester::new(
-        GameState::<State>::default(),
-        [
-            PlayerSecret::new(0, Default::default()),
-            PlayerSecret::new(1, Default::default()),
-        ],
-        Default::default(),
-        |_, _, _| {},
-        |_, _, _| {},
-        false,
-    )
-    .unwrap();
+fn recover_from_dust() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
 
-    tester.apply(Some(0), &Action::InstanceFromIDSetup).unwrap();
+    tester.apply(Some(0), &Action::RecoverFromDust).unwrap();
 
-    // This is an implementation detail.
-    // Constructing a public card in public limbo gives the parent card the ID after the attachment.
+    let secrets = [tester.secret(0), tester.secret(1)];
 
-    let id: InstanceID = InstanceID::from_raw(0);
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
 
-    assert!(id
-        .instance(tester.state(), Some(&tester.secret(0)))
-        .is_some());
+    // Only the owner is told the card's index in secret dust.
+    let recovered = |player: usize| {
+        player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .find_map(|event| match event {
+                CardEvent::MoveCard {
+                    from:
+                        CardLocation {
+                            location: Some((Zone::Dust { public: false }, index)),
+                            ..
+                        },
+                    to:
+                        ExactCardLocation {
+                            location: (Zone::Hand { public: false }, _),
+                            ..
+                        },
+                    ..
+                } => Some(*index),
+                _ => None,
+            })
+    };
+
+    assert_eq!(recovered(0), Some(Some(0)));
+    assert_eq!(recovered(1), Some(None));
 }
 
 #[test]
-fn opponent_instance_from_id() {
-    let mut tester = Tester::new(
-        GameState::<State>::default(),
-        [
-            PlayerSecret::new(0, Default::default()),
-            PlayerSecret::new(1, Default::default()),
-        ],
-        Default::default(),
-        |_, _, _| {},
-        |_, _, _| {},
-        false,
-    )
-    .unwrap();
+fn destroy_instance() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
 
-    tester.apply(Some(0), &Action::InstanceFromIDSetup).unwrap();
+    tester.apply(Some(0), &Action::DestroyInstance).unwrap();
 
-    // This is an implementation detail.
-    // Constructing a public card in public limbo gives the parent card the ID after the attachment.
+    let secrets = [tester.secret(0), tester.secret(1)];
 
-    let id: InstanceID = InstanceID::from_raw(4);
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
 
-    assert!(id
-        .instance(tester.state(), Some(&tester.secret(0)))
-        .is_none());
+    // The secret card is only destroyed in its owner's secret.
+    let destroyed = |player: usize| {
+        player_logs.try_borrow().unwrap()[player]
+            .iter()
+            .filter(|event| matches!(event, CardEvent::DestroyCard { .. }))
+            .count()
+    };
+
+    assert_eq!(destroyed(0), 3);
+    assert_eq!(destroyed(1), 2);
 }
 
 #[test]
-fn move_secret_hand_to_public_hand() {
+fn attach_result() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::AttachResult).unwrap();
+}
+
+#[test]
+fn swap_attachments() {
     let (mut tester, _owner_logs, player_logs) = make_tester();
-    tester
-        .apply(Some(0), &Action::RevealSecretHandCard)
+
+    tester.apply(Some(0), &Action::SwapAttachments).unwrap();
+
+    // The swap is logged as one event, without moving either attachment.
+    let logs = player_logs.try_borrow().unwrap();
+    let swap = |player: usize| {
+        logs[player]
+            .iter()
+            .position(|event| matches!(event, CardEvent::SwapAttachments { .. }))
+            .unwrap()
+    };
+
+    assert!(matches!(
+        &logs[0][swap(0)],
+        CardEvent::SwapAttachments {
+            instances: (Some(..), Some(..)),
+            ..
+        }
+    ));
+    assert!(matches!(
+        &logs[1][swap(1)],
+        CardEvent::SwapAttachments {
+            instances: (Some(..), None),
+            ..
+        }
+    ));
+    assert!(!logs[1][swap(1)..]
+        .iter()
+        .any(|event| matches!(event, CardEvent::MoveCard { .. })));
+}
+
+#[test]
+fn swap_cards() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::SwapCards).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
+    assert_eq!(
+        player_logs.try_borrow().unwrap()[1]
+            .iter()
+            .filter(|event| matches!(event, CardEvent::SwapCards { .. }))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn new_card_in() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::NewCardIn).unwrap();
+
+    // Each card is logged once where it's created, without passing through limbo.
+    let events = &player_logs.try_borrow().unwrap()[1];
+
+    assert_eq!(
+        events
+            .iter()
+            .filter_map(|event| match event {
+                CardEvent::NewCard {
+                    instance, location, ..
+                } => Some((
+                    instance.is_some(),
+                    match location.location.0 {
+                        Zone::Attachment { .. } => "attachment".to_string(),
+                        zone => zone.to_string(),
+                    },
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>(),
+        vec![
+            (true, "field".to_string()),
+            (true, "attachment".to_string()),
+            (false, "deck".to_string()),
+        ]
+    );
+    assert!(!events.iter().any(|event| matches!(
+        event,
+        CardEvent::MoveCard { .. } | CardEvent::NewPointer { .. }
+    )));
+}
+
+#[test]
+fn tokens() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Tokens).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
+}
+
+#[test]
+fn transform_field_card() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    WITH_ATTACHMENT_FIRST_FIELD_ORDER.with(|cell| cell.set(true));
+    let result = tester.apply(Some(0), &Action::TransformFieldCard);
+    WITH_ATTACHMENT_FIRST_FIELD_ORDER.with(|cell| cell.set(false));
+
+    result.unwrap();
+
+    let logs = &player_logs.try_borrow().unwrap()[1];
+    let transform = logs
+        .iter()
+        .position(|event| matches!(event, CardEvent::TransformCard { .. }))
         .unwrap();
 
-    println!(
-        "
+    // The field is sorted after the transform, without any conflict.
+    assert!(logs[transform..]
+        .iter()
+        .any(|event| matches!(event, CardEvent::SortField { real: true, .. })));
+    assert!(!logs
+        .iter()
+        .any(|event| matches!(event, CardEvent::FieldOrderConflict { .. })));
+}
 
-All Logs:"
+#[test]
+fn transform_card() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::TransformCard).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
     );
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        println!("{}", card);
-    }
-    println!(
-        "
-"
+
+    let transforms: Vec<_> = player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .filter_map(|event| match event {
+            CardEvent::TransformCard { instance, .. } => Some(instance.is_some()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(transforms, vec![true, true, false]);
+}
+
+#[test]
+fn face_down() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::FaceDown).unwrap();
+
+    assert_eq!(tester.state().face_down_cards().len(), 1);
+    assert_eq!(
+        player_logs.try_borrow().unwrap()[1]
+            .iter()
+            .filter(|event| matches!(event, CardEvent::FaceDown { .. }))
+            .count(),
+        2
     );
+}
 
-    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0].clone().into_iter();
+#[test]
+fn reveal_hand_card() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
 
-    for i in 0..5 {
-        let move_to_secret_hand_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::MoveCard), got None.");
-        if let CardEvent::MoveCard {
-            to:
-                ExactCardLocation {
-                    player: 0,
-                    location: (Zone::Hand { public: false }, index),
-                },
-            ..
-        } = move_to_secret_hand_event
-        {
-            assert_eq!(index, i);
-        } else {
-            unreachable!(
-                "Expected MoveCard to secret hand, got {:?}",
-                move_to_secret_hand_event
-            );
-        };
-    }
+    tester.apply(Some(0), &Action::RevealHandCard).unwrap();
 
-    let reveal_hand_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
-    assert!(
-        matches!(
-            reveal_hand_event,
-            CardEvent::MoveCard {
-                to: ExactCardLocation {
-                    player: 0,
-                    location: (Zone::Hand { public: true }, 2)
-                },
+    assert!(player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::RevealHandCard { index: 2, .. })));
+}
+
+#[test]
+fn conceal_hand_card() {
+    let (mut tester, owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ConcealHandCard).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
+    assert_eq!(
+        secrets[0]
+            .hand()
+            .iter()
+            .map(|id| id.is_some())
+            .collect::<Vec<_>>(),
+        vec![true, true, true, true, false]
+    );
+
+    // The concealed card's move says where it really went.
+    let concealed = owner_logs
+        .try_borrow()
+        .unwrap()
+        .iter()
+        .rev()
+        .find_map(|event| match event {
+            CardEvent::MoveCard { to, .. } if to.location.0.is_secret_hand() => Some(to.location.1),
+            _ => None,
+        });
+    assert_eq!(concealed, Some(2));
+}
+
+#[test]
+fn piles() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Piles).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
+    assert_eq!(secrets[0].pile(PileID(0)).len(), 1);
+    assert_eq!(secrets[0].pile(PileID(1)).len(), 1);
+    assert!(!player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::ShufflePile { .. })));
+}
+
+#[test]
+fn sideboard() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Sideboard).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
+    assert!(player_logs.try_borrow().unwrap()[1]
+        .iter()
+        .any(|event| matches!(
+            event,
+            CardEvent::Sideboard {
+                deck: 4,
+                sideboard: 2,
                 ..
             }
-        ),
-        "Expected MoveCard to public hand position 2, got {:#?}",
-        reveal_hand_event
-    );
+        )));
 }
 
-fn make_tester() -> (
-    Tester<GameState<State>>,
-    Rc<RefCell<Vec<CardEvent<State>>>>,
-    Rc<RefCell<[Vec<CardEvent<State>>; 2]>>,
-) {
-    let owner_logs: Rc<RefCell<Vec<CardEvent<State>>>> = Rc::new(RefCell::new(vec![]));
-    let player_logs: Rc<RefCell<[Vec<CardEvent<State>>; 2]>> =
-        Rc::new(RefCell::new([vec![], vec![]]));
+#[test]
+fn match_state() {
+    let logs: Rc<RefCell<Vec<CardEvent<State>>>> = Rc::new(RefCell::new(vec![]));
+    let logs_clone = logs.clone();
 
-    let owner_logs_clone = owner_logs.clone();
-    let player_logs_clone = player_logs.clone();
-    let tester = Tester::new(
-        GameState::<State>::default(),
+    let mut tester = Tester::new(
+        MatchState::new(GameState::<State>::default(), 3),
         [
             PlayerSecret::new(0, Default::default()),
             PlayerSecret::new(1, Default::default()),
         ],
         Default::default(),
         |_, _, _| {},
-        move |player, _, message| match player {
-            None => owner_logs_clone.try_borrow_mut().unwrap().push(message),
-            Some(p) => player_logs_clone.try_borrow_mut().unwrap()[p as usize].push(message),
+        move |player, _, message| {
+            if player.is_none() {
+                logs_clone.try_borrow_mut().unwrap().push(message);
+            }
         },
         false,
     )
     .unwrap();
 
-    (tester, owner_logs, player_logs)
+    tester
+        .apply(Some(0), &Action::EndGame { winner: 0 })
+        .unwrap();
+
+    assert_eq!(tester.state().wins(), [1, 0]);
+    assert_eq!(tester.state().first_player(), 1);
+    assert_eq!(tester.state().game().winner(), None);
+    assert!(tester.state().game().player_cards(0).field().is_empty());
+    assert!(tester.secret(0).deck().is_empty());
+
+    tester
+        .apply(Some(1), &Action::EndGame { winner: 1 })
+        .unwrap();
+    tester
+        .apply(Some(0), &Action::EndGame { winner: 0 })
+        .unwrap();
+
+    assert_eq!(tester.state().wins(), [2, 1]);
+    assert_eq!(tester.state().games(), 3);
+    assert_eq!(tester.state().winner(), Some(0));
+    assert_eq!(tester.state().game().winner(), Some(0));
+    assert!(tester
+        .apply(Some(1), &Action::EndGame { winner: 1 })
+        .is_err());
+
+    assert_eq!(
+        logs.try_borrow()
+            .unwrap()
+            .iter()
+            .filter(|event| matches!(event, CardEvent::NextGame { .. }))
+            .count(),
+        2
+    );
 }
 
-fn test_move(
-    card_ptr_bucket: Option<Player>,
-    base_card_type: BaseCard,
-    from_player: Player,
-    from_zone: Zone,
-    to_player: Player,
-    to_zone: Zone,
-) {
-    let (mut tester, _owner_logs, player_logs) = make_tester();
+#[test]
+fn end_game() {
+    let (mut tester, owner_logs, _player_logs) = make_tester();
+
     tester
-        .apply(
-            Some(0),
-            &Action::Move {
-                card_ptr_bucket,
-                base_card_type,
-                from_player,
-                to_player,
-                from_zone,
-                to_zone,
-            },
-        )
+        .apply(Some(1), &Action::EndGame { winner: 1 })
         .unwrap();
 
-    println!("\n\nAll Logs:");
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        {
-            println!("{}", card);
-        }
-    }
-    println!("\n");
+    assert_eq!(tester.state().result(), Some(GameResult::Win(1)));
+    assert!(matches!(
+        owner_logs.try_borrow().unwrap().last(),
+        Some(CardEvent::GameOver {
+            result: GameResult::Win(1)
+        })
+    ));
+    assert!(tester.apply(Some(0), &Action::Summarize).is_err());
+}
 
-    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0]
-        .clone()
-        .into_iter()
-        .peekable();
+#[test]
+fn concede() {
+    let (mut tester, owner_logs, _player_logs) = make_tester();
 
-    // If our BaseCard has an attachment, we'll see a MoveCard to attach it upon creation.
-    if base_card_type == BaseCard::WithAttachment {
-        let attach_event = actual_player_logs
-            .next()
-            .expect("Expected attach event, got None.");
-        assert!(
-            matches!(
-                attach_event,
-                CardEvent::MoveCard {
-                    to: ExactCardLocation {
-                        location: (
-                            Zone::Attachment {
-                                parent: Card::ID(_)
-                            },
-                            _
-                        ),
-                        ..
-                    },
-                    instance: Some(_),
-                    ..
-                }
-            ),
-            "Base card has attachment, so expected attach event.\nGot {:#?}.",
-            attach_event
-        );
+    tester.apply(Some(0), &Action::FlagLimbo).unwrap();
 
-        // ModifyCard of parent from attach callback.
-        let modify_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::ModifyCard), got None.");
-        assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
-    };
+    assert!(arcadeum::store::State::verify(
+        tester.state(),
+        Some(0),
+        &Action::Concede { player: 1 }
+    )
+    .is_err());
 
-    let move_to_start_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    // Player 1's limbo still holds a card, but isn't checked, since they may have disconnected.
+    tester
+        .apply(Some(1), &Action::Concede { player: 1 })
+        .unwrap();
+
+    assert_eq!(tester.state().result(), Some(GameResult::Conceded(1)));
+    assert_eq!(tester.state().winner(), Some(0));
     assert!(matches!(
-        move_to_start_zone_event,
-        CardEvent::MoveCard { .. }
+        owner_logs.try_borrow().unwrap().last(),
+        Some(CardEvent::GameOver {
+            result: GameResult::Conceded(1)
+        })
     ));
+    assert!(tester
+        .apply(Some(0), &Action::Concede { player: 0 })
+        .is_err());
+}
 
-    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
-        actual_player_logs.next(); // allow sort fields
-    }
+#[test]
+fn reveal_after_concession() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    // Event should fire if we moved to a different zone.
-    // Player 0 should see the card instance in every event involving it.
-    let move_to_end_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
-    if from_player == 0
-        || to_player == 0
-        || to_zone.is_public().unwrap()
-        || from_zone.is_public().unwrap()
-    {
-        assert!(matches!(
-            move_to_end_zone_event,
-            CardEvent::MoveCard {
-                instance: Some(_),
-                ..
-            }
-        ));
-    } else {
-        assert!(matches!(
-            move_to_end_zone_event,
-            CardEvent::MoveCard { instance: None, .. }
-        ));
-    }
+    tester
+        .apply(Some(0), &Action::RevealAfterConcession)
+        .unwrap();
+
+    assert_eq!(tester.state().result(), Some(GameResult::Conceded(1)));
 }
 
-fn test_detach(
-    parent_zone: Zone,
-    attachment_ptr_bucket: Option<Player>,
-    to_player: Player,
-    to_zone: Zone,
-) {
-    let (mut tester, _owner_logs, player_logs) = make_tester();
+#[test]
+fn checksum() {
+    let (mut tester, owner_logs, _player_logs) = make_tester();
 
-    tester
-        .apply(
-            Some(0),
-            &Action::Detach {
-                parent_zone,
-                attachment_ptr_bucket,
-                to_player,
-                to_zone,
+    let empty = tester.state().checksum();
+
+    tester.apply(Some(0), &Action::Checksum).unwrap();
+
+    let checksum = tester.state().checksum();
+
+    assert_ne!(checksum, empty);
+    assert!(matches!(
+        owner_logs.try_borrow().unwrap().last(),
+        Some(CardEvent::Checksum { checksum: logged }) if *logged == checksum
+    ));
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        tester.state().player_checksum(&secrets[0]),
+        tester.state().player_checksum(&secrets[0].clone())
+    );
+    assert_ne!(
+        tester.state().player_checksum(&secrets[0]),
+        tester.state().player_checksum(&secrets[1])
+    );
+}
+
+#[test]
+fn divergence_report() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+    let (other, _owner_logs, _player_logs) = make_tester();
+
+    assert!(tester.state().divergence_report(other.state()).is_empty());
+
+    tester.apply(Some(0), &Action::Checksum).unwrap();
+
+    let report = tester.state().divergence_report(other.state());
+
+    assert!(matches!(
+        report[..],
+        [
+            Divergence::InstanceCount { this: 1, other: 0 },
+            Divergence::ZoneSize {
+                player: 0,
+                zone: Zone::Field,
+                this: 1,
+                other: 0,
             },
-        )
-        .unwrap();
+        ]
+    ));
+}
 
-    println!("\n\nAll Logs:");
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        println!("{:#?}\n", card);
-    }
-    println!("\n");
+#[test]
+fn find_instance() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0]
-        .clone()
-        .into_iter()
-        .peekable();
+    tester.apply(Some(0), &Action::FlagLimbo).unwrap();
 
-    // Our BaseCard has an attachment, so we'll see a MoveCard to attach it upon creation.
-    let attach_event = actual_player_logs
-        .next()
-        .expect("Expected attach event, got None.");
-    assert!(
-        matches!(
-            attach_event,
-            CardEvent::MoveCard {
-                to: ExactCardLocation {
-                    location: (
-                        Zone::Attachment {
-                            parent: Card::ID(_)
-                        },
-                        _
-                    ),
-                    ..
-                },
-                instance: Some(_),
-                ..
-            }
-        ),
-        "Base card has attachment, so expected attach event.\nGot {:#?}.",
-        attach_event
+    let secrets = [tester.secret(0), tester.secret(1)];
+    let id = secrets[1].limbo()[0];
+
+    assert_eq!(tester.state().instance_bucket(id), Some(1));
+    assert!(tester
+        .state()
+        .find_instance(id, &[Some(&secrets[0])])
+        .is_none());
+    assert_eq!(
+        tester
+            .state()
+            .find_instance(id, &[Some(&secrets[0]), Some(&secrets[1])])
+            .map(|instance| instance.id()),
+        Some(id)
     );
+}
 
-    // ModifyCard of parent from attach callback.
-    let modify_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::ModifyCard), got None.");
-    assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
+#[test]
+fn compare_cards_across_players() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    let move_to_start_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    tester.apply(Some(0), &Action::CompareCards).unwrap();
+
+    let reveals = tester.state().reveals().unwrap();
+
+    assert_eq!(reveals.len(), 3);
+    assert_eq!(reveals[0].player, 0);
+    assert_eq!(reveals[0].value::<bool>(), Some(false));
+    assert_eq!(reveals[1].player, 0);
+    assert!(reveals[1].value::<InstanceID>().is_some());
+    assert_eq!(reveals[2].player, 1);
+    assert_eq!(reveals[2].value::<bool>(), Some(false));
+}
+
+#[test]
+fn reveal_if_same_zone() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::SameZone).unwrap();
+
+    let reveals = tester.state().reveals().unwrap();
+
+    assert_eq!(reveals.len(), 7);
+    assert_eq!(reveals[0].value::<bool>(), Some(true));
+    assert_eq!(reveals[1].value::<bool>(), Some(false));
+    assert_eq!(reveals[2].player, 0);
     assert!(matches!(
-        move_to_start_zone_event,
-        CardEvent::MoveCard { .. }
+        reveals[2].value::<(Player, Option<Zone>)>(),
+        Some((0, Some(Zone::Limbo { public: false })))
     ));
+    assert_eq!(reveals[3].player, 1);
+    assert_eq!(reveals[3].value::<bool>(), Some(false));
+
+    // The pointer into the other secret is followed there.
+    assert_eq!(reveals[5].player, 1);
+    assert!(reveals[5].is::<bool>());
+    assert_eq!(reveals[5].value::<bool>(), None);
+    assert_eq!(reveals[6].player, 0);
+    assert_eq!(reveals[6].value::<bool>(), Some(true));
+}
+
+#[test]
+fn count_matching() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::CountMatching).unwrap();
+
+    let reveals = tester.state().reveals().unwrap();
+
+    assert_eq!(reveals.len(), 2);
+    assert_eq!(reveals[0].player, 0);
+    assert_eq!(
+        reveals[0].value::<(usize, Vec<InstanceID>)>(),
+        Some((2, Vec::new()))
+    );
+    assert_eq!(reveals[1].player, 1);
+    assert_eq!(
+        reveals[1].value::<(usize, Vec<InstanceID>)>(),
+        Some((1, Vec::new()))
+    );
+}
+
+#[test]
+fn choose_random_matching() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester
+        .apply(Some(0), &Action::ChooseRandomMatching)
+        .unwrap();
+}
+
+#[test]
+fn prompt() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Discard).unwrap();
+
+    let prompt = tester.state().prompt().unwrap().clone();
+
+    assert_eq!(prompt.player(), 1);
+    assert_eq!(prompt.key(), "discard");
+    assert_eq!(
+        testing::prompt_responses(&prompt, 10),
+        vec![
+            PromptResponse::Cards(vec![0]),
+            PromptResponse::Cards(vec![1])
+        ]
+    );
+    assert_eq!(testing::prompt_responses(&prompt, 1).len(), 1);
+
+    let respond = |indices: Vec<usize>| Action::Respond {
+        response: PromptResponse::Cards(indices),
+    };
+
+    for (player, action) in [
+        (Some(1), Action::Discard),
+        (Some(0), respond(vec![0])),
+        (Some(1), respond(vec![2])),
+        (Some(1), respond(vec![0, 1])),
+        (
+            Some(1),
+            Action::Respond {
+                response: PromptResponse::YesNo(true),
+            },
+        ),
+    ] {
+        assert!(arcadeum::store::State::verify(tester.state(), player, &action).is_err());
+    }
+
+    tester.apply(Some(1), &respond(vec![1])).unwrap();
+
+    assert!(tester.state().prompt().is_none());
+    assert_eq!(tester.state().player_cards(1).graveyard().len(), 1);
+    assert!(player_logs.try_borrow().unwrap()[0]
+        .iter()
+        .any(|event| matches!(
+            event,
+            CardEvent::PromptResponse {
+                player: 1,
+                response: PromptResponse::Cards(indices),
+            } if indices == &vec![1]
+        )));
+    assert!(arcadeum::store::State::verify(tester.state(), Some(1), &respond(vec![0])).is_err());
+}
+
+#[test]
+fn check_targets() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Targets).unwrap();
+}
+
+#[test]
+fn resources() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Resources).unwrap();
+
+    let player_cards = tester.state().player_cards(0);
+
+    assert_eq!(player_cards.resource(&Resource::Health), 17);
+    assert_eq!(player_cards.resource(&Resource::Mana), 0);
+    assert_eq!(tester.state().player_cards(1).resource(&Resource::Mana), -1);
+    assert_eq!(player_cards.counters().len(), 1);
 
-    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
-        actual_player_logs.next(); // allow sort fields
+    for logs in player_logs.try_borrow().unwrap().iter() {
+        assert!(logs.iter().any(|event| matches!(
+            event,
+            CardEvent::ResourceChanged {
+                player: 0,
+                kind: Resource::Health,
+                delta: -3,
+                value: 17,
+            }
+        )));
     }
+}
 
-    let move_to_end_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
-    assert!(
-        matches!(
-            move_to_end_zone_event,
-            CardEvent::MoveCard {
-                instance: Some(_),
-                ..
-            }
-        ),
-        "Expected MoveCard to End Zone, got {:#?}",
-        move_to_end_zone_event
+#[test]
+fn modify_player_secret() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ModifyPlayerSecret).unwrap();
+    tester.apply(Some(0), &Action::ModifyPlayerSecret).unwrap();
+
+    assert_eq!(tester.secret(0).secret.charges, 2);
+    assert_eq!(tester.secret(1).secret.charges, 0);
+
+    let logs = player_logs.try_borrow().unwrap();
+
+    // Two logged by the closure, and one returned by it.
+    assert_eq!(
+        logs[0]
+            .iter()
+            .filter(|event| matches!(event, CardEvent::GameEvent { .. }))
+            .count(),
+        3
     );
+    assert!(!logs[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::GameEvent { .. })));
+}
 
-    // Player 0 should see the card instance in every event involving it.
-    // ModifyCard for parent being modified because of detach.
-    let modify_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::ModifyCard), got None.");
-    if let CardEvent::ModifyCard { instance } = modify_event {
-        assert!(instance.attachment().is_none());
-    } else {
-        unreachable!("Expected ModifyCard, got {:#?}", modify_event);
-    }
+#[test]
+fn return_to_owner() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::ReturnToOwner).unwrap();
 }
 
-fn test_attach(
-    parent_base_card: BaseCard,
-    parent_ptr_bucket: Option<Player>,
-    parent_zone: Zone,
-    card_ptr_bucket: Option<Player>,
-    card_owner: Player,
-    card_zone: Zone,
-) {
+#[test]
+fn flush_deferred_logs() {
     let (mut tester, _owner_logs, player_logs) = make_tester();
 
-    tester
-        .apply(
-            Some(0),
-            &Action::Attach {
-                parent_base_card,
-                parent_ptr_bucket,
-                parent_zone,
-                card_ptr_bucket,
-                card_owner,
-                card_zone,
-            },
-        )
+    tester.apply(Some(0), &Action::DeferLogs).unwrap();
+
+    let logs = player_logs.try_borrow().unwrap();
+
+    let roll = logs[0]
+        .iter()
+        .position(|event| matches!(event, CardEvent::RandomOutcome { sides: 6, .. }))
+        .unwrap();
+    let deferred = logs[0]
+        .iter()
+        .position(|event| matches!(event, CardEvent::GameEvent { .. }))
         .unwrap();
 
-    println!("\n\nAll Logs:");
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        println!("{}\n", card);
-    }
-    println!("\n");
+    assert!(roll < deferred);
+    assert!(!logs[1]
+        .iter()
+        .any(|event| matches!(event, CardEvent::GameEvent { .. })));
+}
 
-    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0]
-        .clone()
-        .into_iter()
-        .peekable();
+#[test]
+fn move_card_source_locations() {
+    let (mut tester, _owner_logs, player_logs) = make_tester();
 
-    if parent_base_card == BaseCard::WithAttachment {
-        // Our BaseCard has an attachment, so we'll see a MoveCard to attach it upon creation.
-        let attach_event = actual_player_logs
-            .next()
-            .expect("Expected attach event, got None.");
-        assert!(
-            matches!(
-                attach_event,
-                CardEvent::MoveCard {
-                    to: ExactCardLocation {
-                        location: (
-                            Zone::Attachment {
-                                parent: Card::ID(_)
-                            },
-                            _
-                        ),
-                        ..
-                    },
-                    instance: Some(_),
-                    ..
-                }
-            ),
-            "Base card has attachment, so expected attach event.\nGot {:#?}.",
-            attach_event
-        );
+    tester.apply(Some(0), &Action::SourceLocations).unwrap();
+
+    let logs = player_logs.try_borrow().unwrap();
+    let sources = |player: usize| -> Vec<_> {
+        logs[player]
+            .iter()
+            .filter_map(|event| match event {
+                CardEvent::MoveCard { from, .. } => Some(from.location),
+                _ => None,
+            })
+            .collect()
+    };
 
-        // ModifyCard of parent from attach callback.
-        let modify_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::ModifyCard), got None.");
-        assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
-    }
-    let move_parent_to_start_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    // The owner sees exactly where each card came from.
     assert!(matches!(
-        move_parent_to_start_zone_event,
-        CardEvent::MoveCard { .. }
+        sources(0)[..],
+        [
+            Some((Zone::Limbo { public: false }, Some(2))),
+            Some((Zone::Limbo { public: false }, Some(1))),
+            Some((Zone::Hand { public: false }, Some(0))),
+        ]
     ));
 
-    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
-        actual_player_logs.next(); // allow sort fields
-    }
-
-    let move_attach_to_start_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    // The opponent sees the zone, and the index if the zone's size is public.
     assert!(matches!(
-        move_attach_to_start_zone_event,
-        CardEvent::MoveCard { .. }
+        sources(1)[..],
+        [
+            Some((Zone::Limbo { public: false }, None)),
+            Some((Zone::Limbo { public: false }, None)),
+            Some((Zone::Hand { public: false }, Some(0))),
+        ]
     ));
+}
 
-    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
-        actual_player_logs.next(); // allow sort fields
-    }
-
-    if parent_base_card == BaseCard::WithAttachment {
-        // Dust current attach
-        let dust_current_attach = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::MoveCard), got None.");
-        assert!(
-            matches!(
-                dust_current_attach,
-                CardEvent::MoveCard {
-                    instance: Some(_),
-                    ..
-                }
-            ),
-            "Expected MoveCard from Zone::Attach to Zone::Dust, got {:#?}.",
-            dust_current_attach
-        );
-
-        // ModifyCard for parent being modified because of detach.
-        let modify_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::ModifyCard), got None.");
-        assert!(
-            matches!(modify_event, CardEvent::ModifyCard { .. }),
-            "Expected CardEvent::ModifyCard for parent because of child being detached, got {:#?}",
-            modify_event
-        );
-    }
-    if matches!(actual_player_logs.peek(), Some(CardEvent::SortField { .. })) {
-        actual_player_logs.next(); // allow sort fields
-    }
+#[test]
+fn can_move_card() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    let attach_attachment_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    tester.apply(Some(0), &Action::CanMoveCard).unwrap();
+}
 
-    let zone = card_zone;
-    let is_mine = card_owner == 0;
-    let has_public_location = match zone {
-        Zone::Deck => true,
-        Zone::Hand { .. } => true,
-        Zone::Field => true,
-        Zone::Graveyard => true,
-        Zone::Dust { .. } => true,
-        Zone::Attachment { .. } => false,
-        Zone::Limbo { public } => is_mine || public,
-        Zone::Casting => true,
-        Zone::CardSelection => true,
-        Zone::HeroAbility => true,
-        Zone::Boon => true,
-    };
-    if has_public_location {
-        assert!(
-            matches!(
-                attach_attachment_event,
-                CardEvent::MoveCard {
-                    instance: Some(_),
-                    from: CardLocation {
-                        location: Some(_),
-                        ..
-                    },
-                    ..
-                }
-            ),
-            "Expected MoveCard to Zone::Attachment with Some(location), got {:#?}.",
-            attach_attachment_event
-        );
-    } else {
-        assert!(
-            matches!(
-                attach_attachment_event,
-                CardEvent::MoveCard {
-                    instance: Some(_),
-                    from: CardLocation { location: None, .. },
-                    ..
-                }
-            ),
-            "Expected MoveCard to Zone::Attachment with location: None, got {:#?}.",
-            attach_attachment_event
-        );
-    }
+#[test]
+fn move_card_errors() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    // Parent being modified because of new attach.
-    let modify_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::ModifyCard), got None.");
-    assert!(
-        matches!(modify_event, CardEvent::ModifyCard { .. }),
-        "Expected CardEvent::ModifyCard for parent because of new attach, got {:#?}",
-        modify_event
-    );
+    tester.apply(Some(0), &Action::MoveCardErrors).unwrap();
 }
 
-fn test_attach_from_attached(
-    parent_base_card: BaseCard,
-    parent_ptr_bucket: Option<Player>,
-    parent_zone: Zone,
-    card_ptr_bucket: Option<Player>,
-    card_owner: Player,
-    card_zone: Zone,
-) {
-    let (mut tester, _owner_logs, player_logs) = make_tester();
-
-    tester
-        .apply(
-            Some(0),
-            &Action::AttachFromAttached {
-                parent_base_card,
-                parent_ptr_bucket,
-                parent_zone,
-                card_ptr_bucket,
-                card_owner,
-                card_zone,
-            },
-        )
-        .unwrap();
+#[test]
+fn unified_errors() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    println!(
-        "Action: {:#?}",
-        Action::AttachFromAttached {
-            parent_base_card,
-            parent_ptr_bucket,
-            parent_zone,
-            card_ptr_bucket,
-            card_owner,
-            card_zone,
-        }
-    );
-    println!("\n\nAll Logs:");
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        println!("{:#?}\n", card);
-    }
-    println!("\n");
+    tester.apply(Some(0), &Action::UnifiedErrors).unwrap();
+}
 
-    let mut actual_player_logs = player_logs.try_borrow_mut().unwrap()[0].clone().into_iter();
+#[test]
+fn try_reveal_from_card() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    let is_mine = card_owner == 0;
+    tester.apply(Some(0), &Action::TryRevealFromCard).unwrap();
+}
 
-    if parent_base_card == BaseCard::WithAttachment {
-        // Our BaseCard has an attachment, so we'll see a MoveCard to attach it upon creation.
-        let attach_event = actual_player_logs
-            .next()
-            .expect("Expected attach event, got None.");
-        assert!(
-            matches!(
-                attach_event,
-                CardEvent::MoveCard {
-                    to: ExactCardLocation {
-                        location: (
-                            Zone::Attachment {
-                                parent: Card::ID(_)
-                            },
-                            _
-                        ),
-                        ..
-                    },
-                    instance: Some(_),
-                    ..
-                }
-            ),
-            "Base card has attachment, so expected attach event.\nGot {:#?}.",
-            attach_event
-        );
+#[test]
+fn lenient_panic_policy() {
+    let (mut tester, owner_logs, player_logs) = make_tester();
 
-        // ModifyCard of parent from attach callback.
-        let modify_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::ModifyCard), got None.");
-        assert!(matches!(modify_event, CardEvent::ModifyCard { .. }));
-    }
-    let move_parent_to_start_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    tester
+        .apply(Some(0), &Action::SetPanicPolicy(PanicPolicy::Lenient))
+        .unwrap();
+
+    let owner_logged = owner_logs.try_borrow().unwrap().len();
+    let player_logged: Vec<_> = player_logs
+        .try_borrow()
+        .unwrap()
+        .iter()
+        .map(Vec::len)
+        .collect();
+
+    tester.apply(Some(0), &Action::Violate).unwrap();
+
+    // The card and the charge are rolled back.
+    assert!(tester.state().player_cards(0).field().is_empty());
+    assert_eq!(tester.secret(0).secret.charges, 0);
+
+    // None of the events logged before the panic are sent.
     assert!(matches!(
-        move_parent_to_start_zone_event,
-        CardEvent::MoveCard { .. }
+        owner_logs.try_borrow().unwrap()[owner_logged..],
+        [CardEvent::ActionAborted]
     ));
 
-    if parent_zone.is_field() {
-        let sort_field_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::SortField), got None.");
-        assert!(matches!(sort_field_event, CardEvent::SortField { .. }));
+    for (logs, logged) in player_logs.try_borrow().unwrap().iter().zip(player_logged) {
+        assert!(logs[logged..]
+            .iter()
+            .all(|event| matches!(event, CardEvent::ActionAborted)));
     }
+}
 
-    let start_parent_gains_attach = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
-    assert!(
-        matches!(
-            start_parent_gains_attach,
-            CardEvent::MoveCard {
-                to: ExactCardLocation {
-                    location: (Zone::Attachment { .. }, _),
-                    ..
-                },
-                ..
-            }
-        ),
-        "Expected Some(CardEvent::MoveCard) for attach coming to starting parent, got {:#?}",
-        start_parent_gains_attach
-    );
+#[test]
+fn lenient_panic_after_reveal() {
+    let (mut tester, owner_logs, player_logs) = make_tester();
 
-    let start_parent_gains_attach_modify = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::ModifyCard), got None.");
-    assert!(
-        matches!(
-            start_parent_gains_attach_modify,
-            CardEvent::ModifyCard { .. }
-        ),
-        "Expected Some(CardEvent::ModifyCard) for attach callback on starting parent, got {:#?}",
-        start_parent_gains_attach_modify
+    tester
+        .apply(Some(0), &Action::SetPanicPolicy(PanicPolicy::Lenient))
+        .unwrap();
+
+    let owner_logged = owner_logs.try_borrow().unwrap().len();
+    let player_logged: Vec<_> = player_logs
+        .try_borrow()
+        .unwrap()
+        .iter()
+        .map(Vec::len)
+        .collect();
+
+    tester.apply(Some(0), &Action::PanicAfterReveal).unwrap();
+
+    // Every client undoes the action, including the card created in player 1's secret.
+    assert!(tester.secret(1).hand().is_empty());
+    assert!(tester.secret(1).limbo().is_empty());
+    assert_eq!(tester.state().player_cards(1).hand().len(), 0);
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
     );
 
-    let move_attach_to_start_zone_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
     assert!(matches!(
-        move_attach_to_start_zone_event,
-        CardEvent::MoveCard { .. }
+        owner_logs.try_borrow().unwrap()[owner_logged..],
+        [CardEvent::ActionAborted]
     ));
-    if card_zone.is_field() {
-        let sort_field_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::SortField), got None.");
-        assert!(matches!(sort_field_event, CardEvent::SortField { .. }));
+
+    for (logs, logged) in player_logs.try_borrow().unwrap().iter().zip(player_logged) {
+        assert!(matches!(logs[logged..], [CardEvent::ActionAborted]));
     }
+}
 
-    if parent_base_card == BaseCard::WithAttachment {
-        // Dust current attach
-        let dust_current_attach = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::MoveCard), got None.");
-        assert!(
-            matches!(
-                dust_current_attach,
-                CardEvent::MoveCard {
-                    instance: Some(_),
-                    ..
-                }
-            ),
-            "Expected MoveCard from Zone::Attach to Zone::Dust, got {:#?}.",
-            dust_current_attach
-        );
+#[test]
+fn forbid_limbo() {
+    let (mut tester, owner_logs, _player_logs) = make_tester();
 
-        // ModifyCard for new parent being modified because of detach of existing attachment.
-        let modify_event = actual_player_logs
-            .next()
-            .expect("Expected Some(CardEvent::ModifyCard), got None.");
-        assert!(
-            matches!(modify_event, CardEvent::ModifyCard { .. }),
-            "Expected CardEvent::ModifyCard for parent because of child being detached, got {:#?}",
-            modify_event
-        );
-    }
+    tester
+        .apply(Some(0), &Action::SetLimboPolicy(LimboPolicy::Forbid))
+        .unwrap();
 
-    let attach_attachment_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::MoveCard), got None.");
+    let owner_logged = owner_logs.try_borrow().unwrap().len();
 
-    let zone = card_zone;
-    let has_public_location = match zone {
-        Zone::Deck => is_mine,
-        Zone::Hand { public } => is_mine || public, // attachment secrecy depends on parent secrecy
-        Zone::Field => true,
-        Zone::Graveyard => true,
-        Zone::Dust { public } => is_mine || public,
-        Zone::Attachment { .. } => unreachable!(),
-        Zone::Limbo { public } => is_mine || public,
-        Zone::Casting => true,
-        Zone::CardSelection => is_mine,
-        Zone::HeroAbility => true,
-        Zone::Boon => true,
-    };
-    if has_public_location {
-        assert!(
-            matches!(
-                attach_attachment_event,
-                CardEvent::MoveCard {
-                    instance: Some(_),
-                    from: CardLocation {
-                        location: Some(_),
-                        ..
-                    },
-                    ..
-                }
-            ),
-            "Expected MoveCard to Zone::Attachment with Some(location), got {:#?}.",
-            attach_attachment_event
-        );
-    } else {
-        assert!(
-            matches!(
-                attach_attachment_event,
-                CardEvent::MoveCard {
-                    instance: Some(_),
-                    from: CardLocation { location: None, .. },
-                    ..
-                }
-            ),
-            "Expected MoveCard to Zone::Attachment with location: None, got {:#?}.",
-            attach_attachment_event
-        );
-    }
+    // Leaving a card in limbo undoes the action instead of panicking.
+    tester.apply(Some(0), &Action::LeaveInLimbo).unwrap();
 
-    // Parent being modified because of new attach.
-    let modify_event = actual_player_logs
-        .next()
-        .expect("Expected Some(CardEvent::ModifyCard), got None.");
-    assert!(
-        matches!(modify_event, CardEvent::ModifyCard { .. }),
-        "Expected CardEvent::ModifyCard for parent because of new attach, got {:#?}",
-        modify_event
-    );
+    assert!(tester.secret(1).limbo().is_empty());
+    assert!(matches!(
+        owner_logs.try_borrow().unwrap()[owner_logged..],
+        [
+            CardEvent::LimboNotEmpty { player: 1 },
+            CardEvent::ActionAborted
+        ]
+    ));
 }
 
-fn test_copy_card(
-    card_ptr_bucket: Option<Player>,
-    base_card_type: BaseCard,
-    card_zone: Zone,
-    deep: bool,
-) {
-    let (mut tester, _owner_logs, player_logs) = make_tester();
+#[test]
+fn action_metrics() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::Metrics).unwrap();
+
+    let metrics = tester.state().last_action_metrics();
+    assert!(metrics.reveals > 0);
+    assert!(metrics.revealed_bytes > 0);
+    assert!(metrics.mutations > 0);
+
+    // Each action is counted separately.
+    // One mutation for the closure, plus one per player clearing deferred locations at the end of the action.
+    tester.apply(Some(0), &Action::ModifyPlayerSecret).unwrap();
+    assert_eq!(
+        tester.state().last_action_metrics(),
+        ActionMetrics {
+            reveals: 0,
+            revealed_bytes: 0,
+            mutations: 3,
+        }
+    );
 
+    // The lenient panic policy's checkpoints aren't counted.
     tester
-        .apply(
-            Some(0),
-            &Action::CopyCard {
-                card_ptr_bucket,
-                base_card_type,
-                card_zone,
-                deep,
-            },
-        )
+        .apply(Some(0), &Action::SetPanicPolicy(PanicPolicy::Lenient))
         .unwrap();
+    tester.apply(Some(0), &Action::ModifyPlayerSecret).unwrap();
+    assert_eq!(tester.state().last_action_metrics().mutations, 3);
+}
 
-    println!("\n\nAll Logs:");
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        println!("{:#?}\n", card);
-    }
-    println!("\n");
+#[test]
+fn destroyed_pointer() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
+
+    tester.apply(Some(0), &Action::DestroyedPointer).unwrap();
+
+    let secrets = [tester.secret(0), tester.secret(1)];
+
+    assert_eq!(
+        invariants::check(tester.state(), &[Some(&secrets[0]), Some(&secrets[1])]),
+        vec![]
+    );
 }
 
-fn test_reset_card(
-    card_ptr_bucket: Option<Player>,   // 3
-    attachment_type: Option<BaseCard>, //2
-    base_card_type: BaseCard,          // 2
-    card_zone: Zone,
-) {
-    let (mut tester, _owner_logs, player_logs) = make_tester();
-    tester
-        .apply(
-            Some(0),
-            &Action::ResetCard {
-                card_ptr_bucket,
-                attachment_type,
-                base_card_type,
-                card_zone,
-            },
-        )
-        .unwrap();
+#[test]
+fn reveal_each_if() {
+    let (mut tester, _owner_logs, _player_logs) = make_tester();
 
-    println!("\n\nAll Logs:");
-    for card in player_logs.try_borrow_mut().unwrap()[0].clone() {
-        println!("{:#?}\n", card);
+    tester.apply(Some(0), &Action::RevealIfSetup).unwrap();
+    tester.apply(Some(0), &Action::RevealEachIf).unwrap();
+
+    // reveal_if only reveals whether player 1's conditions hold together.
+    let reveals = tester.state().reveals().unwrap();
+    assert_eq!(reveals.len(), 1);
+    assert_eq!(reveals[0].value::<bool>(), Some(false));
+}
+
+#[test]
+fn batch_chunks() {
+    let (mut tester, _, _) = make_tester();
+
+    tester.apply(Some(0), &Action::BatchChunks).unwrap();
+}
+
+#[test]
+fn batch_chunks_across_actions() {
+    let (mut tester, _, _) = make_tester();
+
+    tester.apply(Some(0), &Action::StartBatch).unwrap();
+
+    let mut moved = Vec::new();
+    let mut starts = Vec::new();
+
+    // Each action moves one chunk, resuming from the cursor the last one left in the state.
+    while let Some(start) = tester.state().next_chunk {
+        starts.push(start);
+
+        tester.apply(Some(0), &Action::ContinueBatch).unwrap();
+
+        moved.push(tester.state().player_cards(0).graveyard().len());
     }
-    println!("\n");
+
+    assert_eq!(starts, vec![0, 2, 4]);
+    assert_eq!(moved, vec![2, 4, 5]);
+    assert!(tester.state().player_cards(0).field().is_empty());
 }
-include!(concat!(env!("OUT_DIR"), "/generated_tests.rs"));